@@ -0,0 +1,33 @@
+use crate::annotations::ClassTags;
+use crate::link::ClassLink;
+use crate::metrics::ClassMetrics;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+pub trait FileAnalyzer {
+    fn analyze(&self, content: &str, file_path: &str) -> AnalysisResult;
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalysisResult {
+    pub classes: HashSet<String>,
+    pub links: Vec<ClassLink>,
+    // Size of each class defined in code; Markdown docs have none
+    pub class_metrics: HashMap<String, ClassMetrics>,
+    // Deprecated/internal markers found on class declarations
+    pub class_tags: ClassTags,
+    // Most classes a class may depend on, declared with a
+    // `classlink: max-deps=N` comment on its declaration
+    pub dependency_budgets: HashMap<String, usize>,
+    // Namespace each class is declared in, for languages that declare one
+    // (C# `namespace`); others are inferred from paths, see
+    // `manifest::class_namespaces`
+    pub namespaces: HashMap<String, String>,
+    // Dependency rules from ```classlink-assert blocks of Markdown pages,
+    // by line number, e.g. `UserService -> !DatabaseContext`
+    pub assertions: BTreeMap<usize, String>,
+    // Source text of the lines links were found on, by line number; only
+    // filled when the scan is asked to capture snippets
+    pub snippets: BTreeMap<usize, String>,
+}
@@ -0,0 +1,383 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::annotations::ClassTags;
+use crate::graph::ClassGraph;
+use crate::link::{ClassLink, LinkKind};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkStatistics {
+    pub total_classes: usize,
+    pub isolated_classes: Vec<String>,
+    pub class_link_counts: HashMap<String, ClassLinkCount>,
+    pub most_linked_class: Option<(String, usize)>,
+    pub most_linking_class: Option<(String, usize)>,
+    // One path per cycle, starting and ending at the cycle's first class by
+    // name, see `ClassGraph::cycle_path`
+    #[serde(default)]
+    pub circular_dependencies: Vec<Vec<String>>,
+    // Per class, filled only when abstraction coupling is asked for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abstraction_coupling: Option<BTreeMap<String, AbstractionCoupling>>,
+    // Why each isolated class has no links, filled by project runs that
+    // know their entry points and test files
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub isolation: BTreeMap<String, Isolation>,
+    // Registered metrics: name -> class -> value
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom_metrics: BTreeMap<String, BTreeMap<String, f64>>,
+}
+
+// Not every isolated class is dead code: entry points are started from
+// outside, test helpers serve tests and exported classes may be used by
+// other projects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Isolation {
+    Dead,
+    EntryPoint,
+    TestHelper,
+    Exported,
+}
+
+// Outgoing links of one class split by whether the target is an interface
+// or abstract class. Only links to classes defined in the project count,
+// since the kind of anything else is unknown; doc links are left out.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AbstractionCoupling {
+    pub abstract_links: usize,
+    pub concrete_links: usize,
+    // Share of the links that go to abstractions; 1.0 follows the
+    // dependency inversion principle fully
+    pub ratio: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassLinkCount {
+    pub outgoing_links: usize,
+    pub incoming_links: usize,
+}
+
+pub struct LinkAnalyzer;
+
+impl Default for LinkAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinkAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn analyze(&self, classes: HashSet<String>, links: Vec<ClassLink>) -> LinkStatistics {
+        let mut class_link_counts = HashMap::new();
+        let mut linked_classes = HashSet::new();
+        
+        // Initialize all classes with zero counts
+        for class in &classes {
+            class_link_counts.insert(
+                class.clone(),
+                ClassLinkCount {
+                    outgoing_links: 0,
+                    incoming_links: 0,
+                },
+            );
+        }
+        
+        // Count links; parsers emit one per occurrence, a class pair counts once
+        let mut counted = HashSet::new();
+        for link in &links {
+            linked_classes.insert(link.from_class.clone());
+            linked_classes.insert(link.to_class.clone());
+            if !counted.insert((&link.from_class, &link.to_class)) {
+                continue;
+            }
+            
+            // Update outgoing links for source class
+            if let Some(count) = class_link_counts.get_mut(&link.from_class) {
+                count.outgoing_links += 1;
+            }
+            
+            // Update incoming links for target class
+            if let Some(count) = class_link_counts.get_mut(&link.to_class) {
+                count.incoming_links += 1;
+            }
+        }
+        
+        // Find isolated classes (no incoming or outgoing links)
+        let isolated_classes: Vec<String> = classes
+            .iter()
+            .filter(|class| !linked_classes.contains(*class))
+            .cloned()
+            .collect();
+        
+        // Find most linked class (highest incoming links)
+        let most_linked_class = class_link_counts
+            .iter()
+            .max_by_key(|(_, count)| count.incoming_links)
+            .map(|(class, count)| (class.clone(), count.incoming_links))
+            .filter(|(_, count)| *count > 0);
+        
+        // Find most linking class (highest outgoing links)
+        let most_linking_class = class_link_counts
+            .iter()
+            .max_by_key(|(_, count)| count.outgoing_links)
+            .map(|(class, count)| (class.clone(), count.outgoing_links))
+            .filter(|(_, count)| *count > 0);
+        
+        let graph = ClassGraph::new(&classes, &links);
+        let circular_dependencies = graph
+            .find_cycles()
+            .iter()
+            .map(|component| graph.cycle_path(component))
+            .collect();
+
+        LinkStatistics {
+            total_classes: classes.len(),
+            isolated_classes,
+            class_link_counts,
+            most_linked_class,
+            most_linking_class,
+            circular_dependencies,
+            abstraction_coupling: None,
+            isolation: BTreeMap::new(),
+            custom_metrics: BTreeMap::new(),
+        }
+    }
+
+    pub fn abstraction_coupling(
+        &self,
+        classes: &HashSet<String>,
+        links: &[ClassLink],
+        tags: &ClassTags,
+    ) -> BTreeMap<String, AbstractionCoupling> {
+        let is_abstraction = |class: &str| tags.get(class).is_some_and(|tags| tags.iter().any(|tag| tag.is_abstraction()));
+        let mut coupling: BTreeMap<String, AbstractionCoupling> = BTreeMap::new();
+        for link in links {
+            if link.kind == LinkKind::Doc || !classes.contains(&link.from_class) || !classes.contains(&link.to_class) {
+                continue;
+            }
+            let entry = coupling.entry(link.from_class.clone()).or_default();
+            if is_abstraction(&link.to_class) {
+                entry.abstract_links += 1;
+            } else {
+                entry.concrete_links += 1;
+            }
+        }
+        for entry in coupling.values_mut() {
+            entry.ratio = entry.abstract_links as f64 / (entry.abstract_links + entry.concrete_links) as f64;
+        }
+        coupling
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_isolated_classes() {
+        let analyzer = LinkAnalyzer::new();
+        let classes: HashSet<String> = ["ClassA", "ClassB", "ClassC", "IsolatedClass"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        
+        let links = vec![
+            ClassLink {
+                from_class: "ClassA".to_string(),
+                to_class: "ClassB".to_string(),
+                line_number: 1,
+                kind: Default::default(),
+                position: None,
+            },
+            ClassLink {
+                from_class: "ClassB".to_string(),
+                to_class: "ClassC".to_string(),
+                line_number: 2,
+                kind: Default::default(),
+                position: None,
+            },
+        ];
+        
+        let stats = analyzer.analyze(classes, links);
+        
+        assert_eq!(stats.total_classes, 4);
+        assert_eq!(stats.isolated_classes.len(), 1);
+        assert!(stats.isolated_classes.contains(&"IsolatedClass".to_string()));
+    }
+
+    #[test]
+    fn test_class_link_counts() {
+        let analyzer = LinkAnalyzer::new();
+        let classes: HashSet<String> = ["ClassA", "ClassB", "ClassC"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        
+        let links = vec![
+            ClassLink {
+                from_class: "ClassA".to_string(),
+                to_class: "ClassB".to_string(),
+                line_number: 1,
+                kind: Default::default(),
+                position: None,
+            },
+            ClassLink {
+                from_class: "ClassA".to_string(),
+                to_class: "ClassC".to_string(),
+                line_number: 2,
+                kind: Default::default(),
+                position: None,
+            },
+            ClassLink {
+                from_class: "ClassC".to_string(),
+                to_class: "ClassB".to_string(),
+                line_number: 3,
+                kind: Default::default(),
+                position: None,
+            },
+        ];
+        
+        let stats = analyzer.analyze(classes, links);
+        
+        assert_eq!(stats.class_link_counts["ClassA"].outgoing_links, 2);
+        assert_eq!(stats.class_link_counts["ClassA"].incoming_links, 0);
+        
+        assert_eq!(stats.class_link_counts["ClassB"].outgoing_links, 0);
+        assert_eq!(stats.class_link_counts["ClassB"].incoming_links, 2);
+        
+        assert_eq!(stats.class_link_counts["ClassC"].outgoing_links, 1);
+        assert_eq!(stats.class_link_counts["ClassC"].incoming_links, 1);
+    }
+
+    #[test]
+    fn test_repeated_occurrences_of_a_link_count_once() {
+        let analyzer = LinkAnalyzer::new();
+        let classes: HashSet<String> = ["Order", "Money"].iter().map(|s| s.to_string()).collect();
+        let link = |line_number| ClassLink {
+            from_class: "Order".to_string(),
+            to_class: "Money".to_string(),
+            line_number,
+            kind: Default::default(),
+            position: None,
+        };
+
+        let stats = analyzer.analyze(classes, vec![link(3), link(7)]);
+
+        assert_eq!(stats.class_link_counts["Order"].outgoing_links, 1);
+        assert_eq!(stats.class_link_counts["Money"].incoming_links, 1);
+        assert_eq!(stats.most_linking_class, Some(("Order".to_string(), 1)));
+    }
+
+    #[test]
+    fn test_most_linked_and_linking_classes() {
+        let analyzer = LinkAnalyzer::new();
+        let classes: HashSet<String> = ["Hub", "Node1", "Node2", "Node3"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        
+        let links = vec![
+            ClassLink {
+                from_class: "Hub".to_string(),
+                to_class: "Node1".to_string(),
+                line_number: 1,
+                kind: Default::default(),
+                position: None,
+            },
+            ClassLink {
+                from_class: "Hub".to_string(),
+                to_class: "Node2".to_string(),
+                line_number: 2,
+                kind: Default::default(),
+                position: None,
+            },
+            ClassLink {
+                from_class: "Hub".to_string(),
+                to_class: "Node3".to_string(),
+                line_number: 3,
+                kind: Default::default(),
+                position: None,
+            },
+            ClassLink {
+                from_class: "Node1".to_string(),
+                to_class: "Hub".to_string(),
+                line_number: 4,
+                kind: Default::default(),
+                position: None,
+            },
+            ClassLink {
+                from_class: "Node2".to_string(),
+                to_class: "Hub".to_string(),
+                line_number: 5,
+                kind: Default::default(),
+                position: None,
+            },
+        ];
+        
+        let stats = analyzer.analyze(classes, links);
+        
+        assert_eq!(stats.most_linking_class, Some(("Hub".to_string(), 3)));
+        assert_eq!(stats.most_linked_class, Some(("Hub".to_string(), 2)));
+    }
+
+    #[test]
+    fn test_circular_dependencies_are_reported_as_paths() {
+        let link = |from: &str, to: &str| ClassLink {
+            from_class: from.to_string(),
+            to_class: to.to_string(),
+            line_number: 1,
+            kind: Default::default(),
+            position: None,
+        };
+        let classes: HashSet<String> =
+            ["Order", "Invoice", "Payment", "Ledger", "Node"].iter().map(|s| s.to_string()).collect();
+        let links = vec![
+            link("Order", "Invoice"),
+            link("Invoice", "Payment"),
+            link("Payment", "Order"),
+            link("Invoice", "Order"),
+            link("Payment", "Ledger"),
+            link("Node", "Node"),
+        ];
+
+        let stats = LinkAnalyzer::new().analyze(classes, links);
+
+        // The shortest way round wins, so Payment is left out of the first
+        assert_eq!(stats.circular_dependencies, vec![vec!["Invoice", "Order", "Invoice"], vec!["Node", "Node"]]);
+    }
+
+    #[test]
+    fn test_abstraction_coupling() {
+        use crate::annotations::ClassTag;
+
+        let classes: HashSet<String> = ["Service", "IRepo", "Repo", "Shape"].iter().map(|s| s.to_string()).collect();
+        let link = |from: &str, to: &str, kind: LinkKind| ClassLink {
+            from_class: from.to_string(),
+            to_class: to.to_string(),
+            line_number: 1,
+            kind,
+            position: None,
+        };
+        let links = vec![
+            link("Service", "IRepo", LinkKind::Composition),
+            link("Service", "IRepo", LinkKind::Usage),
+            link("Service", "Repo", LinkKind::Usage),
+            link("Service", "Json", LinkKind::Usage),
+            link("Repo", "IRepo", LinkKind::Inheritance),
+            link("Repo", "Shape", LinkKind::Doc),
+        ];
+        let mut tags = ClassTags::new();
+        tags.entry("IRepo".to_string()).or_default().insert(ClassTag::Interface);
+
+        let coupling = LinkAnalyzer::new().abstraction_coupling(&classes, &links, &tags);
+
+        let service = &coupling["Service"];
+        assert_eq!((service.abstract_links, service.concrete_links), (2, 1));
+        assert!((service.ratio - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(coupling["Repo"].ratio, 1.0);
+        assert_eq!(coupling.len(), 2);
+    }
+}
@@ -0,0 +1,417 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+// Language-native markers on a class declaration, kept as metadata that
+// rules, scripts and reports can filter on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClassTag {
+    // JSDoc/TSDoc `@deprecated`, C# `[Obsolete]`, Python `@deprecated`,
+    // Rust `#[deprecated]`, Java `@Deprecated`, a Go `// Deprecated:` doc
+    // paragraph
+    Deprecated,
+    // JSDoc/TSDoc `@internal`, C# `internal`, Python classes left out of a
+    // module's `__all__`, Rust `pub(crate)` and other restricted visibility,
+    // Java package-private classes, Go types with a lowercase name
+    Internal,
+    // TypeScript/C#/Java `abstract class`, Python classes deriving from `ABC`
+    Abstract,
+    // TypeScript/C#/Java/Go `interface`, Python `Protocol` classes, Rust
+    // `trait`
+    Interface,
+    // TypeScript `export`, C# and Java `public`, Python classes listed in
+    // `__all__`, Rust `pub`, capitalized Go types, JavaScript classes
+    // assigned to `module.exports`; `@internal` wins over `export`
+    Exported,
+    // Defined outside the analyzed root, in a file the project imports
+    External,
+}
+
+impl ClassTag {
+    // Abstractions a class can depend on instead of an implementation
+    pub fn is_abstraction(self) -> bool {
+        matches!(self, ClassTag::Abstract | ClassTag::Interface)
+    }
+}
+
+impl fmt::Display for ClassTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ClassTag::Deprecated => "deprecated",
+            ClassTag::Internal => "internal",
+            ClassTag::Abstract => "abstract",
+            ClassTag::Interface => "interface",
+            ClassTag::Exported => "exported",
+            ClassTag::External => "external",
+        })
+    }
+}
+
+impl std::str::FromStr for ClassTag {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "deprecated" => Ok(ClassTag::Deprecated),
+            "internal" => Ok(ClassTag::Internal),
+            "abstract" => Ok(ClassTag::Abstract),
+            "interface" => Ok(ClassTag::Interface),
+            "exported" => Ok(ClassTag::Exported),
+            "external" => Ok(ClassTag::External),
+            _ => Err(format!(
+                "Unknown class tag: {} (expected deprecated, internal, abstract, interface, exported, external)",
+                s
+            )),
+        }
+    }
+}
+
+pub type ClassTags = HashMap<String, BTreeSet<ClassTag>>;
+
+// Lines directly above a declaration that belong to it: attributes,
+// decorators and doc comments
+fn preamble<'a>(lines: &[&'a str], start: usize) -> Vec<&'a str> {
+    let mut preamble = Vec::new();
+    let mut in_block_comment = false;
+    for line in lines[..start].iter().rev() {
+        let trimmed = line.trim();
+        if trimmed.ends_with("*/") && !trimmed.starts_with("/*") {
+            in_block_comment = true;
+        }
+        let belongs = in_block_comment
+            || trimmed.starts_with("/**")
+            || trimmed.starts_with("//")
+            || trimmed.starts_with('@')
+            || trimmed.starts_with('[')
+            || trimmed.starts_with('#');
+        if !belongs {
+            break;
+        }
+        if trimmed.starts_with("/*") {
+            in_block_comment = false;
+        }
+        preamble.push(*line);
+    }
+    preamble
+}
+
+fn tag(tags: &mut ClassTags, class: &str, tag: ClassTag) {
+    tags.entry(class.to_string()).or_default().insert(tag);
+}
+
+// `abstract` and `interface` keywords ahead of the name on the declaration
+// line, shared by TypeScript, C# and Java
+fn tag_declaration_kind(tags: &mut ClassTags, class: &str, declaration: &str) {
+    let keywords = Regex::new(r"\b(abstract|interface)\s").unwrap();
+    for caps in keywords.captures_iter(declaration) {
+        let kind = if &caps[1] == "abstract" { ClassTag::Abstract } else { ClassTag::Interface };
+        tag(tags, class, kind);
+    }
+}
+
+pub fn typescript_tags(lines: &[&str], starts: &[(String, usize)]) -> ClassTags {
+    let mut tags = ClassTags::new();
+    for (class, start) in starts {
+        let docs: Vec<&str> = preamble(lines, *start)
+            .into_iter()
+            .filter(|line| !line.trim().starts_with('@'))
+            .collect();
+        if docs.iter().any(|line| line.contains("@deprecated")) {
+            tag(&mut tags, class, ClassTag::Deprecated);
+        }
+        let declaration = declaration(lines, *start, class);
+        if docs.iter().any(|line| line.contains("@internal")) {
+            tag(&mut tags, class, ClassTag::Internal);
+        } else if declaration.trim_start().starts_with("export ") {
+            tag(&mut tags, class, ClassTag::Exported);
+        }
+        tag_declaration_kind(&mut tags, class, declaration);
+    }
+    tags
+}
+
+// JSDoc and `export` read as in TypeScript; CommonJS modules export with
+// `module.exports = Order`, `module.exports = { Order }` or
+// `exports.Order = Order`
+pub fn javascript_tags(lines: &[&str], starts: &[(String, usize)]) -> ClassTags {
+    let mut tags = typescript_tags(lines, starts);
+    let exports = Regex::new(r"^\s*(?:module\.exports|exports\.(\w+))\s*=\s*(\{[^}]*\}?|\w+)").unwrap();
+    let mut exported = HashSet::new();
+    for caps in lines.iter().filter_map(|line| exports.captures(line)) {
+        exported.extend(caps.get(1).map(|m| m.as_str().to_string()));
+        let value = caps[2].trim_matches(|c: char| c == '{' || c == '}');
+        for entry in value.split(',') {
+            // `{ Order: PurchaseOrder }` exports the class `PurchaseOrder`
+            let class = entry.rsplit(':').next().unwrap_or_default().trim();
+            exported.insert(class.to_string());
+        }
+    }
+    for (class, _) in starts {
+        let internal = tags.get(class).is_some_and(|tags| tags.contains(&ClassTag::Internal));
+        if exported.contains(class) && !internal {
+            tag(&mut tags, class, ClassTag::Exported);
+        }
+    }
+    tags
+}
+
+pub fn csharp_tags(lines: &[&str], starts: &[(String, usize)]) -> ClassTags {
+    let obsolete = Regex::new(r"\[\s*(?:System\.)?Obsolete(?:Attribute)?\b").unwrap();
+    let internal = Regex::new(r"\binternal\b").unwrap();
+    let public = Regex::new(r"\bpublic\b").unwrap();
+    let mut tags = ClassTags::new();
+    for (class, start) in starts {
+        let attributes: Vec<&str> = preamble(lines, *start)
+            .into_iter()
+            .filter(|line| line.trim().starts_with('['))
+            .collect();
+        if attributes.iter().any(|line| obsolete.is_match(line)) {
+            tag(&mut tags, class, ClassTag::Deprecated);
+        }
+        let declaration = declaration(lines, *start, class);
+        if internal.is_match(declaration) {
+            tag(&mut tags, class, ClassTag::Internal);
+        } else if public.is_match(declaration) {
+            tag(&mut tags, class, ClassTag::Exported);
+        }
+        tag_declaration_kind(&mut tags, class, declaration);
+    }
+    tags
+}
+
+pub fn python_tags(lines: &[&str], starts: &[(String, usize)]) -> ClassTags {
+    let deprecated = Regex::new(r"^\s*@(?:\w+\.)*deprecated\b").unwrap();
+    let bases = Regex::new(r"\(([^)]*)\)").unwrap();
+    let mut tags = ClassTags::new();
+    for (class, start) in starts {
+        if preamble(lines, *start).iter().any(|line| deprecated.is_match(line)) {
+            tag(&mut tags, class, ClassTag::Deprecated);
+        }
+        // `abc.ABC`, `metaclass=ABCMeta`, `typing.Protocol`, `Protocol[T]`
+        let names: Vec<&str> = bases
+            .captures(lines[*start])
+            .map(|caps| caps.get(1).unwrap().as_str())
+            .unwrap_or_default()
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .collect();
+        if names.iter().any(|name| *name == "ABC" || *name == "ABCMeta") {
+            tag(&mut tags, class, ClassTag::Abstract);
+        }
+        if names.contains(&"Protocol") {
+            tag(&mut tags, class, ClassTag::Interface);
+        }
+    }
+    if let Some(exported) = python_all(lines) {
+        // Nested classes are reached through their module-level parent
+        let top_level = |start: usize| !lines[start].starts_with(char::is_whitespace);
+        for (class, _) in starts.iter().filter(|(_, start)| top_level(*start)) {
+            let kind = if exported.contains(class) { ClassTag::Exported } else { ClassTag::Internal };
+            tag(&mut tags, class, kind);
+        }
+    }
+    tags
+}
+
+pub fn rust_tags(lines: &[&str], starts: &[(String, usize)]) -> ClassTags {
+    let deprecated = Regex::new(r"^\s*#\[\s*deprecated\b").unwrap();
+    let visibility = Regex::new(r"\bpub\b(\s*\()?").unwrap();
+    let trait_keyword = Regex::new(r"\btrait\s").unwrap();
+    let mut tags = ClassTags::new();
+    for (class, start) in starts {
+        if preamble(lines, *start).iter().any(|line| deprecated.is_match(line)) {
+            tag(&mut tags, class, ClassTag::Deprecated);
+        }
+        let declaration = declaration(lines, *start, class);
+        // `pub(crate)`, `pub(super)` and `pub(in path)` stay inside the crate
+        match visibility.captures(declaration) {
+            Some(caps) if caps.get(1).is_some() => tag(&mut tags, class, ClassTag::Internal),
+            Some(_) => tag(&mut tags, class, ClassTag::Exported),
+            None => {}
+        }
+        if trait_keyword.is_match(declaration) {
+            tag(&mut tags, class, ClassTag::Interface);
+        }
+    }
+    tags
+}
+
+pub fn java_tags(lines: &[&str], starts: &[(String, usize)]) -> ClassTags {
+    // The annotation, or the Javadoc tag in the comment above
+    let deprecated = Regex::new(r"@(?:java\.lang\.)?[Dd]eprecated\b").unwrap();
+    let access = Regex::new(r"\b(public|protected|private)\b").unwrap();
+    let mut tags = ClassTags::new();
+    for (class, start) in starts {
+        let declaration = declaration(lines, *start, class);
+        if preamble(lines, *start).iter().chain([&declaration]).any(|line| deprecated.is_match(line)) {
+            tag(&mut tags, class, ClassTag::Deprecated);
+        }
+        // Without an access modifier a class is only visible in its package
+        match access.captures(declaration).as_ref().map(|caps| &caps[1]) {
+            Some("public") => tag(&mut tags, class, ClassTag::Exported),
+            None => tag(&mut tags, class, ClassTag::Internal),
+            Some(_) => {}
+        }
+        tag_declaration_kind(&mut tags, class, declaration);
+    }
+    tags
+}
+
+pub fn go_tags(lines: &[&str], starts: &[(String, usize)]) -> ClassTags {
+    let deprecated = Regex::new(r"^\s*//\s*Deprecated:").unwrap();
+    let interface = Regex::new(r"\binterface\b").unwrap();
+    let mut tags = ClassTags::new();
+    for (class, start) in starts {
+        if preamble(lines, *start).iter().any(|line| deprecated.is_match(line)) {
+            tag(&mut tags, class, ClassTag::Deprecated);
+        }
+        // Go exports by case alone
+        let kind = if class.starts_with(char::is_uppercase) { ClassTag::Exported } else { ClassTag::Internal };
+        tag(&mut tags, class, kind);
+        if interface.is_match(lines[*start]) {
+            tag(&mut tags, class, ClassTag::Interface);
+        }
+    }
+    tags
+}
+
+// The declaration line up to the class name, where the modifiers are
+fn declaration<'a>(lines: &[&'a str], start: usize, class: &str) -> &'a str {
+    let line = lines[start];
+    line.find(&format!(" {}", class)).map_or(line, |end| &line[..end + 1])
+}
+
+// Names listed in a module-level `__all__ = [...]` (or tuple), which may
+// span several lines
+fn python_all(lines: &[&str]) -> Option<HashSet<String>> {
+    let start = lines.iter().position(|line| line.starts_with("__all__"))?;
+    let mut text = String::new();
+    for line in &lines[start..] {
+        text.push_str(line);
+        if line.contains(']') || line.contains(')') {
+            break;
+        }
+    }
+    let name = Regex::new(r#"["'](\w+)["']"#).unwrap();
+    Some(name.captures_iter(&text).map(|caps| caps[1].to_string()).collect())
+}
+
+// `// classlink: max-deps=5` (or `# ...` in Python) above a declaration or
+// trailing it on the same line
+pub fn dependency_budgets(lines: &[&str], starts: &[(String, usize)]) -> HashMap<String, usize> {
+    let marker = Regex::new(r"classlink:\s*max-deps\s*=\s*(\d+)").unwrap();
+    let mut budgets = HashMap::new();
+    for (class, start) in starts {
+        let declared = std::iter::once(lines[*start])
+            .chain(preamble(lines, *start))
+            .find_map(|line| marker.captures(line).and_then(|caps| caps[1].parse().ok()));
+        if let Some(max) = declared {
+            budgets.insert(class.clone(), max);
+        }
+    }
+    budgets
+}
+
+// A class declared twice keeps the tighter budget
+pub fn merge_budgets(into: &mut HashMap<String, usize>, from: HashMap<String, usize>) {
+    for (class, max) in from {
+        into.entry(class).and_modify(|existing| *existing = (*existing).min(max)).or_insert(max);
+    }
+}
+
+pub fn merge_tags(into: &mut ClassTags, from: ClassTags) {
+    for (class, tags) in from {
+        into.entry(class).or_default().extend(tags);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn starts(lines: &[&str], pattern: &str) -> Vec<(String, usize)> {
+        let regex = Regex::new(pattern).unwrap();
+        lines
+            .iter()
+            .enumerate()
+            .filter_map(|(i, line)| regex.captures(line).map(|caps| (caps[1].to_string(), i)))
+            .collect()
+    }
+
+    fn tags_of(tags: &ClassTags, class: &str) -> Vec<ClassTag> {
+        tags.get(class).map(|t| t.iter().copied().collect()).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_typescript_jsdoc_tags() {
+        let source = "/**\n * Old client.\n * @deprecated use ApiClient\n */\n@Injectable()\nexport class LegacyClient {}\n\n/** @internal */\nclass Helper {}\n\nexport class ApiClient {}\n";
+        let lines: Vec<&str> = source.lines().collect();
+        let tags = typescript_tags(&lines, &starts(&lines, r"class\s+(\w+)"));
+
+        assert_eq!(tags_of(&tags, "LegacyClient"), vec![ClassTag::Deprecated, ClassTag::Exported]);
+        assert_eq!(tags_of(&tags, "Helper"), vec![ClassTag::Internal]);
+        assert_eq!(tags_of(&tags, "ApiClient"), vec![ClassTag::Exported]);
+    }
+
+    #[test]
+    fn test_csharp_attributes_and_modifiers() {
+        let source = "/// <summary>Old</summary>\n[Serializable]\n[Obsolete(\"Use NewRepo\")]\npublic class OldRepo\n{\n}\n\ninternal sealed class Cache\n{\n}\n\npublic class NewRepo\n{\n}\n";
+        let lines: Vec<&str> = source.lines().collect();
+        let tags = csharp_tags(&lines, &starts(&lines, r"class\s+(\w+)"));
+
+        assert_eq!(tags_of(&tags, "OldRepo"), vec![ClassTag::Deprecated, ClassTag::Exported]);
+        assert_eq!(tags_of(&tags, "Cache"), vec![ClassTag::Internal]);
+        assert_eq!(tags_of(&tags, "NewRepo"), vec![ClassTag::Exported]);
+    }
+
+    #[test]
+    fn test_python_decorators_and_all() {
+        let source = "__all__ = [\n    \"Public\",\n    \"Old\",\n]\n\n@warnings.deprecated(\"gone\")\nclass Old:\n    pass\n\nclass Public:\n    pass\n\nclass _Helper:\n    pass\n";
+        let lines: Vec<&str> = source.lines().collect();
+        let tags = python_tags(&lines, &starts(&lines, r"^class\s+(\w+)"));
+
+        assert_eq!(tags_of(&tags, "Old"), vec![ClassTag::Deprecated, ClassTag::Exported]);
+        assert_eq!(tags_of(&tags, "Public"), vec![ClassTag::Exported]);
+        assert_eq!(tags_of(&tags, "_Helper"), vec![ClassTag::Internal]);
+
+        let no_all: Vec<&str> = vec!["class Anything:", "    pass"];
+        assert!(python_tags(&no_all, &starts(&no_all, r"^class\s+(\w+)")).is_empty());
+    }
+
+    #[test]
+    fn test_abstract_and_interface_declarations() {
+        let typescript: Vec<&str> = vec!["export abstract class Shape {}", "export interface Drawable {}", "class Circle extends Shape {}"];
+        let tags = typescript_tags(&typescript, &starts(&typescript, r"(?:class|interface)\s+(\w+)"));
+        assert_eq!(tags_of(&tags, "Shape"), vec![ClassTag::Abstract, ClassTag::Exported]);
+        assert_eq!(tags_of(&tags, "Drawable"), vec![ClassTag::Interface, ClassTag::Exported]);
+        assert!(tags_of(&tags, "Circle").is_empty());
+
+        let csharp: Vec<&str> = vec!["public abstract class RepoBase", "internal interface IRepo", "public sealed class Repo : RepoBase, IRepo"];
+        let tags = csharp_tags(&csharp, &starts(&csharp, r"(?:class|interface)\s+(\w+)"));
+        assert_eq!(tags_of(&tags, "RepoBase"), vec![ClassTag::Abstract, ClassTag::Exported]);
+        assert_eq!(tags_of(&tags, "IRepo"), vec![ClassTag::Internal, ClassTag::Interface]);
+        assert_eq!(tags_of(&tags, "Repo"), vec![ClassTag::Exported]);
+
+        let python: Vec<&str> = vec!["class Store(abc.ABC):", "class Reader(typing.Protocol):", "class Meta(metaclass=ABCMeta):", "class Impl(Store):"];
+        let tags = python_tags(&python, &starts(&python, r"^class\s+(\w+)"));
+        assert_eq!(tags_of(&tags, "Store"), vec![ClassTag::Abstract]);
+        assert_eq!(tags_of(&tags, "Reader"), vec![ClassTag::Interface]);
+        assert_eq!(tags_of(&tags, "Meta"), vec![ClassTag::Abstract]);
+        assert!(tags_of(&tags, "Impl").is_empty());
+    }
+
+    #[test]
+    fn test_dependency_budget_markers() {
+        let source = "// classlink: max-deps=5\nexport class Shop {}\nclass Cart { // classlink: max-deps = 2\n}\nclass Free {}\n";
+        let lines: Vec<&str> = source.lines().collect();
+        let budgets = dependency_budgets(&lines, &starts(&lines, r"class\s+(\w+)"));
+
+        assert_eq!(budgets.get("Shop"), Some(&5));
+        assert_eq!(budgets.get("Cart"), Some(&2));
+        assert_eq!(budgets.get("Free"), None);
+
+        let python: Vec<&str> = vec!["# classlink: max-deps=3", "@dataclass", "class Order:", "    pass"];
+        assert_eq!(dependency_budgets(&python, &starts(&python, r"^class\s+(\w+)")).get("Order"), Some(&3));
+    }
+}
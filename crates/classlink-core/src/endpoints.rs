@@ -0,0 +1,72 @@
+// HTTP endpoints as graph nodes. Route declarations bind an endpoint to the
+// class serving it and client calls use it, so a frontend class reaches the
+// backend controller through the endpoint even across languages. Endpoints
+// are named by their normalized path, which is what both sides must agree
+// on: `/users/{id}`, `/users/:id`, `/users/<int:id>` and `/users/${id}` are
+// all `/users/{}`.
+
+pub fn is_endpoint(name: &str) -> bool {
+    name.starts_with('/')
+}
+
+fn is_parameter(segment: &str) -> bool {
+    segment.starts_with(':')
+        || (segment.starts_with('{') && segment.ends_with('}'))
+        || (segment.starts_with("${") && segment.ends_with('}'))
+        || (segment.starts_with('<') && segment.ends_with('>'))
+}
+
+// Normalizes a route template or a URL. URLs keep only their path: the
+// scheme and host, the query string and the fragment are dropped. Path
+// segments compare case-insensitively.
+pub fn normalize(route: &str) -> String {
+    let mut path = route.trim();
+    if let Some((_, rest)) = path.split_once("://") {
+        path = rest.find('/').map_or("", |slash| &rest[slash..]);
+    }
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != "~")
+        .map(|segment| if is_parameter(segment) { "{}".to_string() } else { segment.to_lowercase() })
+        .collect();
+    format!("/{}", segments.join("/"))
+}
+
+// A client URL only counts when it is clearly a path on some server. A
+// leading base-URL variable, `${API_URL}` or an f-string's `{BASE}`, is
+// dropped.
+pub fn client_endpoint(url: &str) -> Option<String> {
+    let mut url = url.trim();
+    if url.starts_with("${") || url.starts_with('{') {
+        url = url.find('}').map_or("", |end| &url[end + 1..]);
+    }
+    (url.starts_with('/') || url.contains("://")).then(|| normalize(url))
+}
+
+// A method route under its class prefix, like `@Controller('users')` and
+// `@Get(':id')`. ASP.NET's `[controller]` token is the class name without
+// its `Controller` suffix.
+pub fn join(prefix: &str, route: &str, class: &str) -> String {
+    let controller = class.strip_suffix("Controller").unwrap_or(class).to_lowercase();
+    normalize(&format!("{}/{}", prefix, route).replace("[controller]", &controller))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routes_and_urls_normalize_alike() {
+        assert_eq!(normalize("/users/{id}"), "/users/{}");
+        assert_eq!(normalize("users/:id/"), "/users/{}");
+        assert_eq!(normalize("/Users/<int:id>"), "/users/{}");
+        assert_eq!(client_endpoint("${API_URL}/users/${user.id}?full=1"), Some("/users/{}".to_string()));
+        assert_eq!(client_endpoint("https://api.example.com/users"), Some("/users".to_string()));
+        assert_eq!(client_endpoint("{BASE}/users"), Some("/users".to_string()));
+        assert_eq!(client_endpoint("users"), None);
+        assert_eq!(join("api/[controller]", "{id}", "UsersController"), "/api/users/{}");
+        assert_eq!(join("users", "/profile", "UsersController"), "/users/profile");
+        assert_eq!(join("", "", "Home"), "/");
+    }
+}
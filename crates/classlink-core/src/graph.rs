@@ -0,0 +1,337 @@
+use crate::link::{ClassLink, LinkKind};
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+
+#[derive(Debug, Clone, Default)]
+pub struct ClassGraph {
+    outgoing: BTreeMap<String, BTreeSet<String>>,
+    incoming: BTreeMap<String, BTreeSet<String>>,
+    // Edges made only of bindings. They point back along the implementation's
+    // own `implements` link, or the handler's use of its event, so cycle
+    // detection skips them rather than report every binding as a cycle.
+    bindings: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ClassGraph {
+    pub fn new(classes: &HashSet<String>, links: &[ClassLink]) -> Self {
+        let mut graph = Self::default();
+
+        for class in classes {
+            graph.add_node(class);
+        }
+
+        let mut other = BTreeSet::new();
+        for link in links {
+            graph.add_edge(&link.from_class, &link.to_class);
+            if link.kind != LinkKind::Binding {
+                other.insert((link.from_class.as_str(), link.to_class.as_str()));
+            }
+        }
+        for link in links.iter().filter(|link| link.kind == LinkKind::Binding) {
+            if !other.contains(&(link.from_class.as_str(), link.to_class.as_str())) {
+                graph.bindings.entry(link.from_class.clone()).or_default().insert(link.to_class.clone());
+            }
+        }
+
+        graph
+    }
+
+    fn add_node(&mut self, class: &str) {
+        self.outgoing.entry(class.to_string()).or_default();
+        self.incoming.entry(class.to_string()).or_default();
+    }
+
+    fn add_edge(&mut self, from: &str, to: &str) {
+        self.add_node(from);
+        self.add_node(to);
+        self.outgoing.get_mut(from).unwrap().insert(to.to_string());
+        self.incoming.get_mut(to).unwrap().insert(from.to_string());
+    }
+
+    pub fn contains(&self, class: &str) -> bool {
+        self.outgoing.contains_key(class)
+    }
+
+    pub fn classes(&self) -> impl Iterator<Item = &String> {
+        self.outgoing.keys()
+    }
+
+    pub fn dependencies(&self, class: &str) -> Vec<String> {
+        self.outgoing
+            .get(class)
+            .map(|deps| deps.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn dependents(&self, class: &str) -> Vec<String> {
+        self.incoming
+            .get(class)
+            .map(|deps| deps.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    // Breadth-first walk over reverse edges: level 0 holds the direct
+    // dependents of `seeds`, level 1 their dependents, and so on
+    pub fn dependents_by_depth(&self, seeds: &[String]) -> Vec<Vec<String>> {
+        let mut seen: BTreeSet<&str> = seeds.iter().map(String::as_str).collect();
+        let mut frontier: Vec<&str> = seen.iter().copied().collect();
+        let mut levels = Vec::new();
+
+        while !frontier.is_empty() {
+            let mut next = BTreeSet::new();
+            for class in frontier {
+                for dependent in self.incoming.get(class).into_iter().flatten() {
+                    if seen.insert(dependent) {
+                        next.insert(dependent.as_str());
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            levels.push(next.iter().map(|c| c.to_string()).collect());
+            frontier = next.into_iter().collect();
+        }
+
+        levels
+    }
+
+    // Every class within `depth` links of `center`, following edges in both
+    // directions. Empty when `center` is not in the graph.
+    pub fn neighbourhood(&self, center: &str, depth: usize) -> BTreeSet<String> {
+        let mut seen = BTreeSet::new();
+        if !self.contains(center) {
+            return seen;
+        }
+        seen.insert(center.to_string());
+        let mut frontier = vec![center.to_string()];
+
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for class in &frontier {
+                let neighbours = self.outgoing[class].iter().chain(&self.incoming[class]);
+                for neighbour in neighbours {
+                    if seen.insert(neighbour.clone()) {
+                        next.push(neighbour.clone());
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+        seen
+    }
+
+    pub fn isolated_classes(&self) -> Vec<String> {
+        self.outgoing
+            .iter()
+            .filter(|(class, deps)| deps.is_empty() && self.incoming[*class].is_empty())
+            .map(|(class, _)| class.clone())
+            .collect()
+    }
+
+    // Tarjan's strongly connected components. Every component with more than
+    // one class, or a class linking to itself, is reported as a cycle.
+    pub fn find_cycles(&self) -> Vec<Vec<String>> {
+        let mut state = TarjanState::default();
+
+        for class in self.outgoing.keys() {
+            if !state.indices.contains_key(class.as_str()) {
+                self.strong_connect(class, &mut state);
+            }
+        }
+
+        let mut cycles: Vec<Vec<String>> = state
+            .components
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1 || self.outgoing[&component[0]].contains(&component[0])
+            })
+            .map(|mut component| {
+                component.sort();
+                component
+            })
+            .collect();
+        cycles.sort();
+        cycles
+    }
+
+    // One concrete cycle through a component of `find_cycles`: the shortest
+    // way from its first class back to itself inside the component, with the
+    // first class at both ends, e.g. `[A, B, C, A]`
+    pub fn cycle_path(&self, component: &[String]) -> Vec<String> {
+        let Some(start) = component.first().map(String::as_str) else {
+            return Vec::new();
+        };
+        let members: BTreeSet<&str> = component.iter().map(String::as_str).collect();
+        let mut previous: BTreeMap<&str, &str> = BTreeMap::new();
+        let mut frontier = VecDeque::from([start]);
+        while let Some(class) = frontier.pop_front() {
+            let bindings = self.bindings.get(class);
+            for dependency in self.outgoing.get(class).into_iter().flatten() {
+                let dependency = dependency.as_str();
+                if !members.contains(dependency) || bindings.is_some_and(|bound| bound.contains(dependency)) {
+                    continue;
+                }
+                if dependency == start {
+                    let mut path = vec![start.to_string()];
+                    let mut at = class;
+                    while at != start {
+                        path.push(at.to_string());
+                        at = previous[at];
+                    }
+                    path.push(start.to_string());
+                    path.reverse();
+                    return path;
+                }
+                if !previous.contains_key(dependency) {
+                    previous.insert(dependency, class);
+                    frontier.push_back(dependency);
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    fn strong_connect<'a>(&'a self, class: &'a str, state: &mut TarjanState<'a>) {
+        state.indices.insert(class, state.next_index);
+        state.low_links.insert(class, state.next_index);
+        state.next_index += 1;
+        state.stack.push(class);
+        state.on_stack.insert(class);
+
+        let bindings = self.bindings.get(class);
+        for dependency in &self.outgoing[class] {
+            if bindings.is_some_and(|bound| bound.contains(dependency)) {
+                continue;
+            }
+            let dependency = dependency.as_str();
+            if !state.indices.contains_key(dependency) {
+                self.strong_connect(dependency, state);
+                let low = state.low_links[class].min(state.low_links[dependency]);
+                state.low_links.insert(class, low);
+            } else if state.on_stack.contains(dependency) {
+                let low = state.low_links[class].min(state.indices[dependency]);
+                state.low_links.insert(class, low);
+            }
+        }
+
+        if state.low_links[class] == state.indices[class] {
+            let mut component = Vec::new();
+            while let Some(member) = state.stack.pop() {
+                state.on_stack.remove(member);
+                component.push(member.to_string());
+                if member == class {
+                    break;
+                }
+            }
+            state.components.push(component);
+        }
+    }
+}
+
+#[derive(Default)]
+struct TarjanState<'a> {
+    next_index: usize,
+    indices: BTreeMap<&'a str, usize>,
+    low_links: BTreeMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: BTreeSet<&'a str>,
+    components: Vec<Vec<String>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(from: &str, to: &str) -> ClassLink {
+        ClassLink {
+            from_class: from.to_string(),
+            to_class: to.to_string(),
+            line_number: 1,
+            kind: Default::default(),
+            position: None,
+        }
+    }
+
+    fn classes(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_dependencies_and_dependents() {
+        let graph = ClassGraph::new(
+            &classes(&["A", "B", "C"]),
+            &[link("A", "B"), link("A", "C"), link("C", "B")],
+        );
+
+        assert_eq!(graph.dependencies("A"), vec!["B", "C"]);
+        assert_eq!(graph.dependents("B"), vec!["A", "C"]);
+        assert!(graph.dependencies("Unknown").is_empty());
+    }
+
+    #[test]
+    fn test_find_cycles() {
+        let graph = ClassGraph::new(
+            &classes(&["A", "B", "C", "D", "E"]),
+            &[
+                link("A", "B"),
+                link("B", "C"),
+                link("C", "A"),
+                link("C", "D"),
+                link("E", "E"),
+            ],
+        );
+
+        let cycles = graph.find_cycles();
+
+        assert_eq!(cycles.len(), 2);
+        assert_eq!(cycles[0], vec!["A", "B", "C"]);
+        assert_eq!(cycles[1], vec!["E"]);
+    }
+
+    #[test]
+    fn test_dependents_by_depth() {
+        let graph = ClassGraph::new(
+            &classes(&["Api", "Service", "Repo", "Cli"]),
+            &[link("Api", "Service"), link("Cli", "Service"), link("Service", "Repo"), link("Repo", "Service")],
+        );
+
+        let levels = graph.dependents_by_depth(&["Repo".to_string()]);
+
+        assert_eq!(levels, vec![vec!["Service"], vec!["Api", "Cli"]]);
+    }
+
+    #[test]
+    fn test_isolated_classes() {
+        let graph = ClassGraph::new(&classes(&["A", "B", "Lonely"]), &[link("A", "B")]);
+
+        assert_eq!(graph.isolated_classes(), vec!["Lonely"]);
+    }
+
+    #[test]
+    fn test_neighbourhood_follows_both_directions() {
+        let graph = ClassGraph::new(
+            &classes(&["A", "B", "C", "D"]),
+            &[link("A", "B"), link("C", "B"), link("D", "C")],
+        );
+
+        assert_eq!(graph.neighbourhood("B", 1), ["A", "B", "C"].iter().map(|s| s.to_string()).collect());
+        assert!(graph.neighbourhood("B", 2).contains("D"));
+        assert_eq!(graph.neighbourhood("B", 0).len(), 1);
+        assert!(graph.neighbourhood("Missing", 3).is_empty());
+    }
+
+    #[test]
+    fn test_bindings_do_not_form_cycles() {
+        let binding = ClassLink {
+            kind: LinkKind::Binding,
+            ..link("IRepo", "SqlRepo")
+        };
+        let graph = ClassGraph::new(&classes(&["IRepo", "SqlRepo", "Service"]), &[link("SqlRepo", "IRepo"), binding, link("Service", "IRepo")]);
+
+        assert!(graph.find_cycles().is_empty());
+        assert_eq!(graph.dependents_by_depth(&["SqlRepo".to_string()]), vec![vec!["IRepo"], vec!["Service"]]);
+    }
+}
@@ -0,0 +1,20 @@
+// What every parser shares: the link model, the analyzer trait and the
+// parser and metric registries, plus the graph and statistics built from
+// the links.
+// Language parsers live in their own `classlink-parser-*` crates.
+pub mod link;
+pub mod position;
+pub mod analysis;
+pub mod annotations;
+pub mod metrics;
+pub mod endpoints;
+pub mod graph;
+pub mod analyzer;
+pub mod registry;
+pub mod preset;
+
+pub use link::{ClassLink, LinkKind};
+pub use position::{ColumnEncoding, SourcePosition};
+pub use analysis::{AnalysisResult, FileAnalyzer};
+pub use registry::{LanguageParser, Metric, MetricRegistry, ParseError, ParserRegistry};
+pub use preset::ExtractionPreset;
@@ -0,0 +1,73 @@
+use crate::position::SourcePosition;
+use serde::{Deserialize, Serialize};
+
+// How one class depends on another, roughly from most to least costly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    Inheritance,
+    Composition,
+    #[default]
+    Usage,
+    // Runtime wiring the code never spells out: from an interface to the
+    // implementation a container registers for it, or from an event to
+    // the handlers subscribed to it
+    Binding,
+    Doc,
+}
+
+impl std::str::FromStr for LinkKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "inheritance" => Ok(LinkKind::Inheritance),
+            "composition" => Ok(LinkKind::Composition),
+            "usage" => Ok(LinkKind::Usage),
+            "binding" => Ok(LinkKind::Binding),
+            "doc" => Ok(LinkKind::Doc),
+            _ => Err(format!(
+                "Unknown link kind: {} (expected inheritance, composition, usage, binding, doc)",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for LinkKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LinkKind::Inheritance => "inheritance",
+            LinkKind::Composition => "composition",
+            LinkKind::Usage => "usage",
+            LinkKind::Binding => "binding",
+            LinkKind::Doc => "doc",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassLink {
+    pub from_class: String,
+    pub to_class: String,
+    pub line_number: usize,
+    pub kind: LinkKind,
+    // Where the target is referenced; on line `line_number`, filled in
+    // after parsing, see `position::locate_links`
+    pub position: Option<SourcePosition>,
+}
+
+// `user-service`, `user_service`, `user.service` and `user service` become
+// `UserService`
+pub fn pascal_case(slug: &str) -> String {
+    slug.split(|c: char| c == '-' || c == '_' || c == '.' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Rough, regex-level size of a class. Good enough to tell a 40-line value
+// object from a 900-line god class; not a substitute for a real parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ClassMetrics {
+    pub start_line: usize,
+    pub end_line: usize,
+    pub lines: usize,
+    pub methods: usize,
+}
+
+impl ClassMetrics {
+    // `start` and `end` are 0-based line indices into `lines`
+    pub fn measure(lines: &[&str], start: usize, end: usize, is_method: impl Fn(&str) -> bool) -> Self {
+        let end = end.max(start).min(lines.len().saturating_sub(1));
+        let methods = lines
+            .get(start + 1..=end)
+            .unwrap_or_default()
+            .iter()
+            .filter(|line| is_method(line))
+            .count();
+
+        Self {
+            start_line: start + 1,
+            end_line: end + 1,
+            lines: end - start + 1,
+            methods,
+        }
+    }
+
+    // Partial classes (C#) or the same name in several files add up
+    pub fn combine(&mut self, other: &ClassMetrics) {
+        self.start_line = self.start_line.min(other.start_line);
+        self.end_line = self.end_line.max(other.end_line);
+        self.lines += other.lines;
+        self.methods += other.methods;
+    }
+}
+
+// Last line of a `{ ... }` block opened on or after `start`. Braces inside
+// `//` comments and string literals are ignored.
+pub fn brace_block_end(lines: &[&str], start: usize) -> usize {
+    let mut depth = 0usize;
+    let mut opened = false;
+
+    for (index, line) in lines.iter().enumerate().skip(start) {
+        let mut quote = None;
+        let mut previous = '\0';
+        for c in line.chars() {
+            match quote {
+                Some(q) if c == q && previous != '\\' => quote = None,
+                Some(_) => {}
+                None => match c {
+                    '"' | '\'' | '`' => quote = Some(c),
+                    '/' if previous == '/' => break,
+                    '{' => {
+                        depth += 1;
+                        opened = true;
+                    }
+                    '}' => depth = depth.saturating_sub(1),
+                    _ => {}
+                },
+            }
+            previous = c;
+        }
+        if opened && depth == 0 {
+            return index;
+        }
+    }
+    lines.len().saturating_sub(1)
+}
+
+// Last line of a Python-style block: everything indented deeper than the
+// header line, ignoring blank lines and comments
+pub fn indented_block_end(lines: &[&str], start: usize) -> usize {
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let header_indent = lines.get(start).map(|line| indent(line)).unwrap_or(0);
+    let mut end = start;
+
+    for (index, line) in lines.iter().enumerate().skip(start + 1) {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if indent(line) <= header_indent {
+            break;
+        }
+        end = index;
+    }
+    end
+}
+
+// Measures every `(class, 0-based start line)` found by a parser. A class
+// declared twice in one file (nested or redeclared) adds up.
+pub fn measure_classes(
+    lines: &[&str],
+    starts: Vec<(String, usize)>,
+    block_end: fn(&[&str], usize) -> usize,
+    is_method: impl Fn(&str) -> bool,
+) -> HashMap<String, ClassMetrics> {
+    let mut metrics: HashMap<String, ClassMetrics> = HashMap::new();
+    for (class, start) in starts {
+        let size = ClassMetrics::measure(lines, start, block_end(lines, start), &is_method);
+        metrics
+            .entry(class)
+            .and_modify(|existing| existing.combine(&size))
+            .or_insert(size);
+    }
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_ends() {
+        let braces: Vec<&str> = "class A {\n  m() { return \"}\"; }\n  // }\n}\nclass B {}\n".lines().collect();
+        assert_eq!(brace_block_end(&braces, 0), 3);
+        assert_eq!(brace_block_end(&braces, 4), 4);
+
+        let indented: Vec<&str> = "class A:\n    def m(self):\n\n        pass\nclass B:\n    pass\n".lines().collect();
+        assert_eq!(indented_block_end(&indented, 0), 3);
+    }
+}
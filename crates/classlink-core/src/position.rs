@@ -0,0 +1,152 @@
+use crate::link::ClassLink;
+use serde::{Deserialize, Serialize};
+
+// How columns are counted. Editors disagree: LSP and SARIF count UTF-16
+// code units, Vim counts bytes, others count characters. They only differ
+// on lines with non-ASCII text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ColumnEncoding {
+    #[serde(rename = "utf-8")]
+    Utf8,
+    #[default]
+    #[serde(rename = "utf-16")]
+    Utf16,
+    // Unicode scalar values, i.e. characters
+    #[serde(rename = "utf-32")]
+    Utf32,
+}
+
+impl std::str::FromStr for ColumnEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "utf-8" | "utf8" => Ok(ColumnEncoding::Utf8),
+            "utf-16" | "utf16" => Ok(ColumnEncoding::Utf16),
+            "utf-32" | "utf32" => Ok(ColumnEncoding::Utf32),
+            _ => Err(format!("Unknown column encoding: {} (expected utf-8, utf-16, utf-32)", s)),
+        }
+    }
+}
+
+impl ColumnEncoding {
+    fn width(self, c: char) -> usize {
+        match self {
+            ColumnEncoding::Utf8 => c.len_utf8(),
+            ColumnEncoding::Utf16 => c.len_utf16(),
+            ColumnEncoding::Utf32 => 1,
+        }
+    }
+}
+
+// A place in a source file. `line` and `column` are 1-based, `column` in
+// the encoding it was computed with; `byte_offset` is 0-based into the
+// UTF-8 text that was analyzed, so for transcoded UTF-16 files it points
+// into the decoded text, not the file's bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub struct SourcePosition {
+    pub line: usize,
+    pub column: usize,
+    pub byte_offset: usize,
+}
+
+// Byte offsets of line starts, to turn offsets into positions and back
+pub struct LineIndex<'a> {
+    content: &'a str,
+    starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(content: &'a str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self { content, starts }
+    }
+
+    // Text of a 1-based line, without its line break
+    pub fn line(&self, line: usize) -> Option<&'a str> {
+        let start = *self.starts.get(line.checked_sub(1)?)?;
+        let end = self.starts.get(line).map_or(self.content.len(), |next| next - 1);
+        Some(self.content[start..end].strip_suffix('\r').unwrap_or(&self.content[start..end]))
+    }
+
+    // An offset inside a multi-byte character counts as that character
+    pub fn position(&self, byte_offset: usize, encoding: ColumnEncoding) -> SourcePosition {
+        let byte_offset = byte_offset.min(self.content.len());
+        let line = self.starts.partition_point(|start| *start <= byte_offset);
+        let start = self.starts[line - 1];
+        let column = self.content[start..]
+            .char_indices()
+            .take_while(|(index, _)| start + index < byte_offset)
+            .map(|(_, c)| encoding.width(c))
+            .sum::<usize>()
+            + 1;
+        SourcePosition {
+            line,
+            column,
+            byte_offset,
+        }
+    }
+
+    // Where `to_class` is named on the link's line, as a whole word; links
+    // whose target is not spelled out there (slugs, normalized endpoints)
+    // point at the line's first non-blank character
+    pub fn locate(&self, link: &ClassLink, encoding: ColumnEncoding) -> Option<SourcePosition> {
+        let text = self.line(link.line_number)?;
+        let start = self.starts[link.line_number - 1];
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
+        let found = text.match_indices(link.to_class.as_str()).map(|(index, _)| index).find(|&index| {
+            let before = text[..index].chars().next_back();
+            let after = text[index + link.to_class.len()..].chars().next();
+            !before.is_some_and(is_word) && !after.is_some_and(is_word)
+        });
+        let column = found.unwrap_or_else(|| text.len() - text.trim_start().len());
+        Some(self.position(start + column, encoding))
+    }
+}
+
+// Fills in the position of every link that lacks one
+pub fn locate_links(content: &str, links: &mut [ClassLink], encoding: ColumnEncoding) {
+    let index = LineIndex::new(content);
+    for link in links.iter_mut().filter(|link| link.position.is_none()) {
+        link.position = index.locate(link, encoding);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(to: &str, line: usize) -> ClassLink {
+        ClassLink {
+            from_class: "Shop".to_string(),
+            to_class: to.to_string(),
+            line_number: line,
+            kind: Default::default(),
+            position: None,
+        }
+    }
+
+    #[test]
+    fn test_columns_count_in_the_chosen_encoding() {
+        let content = "class Shop:\r\n    名前 = \"🛒\"; self.cart = Cart()\n  /users\n";
+        let index = LineIndex::new(content);
+
+        assert_eq!(index.line(1), Some("class Shop:"));
+        let cart = link("Cart", 2);
+        let utf8 = index.locate(&cart, ColumnEncoding::Utf8).unwrap();
+        assert_eq!((utf8.line, utf8.column), (2, 34));
+        assert_eq!(&content[utf8.byte_offset..utf8.byte_offset + 4], "Cart");
+        assert_eq!(index.locate(&cart, ColumnEncoding::Utf16).unwrap().column, 28);
+        assert_eq!(index.locate(&cart, ColumnEncoding::Utf32).unwrap().column, 27);
+
+        let mut links = vec![link("Car", 2), link("/users", 3), link("Missing", 9)];
+        locate_links(content, &mut links, ColumnEncoding::Utf32);
+        // `Car` only occurs inside `Cart`, so the line start is used
+        assert_eq!(links[0].position.map(|p| p.column), Some(5));
+        assert_eq!(links[1].position.map(|p| (p.line, p.column)), Some((3, 3)));
+        assert_eq!(links[2].position, None);
+        assert_eq!("utf-16".parse::<ColumnEncoding>(), Ok(ColumnEncoding::Utf16));
+    }
+}
@@ -0,0 +1,195 @@
+use crate::analysis::{AnalysisResult, FileAnalyzer};
+use crate::graph::ClassGraph;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+// A parser for one language. This is the surface a parser crate builds
+// against: `classlink-parser-elixir` depends on `classlink-core` alone,
+// implements this trait, and the checker registers it behind a feature.
+pub trait LanguageParser: FileAnalyzer + Send + Sync {
+    // Short lowercase name, e.g. `elixir`
+    fn language(&self) -> &str;
+    // Extensions handled, without the dot
+    fn extensions(&self) -> Vec<String>;
+    // Parsers that can fail on a file, like AST backends on syntax they
+    // do not support, return the error here. The built-in regex parser for
+    // the file's language then takes over.
+    fn try_parse(&self, content: &str, file_path: &str) -> Result<AnalysisResult, ParseError> {
+        Ok(self.analyze(content, file_path))
+    }
+}
+
+// Where and why a parser gave up on a file; lines and columns are 1-based
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+// Parsers registered on top of the built-in ones. The first parser
+// handling an extension wins, and registered parsers take over files the
+// built-in ones would handle.
+#[derive(Clone, Default)]
+pub struct ParserRegistry {
+    parsers: Vec<Arc<dyn LanguageParser>>,
+}
+
+impl ParserRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(mut self, parser: impl LanguageParser + 'static) -> Self {
+        self.parsers.push(Arc::new(parser));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parsers.is_empty()
+    }
+
+    pub fn languages(&self) -> Vec<&str> {
+        self.parsers.iter().map(|parser| parser.language()).collect()
+    }
+
+    pub fn find(&self, path: &str) -> Option<&dyn LanguageParser> {
+        let extension = Path::new(path).extension()?.to_str()?;
+        self.parsers
+            .iter()
+            .find(|parser| parser.extensions().iter().any(|handled| handled == extension))
+            .map(|parser| parser.as_ref())
+    }
+
+    // Globs for the walker, so files of registered languages are found
+    pub fn file_patterns(&self) -> Vec<String> {
+        self.parsers
+            .iter()
+            .flat_map(|parser| parser.extensions())
+            .map(|extension| format!("*.{}", extension))
+            .collect()
+    }
+}
+
+// A per-class number computed over the whole graph, e.g. the distance of
+// each class from a team's core domain package. Registered metrics are
+// reported next to the built-in statistics in every output.
+pub trait Metric: Send + Sync {
+    // Name in reports, e.g. `domain_distance`
+    fn name(&self) -> &str;
+    // Value per class; classes without a value are left out
+    fn compute(&self, graph: &ClassGraph) -> BTreeMap<String, f64>;
+}
+
+// Metrics computed on every run. Names should be unique; a later metric
+// replaces the values of an earlier one with the same name.
+#[derive(Clone, Default)]
+pub struct MetricRegistry {
+    metrics: Vec<Arc<dyn Metric>>,
+}
+
+impl MetricRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(mut self, metric: impl Metric + 'static) -> Self {
+        self.metrics.push(Arc::new(metric));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.metrics.is_empty()
+    }
+
+    // Metric name -> class -> value
+    pub fn compute(&self, graph: &ClassGraph) -> BTreeMap<String, BTreeMap<String, f64>> {
+        self.metrics
+            .iter()
+            .map(|metric| (metric.name().to_string(), metric.compute(graph)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::link::ClassLink;
+    use std::collections::HashSet;
+
+    struct ElixirParser;
+
+    impl FileAnalyzer for ElixirParser {
+        fn analyze(&self, content: &str, _file_path: &str) -> AnalysisResult {
+            AnalysisResult {
+                classes: content
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("defmodule ")?.split_whitespace().next())
+                    .map(str::to_string)
+                    .collect(),
+                ..Default::default()
+            }
+        }
+    }
+
+    impl LanguageParser for ElixirParser {
+        fn language(&self) -> &str {
+            "elixir"
+        }
+
+        fn extensions(&self) -> Vec<String> {
+            vec!["ex".to_string(), "exs".to_string()]
+        }
+    }
+
+    #[test]
+    fn test_registry_finds_parser_by_extension() {
+        let registry = ParserRegistry::new().register(ElixirParser);
+
+        let parser = registry.find("lib/shop/cart.ex").unwrap();
+        assert_eq!(parser.language(), "elixir");
+        assert!(parser.analyze("defmodule Shop.Cart do\nend\n", "cart.ex").classes.contains("Shop.Cart"));
+        assert!(registry.find("lib/cart.py").is_none());
+        assert!(registry.find("Makefile").is_none());
+        assert_eq!(registry.file_patterns(), vec!["*.ex", "*.exs"]);
+    }
+
+    // Hops from each class to `Order` along its dependencies
+    struct OrderDistance;
+
+    impl Metric for OrderDistance {
+        fn name(&self) -> &str {
+            "order_distance"
+        }
+
+        fn compute(&self, graph: &ClassGraph) -> BTreeMap<String, f64> {
+            let mut distances = BTreeMap::from([("Order".to_string(), 0.0)]);
+            for (depth, classes) in graph.dependents_by_depth(&["Order".to_string()]).into_iter().enumerate() {
+                for class in classes {
+                    distances.insert(class, (depth + 1) as f64);
+                }
+            }
+            distances
+        }
+    }
+
+    #[test]
+    fn test_metric_registry_computes_every_metric() {
+        let link = |from: &str, to: &str| ClassLink {
+            from_class: from.to_string(),
+            to_class: to.to_string(),
+            line_number: 1,
+            kind: Default::default(),
+            position: None,
+        };
+        let classes: HashSet<String> = ["Shop", "Cart", "Order", "Logger"].iter().map(|s| s.to_string()).collect();
+        let graph = ClassGraph::new(&classes, &[link("Shop", "Cart"), link("Cart", "Order")]);
+
+        let values = MetricRegistry::new().register(OrderDistance).compute(&graph);
+
+        let distances = &values["order_distance"];
+        assert_eq!((distances["Order"], distances["Cart"], distances["Shop"]), (0.0, 1.0, 2.0));
+        assert!(!distances.contains_key("Logger"));
+    }
+}
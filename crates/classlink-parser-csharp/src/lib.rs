@@ -0,0 +1,366 @@
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use classlink_core::{annotations, endpoints, metrics};
+use classlink_core::link::{ClassLink, LinkKind};
+use classlink_core::analysis::{AnalysisResult, FileAnalyzer};
+use classlink_core::preset::{self, ExtractionPreset};
+use classlink_core::registry::LanguageParser;
+use serde::{Deserialize, Serialize};
+
+// Goes into the parse cache key, so a parser change re-parses its files
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "CSharpParserConfig")]
+pub struct CSharpParserOptions {
+    // Parameter, type-declaration and generic-argument references, as
+    // opposed to base lists, fields and properties
+    pub include_type_only: bool,
+    // Only link to types the file defines or brings in with `using`; off
+    // links any capitalized type name
+    pub known_targets_only: bool,
+    // Every capitalized name in a class body, subject to `known_targets_only`
+    pub any_capitalized: bool,
+}
+
+impl Default for CSharpParserOptions {
+    fn default() -> Self {
+        Self::preset(ExtractionPreset::Balanced)
+    }
+}
+
+impl CSharpParserOptions {
+    pub fn preset(preset: ExtractionPreset) -> Self {
+        Self {
+            include_type_only: preset != ExtractionPreset::Strict,
+            known_targets_only: preset != ExtractionPreset::Greedy,
+            any_capitalized: preset == ExtractionPreset::Greedy,
+        }
+    }
+}
+
+// Options as written in a config file: a preset, with any field given
+// overriding it
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct CSharpParserConfig {
+    preset: ExtractionPreset,
+    include_type_only: Option<bool>,
+    known_targets_only: Option<bool>,
+    any_capitalized: Option<bool>,
+}
+
+impl From<CSharpParserConfig> for CSharpParserOptions {
+    fn from(config: CSharpParserConfig) -> Self {
+        let preset = Self::preset(config.preset);
+        Self {
+            include_type_only: config.include_type_only.unwrap_or(preset.include_type_only),
+            known_targets_only: config.known_targets_only.unwrap_or(preset.known_targets_only),
+            any_capitalized: config.any_capitalized.unwrap_or(preset.any_capitalized),
+        }
+    }
+}
+
+pub struct CSharpParser {
+    options: CSharpParserOptions,
+    class_regex: Regex,
+    interface_regex: Regex,
+    base_list_regex: Regex,
+    field_regex: Regex,
+    using_regex: Regex,
+    namespace_regex: Regex,
+    method_regex: Regex,
+    interface_member_regex: Regex,
+    registration_regex: Regex,
+    handler_regex: Regex,
+    publish_regex: Regex,
+    route_regex: Regex,
+    http_method_regex: Regex,
+    http_client_regex: Regex,
+}
+
+impl Default for CSharpParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CSharpParser {
+    pub fn new() -> Self {
+        Self {
+            options: CSharpParserOptions::default(),
+            class_regex: Regex::new(r"^\s*(?:public|private|protected|internal)?\s*(?:partial|abstract|sealed)?\s*class\s+(\w+)").unwrap(),
+            interface_regex: Regex::new(r"^\s*(?:public|private|protected|internal)?\s*interface\s+(\w+)").unwrap(),
+            base_list_regex: Regex::new(r"\b(?:class|interface)\s+\w+(?:<[^>]*>)?\s*:\s*([^{]+)").unwrap(),
+            field_regex: Regex::new(r"^\s*(?:private|protected|public|internal)\s+(?:readonly\s+)?([A-Z]\w+)\s+_?\w+\s*[;=]").unwrap(),
+            using_regex: Regex::new(r"^\s*using\s+([\w.]+);").unwrap(),
+            // Block-scoped `namespace A.B {` and file-scoped `namespace A.B;`
+            namespace_regex: Regex::new(r"^\s*namespace\s+([\w.]+)").unwrap(),
+            method_regex: Regex::new(r"^\s*(?:(?:public|private|protected|internal|static|virtual|override|async|abstract|sealed|partial|extern)\s+)+(?:[\w<>\[\],.?]+\s+)?\w+\s*(?:<[^>]*>)?\s*\(").unwrap(),
+            interface_member_regex: Regex::new(r"^\s*[A-Z][\w<>\[\],.?]*\s+\w+\s*\([^)]*\)\s*;").unwrap(),
+            registration_regex: Regex::new(r"\.Add(?:Scoped|Transient|Singleton)\s*<\s*([\w.]+)\s*,\s*([\w.]+)\s*>").unwrap(),
+            handler_regex: Regex::new(r"\b(?:IEventHandler|IIntegrationEventHandler|INotificationHandler|IConsumer|IHandleMessages)\s*<\s*([\w.]+)\s*>").unwrap(),
+            route_regex: Regex::new(r#"^\s*\[Route\(\s*"([^"]*)""#).unwrap(),
+            http_method_regex: Regex::new(r#"\[Http(?:Get|Post|Put|Patch|Delete)(?:\(\s*"([^"]*)")?"#).unwrap(),
+            http_client_regex: Regex::new(r#"\.(?:Get|Post|Put|Patch|Delete)(?:FromJson|AsJson)?Async\s*(?:<[^>]*>)?\(\s*\$?"([^"]+)""#).unwrap(),
+            publish_regex: Regex::new(r"\.(?:Publish|PublishAsync|Send|SendAsync)\s*(?:<\s*([\w.]+)\s*>\s*\(|\(\s*new\s+([\w.]+))").unwrap(),
+        }
+    }
+
+    pub fn with_options(options: CSharpParserOptions) -> Self {
+        Self { options, ..Self::new() }
+    }
+    
+    // Methods and constructors carry an access modifier; interface members
+    // are bare signatures ending in `;`
+    fn is_method(&self, line: &str) -> bool {
+        self.method_regex.is_match(line) || self.interface_member_regex.is_match(line)
+    }
+
+    // Stronger kinds come first so they win when duplicate links are dropped
+    fn extract_class_references(&self, line: &str) -> Vec<(String, LinkKind)> {
+        let mut references = Vec::new();
+
+        // Base lists like `class UserService : ServiceBase, IUserService`
+        if let Some(caps) = self.base_list_regex.captures(line) {
+            for base in caps[1].split(',') {
+                let name = base.trim().split('<').next().unwrap_or_default().trim();
+                let name = name.rsplit('.').next().unwrap_or_default();
+                references.push((name.to_string(), LinkKind::Inheritance));
+            }
+        }
+
+        // Fields like `private readonly IUserRepository _repository;`
+        if let Some(caps) = self.field_regex.captures(line) {
+            references.push((caps[1].to_string(), LinkKind::Composition));
+        }
+
+        // Property types
+        let property_regex = Regex::new(r"(?:public|private|protected|internal)?\s*([A-Z]\w+)\s+\w+\s*\{").unwrap();
+        for caps in property_regex.captures_iter(line) {
+            references.push((caps[1].to_string(), LinkKind::Composition));
+        }
+        
+        if self.options.any_capitalized {
+            for name in preset::capitalized_names(line, &[]) {
+                references.push((name.to_string(), LinkKind::Usage));
+            }
+        }
+
+        if !self.options.include_type_only {
+            return references;
+        }
+
+        // Type declarations like : IUserService
+        let inheritance_regex = Regex::new(r":\s*([A-Z]\w+)").unwrap();
+        for caps in inheritance_regex.captures_iter(line) {
+            references.push((caps[1].to_string(), LinkKind::Usage));
+        }
+        
+        // Constructor parameters and method parameters
+        let param_regex = Regex::new(r"([A-Z]\w+)\s+\w+").unwrap();
+        for caps in param_regex.captures_iter(line) {
+            references.push((caps[1].to_string(), LinkKind::Usage));
+        }
+        
+        // Generic types like List<User>, Task<User>
+        let generic_regex = Regex::new(r"<([A-Z]\w+)>").unwrap();
+        for caps in generic_regex.captures_iter(line) {
+            references.push((caps[1].to_string(), LinkKind::Usage));
+        }
+        
+        references
+    }
+}
+
+impl FileAnalyzer for CSharpParser {
+    fn analyze(&self, content: &str, _file_path: &str) -> AnalysisResult {
+        let mut classes = HashSet::new();
+        let mut links = Vec::new();
+        let mut current_class = None;
+        let mut imported_types = HashSet::new();
+        let mut class_starts = Vec::new();
+        let mut namespace: Option<String> = None;
+        let mut namespaces = HashMap::new();
+        let lines: Vec<&str> = content.lines().collect();
+        // `[Route]` before a class prefixes its actions; before a method it
+        // is the action's own route
+        let mut pending_route: Option<String> = None;
+        let mut route_prefix = String::new();
+        
+        for (line_num, line) in lines.iter().copied().enumerate() {
+            let is_declaration = self.class_regex.is_match(line);
+
+            // Check for using statements
+            if let Some(caps) = self.using_regex.captures(line) {
+                let namespace = &caps[1];
+                // Extract the last part as potential class name
+                if let Some(last_part) = namespace.split('.').next_back() {
+                    if last_part.chars().next().is_some_and(|c| c.is_uppercase()) {
+                        imported_types.insert(last_part.to_string());
+                    }
+                }
+            }
+            
+            if let Some(caps) = self.namespace_regex.captures(line) {
+                namespace = Some(caps[1].to_string());
+            }
+
+            // Check for class definitions
+            if let Some(caps) = self.class_regex.captures(line) {
+                let class_name = caps[1].to_string();
+                classes.insert(class_name.clone());
+                namespaces.extend(namespace.clone().map(|namespace| (class_name.clone(), namespace)));
+                class_starts.push((class_name.clone(), line_num));
+                route_prefix = pending_route.take().unwrap_or_default();
+                current_class = Some(class_name);
+            }
+            
+            // Check for interface definitions
+            if let Some(caps) = self.interface_regex.captures(line) {
+                let interface_name = caps[1].to_string();
+                classes.insert(interface_name.clone());
+                namespaces.extend(namespace.clone().map(|namespace| (interface_name.clone(), namespace)));
+                class_starts.push((interface_name.clone(), line_num));
+                current_class = Some(interface_name);
+            }
+            
+            // Container registrations like `services.AddScoped<IUserService, UserService>()`,
+            // usually in `Program.cs` outside any class
+            for caps in self.registration_regex.captures_iter(line) {
+                let name = |capture: &str| capture.rsplit('.').next().unwrap_or_default().to_string();
+                links.push(ClassLink {
+                    from_class: name(&caps[1]),
+                    to_class: name(&caps[2]),
+                    line_number: line_num + 1,
+                    kind: LinkKind::Binding,
+                    position: None,
+                });
+            }
+
+            // Endpoints: actions are bound to their routes, and `HttpClient` calls
+            // like `GetFromJsonAsync<User>("/users/1")` use them
+            if let Some(caps) = self.route_regex.captures(line) {
+                pending_route = Some(caps[1].to_string());
+            }
+            if let Some(ref class) = current_class {
+                let mut routes: Vec<String> = self
+                    .http_method_regex
+                    .captures_iter(line)
+                    .map(|caps| caps.get(1).map_or("", |m| m.as_str()).to_string())
+                    .collect();
+                if !is_declaration && self.is_method(line) {
+                    routes.extend(pending_route.take());
+                }
+                for route in routes {
+                    let endpoint = if route.starts_with('/') || route.starts_with("~/") {
+                        endpoints::normalize(&route)
+                    } else {
+                        endpoints::join(&route_prefix, &route, class)
+                    };
+                    links.push(ClassLink {
+                        from_class: endpoint,
+                        to_class: class.clone(),
+                        line_number: line_num + 1,
+                        kind: LinkKind::Binding,
+                        position: None,
+                    });
+                }
+                for caps in self.http_client_regex.captures_iter(line) {
+                    if let Some(endpoint) = endpoints::client_endpoint(&caps[1]) {
+                        links.push(ClassLink {
+                            from_class: class.clone(),
+                            to_class: endpoint,
+                            line_number: line_num + 1,
+                            kind: LinkKind::Usage,
+                            position: None,
+                        });
+                    }
+                }
+            }
+
+            // Handlers like `class OrderMailer : INotificationHandler<OrderCreated>` are
+            // bound to their event, and `Publish(new OrderCreated(..))` uses it, so
+            // publishers reach their handlers through the event class
+            if let Some(ref class) = current_class {
+                let name = |capture: &str| capture.rsplit('.').next().unwrap_or_default().to_string();
+                if is_declaration {
+                    for caps in self.handler_regex.captures_iter(line) {
+                        links.push(ClassLink {
+                            from_class: name(&caps[1]),
+                            to_class: class.clone(),
+                            line_number: line_num + 1,
+                            kind: LinkKind::Binding,
+                            position: None,
+                        });
+                    }
+                }
+                for caps in self.publish_regex.captures_iter(line) {
+                    let event = name(caps.get(1).or(caps.get(2)).map_or("", |m| m.as_str()));
+                    if event != *class {
+                        links.push(ClassLink {
+                            from_class: class.clone(),
+                            to_class: event,
+                            line_number: line_num + 1,
+                            kind: LinkKind::Usage,
+                            position: None,
+                        });
+                    }
+                }
+            }
+
+            // Find class references
+            if let Some(ref from_class) = current_class {
+                for (to_class, kind) in self.extract_class_references(line) {
+                    if &to_class != from_class {
+                        // Check if it's a known type or common .NET type
+                        let is_known_type = classes.contains(&to_class) || 
+                                          imported_types.contains(&to_class) ||
+                                          // Common C# types to include
+                                          ["User", "DatabaseContext", "IUserService"].contains(&to_class.as_str());
+                        
+                        if is_known_type || !self.options.known_targets_only {
+                            // One link per place: several patterns can match the same
+                            // reference, but every line it occurs on is kept
+                            let already_exists = links.iter().any(|l: &ClassLink| l.from_class == *from_class && l.to_class == to_class && l.line_number == line_num + 1);
+                            if !already_exists {
+                                links.push(ClassLink {
+                                    from_class: from_class.clone(),
+                                    to_class,
+                                    line_number: line_num + 1,
+                                    kind,
+                                    position: None,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        
+        let class_tags = annotations::csharp_tags(&lines, &class_starts);
+        let dependency_budgets = annotations::dependency_budgets(&lines, &class_starts);
+        let class_metrics =
+            metrics::measure_classes(&lines, class_starts, metrics::brace_block_end, |line| self.is_method(line));
+
+        AnalysisResult {
+            classes,
+            links,
+            class_metrics,
+            class_tags,
+            dependency_budgets,
+            namespaces,
+            ..Default::default()
+        }
+    }
+}
+
+impl LanguageParser for CSharpParser {
+    fn language(&self) -> &str {
+        "csharp"
+    }
+
+    fn extensions(&self) -> Vec<String> {
+        vec!["cs".to_string()]
+    }
+}
@@ -0,0 +1,559 @@
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use classlink_core::{annotations, metrics};
+use classlink_core::link::{ClassLink, LinkKind};
+use classlink_core::analysis::{AnalysisResult, FileAnalyzer};
+use classlink_core::metrics::ClassMetrics;
+use classlink_core::preset::{self, ExtractionPreset};
+use classlink_core::registry::LanguageParser;
+use serde::{Deserialize, Serialize};
+
+// Goes into the parse cache key, so a parser change re-parses its files
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "GoParserConfig")]
+pub struct GoParserOptions {
+    // Method and interface method signatures, as opposed to struct fields
+    // and embedded types
+    pub include_type_only: bool,
+    // Only link to types the file defines or qualifies with a project
+    // package it imports, like `billing.Invoice`; off links any type name
+    pub known_targets_only: bool,
+    // Every capitalized name in a type or method body, subject to
+    // `known_targets_only`
+    pub any_capitalized: bool,
+}
+
+impl Default for GoParserOptions {
+    fn default() -> Self {
+        Self::preset(ExtractionPreset::Balanced)
+    }
+}
+
+impl GoParserOptions {
+    pub fn preset(preset: ExtractionPreset) -> Self {
+        Self {
+            include_type_only: preset != ExtractionPreset::Strict,
+            known_targets_only: preset != ExtractionPreset::Greedy,
+            any_capitalized: preset == ExtractionPreset::Greedy,
+        }
+    }
+}
+
+// Options as written in a config file: a preset, with any field given
+// overriding it
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct GoParserConfig {
+    preset: ExtractionPreset,
+    include_type_only: Option<bool>,
+    known_targets_only: Option<bool>,
+    any_capitalized: Option<bool>,
+}
+
+impl From<GoParserConfig> for GoParserOptions {
+    fn from(config: GoParserConfig) -> Self {
+        let preset = Self::preset(config.preset);
+        Self {
+            include_type_only: config.include_type_only.unwrap_or(preset.include_type_only),
+            known_targets_only: config.known_targets_only.unwrap_or(preset.known_targets_only),
+            any_capitalized: config.any_capitalized.unwrap_or(preset.any_capitalized),
+        }
+    }
+}
+
+// Predeclared types and the keywords of type expressions, which are never
+// classes
+const BUILTINS: &[&str] = &[
+    "any", "bool", "byte", "chan", "comparable", "complex128", "complex64", "error", "float32", "float64", "func",
+    "int", "int16", "int32", "int64", "int8", "interface", "map", "rune", "string", "struct", "uint", "uint16",
+    "uint32", "uint64", "uint8", "uintptr",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ItemKind {
+    Struct,
+    Interface,
+    // A method, which belongs to its receiver's type
+    Method,
+}
+
+// The declaration whose body the parser is in
+struct Item {
+    name: String,
+    kind: ItemKind,
+    // Brace depth the item was declared at
+    depth: usize,
+    opened: bool,
+    type_params: Vec<String>,
+}
+
+pub struct GoParser {
+    options: GoParserOptions,
+    type_regex: Regex,
+    method_regex: Regex,
+    import_regex: Regex,
+    signature_regex: Regex,
+    field_regex: Regex,
+    ident_regex: Regex,
+}
+
+impl Default for GoParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GoParser {
+    pub fn new() -> Self {
+        Self {
+            options: GoParserOptions::default(),
+            // `type Order struct {`, or `Order struct {` inside `type (...)`
+            type_regex: Regex::new(r"^\s*(type\s+)?([A-Za-z_]\w*)\s*(\[[^\]]*\])?\s+(struct|interface)\b").unwrap(),
+            method_regex: Regex::new(r"^\s*func\s*\(\s*(?:\w+\s+)?\*?\s*([A-Za-z_]\w*)\s*(\[[^\]]*\])?\s*\)").unwrap(),
+            import_regex: Regex::new(r#"^\s*(?:import\s+)?(?:([A-Za-z_]\w*|\.)\s+)?"([^"]+)""#).unwrap(),
+            signature_regex: Regex::new(r"^\s*[A-Za-z_]\w*\s*(?:\[[^\]]*\])?\s*\(").unwrap(),
+            field_regex: Regex::new(r"^([A-Za-z_]\w*(?:\s*,\s*[A-Za-z_]\w*)*)\s+(\S.*)$").unwrap(),
+            ident_regex: Regex::new(r"(?:\b([A-Za-z_]\w*)\s*\.\s*)?\b([A-Za-z_]\w*)").unwrap(),
+        }
+    }
+
+    pub fn with_options(options: GoParserOptions) -> Self {
+        Self { options, ..Self::new() }
+    }
+
+    // Struct and interface types declared at the top level, alone or in a
+    // `type (...)` group
+    fn declared_types(&self, code_lines: &[String]) -> HashSet<String> {
+        let mut types = HashSet::new();
+        let mut depth = 0usize;
+        let mut in_type_block = false;
+        for line in code_lines {
+            let trimmed = line.trim();
+            if depth == 0 {
+                if trimmed.starts_with("type (") || trimmed == "type(" {
+                    in_type_block = true;
+                } else if in_type_block && trimmed == ")" {
+                    in_type_block = false;
+                } else if let Some(caps) = self.type_regex.captures(line).filter(|caps| caps.get(1).is_some() || in_type_block) {
+                    types.insert(caps[2].to_string());
+                }
+            }
+            depth = (depth + line.matches('{').count()).saturating_sub(line.matches('}').count());
+        }
+        types
+    }
+
+    // Named types of a type expression or signature, each with the package
+    // qualifying it, if any: `map[string][]*billing.Invoice` names
+    // `Invoice` from `billing`
+    fn type_names(&self, text: &str, type_params: &[String]) -> Vec<(String, Option<String>)> {
+        let text = drop_param_names(text);
+        self.ident_regex
+            .captures_iter(&text)
+            .filter_map(|caps| {
+                let name = caps[2].to_string();
+                let package = caps.get(1).map(|m| m.as_str().to_string());
+                if package.is_none() && (BUILTINS.contains(&name.as_str()) || type_params.contains(&name)) {
+                    return None;
+                }
+                Some((name, package))
+            })
+            .collect()
+    }
+}
+
+impl GoParser {
+    // A struct field like `Lines []*OrderLine `json:"lines"``, or an
+    // embedded type like `*Base` or `billing.Account`, which needs no
+    // import to count
+    fn field_references(&self, member: &str, type_params: &[String], imports: &HashMap<String, bool>) -> Vec<(String, bool, LinkKind)> {
+        let field = member.replace("``", "").replace("\"\"", "");
+        let field = field.trim().trim_end_matches(['{', '}']).trim();
+        if let Some(caps) = self.field_regex.captures(field) {
+            self.type_names(&caps[2], type_params)
+                .into_iter()
+                .map(|(name, package)| (name, is_imported(package.as_deref(), imports), LinkKind::Composition))
+                .collect()
+        } else if !field.is_empty() {
+            let types = self.type_names(field, type_params);
+            types.into_iter().map(|(name, _)| (name, true, LinkKind::Inheritance)).collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    // A method signature like `Find(ctx context.Context, id OrderID)
+    // (*Order, error)`, or an embedded interface
+    fn method_spec_references(&self, member: &str, type_params: &[String], imports: &HashMap<String, bool>) -> Vec<(String, bool, LinkKind)> {
+        let member = member.trim();
+        if self.signature_regex.is_match(member) {
+            if !self.options.include_type_only {
+                return Vec::new();
+            }
+            let parameters = &member[member.find('(').unwrap_or(0)..];
+            self.type_names(parameters, type_params)
+                .into_iter()
+                .map(|(name, package)| (name, is_imported(package.as_deref(), imports), LinkKind::Usage))
+                .collect()
+        } else if !member.is_empty() && member != "}" {
+            let types = self.type_names(member.trim_end_matches(['{', '}']), type_params);
+            types.into_iter().map(|(name, _)| (name, true, LinkKind::Inheritance)).collect()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+// The members of a body opened on the declaration line, like
+// `struct{ Price Money; Qty int }`: from the `{` to the brace closing it or
+// the end of the line, split at the `;` between them
+fn inline_members(line: &str) -> Vec<&str> {
+    let Some(open) = line.find('{') else {
+        return Vec::new();
+    };
+    let body = &line[open + 1..];
+    let mut depth = 0usize;
+    let end = body
+        .char_indices()
+        .find(|&(_, c)| match c {
+            '{' => {
+                depth += 1;
+                false
+            }
+            '}' if depth == 0 => true,
+            '}' => {
+                depth -= 1;
+                false
+            }
+            _ => false,
+        })
+        .map_or(body.len(), |(index, _)| index);
+    body[..end].split(';').filter(|member| !member.trim().is_empty()).collect()
+}
+
+// A qualified name is known when its package is a project package the file
+// imports; unqualified names are known when the file defines them
+fn is_imported(package: Option<&str>, imports: &HashMap<String, bool>) -> bool {
+    package.is_some_and(|package| imports.get(package).copied().unwrap_or(false))
+}
+
+// Names of type parameters like `[K comparable, V any]` or a receiver's
+// `[K, V]`
+fn type_params(brackets: Option<&str>) -> Vec<String> {
+    let inner = brackets.unwrap_or_default().trim_start_matches('[').trim_end_matches(']');
+    split_top_level(inner)
+        .into_iter()
+        .filter_map(|param| param.split_whitespace().next())
+        .map(str::to_string)
+        .collect()
+}
+
+// The package name an import path brings into scope: its last element,
+// skipping a major version like `/v2` and a `.v3` suffix
+fn package_name(path: &str) -> &str {
+    let mut elements = path.rsplit('/');
+    let mut last = elements.next().unwrap_or_default();
+    let version = |element: &str| element.len() > 1 && element.starts_with('v') && element[1..].chars().all(|c| c.is_ascii_digit());
+    if version(last) {
+        last = elements.next().unwrap_or(last);
+    }
+    last.split('.').next().unwrap_or(last)
+}
+
+// `text` with the parameter names of every `(...)` list dropped, so
+// `(ctx context.Context, id OrderID) (*Order, error)` keeps only types. An
+// entry of one word is a type.
+fn drop_param_names(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(open) = rest.find('(') {
+        out.push_str(&rest[..=open]);
+        let close = matching_paren(&rest[open..]).map(|close| open + close);
+        let inner = &rest[open + 1..close.unwrap_or(rest.len())];
+        let entries: Vec<String> = split_top_level(inner)
+            .into_iter()
+            .map(|entry| match entry.split_once(char::is_whitespace) {
+                Some((name, type_expr)) if is_param_name(name) => drop_param_names(type_expr.trim()),
+                _ => drop_param_names(entry),
+            })
+            .collect();
+        out.push_str(&entries.join(", "));
+        match close {
+            Some(close) => {
+                out.push(')');
+                rest = &rest[close + 1..];
+            }
+            None => rest = "",
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn is_param_name(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && word.chars().all(|c| c.is_alphanumeric() || c == '_')
+        && !BUILTINS.contains(&word)
+}
+
+// Index of the `)` closing the `(` the text starts with
+fn matching_paren(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (index, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// Comma-separated entries outside nested brackets
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut entries = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                entries.push(&text[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    entries.push(&text[start..]);
+    entries.into_iter().map(str::trim).filter(|entry| !entry.is_empty()).collect()
+}
+
+// The file's lines without comments and with the contents of string and
+// rune literals dropped, so braces and names in them do not count. Block
+// comments and raw strings may span lines.
+fn code_lines(lines: &[&str]) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut in_comment = false;
+    let mut in_raw_string = false;
+    for line in lines {
+        let chars: Vec<char> = line.chars().collect();
+        let mut code = String::new();
+        let mut quote: Option<char> = None;
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let next = chars.get(i + 1).copied();
+            if in_comment {
+                if c == '*' && next == Some('/') {
+                    in_comment = false;
+                    code.push(' ');
+                    i += 1;
+                }
+            } else if in_raw_string {
+                if c == '`' {
+                    in_raw_string = false;
+                    code.push(c);
+                }
+            } else if let Some(q) = quote {
+                if c == '\\' {
+                    i += 1;
+                } else if c == q {
+                    quote = None;
+                    code.push(c);
+                }
+            } else if c == '/' && next == Some('/') {
+                break;
+            } else if c == '/' && next == Some('*') {
+                in_comment = true;
+                i += 1;
+            } else {
+                match c {
+                    '`' => in_raw_string = true,
+                    '"' | '\'' => quote = Some(c),
+                    _ => {}
+                }
+                code.push(c);
+            }
+            i += 1;
+        }
+        out.push(code);
+    }
+    out
+}
+
+impl FileAnalyzer for GoParser {
+    fn analyze(&self, content: &str, _file_path: &str) -> AnalysisResult {
+        let lines: Vec<&str> = content.lines().collect();
+        let code_lines = code_lines(&lines);
+        // Methods are often declared above their type
+        let classes = self.declared_types(&code_lines);
+        // Package names in scope, and whether each is a project package
+        // rather than the standard library, whose import paths have no
+        // domain
+        let mut imports: HashMap<String, bool> = HashMap::new();
+        let mut links: Vec<ClassLink> = Vec::new();
+        let mut class_starts = Vec::new();
+        let mut method_starts = Vec::new();
+        let mut item: Option<Item> = None;
+        let mut depth = 0usize;
+        let mut in_import_block = false;
+        let mut in_type_block = false;
+        let mut in_signature = false;
+
+        for (index, line) in code_lines.iter().enumerate() {
+            let trimmed = line.trim();
+            // Type names with whether they count as known whatever the file
+            // defines
+            let mut references: Vec<(String, bool, LinkKind)> = Vec::new();
+            let mut signature: Option<&str> = None;
+
+            // Declarations inside function bodies are local
+            if item.is_none() && depth == 0 {
+                let declaration = self.type_regex.captures(line).filter(|caps| caps.get(1).is_some() || in_type_block);
+                if in_import_block || trimmed.starts_with("import ") {
+                    in_import_block = (in_import_block || trimmed.starts_with("import (")) && !trimmed.ends_with(')');
+                    // Paths are dropped from the code lines, so read the
+                    // original line
+                    if let Some(caps) = self.import_regex.captures(lines[index]) {
+                        let path = &caps[2];
+                        let name = caps.get(1).map_or_else(|| package_name(path), |m| m.as_str());
+                        if name != "_" && name != "." {
+                            let project = path.split('/').next().is_some_and(|host| host.contains('.'));
+                            imports.insert(name.to_string(), project);
+                        }
+                    }
+                } else if trimmed.starts_with("type (") || trimmed == "type(" {
+                    in_type_block = true;
+                } else if in_type_block && trimmed == ")" {
+                    in_type_block = false;
+                } else if let Some(caps) = declaration {
+                    let name = caps[2].to_string();
+                    let kind = if &caps[4] == "struct" { ItemKind::Struct } else { ItemKind::Interface };
+                    let type_params = type_params(caps.get(3).map(|m| m.as_str()));
+                    // Bodies written on the declaration line
+                    let body = &line[caps.get(0).map_or(0, |m| m.end())..];
+                    for member in inline_members(body) {
+                        references.extend(match kind {
+                            ItemKind::Struct => self.field_references(member, &type_params, &imports),
+                            _ => self.method_spec_references(member, &type_params, &imports),
+                        });
+                    }
+                    class_starts.push((name.clone(), index));
+                    item = Some(Item { name, kind, depth, opened: false, type_params });
+                } else if let Some(caps) = self.method_regex.captures(line) {
+                    let name = caps[1].to_string();
+                    method_starts.push((name.clone(), index));
+                    // From the parameters on, leaving out the method name
+                    let rest = &line[caps.get(0).map_or(0, |m| m.end())..];
+                    signature = Some(&rest[rest.find('(').unwrap_or(rest.len())..]);
+                    in_signature = true;
+                    item = Some(Item { name, kind: ItemKind::Method, depth, opened: false, type_params: type_params(caps.get(2).map(|m| m.as_str())) });
+                }
+            } else if let Some(current) = &item {
+                match current.kind {
+                    ItemKind::Struct => references.extend(self.field_references(trimmed, &current.type_params, &imports)),
+                    ItemKind::Interface => references.extend(self.method_spec_references(trimmed, &current.type_params, &imports)),
+                    // Signatures may wrap up to the body
+                    ItemKind::Method if in_signature => signature = Some(line.as_str()),
+                    ItemKind::Method => {}
+                }
+            }
+
+            if let (Some(signature), Some(current)) = (signature, &item) {
+                if self.options.include_type_only {
+                    let signature = signature.split('{').next().unwrap_or_default();
+                    for (name, package) in self.type_names(signature, &current.type_params) {
+                        references.push((name, is_imported(package.as_deref(), &imports), LinkKind::Usage));
+                    }
+                }
+                if line.contains('{') {
+                    in_signature = false;
+                }
+            }
+            if let Some(current) = &item {
+                if self.options.any_capitalized && signature.is_none() {
+                    for name in preset::capitalized_names(line, &[]) {
+                        references.push((name.to_string(), false, LinkKind::Usage));
+                    }
+                }
+                for (name, known, kind) in references {
+                    let known = known || classes.contains(&name);
+                    if name == current.name || (!known && self.options.known_targets_only) {
+                        continue;
+                    }
+                    // One link per place, the strongest kind first
+                    let already_exists = links
+                        .iter()
+                        .any(|l| l.from_class == current.name && l.to_class == name && l.line_number == index + 1);
+                    if !already_exists {
+                        links.push(ClassLink {
+                            from_class: current.name.clone(),
+                            to_class: name,
+                            line_number: index + 1,
+                            kind,
+                            position: None,
+                        });
+                    }
+                }
+            }
+
+            for c in line.chars() {
+                match c {
+                    '{' => {
+                        depth += 1;
+                        if let Some(item) = &mut item {
+                            item.opened = true;
+                        }
+                    }
+                    '}' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+            }
+            if item.as_ref().is_some_and(|item| item.opened && depth <= item.depth) {
+                item = None;
+                in_signature = false;
+            }
+        }
+
+        let class_tags = annotations::go_tags(&lines, &class_starts);
+        let dependency_budgets = annotations::dependency_budgets(&lines, &class_starts);
+        let mut class_metrics = metrics::measure_classes(&lines, class_starts, metrics::brace_block_end, |line| {
+            self.signature_regex.is_match(line)
+        });
+        // Each method is a block of its own that counts towards its type
+        for (name, start) in method_starts {
+            if let Some(size) = class_metrics.get_mut(&name) {
+                let mut method = ClassMetrics::measure(&lines, start, metrics::brace_block_end(&lines, start), |_| false);
+                method.methods = 1;
+                size.combine(&method);
+            }
+        }
+
+        AnalysisResult {
+            classes,
+            links,
+            class_metrics,
+            class_tags,
+            dependency_budgets,
+            ..Default::default()
+        }
+    }
+}
+
+impl LanguageParser for GoParser {
+    fn language(&self) -> &str {
+        "go"
+    }
+
+    fn extensions(&self) -> Vec<String> {
+        vec!["go".to_string()]
+    }
+}
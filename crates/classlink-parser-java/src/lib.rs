@@ -0,0 +1,496 @@
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use classlink_core::{annotations, metrics};
+use classlink_core::link::{ClassLink, LinkKind};
+use classlink_core::analysis::{AnalysisResult, FileAnalyzer};
+use classlink_core::preset::{self, ExtractionPreset};
+use classlink_core::registry::LanguageParser;
+use serde::{Deserialize, Serialize};
+
+// Goes into the parse cache key, so a parser change re-parses its files
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "JavaParserConfig")]
+pub struct JavaParserOptions {
+    // Method signature, local variable and generic-argument types, as
+    // opposed to `extends`/`implements` lists, fields and record components
+    pub include_type_only: bool,
+    // Only link the capitalized names `any_capitalized` adds to types the
+    // file defines or imports. Names in type positions always link: classes
+    // of the same package need no import, so the project decides which of
+    // them it defines, and library types are dropped there.
+    pub known_targets_only: bool,
+    // Every capitalized name in a class body, subject to `known_targets_only`
+    pub any_capitalized: bool,
+}
+
+impl Default for JavaParserOptions {
+    fn default() -> Self {
+        Self::preset(ExtractionPreset::Balanced)
+    }
+}
+
+impl JavaParserOptions {
+    pub fn preset(preset: ExtractionPreset) -> Self {
+        Self {
+            include_type_only: preset != ExtractionPreset::Strict,
+            known_targets_only: preset != ExtractionPreset::Greedy,
+            any_capitalized: preset == ExtractionPreset::Greedy,
+        }
+    }
+}
+
+// Options as written in a config file: a preset, with any field given
+// overriding it
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct JavaParserConfig {
+    preset: ExtractionPreset,
+    include_type_only: Option<bool>,
+    known_targets_only: Option<bool>,
+    any_capitalized: Option<bool>,
+}
+
+impl From<JavaParserConfig> for JavaParserOptions {
+    fn from(config: JavaParserConfig) -> Self {
+        let preset = Self::preset(config.preset);
+        Self {
+            include_type_only: config.include_type_only.unwrap_or(preset.include_type_only),
+            known_targets_only: config.known_targets_only.unwrap_or(preset.known_targets_only),
+            any_capitalized: config.any_capitalized.unwrap_or(preset.any_capitalized),
+        }
+    }
+}
+
+// Annotations greedy extraction would otherwise take for types
+const NOT_TYPES: &[&str] = &["Override", "Deprecated", "SuppressWarnings", "FunctionalInterface", "SafeVarargs"];
+
+const MODIFIERS: &str = r"public|protected|private|static|final|abstract|default|synchronized|native|transient|volatile|strictfp|sealed|non-sealed";
+
+// Annotations on the same line as the declaration, like `@Autowired private`
+const ANNOTATIONS: &str = r"(?:@[\w.]+(?:\([^)]*\))?\s+)*";
+
+// A reference type: capitalized or package-qualified, with generic
+// arguments and array brackets
+const TYPE: &str = r"(?:[a-z]\w*\.)*[A-Z][\w.]*(?:<.*?>)?(?:\[\])*(?:\.\.\.)?";
+
+// Words that can stand where a method's return type or name would
+const STATEMENTS: &[&str] = &[
+    "assert", "case", "catch", "class", "do", "else", "enum", "for", "if", "interface", "new", "record", "return",
+    "switch", "synchronized", "throw", "try", "while", "yield",
+];
+
+// The class whose header or body the parser is in; nested classes stack
+struct Frame {
+    name: String,
+    // Brace depth the class was declared at
+    depth: usize,
+    opened: bool,
+    // Declaration text after the name, up to the body
+    header: String,
+    start: usize,
+    // Names of the class's own type parameters, which are not types
+    type_params: Vec<String>,
+}
+
+pub struct JavaParser {
+    options: JavaParserOptions,
+    class_regex: Regex,
+    package_regex: Regex,
+    import_regex: Regex,
+    method_regex: Regex,
+    field_regex: Regex,
+    local_regex: Regex,
+    new_regex: Regex,
+    section_regex: Regex,
+    type_regex: Regex,
+}
+
+impl Default for JavaParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JavaParser {
+    pub fn new() -> Self {
+        Self {
+            options: JavaParserOptions::default(),
+            class_regex: Regex::new(&format!(r"^\s*{}(?:(?:{})\s+)*(class|interface|enum|record)\s+([A-Za-z_]\w*)", ANNOTATIONS, MODIFIERS)).unwrap(),
+            package_regex: Regex::new(r"^\s*package\s+([\w.]+)\s*;").unwrap(),
+            import_regex: Regex::new(r"^\s*import\s+(static\s+)?([\w.*]+)\s*;").unwrap(),
+            method_regex: Regex::new(&format!(r"^\s*{}((?:(?:{})\s+)*)(?:<[^>]*>\s*)?(?:([\w.]+(?:<.*?>)?(?:\[\])*)\s+)?(\w+)\s*\(", ANNOTATIONS, MODIFIERS)).unwrap(),
+            field_regex: Regex::new(&format!(r"^\s*{}(?:(?:{})\s+)*({})\s+\w+\s*[=;,]", ANNOTATIONS, MODIFIERS, TYPE)).unwrap(),
+            // Also the loop variable of `for (Order order : orders)`
+            local_regex: Regex::new(&format!(r"^\s*(?:for\s*\(\s*)?(?:final\s+)?({})\s+\w+\s*[=;:]", TYPE)).unwrap(),
+            new_regex: Regex::new(r"\bnew\s+((?:[a-z]\w*\.)*)([A-Z][\w.]*)").unwrap(),
+            section_regex: Regex::new(r"\b(extends|implements|permits)\b").unwrap(),
+            type_regex: Regex::new(r"\b[A-Z]\w*").unwrap(),
+        }
+    }
+
+    pub fn with_options(options: JavaParserOptions) -> Self {
+        Self { options, ..Self::new() }
+    }
+
+    // Methods and constructors; constructors have no return type, so they
+    // need a modifier to tell them from calls
+    fn is_method(&self, line: &str) -> bool {
+        let Some(caps) = self.method_regex.captures(line) else {
+            return false;
+        };
+        if STATEMENTS.contains(&&caps[3]) {
+            return false;
+        }
+        match caps.get(2) {
+            Some(return_type) => !STATEMENTS.contains(&return_type.as_str()),
+            None => !caps[1].trim().is_empty(),
+        }
+    }
+
+    // Capitalized names of a type or signature. Annotations, single letters
+    // and the class's type parameters are not types.
+    fn type_names(&self, text: &str, type_params: &[String]) -> Vec<String> {
+        self.type_regex
+            .find_iter(text)
+            .filter(|m| m.len() > 1 && !text[..m.start()].ends_with('@') && !type_params.iter().any(|p| p == m.as_str()))
+            .map(|m| m.as_str().to_string())
+            .collect()
+    }
+
+    // References of a complete class header: bounds of the type parameters,
+    // record components like `record Line(Product product, int quantity)`
+    // and the `extends` and `implements` lists
+    fn header_references(&self, header: &str) -> (Vec<String>, Vec<(String, bool, LinkKind)>) {
+        let header = header.split('{').next().unwrap_or_default();
+        let (params, rest) = split_generics(header);
+        let mut type_params = Vec::new();
+        let mut references = Vec::new();
+        for param in split_top_level(params) {
+            let mut words = param.split_whitespace();
+            type_params.extend(words.next().map(str::to_string));
+            if self.options.include_type_only {
+                let bounds = words.collect::<Vec<_>>().join(" ");
+                references.extend(self.type_names(&bounds, &type_params).into_iter().map(|n| (n, true, LinkKind::Usage)));
+            }
+        }
+        let mut rest = rest.trim_start();
+        if rest.starts_with('(') {
+            let end = matching_paren(rest);
+            let components = self.type_names(&rest[1..end], &type_params);
+            references.extend(components.into_iter().map(|n| (n, true, LinkKind::Composition)));
+            rest = &rest[(end + 1).min(rest.len())..];
+        }
+        let sections: Vec<(usize, usize, &str)> = self
+            .section_regex
+            .captures_iter(rest)
+            .map(|caps| {
+                let keyword = caps.get(1).unwrap();
+                (keyword.start(), keyword.end(), keyword.as_str())
+            })
+            .collect();
+        for (index, (_, end, keyword)) in sections.iter().enumerate() {
+            // Sealed classes name their subclasses in `permits`, which
+            // depend on them rather than the other way round
+            if *keyword == "permits" {
+                continue;
+            }
+            let until = sections.get(index + 1).map_or(rest.len(), |next| next.0);
+            for base in split_top_level(&rest[*end..until]) {
+                let (name, arguments) = base.split_once('<').unwrap_or((base, ""));
+                let name = name.trim().rsplit('.').next().unwrap_or_default();
+                if !name.is_empty() {
+                    references.push((name.to_string(), true, LinkKind::Inheritance));
+                }
+                if self.options.include_type_only {
+                    references.extend(self.type_names(arguments, &type_params).into_iter().map(|n| (n, true, LinkKind::Usage)));
+                }
+            }
+        }
+        (type_params, references)
+    }
+}
+
+// The file's lines without comments and with the contents of string and
+// character literals dropped, so braces and names in them do not count.
+// Block comments and text blocks may span lines.
+fn code_lines(lines: &[&str]) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut in_comment = false;
+    let mut in_text_block = false;
+    for line in lines {
+        let chars: Vec<char> = line.chars().collect();
+        let triple_quote = |i: usize| chars.get(i..i + 3) == Some(&['"', '"', '"'][..]);
+        let mut code = String::new();
+        let mut quote: Option<char> = None;
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let next = chars.get(i + 1).copied();
+            if in_comment {
+                if c == '*' && next == Some('/') {
+                    in_comment = false;
+                    code.push(' ');
+                    i += 1;
+                }
+            } else if in_text_block {
+                if triple_quote(i) {
+                    in_text_block = false;
+                    code.push('"');
+                    i += 2;
+                }
+            } else if let Some(q) = quote {
+                if c == '\\' {
+                    i += 1;
+                } else if c == q {
+                    quote = None;
+                    code.push(c);
+                }
+            } else if c == '/' && next == Some('/') {
+                break;
+            } else if c == '/' && next == Some('*') {
+                in_comment = true;
+                i += 1;
+            } else if triple_quote(i) {
+                in_text_block = true;
+                code.push('"');
+                i += 2;
+            } else {
+                if c == '"' || c == '\'' {
+                    quote = Some(c);
+                }
+                code.push(c);
+            }
+            i += 1;
+        }
+        out.push(code);
+    }
+    out
+}
+
+// The `<...>` type parameters a header starts with, and the text after them
+fn split_generics(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    if !text.starts_with('<') {
+        return ("", text);
+    }
+    let mut depth = 0usize;
+    for (index, c) in text.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return (&text[1..index], &text[index + 1..]);
+                }
+            }
+            _ => {}
+        }
+    }
+    (&text[1..], "")
+}
+
+// Index of the `)` closing the `(` the text starts with
+fn matching_paren(text: &str) -> usize {
+    let mut depth = 0usize;
+    for (index, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return index;
+                }
+            }
+            _ => {}
+        }
+    }
+    text.len().saturating_sub(1)
+}
+
+// Comma-separated entries outside generic arguments, like the bases of
+// `implements Repository<Order, OrderId>, Auditable`
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut entries = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                entries.push(&text[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    entries.push(&text[start..]);
+    entries.into_iter().map(str::trim).filter(|entry| !entry.is_empty()).collect()
+}
+
+impl FileAnalyzer for JavaParser {
+    fn analyze(&self, content: &str, _file_path: &str) -> AnalysisResult {
+        let lines: Vec<&str> = content.lines().collect();
+        let code_lines = code_lines(&lines);
+        // Nested classes are often used above their declaration
+        let classes: HashSet<String> = code_lines
+            .iter()
+            .filter_map(|line| self.class_regex.captures(line))
+            .map(|caps| caps[2].to_string())
+            .collect();
+        let mut imported = HashSet::new();
+        let mut namespace: Option<String> = None;
+        let mut namespaces = HashMap::new();
+        let mut links: Vec<ClassLink> = Vec::new();
+        let mut class_starts = Vec::new();
+        let mut frames: Vec<Frame> = Vec::new();
+        let mut depth = 0usize;
+        let mut in_signature = false;
+
+        for (index, line) in code_lines.iter().enumerate() {
+            let mut references: Vec<(String, bool, LinkKind)> = Vec::new();
+            let mut line_number = index + 1;
+
+            if frames.is_empty() {
+                if let Some(caps) = self.package_regex.captures(line) {
+                    namespace = Some(caps[1].to_string());
+                }
+                // `import com.shop.billing.Invoice;`; wildcard and static
+                // imports name no class
+                if let Some(caps) = self.import_regex.captures(line) {
+                    let name = caps[2].rsplit('.').next().unwrap_or_default();
+                    if caps.get(1).is_none() && name.starts_with(|c: char| c.is_uppercase()) {
+                        imported.insert(name.to_string());
+                    }
+                }
+            }
+
+            if let Some(caps) = self.class_regex.captures(line) {
+                let name = caps[2].to_string();
+                namespaces.extend(namespace.clone().map(|namespace| (name.clone(), namespace)));
+                class_starts.push((name.clone(), index));
+                frames.push(Frame {
+                    name,
+                    depth,
+                    opened: false,
+                    header: line[caps.get(0).map_or(0, |m| m.end())..].to_string(),
+                    start: index,
+                    type_params: Vec::new(),
+                });
+                in_signature = false;
+            } else if let Some(frame) = frames.last_mut().filter(|frame| !frame.opened) {
+                // Headers like `extends` and `implements` lists may wrap
+                frame.header.push(' ');
+                frame.header.push_str(line);
+            } else if let Some(frame) = frames.last() {
+                let member_depth = frame.depth + 1;
+                if depth == member_depth {
+                    // Signatures may wrap up to the body or the `;` of an
+                    // abstract method
+                    if in_signature || self.is_method(line) {
+                        in_signature = !line.contains('{') && !line.contains(';');
+                        if self.options.include_type_only {
+                            let signature = line.split('{').next().unwrap_or_default();
+                            let types = self.type_names(signature, &frame.type_params);
+                            references.extend(types.into_iter().map(|n| (n, true, LinkKind::Usage)));
+                        }
+                    } else if let Some(caps) = self.field_regex.captures(line) {
+                        let types = self.type_names(&caps[1], &frame.type_params);
+                        references.extend(types.into_iter().map(|n| (n, true, LinkKind::Composition)));
+                    }
+                } else if depth > member_depth && self.options.include_type_only {
+                    if let Some(caps) = self.local_regex.captures(line) {
+                        let types = self.type_names(&caps[1], &frame.type_params);
+                        references.extend(types.into_iter().map(|n| (n, true, LinkKind::Usage)));
+                    }
+                }
+                // Instantiations like `new Invoice(..)` or `new com.shop.Invoice(..)`
+                for caps in self.new_regex.captures_iter(line) {
+                    let name = caps[2].rsplit('.').next().unwrap_or_default().to_string();
+                    references.push((name, true, LinkKind::Usage));
+                }
+                if self.options.any_capitalized {
+                    for name in preset::capitalized_names(line, NOT_TYPES) {
+                        references.push((name.to_string(), false, LinkKind::Usage));
+                    }
+                }
+            }
+
+            // With the body opening, the header is complete
+            if let Some(frame) = frames.last_mut().filter(|frame| !frame.opened && frame.header.contains('{')) {
+                let (type_params, header_references) = self.header_references(&frame.header);
+                frame.type_params = type_params;
+                references.extend(header_references);
+                line_number = frame.start + 1;
+            }
+
+            if let Some(current) = frames.last() {
+                for (name, type_position, kind) in references {
+                    let known = type_position || classes.contains(&name) || imported.contains(&name);
+                    if name == current.name || (!known && self.options.known_targets_only) {
+                        continue;
+                    }
+                    // One link per place, the strongest kind first
+                    let already_exists = links
+                        .iter()
+                        .any(|l| l.from_class == current.name && l.to_class == name && l.line_number == line_number);
+                    if !already_exists {
+                        links.push(ClassLink {
+                            from_class: current.name.clone(),
+                            to_class: name,
+                            line_number,
+                            kind,
+                            position: None,
+                        });
+                    }
+                }
+            }
+
+            for c in line.chars() {
+                match c {
+                    '{' => {
+                        depth += 1;
+                        if let Some(frame) = frames.last_mut().filter(|frame| !frame.opened) {
+                            frame.opened = true;
+                        }
+                    }
+                    '}' => {
+                        depth = depth.saturating_sub(1);
+                        if frames.last().is_some_and(|frame| frame.opened && depth <= frame.depth) {
+                            frames.pop();
+                            in_signature = false;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let class_tags = annotations::java_tags(&lines, &class_starts);
+        let dependency_budgets = annotations::dependency_budgets(&lines, &class_starts);
+        let class_metrics =
+            metrics::measure_classes(&lines, class_starts, metrics::brace_block_end, |line| self.is_method(line));
+
+        AnalysisResult {
+            classes,
+            links,
+            class_metrics,
+            class_tags,
+            dependency_budgets,
+            namespaces,
+            ..Default::default()
+        }
+    }
+}
+
+impl LanguageParser for JavaParser {
+    fn language(&self) -> &str {
+        "java"
+    }
+
+    fn extensions(&self) -> Vec<String> {
+        vec!["java".to_string()]
+    }
+}
@@ -0,0 +1,374 @@
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use classlink_core::{annotations, metrics};
+use classlink_core::link::{ClassLink, LinkKind};
+use classlink_core::analysis::{AnalysisResult, FileAnalyzer};
+use classlink_core::preset::{self, ExtractionPreset};
+use classlink_core::registry::LanguageParser;
+use serde::{Deserialize, Serialize};
+
+// Goes into the parse cache key, so a parser change re-parses its files
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "JavaScriptParserConfig")]
+pub struct JavaScriptParserOptions {
+    // JSDoc types like `@param {Order} order`, which only editors and
+    // type checkers read
+    pub include_type_only: bool,
+    // Only link to classes the file imports, requires or defines; off links
+    // any capitalized name after `new` and in JSDoc types
+    pub known_targets_only: bool,
+    // Every capitalized name in a class body, subject to `known_targets_only`
+    pub any_capitalized: bool,
+}
+
+impl Default for JavaScriptParserOptions {
+    fn default() -> Self {
+        Self::preset(ExtractionPreset::Balanced)
+    }
+}
+
+impl JavaScriptParserOptions {
+    pub fn preset(preset: ExtractionPreset) -> Self {
+        Self {
+            include_type_only: preset != ExtractionPreset::Strict,
+            known_targets_only: preset != ExtractionPreset::Greedy,
+            any_capitalized: preset == ExtractionPreset::Greedy,
+        }
+    }
+}
+
+// Options as written in a config file: a preset, with any field given
+// overriding it
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct JavaScriptParserConfig {
+    preset: ExtractionPreset,
+    include_type_only: Option<bool>,
+    known_targets_only: Option<bool>,
+    any_capitalized: Option<bool>,
+}
+
+impl From<JavaScriptParserConfig> for JavaScriptParserOptions {
+    fn from(config: JavaScriptParserConfig) -> Self {
+        let preset = Self::preset(config.preset);
+        Self {
+            include_type_only: config.include_type_only.unwrap_or(preset.include_type_only),
+            known_targets_only: config.known_targets_only.unwrap_or(preset.known_targets_only),
+            any_capitalized: config.any_capitalized.unwrap_or(preset.any_capitalized),
+        }
+    }
+}
+
+// A class whose body the parser is in
+struct Frame {
+    name: String,
+    // Brace depth the class was declared at
+    depth: usize,
+    opened: bool,
+}
+
+// What the file brings into scope
+#[derive(Default)]
+struct Imports {
+    // Local name -> the exported name it stands for: `import { Order as
+    // PurchaseOrder }` maps `PurchaseOrder` to `Order`
+    names: HashMap<String, String>,
+    // Modules bound to a name, whose members like `models.Order` count as
+    // imported: `import * as models`, default imports and `require()`
+    modules: HashSet<String>,
+}
+
+pub struct JavaScriptParser {
+    options: JavaScriptParserOptions,
+    class_regex: Regex,
+    esm_import_regex: Regex,
+    require_regex: Regex,
+    new_regex: Regex,
+    member_regex: Regex,
+    jsx_regex: Regex,
+    jsdoc_regex: Regex,
+    method_regex: Regex,
+    arrow_method_regex: Regex,
+}
+
+impl Default for JavaScriptParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JavaScriptParser {
+    pub fn new() -> Self {
+        Self {
+            options: JavaScriptParserOptions::default(),
+            // `class Order extends Base {` and `const Order = class extends Base {`
+            class_regex: Regex::new(
+                r"^\s*(?:export\s+(?:default\s+)?)?(?:class\s+([A-Za-z_$][\w$]*)|(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*=\s*class\b)",
+            )
+            .unwrap(),
+            esm_import_regex: Regex::new(
+                r#"\bimport\s+(?:([A-Za-z_$][\w$]*)\s*,?\s*)?(?:\*\s*as\s+([A-Za-z_$][\w$]*))?(?:\{([^}]*)\})?\s*from\s*['"]"#,
+            )
+            .unwrap(),
+            require_regex: Regex::new(r"\b(?:const|let|var)\s+(?:([A-Za-z_$][\w$]*)|\{([^}]*)\})\s*=\s*require\s*\(").unwrap(),
+            new_regex: Regex::new(r"\bnew\s+((?:[A-Za-z_$][\w$]*\.)*)([A-Z][\w$]*)").unwrap(),
+            // `this.repository = new ...`, or a class field `repository = new ...`
+            member_regex: Regex::new(r"^\s*(this\.)?(?:static\s+)?#?[\w$]+\s*=\s*new\b").unwrap(),
+            jsx_regex: Regex::new(r"(?:^|[^\w.$])<([A-Z][\w.]*)(?:[\s/>]|$)").unwrap(),
+            jsdoc_regex: Regex::new(r"@(?:type|param|returns?|property|prop|throws|typedef)\s*\{([^}]*)\}").unwrap(),
+            method_regex: Regex::new(r"^\s*(?:(?:static|async|get|set)\s+)*\*?#?([A-Za-z_$][\w$]*)\s*\([^;]*$").unwrap(),
+            arrow_method_regex: Regex::new(r"^\s*(?:static\s+)?#?[\w$]+\s*=\s*(?:async\s+)?(?:\([^)]*\)|[\w$]+)\s*=>").unwrap(),
+        }
+    }
+
+    pub fn with_options(options: JavaScriptParserOptions) -> Self {
+        Self { options, ..Self::new() }
+    }
+
+    // Methods, constructors, accessors and arrow-function fields; control
+    // statements look the same to the regex and are filtered out by name
+    fn is_method(&self, line: &str) -> bool {
+        let method = self
+            .method_regex
+            .captures(line)
+            .is_some_and(|caps| !["if", "for", "while", "switch", "catch", "return", "function"].contains(&&caps[1]));
+        method || self.arrow_method_regex.is_match(line)
+    }
+
+    // Multi-line import lists are common, so imports are read from the
+    // whole file
+    fn imports(&self, code: &str) -> Imports {
+        let mut imports = Imports::default();
+        let add_list = |imports: &mut Imports, list: &str, separator: &str| {
+            for entry in list.split(',') {
+                let (exported, local) = entry.split_once(separator).unwrap_or((entry, entry));
+                let (exported, local) = (exported.trim(), local.trim());
+                if !exported.is_empty() && !local.is_empty() {
+                    imports.names.insert(local.to_string(), exported.to_string());
+                }
+            }
+        };
+        for caps in self.esm_import_regex.captures_iter(code) {
+            if let Some(default) = caps.get(1) {
+                imports.names.insert(default.as_str().to_string(), default.as_str().to_string());
+                imports.modules.insert(default.as_str().to_string());
+            }
+            if let Some(namespace) = caps.get(2) {
+                imports.modules.insert(namespace.as_str().to_string());
+            }
+            if let Some(list) = caps.get(3) {
+                add_list(&mut imports, list.as_str(), " as ");
+            }
+        }
+        for caps in self.require_regex.captures_iter(code) {
+            if let Some(module) = caps.get(1) {
+                imports.names.insert(module.as_str().to_string(), module.as_str().to_string());
+                imports.modules.insert(module.as_str().to_string());
+            }
+            if let Some(list) = caps.get(2) {
+                add_list(&mut imports, list.as_str(), ":");
+            }
+        }
+        imports
+    }
+}
+
+// Capitalized names in the `extends` expression of a declaration line:
+// `React.Component` gives `Component`, `mixin(Base, Auditable)` gives both
+fn heritage(line: &str) -> Vec<String> {
+    let Some(start) = line.find("extends").filter(|start| line[..*start].ends_with(char::is_whitespace)) else {
+        return Vec::new();
+    };
+    let expression = &line[start + "extends".len()..];
+    let expression = expression.split('{').next().unwrap_or_default();
+    let name = Regex::new(r"[A-Za-z_$][\w$]*(?:\s*\.\s*[A-Za-z_$][\w$]*)*").unwrap();
+    name.find_iter(expression)
+        .filter_map(|m| m.as_str().rsplit('.').next().map(str::trim))
+        .filter(|name| name.starts_with(|c: char| c.is_uppercase()))
+        .map(str::to_string)
+        .collect()
+}
+
+// The file's lines without comments and with the contents of string and
+// template literals dropped, so braces and names in them do not count.
+// Block comments and template literals may span lines.
+fn code_lines(lines: &[&str]) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut in_comment = false;
+    let mut in_template = false;
+    for line in lines {
+        let chars: Vec<char> = line.chars().collect();
+        let mut code = String::new();
+        let mut quote: Option<char> = None;
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let next = chars.get(i + 1).copied();
+            if in_comment {
+                if c == '*' && next == Some('/') {
+                    in_comment = false;
+                    code.push(' ');
+                    i += 1;
+                }
+            } else if in_template {
+                if c == '\\' {
+                    i += 1;
+                } else if c == '`' {
+                    in_template = false;
+                    code.push(c);
+                }
+            } else if let Some(q) = quote {
+                if c == '\\' {
+                    i += 1;
+                } else if c == q {
+                    quote = None;
+                    code.push(c);
+                }
+            } else if c == '/' && next == Some('/') {
+                break;
+            } else if c == '/' && next == Some('*') {
+                in_comment = true;
+                i += 1;
+            } else {
+                match c {
+                    '`' => in_template = true,
+                    '"' | '\'' => quote = Some(c),
+                    _ => {}
+                }
+                code.push(c);
+            }
+            i += 1;
+        }
+        out.push(code);
+    }
+    out
+}
+
+impl FileAnalyzer for JavaScriptParser {
+    fn analyze(&self, content: &str, _file_path: &str) -> AnalysisResult {
+        let lines: Vec<&str> = content.lines().collect();
+        let code_lines = code_lines(&lines);
+        let imports = self.imports(&code_lines.join("\n"));
+        let classes: HashSet<String> = code_lines
+            .iter()
+            .filter_map(|line| self.class_regex.captures(line))
+            .filter_map(|caps| caps.get(1).or(caps.get(2)).map(|m| m.as_str().to_string()))
+            .collect();
+        let mut links: Vec<ClassLink> = Vec::new();
+        let mut class_starts = Vec::new();
+        let mut stack: Vec<Frame> = Vec::new();
+        let mut depth = 0usize;
+
+        for (index, line) in code_lines.iter().enumerate() {
+            // Names with whether they count as known whatever the file
+            // imports
+            let mut references: Vec<(String, bool, LinkKind)> = Vec::new();
+            let declaration = self.class_regex.captures(line);
+            if let Some(caps) = &declaration {
+                if let Some(name) = caps.get(1).or(caps.get(2)) {
+                    let name = name.as_str().to_string();
+                    class_starts.push((name.clone(), index));
+                    stack.push(Frame { name, depth, opened: false });
+                    // Bases link whether the file imports them or not
+                    references.extend(heritage(line).into_iter().map(|base| (base, true, LinkKind::Inheritance)));
+                }
+            }
+
+            if let Some(frame) = stack.last() {
+                // Instances the class keeps are composition, others usage
+                let member = self.member_regex.captures(line).filter(|caps| caps.get(1).is_some() || depth == frame.depth + 1);
+                let kind = if member.is_some() { LinkKind::Composition } else { LinkKind::Usage };
+                for caps in self.new_regex.captures_iter(line) {
+                    let module = caps[1].split('.').next().unwrap_or_default();
+                    references.push((caps[2].to_string(), imports.modules.contains(module), kind));
+                }
+                // Components a React class renders
+                for caps in self.jsx_regex.captures_iter(line) {
+                    let name = caps[1].rsplit('.').next().unwrap_or_default().to_string();
+                    references.push((name, false, LinkKind::Composition));
+                }
+                // JSDoc is read from the original line, comments included
+                if self.options.include_type_only {
+                    for caps in self.jsdoc_regex.captures_iter(lines[index]) {
+                        for name in preset::capitalized_names(&caps[1], &[]) {
+                            references.push((name.to_string(), false, LinkKind::Usage));
+                        }
+                    }
+                }
+                if self.options.any_capitalized && declaration.is_none() {
+                    for name in preset::capitalized_names(line, &[]) {
+                        references.push((name.to_string(), false, LinkKind::Usage));
+                    }
+                }
+
+                let from_class = &frame.name;
+                for (name, known, kind) in references {
+                    // Links name the exported class, not a local alias
+                    let imported = imports.names.get(&name).cloned();
+                    let known = known || imported.is_some() || classes.contains(&name);
+                    let name = imported.unwrap_or(name);
+                    if name == *from_class || (!known && self.options.known_targets_only) {
+                        continue;
+                    }
+                    // One link per place, the strongest kind first
+                    let already_exists = links
+                        .iter()
+                        .any(|l| l.from_class == *from_class && l.to_class == name && l.line_number == index + 1);
+                    if !already_exists {
+                        links.push(ClassLink {
+                            from_class: from_class.clone(),
+                            to_class: name,
+                            line_number: index + 1,
+                            kind,
+                            position: None,
+                        });
+                    }
+                }
+            }
+
+            for c in line.chars() {
+                match c {
+                    '{' => {
+                        depth += 1;
+                        if let Some(frame) = stack.last_mut() {
+                            frame.opened = true;
+                        }
+                    }
+                    '}' => {
+                        depth = depth.saturating_sub(1);
+                        if stack.last().is_some_and(|frame| frame.opened && depth <= frame.depth) {
+                            stack.pop();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let class_tags = annotations::javascript_tags(&lines, &class_starts);
+        let dependency_budgets = annotations::dependency_budgets(&lines, &class_starts);
+        let class_metrics =
+            metrics::measure_classes(&lines, class_starts, metrics::brace_block_end, |line| self.is_method(line));
+
+        AnalysisResult {
+            classes,
+            links,
+            class_metrics,
+            class_tags,
+            dependency_budgets,
+            ..Default::default()
+        }
+    }
+}
+
+impl LanguageParser for JavaScriptParser {
+    fn language(&self) -> &str {
+        "javascript"
+    }
+
+    fn extensions(&self) -> Vec<String> {
+        vec!["js".to_string(), "jsx".to_string(), "mjs".to_string()]
+    }
+}
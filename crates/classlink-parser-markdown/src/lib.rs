@@ -0,0 +1,402 @@
+use std::collections::{BTreeMap, HashSet};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use classlink_core::analysis::{AnalysisResult, FileAnalyzer};
+use classlink_core::link::{pascal_case, ClassLink, LinkKind};
+use classlink_core::registry::LanguageParser;
+
+// Goes into the parse cache key, so a parser change re-parses its files
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Which `[text](target)` links name a class. `pattern` is a regex over the
+// target; its `class` group, or else its first group, is the class name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkTarget {
+    pub pattern: String,
+    // Turns a slug such as `user-service` or `user_service` into `UserService`
+    #[serde(default)]
+    pub slug: bool,
+}
+
+impl LinkTarget {
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            slug: false,
+        }
+    }
+
+    pub fn slug(mut self) -> Self {
+        self.slug = true;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarkdownParserOptions {
+    // Names ending in `Class` mentioned in prose or code
+    pub include_code_references: bool,
+    // Tried in order, the first match wins; links matching none are ignored
+    pub link_targets: Vec<LinkTarget>,
+}
+
+impl Default for MarkdownParserOptions {
+    fn default() -> Self {
+        Self {
+            include_code_references: true,
+            link_targets: vec![LinkTarget::new(r"^(.+)\.md$")],
+        }
+    }
+}
+
+impl MarkdownParserOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        for target in &self.link_targets {
+            Regex::new(&target.pattern).map_err(|e| format!("markdown link target '{}': {}", target.pattern, e))?;
+        }
+        Ok(())
+    }
+}
+
+pub struct MarkdownParser {
+    options: MarkdownParserOptions,
+    header_regex: Regex,
+    link_regex: Regex,
+    code_class_regex: Regex,
+    // Compiled `options.link_targets`; invalid patterns are left out, see
+    // `MarkdownParserOptions::validate`
+    link_targets: Vec<(Regex, bool)>,
+}
+
+impl Default for MarkdownParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MarkdownParser {
+    pub fn new() -> Self {
+        Self::with_options(MarkdownParserOptions::default())
+    }
+    
+    pub fn with_options(options: MarkdownParserOptions) -> Self {
+        let link_targets = options
+            .link_targets
+            .iter()
+            .filter_map(|target| Regex::new(&target.pattern).ok().map(|regex| (regex, target.slug)))
+            .collect();
+        Self {
+            options,
+            header_regex: Self::create_header_regex(),
+            link_regex: Self::create_link_regex(),
+            code_class_regex: Self::create_code_class_regex(),
+            link_targets,
+        }
+    }
+
+    fn create_header_regex() -> Regex {
+        Regex::new(r"^#\s+(\w+)").unwrap()
+    }
+    
+    fn create_link_regex() -> Regex {
+        Regex::new(r"\[([^\]]+)\]\(([^)]+)\)").unwrap()
+    }
+    
+    fn create_code_class_regex() -> Regex {
+        Regex::new(r"\b(\w+Class)\b").unwrap()
+    }
+
+    pub fn parse_file(&self, content: &str) -> Vec<ClassLink> {
+        let mut links = Vec::new();
+        let mut current_class = None;
+        let mut seen_links = HashSet::new();
+        let skipped = assertion_block_lines(content);
+        
+        for (line_num, line) in content.lines().enumerate() {
+            if skipped[line_num] {
+                continue;
+            }
+            if let Some(class_name) = self.extract_class_from_header(line) {
+                current_class = Some(class_name);
+            }
+            
+            if let Some(ref from_class) = current_class {
+                self.process_markdown_links(line, from_class, line_num + 1, &mut links, &mut seen_links);
+                if self.options.include_code_references {
+                    self.process_code_class_references(line, from_class, line_num + 1, &mut links, &mut seen_links);
+                }
+            }
+        }
+        
+        links
+    }
+    
+    fn extract_class_from_header(&self, line: &str) -> Option<String> {
+        self.header_regex.captures(line).map(|caps| caps[1].to_string())
+    }
+    
+    fn process_markdown_links(
+        &self,
+        line: &str,
+        from_class: &str,
+        line_number: usize,
+        links: &mut Vec<ClassLink>,
+        seen_links: &mut HashSet<(String, String)>,
+    ) {
+        for caps in self.link_regex.captures_iter(line) {
+            if let Some(to_class) = self.link_target_class(caps[2].trim()) {
+                self.add_link_if_new(from_class, &to_class, line_number, links, seen_links);
+            }
+        }
+    }
+
+    fn link_target_class(&self, target: &str) -> Option<String> {
+        self.link_targets.iter().find_map(|(regex, slug)| {
+            let caps = regex.captures(target)?;
+            let name = caps.name("class").or_else(|| caps.get(1))?.as_str();
+            let name = if *slug { pascal_case(name) } else { name.to_string() };
+            (!name.is_empty()).then_some(name)
+        })
+    }
+    
+    fn process_code_class_references(
+        &self,
+        line: &str,
+        from_class: &str,
+        line_number: usize,
+        links: &mut Vec<ClassLink>,
+        seen_links: &mut HashSet<(String, String)>,
+    ) {
+        for caps in self.code_class_regex.captures_iter(line) {
+            let class_name = &caps[1];
+            if self.is_valid_class_reference(class_name, from_class, line) {
+                self.add_link_if_new(from_class, class_name, line_number, links, seen_links);
+            }
+        }
+    }
+    
+    fn is_valid_class_reference(&self, class_name: &str, from_class: &str, line: &str) -> bool {
+        class_name != from_class && !line.contains(&format!("[{}]", class_name))
+    }
+    
+    fn add_link_if_new(
+        &self,
+        from_class: &str,
+        to_class: &str,
+        line_number: usize,
+        links: &mut Vec<ClassLink>,
+        seen_links: &mut HashSet<(String, String)>,
+    ) {
+        let link_key = (from_class.to_string(), to_class.to_string());
+        if !seen_links.contains(&link_key) {
+            seen_links.insert(link_key);
+            links.push(ClassLink {
+                from_class: from_class.to_string(),
+                to_class: to_class.to_string(),
+                line_number,
+                kind: LinkKind::Doc,
+                position: None,
+            });
+        }
+    }
+
+    pub fn extract_classes(&self, content: &str) -> HashSet<String> {
+        let mut classes = HashSet::new();
+        let skipped = assertion_block_lines(content);
+        
+        for (line_num, line) in content.lines().enumerate() {
+            if skipped[line_num] {
+                continue;
+            }
+            if let Some(caps) = self.header_regex.captures(line) {
+                classes.insert(caps[1].to_string());
+            }
+        }
+        
+        classes
+    }
+
+    // Statements of ```classlink-assert blocks by line number; blank lines
+    // and `#` comments are left out
+    pub fn extract_assertions(&self, content: &str) -> BTreeMap<usize, String> {
+        let skipped = assertion_block_lines(content);
+        content
+            .lines()
+            .enumerate()
+            .filter(|(index, line)| skipped[*index] && !line.trim_start().starts_with("```"))
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(line, text)| (line, text.to_string()))
+            .collect()
+    }
+}
+
+// Which lines, fences included, belong to a ```classlink-assert block. Their
+// `#` comments are not headers and their class names are not links.
+fn assertion_block_lines(content: &str) -> Vec<bool> {
+    let mut in_block = false;
+    let mut inside = Vec::new();
+    for line in content.lines() {
+        let fence = line.trim_start().strip_prefix("```");
+        if !in_block && fence.is_some_and(|info| info.trim() == "classlink-assert") {
+            in_block = true;
+            inside.push(true);
+        } else if in_block {
+            inside.push(true);
+            in_block = fence.is_none();
+        } else {
+            inside.push(false);
+        }
+    }
+    inside
+}
+
+impl FileAnalyzer for MarkdownParser {
+    fn analyze(&self, content: &str, _file_path: &str) -> AnalysisResult {
+        AnalysisResult {
+            classes: self.extract_classes(content),
+            links: self.parse_file(content),
+            assertions: self.extract_assertions(content),
+            ..Default::default()
+        }
+    }
+}
+
+impl LanguageParser for MarkdownParser {
+    fn language(&self) -> &str {
+        "markdown"
+    }
+
+    fn extensions(&self) -> Vec<String> {
+        vec!["md".to_string()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_simple_class_link() {
+        let parser = MarkdownParser::new();
+        let content = r#"# MyClass
+
+This class inherits from [BaseClass](BaseClass.md).
+"#;
+        let links = parser.parse_file(content);
+        
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].from_class, "MyClass");
+        assert_eq!(links[0].to_class, "BaseClass");
+        assert_eq!(links[0].line_number, 3);
+    }
+
+    #[test]
+    fn test_extract_classes_from_headers() {
+        let parser = MarkdownParser::new();
+        let content = r#"# ClassA
+
+## Description
+
+# ClassB
+
+### Methods
+"#;
+        let classes = parser.extract_classes(content);
+        
+        assert_eq!(classes.len(), 2);
+        assert!(classes.contains("ClassA"));
+        assert!(classes.contains("ClassB"));
+    }
+
+    #[test]
+    fn test_parse_multiple_links() {
+        let parser = MarkdownParser::new();
+        let content = r#"# MyClass
+
+This class uses [HelperClass](HelperClass.md) and extends [BaseClass](BaseClass.md).
+It also implements [Interface](Interface.md).
+"#;
+        let links = parser.parse_file(content);
+        
+        assert_eq!(links.len(), 3);
+        
+        let link_pairs: Vec<(String, String)> = links.iter()
+            .map(|l| (l.from_class.clone(), l.to_class.clone()))
+            .collect();
+        
+        assert!(link_pairs.contains(&("MyClass".to_string(), "HelperClass".to_string())));
+        assert!(link_pairs.contains(&("MyClass".to_string(), "BaseClass".to_string())));
+        assert!(link_pairs.contains(&("MyClass".to_string(), "Interface".to_string())));
+    }
+
+    #[test]
+    fn test_parse_code_class_references() {
+        let parser = MarkdownParser::new();
+        let content = r#"# MyClass
+
+```rust
+struct MyClass {
+    helper: HelperClass,
+}
+```
+
+The `MyClass` uses `HelperClass` internally.
+"#;
+        let links = parser.parse_file(content);
+        
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].from_class, "MyClass");
+        assert_eq!(links[0].to_class, "HelperClass");
+    }
+
+    #[test]
+    fn test_assertion_blocks_are_not_parsed_as_docs() {
+        let parser = MarkdownParser::new();
+        let content = r#"# UserService
+
+Talks to [UserRepository](UserRepository.md) only.
+
+```classlink-assert
+# services go through repositories
+UserService -> UserRepository
+UserService -> !DatabaseContext
+```
+"#;
+        let result = parser.analyze(content, "docs/UserService.md");
+
+        let assertions: Vec<(usize, &str)> = result.assertions.iter().map(|(line, text)| (*line, text.as_str())).collect();
+        assert_eq!(assertions, vec![(7, "UserService -> UserRepository"), (8, "UserService -> !DatabaseContext")]);
+        assert_eq!(result.classes.len(), 1);
+        assert_eq!(result.links.len(), 1);
+    }
+
+    #[test]
+    fn test_configurable_link_targets() {
+        let parser = MarkdownParser::with_options(MarkdownParserOptions {
+            include_code_references: false,
+            link_targets: vec![
+                LinkTarget::new(r"^(.+)\.md$"),
+                LinkTarget::new(r"(?:^|/)(?P<class>\w+)\.(?:py|ts|cs)$"),
+                LinkTarget::new(r"^https://wiki\.example\.com/classes/([\w-]+)$").slug(),
+                LinkTarget::new(r"^#([\w-]+)$").slug(),
+            ],
+        });
+        let content = r#"# Shop
+
+Uses [Cart](Cart.md), [the model](src/models/User.py) and [billing](https://wiki.example.com/classes/invoice-service).
+See [below](#payment_gateway), but not [the site](https://example.com).
+"#;
+        let links = parser.parse_file(content);
+
+        let targets: Vec<&str> = links.iter().map(|link| link.to_class.as_str()).collect();
+        assert_eq!(targets, vec!["Cart", "User", "InvoiceService", "PaymentGateway"]);
+        assert!(MarkdownParserOptions::default().validate().is_ok());
+        let invalid = MarkdownParserOptions {
+            link_targets: vec![LinkTarget::new("(")],
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
@@ -0,0 +1,431 @@
+pub mod notebook;
+
+use regex::Regex;
+use std::collections::HashSet;
+use classlink_core::{annotations, endpoints, metrics};
+use classlink_core::link::{pascal_case, ClassLink, LinkKind};
+use classlink_core::analysis::{AnalysisResult, FileAnalyzer};
+use classlink_core::preset::{self, ExtractionPreset};
+use classlink_core::registry::LanguageParser;
+use crate::notebook::NotebookSource;
+use serde::{Deserialize, Serialize};
+
+// Goes into the parse cache key, so a parser change re-parses its files
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "PythonParserConfig")]
+pub struct PythonParserOptions {
+    // Link the file's last class to every class it imports
+    pub count_imports_as_links: bool,
+    // Type hints like `user: User`, which create no runtime dependency
+    pub include_type_only: bool,
+    // Django and SQLAlchemy relationship fields, like `ForeignKey(User)`
+    // or `relationship("Order")`, as composition between models
+    pub orm_relationships: bool,
+    // Instantiations like `Mailer()`
+    pub instantiations: bool,
+    // Every capitalized name in a class body, `True`, `False` and `None` aside
+    pub any_capitalized: bool,
+}
+
+impl Default for PythonParserOptions {
+    fn default() -> Self {
+        Self::preset(ExtractionPreset::Balanced)
+    }
+}
+
+impl PythonParserOptions {
+    pub fn preset(preset: ExtractionPreset) -> Self {
+        Self {
+            count_imports_as_links: true,
+            include_type_only: preset != ExtractionPreset::Strict,
+            orm_relationships: true,
+            instantiations: preset != ExtractionPreset::Strict,
+            any_capitalized: preset == ExtractionPreset::Greedy,
+        }
+    }
+}
+
+// Options as written in a config file: a preset, with any field given
+// overriding it
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PythonParserConfig {
+    preset: ExtractionPreset,
+    count_imports_as_links: Option<bool>,
+    include_type_only: Option<bool>,
+    orm_relationships: Option<bool>,
+    instantiations: Option<bool>,
+    any_capitalized: Option<bool>,
+}
+
+impl From<PythonParserConfig> for PythonParserOptions {
+    fn from(config: PythonParserConfig) -> Self {
+        let preset = Self::preset(config.preset);
+        Self {
+            count_imports_as_links: config.count_imports_as_links.unwrap_or(preset.count_imports_as_links),
+            include_type_only: config.include_type_only.unwrap_or(preset.include_type_only),
+            orm_relationships: config.orm_relationships.unwrap_or(preset.orm_relationships),
+            instantiations: config.instantiations.unwrap_or(preset.instantiations),
+            any_capitalized: config.any_capitalized.unwrap_or(preset.any_capitalized),
+        }
+    }
+}
+
+pub struct PythonParser {
+    options: PythonParserOptions,
+    class_regex: Regex,
+    bases_regex: Regex,
+    self_assignment_regex: Regex,
+    import_regex: Regex,
+    from_import_regex: Regex,
+    method_regex: Regex,
+    relationship_regex: Regex,
+    registration_regex: Regex,
+    connect_regex: Regex,
+    receiver_regex: Regex,
+    send_regex: Regex,
+    route_regex: Regex,
+    url_pattern_regex: Regex,
+    http_client_regex: Regex,
+}
+
+// Django sends these for every model save or delete, so their event is the
+// sender model rather than the signal
+const MODEL_SIGNALS: &[&str] = &["pre_save", "post_save", "pre_delete", "post_delete", "m2m_changed"];
+
+impl Default for PythonParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PythonParser {
+    pub fn new() -> Self {
+        Self {
+            options: PythonParserOptions::default(),
+            class_regex: Regex::new(r"^\s*class\s+(\w+)").unwrap(),
+            bases_regex: Regex::new(r"^\s*class\s+\w+\s*\(([^)]*)\)").unwrap(),
+            self_assignment_regex: Regex::new(r"\bself\.\w+\s*(?::[^=]+)?=").unwrap(),
+            import_regex: Regex::new(r"^\s*import\s+(.+)").unwrap(),
+            from_import_regex: Regex::new(r"^\s*from\s+[\w.]+\s+import\s+(.+)").unwrap(),
+            method_regex: Regex::new(r"^\s+(?:async\s+)?def\s+\w+").unwrap(),
+            relationship_regex: Regex::new(
+                r#"\b(?:ForeignKey|OneToOneField|ManyToManyField|relationship)\(\s*(?:"([\w.]+)"|'([\w.]+)'|([A-Za-z_][\w.]*))"#,
+            )
+            .unwrap(),
+            registration_regex: Regex::new(r"\.(?:register|bind)\(\s*([A-Z][\w.]*)\s*,\s*(?:to\s*=\s*)?([A-Z][\w.]*)").unwrap(),
+            connect_regex: Regex::new(r"\b(\w+)\.connect\(\s*([A-Z]\w*)(?:\.\w+)?(?:[^)]*\bsender\s*=\s*([A-Z]\w*))?").unwrap(),
+            receiver_regex: Regex::new(r"^\s+@receiver\(\s*(\w+)(?:[^)]*\bsender\s*=\s*([A-Z]\w*))?").unwrap(),
+            send_regex: Regex::new(r"\b(\w+)\.send\(\s*sender\s*=").unwrap(),
+            route_regex: Regex::new(r#"^(\s*)@\w+\.(?:get|post|put|patch|delete|route|api_route)\(\s*['"]([^'"]*)['"]"#).unwrap(),
+            url_pattern_regex: Regex::new(r#"\bpath\(\s*['"]([^'"]*)['"]\s*,\s*([A-Z]\w*)\.as_view"#).unwrap(),
+            http_client_regex: Regex::new(r#"\b(?:requests|httpx|session|client)\.(?:get|post|put|patch|delete)\(\s*f?['"]([^'"]+)['"]"#).unwrap(),
+        }
+    }
+
+    pub fn with_options(options: PythonParserOptions) -> Self {
+        Self { options, ..Self::new() }
+    }
+    
+    fn extract_base_classes(&self, line: &str) -> Vec<String> {
+        let Some(caps) = self.bases_regex.captures(line) else {
+            return Vec::new();
+        };
+
+        // `models.Base` -> `Base`; keyword arguments like `metaclass=ABCMeta` are skipped
+        caps[1]
+            .split(',')
+            .map(str::trim)
+            .filter(|base| !base.contains('='))
+            .filter_map(|base| base.rsplit('.').next())
+            .filter(|base| base.chars().next().is_some_and(|c| c.is_uppercase()))
+            .map(str::to_string)
+            .collect()
+    }
+
+    // A custom signal `order_created` stands for the event class `OrderCreated`
+    fn signal_event(signal: &str, sender: Option<&str>) -> String {
+        match sender {
+            Some(sender) if MODEL_SIGNALS.contains(&signal) => sender.to_string(),
+            _ => pascal_case(signal),
+        }
+    }
+
+    // The target may be a class or a string naming it, with an optional
+    // `app_label.` prefix. SQLAlchemy's `ForeignKey("users.id")` names a
+    // table column, not a class, and is skipped like any lowercase name.
+    fn extract_relationships(&self, line: &str) -> Vec<String> {
+        self.relationship_regex
+            .captures_iter(line)
+            .filter_map(|caps| {
+                let target = caps.get(1).or(caps.get(2)).or(caps.get(3))?.as_str();
+                let name = target.rsplit('.').next()?;
+                name.chars().next().is_some_and(|c| c.is_uppercase()).then(|| name.to_string())
+            })
+            .collect()
+    }
+
+    fn extract_class_references(&self, line: &str) -> Vec<(String, LinkKind)> {
+        let mut references = Vec::new();
+
+        if self.options.orm_relationships {
+            for model in self.extract_relationships(line) {
+                references.push((model, LinkKind::Composition));
+            }
+        }
+        
+        // Find class instantiations like DatabaseConnection(); assigning one
+        // to `self.db` makes it a component of the class
+        let instantiation_kind = if self.self_assignment_regex.is_match(line) {
+            LinkKind::Composition
+        } else {
+            LinkKind::Usage
+        };
+        if self.options.instantiations {
+            let instantiation_regex = Regex::new(r"\b([A-Z]\w+)\s*\(").unwrap();
+            for caps in instantiation_regex.captures_iter(line) {
+                references.push((caps[1].to_string(), instantiation_kind));
+            }
+        }
+        
+        // Find type hints like user: User
+        if self.options.include_type_only {
+            let type_hint_regex = Regex::new(r":\s*([A-Z]\w+)").unwrap();
+            for caps in type_hint_regex.captures_iter(line) {
+                references.push((caps[1].to_string(), LinkKind::Usage));
+            }
+        }
+
+        if self.options.any_capitalized {
+            for name in preset::capitalized_names(line, &["True", "False", "None"]) {
+                references.push((name.to_string(), LinkKind::Usage));
+            }
+        }
+        
+        references
+    }
+}
+
+// `app/api/users.py` is the module `users`
+fn module_name(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+impl FileAnalyzer for PythonParser {
+    fn analyze(&self, content: &str, file_path: &str) -> AnalysisResult {
+        let mut classes = HashSet::new();
+        let mut links = Vec::new();
+        let mut current_class = None;
+        let mut imported_classes = HashSet::new();
+        let mut class_starts = Vec::new();
+        let lines: Vec<&str> = content.lines().collect();
+        
+        for (line_num, line) in lines.iter().copied().enumerate() {
+            // Check for class definitions
+            if let Some(caps) = self.class_regex.captures(line) {
+                let class_name = caps[1].to_string();
+                classes.insert(class_name.clone());
+                class_starts.push((class_name.clone(), line_num));
+
+                for base in self.extract_base_classes(line) {
+                    links.push(ClassLink {
+                        from_class: class_name.clone(),
+                        to_class: base,
+                        line_number: line_num + 1,
+                        kind: LinkKind::Inheritance,
+                        position: None,
+                    });
+                }
+                current_class = Some(class_name);
+            }
+            
+            // Check for imports
+            if let Some(caps) = self.from_import_regex.captures(line) {
+                let imports = &caps[1];
+                for import in imports.split(',') {
+                    let class_name = import.split_whitespace().next().unwrap_or("");
+                    if class_name.chars().next().is_some_and(|c| c.is_uppercase()) {
+                        imported_classes.insert(class_name.to_string());
+                    }
+                }
+            } else if let Some(caps) = self.import_regex.captures(line) {
+                let module = &caps[1];
+                // Simple heuristic: if it starts with uppercase, consider it a class
+                if module.chars().next().is_some_and(|c| c.is_uppercase()) {
+                    imported_classes.insert(module.to_string());
+                }
+            }
+            
+            // Container registrations like `container.register(IUserRepo, SqlUserRepo)`
+            // or injector's `binder.bind(IUserRepo, to=SqlUserRepo)`
+            for caps in self.registration_regex.captures_iter(line) {
+                let name = |capture: &str| capture.rsplit('.').next().unwrap_or_default().to_string();
+                links.push(ClassLink {
+                    from_class: name(&caps[1]),
+                    to_class: name(&caps[2]),
+                    line_number: line_num + 1,
+                    kind: LinkKind::Binding,
+                    position: None,
+                });
+            }
+
+            // Endpoints: FastAPI and Flask routes are bound to their class, or to
+            // the module when the view is a plain function; Django `path()`
+            // entries to their class-based view. Client calls use them.
+            if let Some(caps) = self.route_regex.captures(line) {
+                let handler = match &current_class {
+                    Some(class) if !caps[1].is_empty() => class.clone(),
+                    _ => module_name(file_path),
+                };
+                links.push(ClassLink {
+                    from_class: endpoints::normalize(&caps[2]),
+                    to_class: handler,
+                    line_number: line_num + 1,
+                    kind: LinkKind::Binding,
+                    position: None,
+                });
+            }
+            for caps in self.url_pattern_regex.captures_iter(line) {
+                links.push(ClassLink {
+                    from_class: endpoints::normalize(&caps[1]),
+                    to_class: caps[2].to_string(),
+                    line_number: line_num + 1,
+                    kind: LinkKind::Binding,
+                    position: None,
+                });
+            }
+            if let Some(ref class) = current_class {
+                for caps in self.http_client_regex.captures_iter(line) {
+                    if let Some(endpoint) = endpoints::client_endpoint(&caps[1]) {
+                        links.push(ClassLink {
+                            from_class: class.clone(),
+                            to_class: endpoint,
+                            line_number: line_num + 1,
+                            kind: LinkKind::Usage,
+                            position: None,
+                        });
+                    }
+                }
+            }
+
+            // Signal handlers, `order_created.connect(Mailer.on_order)` or a
+            // `@receiver(post_save, sender=Order)` method, are bound to the
+            // signal's event; `order_created.send(sender=...)` uses it
+            for caps in self.connect_regex.captures_iter(line) {
+                links.push(ClassLink {
+                    from_class: Self::signal_event(&caps[1], caps.get(3).map(|m| m.as_str())),
+                    to_class: caps[2].to_string(),
+                    line_number: line_num + 1,
+                    kind: LinkKind::Binding,
+                    position: None,
+                });
+            }
+            if let Some(ref class) = current_class {
+                if let Some(caps) = self.receiver_regex.captures(line) {
+                    links.push(ClassLink {
+                        from_class: Self::signal_event(&caps[1], caps.get(2).map(|m| m.as_str())),
+                        to_class: class.clone(),
+                        line_number: line_num + 1,
+                        kind: LinkKind::Binding,
+                        position: None,
+                    });
+                }
+                for caps in self.send_regex.captures_iter(line) {
+                    links.push(ClassLink {
+                        from_class: class.clone(),
+                        to_class: pascal_case(&caps[1]),
+                        line_number: line_num + 1,
+                        kind: LinkKind::Usage,
+                        position: None,
+                    });
+                }
+            }
+
+            // Find class references in the current context
+            if let Some(ref from_class) = current_class {
+                for (to_class, kind) in self.extract_class_references(line) {
+                    let already_exists = links.iter().any(|l: &ClassLink| {
+                        l.from_class == *from_class && l.to_class == to_class && l.line_number == line_num + 1
+                    });
+                    if &to_class != from_class && !already_exists {
+                        links.push(ClassLink {
+                            from_class: from_class.clone(),
+                            to_class,
+                            line_number: line_num + 1,
+                            kind,
+                            position: None,
+                        });
+                    }
+                }
+            }
+        }
+        
+        // Create links from the current class to imported classes
+        if let Some(current_class) = current_class.as_ref().filter(|_| self.options.count_imports_as_links) {
+            for imported in &imported_classes {
+                // Check if we already have this link
+                let already_exists = links.iter().any(|l| l.from_class == *current_class && l.to_class == *imported);
+                if !already_exists {
+                    links.push(ClassLink {
+                        from_class: current_class.clone(),
+                        to_class: imported.clone(),
+                        line_number: 1, // Import typically at top of file
+                        kind: LinkKind::Usage,
+                        position: None,
+                    });
+                }
+            }
+        }
+        
+        let class_tags = annotations::python_tags(&lines, &class_starts);
+        let dependency_budgets = annotations::dependency_budgets(&lines, &class_starts);
+        let class_metrics = metrics::measure_classes(&lines, class_starts, metrics::indented_block_end, |line| {
+            self.method_regex.is_match(line)
+        });
+
+        AnalysisResult {
+            classes,
+            links,
+            class_metrics,
+            class_tags,
+            dependency_budgets,
+            ..Default::default()
+        }
+    }
+}
+
+impl PythonParser {
+    // Line numbers point into the `.ipynb` file, so locations open in an
+    // editor; a notebook that is not valid JSON yields nothing
+    pub fn analyze_notebook(&self, content: &str, path: &str) -> AnalysisResult {
+        let Ok(notebook) = NotebookSource::parse(content) else {
+            return AnalysisResult::default();
+        };
+        let mut result = self.analyze(&notebook.script, path);
+        let file_line = |line: usize| notebook.origin(line).map_or(line, |origin| origin.file_line);
+        for link in &mut result.links {
+            link.line_number = file_line(link.line_number);
+        }
+        for metrics in result.class_metrics.values_mut() {
+            metrics.start_line = file_line(metrics.start_line);
+            metrics.end_line = file_line(metrics.end_line);
+        }
+        result
+    }
+}
+
+impl LanguageParser for PythonParser {
+    fn language(&self) -> &str {
+        "python"
+    }
+
+    fn extensions(&self) -> Vec<String> {
+        vec!["py".to_string()]
+    }
+}
@@ -0,0 +1,201 @@
+use serde::Deserialize;
+
+// The parts of an nbformat 4 notebook the analysis reads; outputs and
+// metadata are ignored
+#[derive(Deserialize)]
+struct RawNotebook {
+    #[serde(default)]
+    cells: Vec<RawCell>,
+}
+
+#[derive(Deserialize)]
+struct RawCell {
+    cell_type: String,
+    #[serde(default)]
+    source: RawSource,
+}
+
+// nbformat allows both a list of lines and a single string
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawSource {
+    Lines(Vec<String>),
+    Text(String),
+}
+
+impl Default for RawSource {
+    fn default() -> Self {
+        RawSource::Text(String::new())
+    }
+}
+
+impl RawSource {
+    fn lines(&self) -> Vec<String> {
+        match self {
+            RawSource::Lines(lines) => lines.concat().lines().map(str::to_string).collect(),
+            RawSource::Text(text) => text.lines().map(str::to_string).collect(),
+        }
+    }
+}
+
+// Where a line of the concatenated script came from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellLine {
+    // Index of the cell among all cells of the notebook, markdown included
+    pub cell: usize,
+    // 1-based line within the cell
+    pub line: usize,
+    // 1-based line of the `.ipynb` file holding it
+    pub file_line: usize,
+}
+
+// The code cells of a notebook joined into one Python script, one blank
+// line between cells, with the origin of every script line
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NotebookSource {
+    pub script: String,
+    // By script line, starting at line 1; the separator lines map to the
+    // cell they follow
+    pub lines: Vec<CellLine>,
+}
+
+pub fn is_notebook(path: &str) -> bool {
+    path.ends_with(".ipynb")
+}
+
+// IPython magics (`%matplotlib`, `!pip install`) are not Python, so their
+// lines are blanked, and so are whole cells run by a `%%` cell magic
+fn is_magic(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with('%') || line.starts_with('!')
+}
+
+impl NotebookSource {
+    pub fn parse(content: &str) -> serde_json::Result<Self> {
+        let notebook: RawNotebook = serde_json::from_str(content)?;
+        let mut source = NotebookSource::default();
+        let mut locator = FileLocator::new(content);
+        for (cell, raw) in notebook.cells.iter().enumerate() {
+            locator.next_cell();
+            if raw.cell_type != "code" {
+                continue;
+            }
+            let lines = raw.source.lines();
+            let cell_magic = lines.first().is_some_and(|line| line.trim_start().starts_with("%%"));
+            let mut last = None;
+            for (index, line) in lines.iter().enumerate() {
+                let file_line = match raw.source {
+                    RawSource::Lines(_) => locator.find(line),
+                    RawSource::Text(_) => locator.line(),
+                };
+                let origin = CellLine {
+                    cell,
+                    line: index + 1,
+                    file_line,
+                };
+                if !cell_magic && !is_magic(line) {
+                    source.script.push_str(line);
+                }
+                source.script.push('\n');
+                source.lines.push(origin);
+                last = Some(origin);
+            }
+            if let Some(last) = last {
+                source.script.push('\n');
+                source.lines.push(last);
+            }
+        }
+        Ok(source)
+    }
+
+    pub fn origin(&self, script_line: usize) -> Option<CellLine> {
+        self.lines.get(script_line.checked_sub(1)?).copied()
+    }
+}
+
+// Finds the file line of each source line by searching the raw JSON in
+// document order, from the cell's `source` key on. A single-string source
+// keeps the whole cell on one file line, so it all maps to that line.
+struct FileLocator<'a> {
+    content: &'a str,
+    at: usize,
+}
+
+impl<'a> FileLocator<'a> {
+    fn new(content: &'a str) -> Self {
+        Self { content, at: 0 }
+    }
+
+    fn line(&self) -> usize {
+        self.content[..self.at].matches('\n').count() + 1
+    }
+
+    fn next_cell(&mut self) {
+        if let Some(offset) = self.content[self.at..].find("\"source\"") {
+            self.at += offset + "\"source\"".len();
+        }
+    }
+
+    // Moves past the first list element starting with `line`
+    fn find(&mut self, line: &str) -> usize {
+        let encoded = serde_json::to_string(line).unwrap_or_default();
+        let prefix = encoded.strip_suffix('"').unwrap_or(&encoded);
+        let Some(offset) = self.content[self.at..].find(prefix) else {
+            return self.line();
+        };
+        self.at += offset;
+        let found = self.line();
+        let mut escaped = false;
+        for (index, c) in self.content[self.at + 1..].char_indices() {
+            match c {
+                '\\' if !escaped => escaped = true,
+                '"' if !escaped => {
+                    self.at += index + 2;
+                    break;
+                }
+                _ => escaped = false,
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_joins_code_cells_and_maps_lines() {
+        let content = r##"{
+ "cells": [
+  {
+   "cell_type": "markdown",
+   "metadata": {},
+   "source": ["# Prototype\n"]
+  },
+  {
+   "cell_type": "code",
+   "metadata": {},
+   "source": [
+    "%matplotlib inline\n",
+    "class Cart:\n",
+    "    pass"
+   ]
+  },
+  {
+   "cell_type": "code",
+   "source": "class Shop:\n    def __init__(self):\n        self.cart = Cart()"
+  }
+ ],
+ "nbformat": 4
+}"##;
+
+        let source = NotebookSource::parse(content).unwrap();
+
+        assert_eq!(source.script, "\nclass Cart:\n    pass\n\nclass Shop:\n    def __init__(self):\n        self.cart = Cart()\n\n");
+        assert_eq!(source.origin(2), Some(CellLine { cell: 1, line: 2, file_line: 13 }));
+        assert_eq!(source.origin(7).map(|origin| (origin.cell, origin.line, origin.file_line)), Some((2, 3, 19)));
+        assert_eq!(source.origin(0), None);
+        assert!(NotebookSource::parse("not json").is_err());
+    }
+}
@@ -0,0 +1,523 @@
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use classlink_core::{annotations, metrics};
+use classlink_core::link::{ClassLink, LinkKind};
+use classlink_core::analysis::{AnalysisResult, FileAnalyzer};
+use classlink_core::preset::{self, ExtractionPreset};
+use classlink_core::registry::LanguageParser;
+use serde::{Deserialize, Serialize};
+
+// Goes into the parse cache key, so a parser change re-parses its files
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RustParserConfig")]
+pub struct RustParserOptions {
+    // Parameter and return types of function signatures, as opposed to
+    // fields, variants, trait impls and supertraits
+    pub include_type_only: bool,
+    // Only link to types the file defines, brings in with `use` or writes
+    // as a path like `billing::Invoice`; off links any capitalized type name
+    pub known_targets_only: bool,
+    // Every capitalized name in an item body, subject to `known_targets_only`
+    pub any_capitalized: bool,
+}
+
+impl Default for RustParserOptions {
+    fn default() -> Self {
+        Self::preset(ExtractionPreset::Balanced)
+    }
+}
+
+impl RustParserOptions {
+    pub fn preset(preset: ExtractionPreset) -> Self {
+        Self {
+            include_type_only: preset != ExtractionPreset::Strict,
+            known_targets_only: preset != ExtractionPreset::Greedy,
+            any_capitalized: preset == ExtractionPreset::Greedy,
+        }
+    }
+}
+
+// Options as written in a config file: a preset, with any field given
+// overriding it
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct RustParserConfig {
+    preset: ExtractionPreset,
+    include_type_only: Option<bool>,
+    known_targets_only: Option<bool>,
+    any_capitalized: Option<bool>,
+}
+
+impl From<RustParserConfig> for RustParserOptions {
+    fn from(config: RustParserConfig) -> Self {
+        let preset = Self::preset(config.preset);
+        Self {
+            include_type_only: config.include_type_only.unwrap_or(preset.include_type_only),
+            known_targets_only: config.known_targets_only.unwrap_or(preset.known_targets_only),
+            any_capitalized: config.any_capitalized.unwrap_or(preset.any_capitalized),
+        }
+    }
+}
+
+// Capitalized names that are not types: the implementing type itself and
+// the `Option`/`Result` variants
+const NOT_TYPES: &[&str] = &["Self", "Some", "None", "Ok", "Err"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ItemKind {
+    // `struct`, `enum` and `union`
+    Data,
+    Trait,
+    Impl,
+}
+
+// The item whose declaration or body the parser is in
+struct Item {
+    name: String,
+    kind: ItemKind,
+    // Brace depth the item was declared at
+    depth: usize,
+    opened: bool,
+}
+
+pub struct RustParser {
+    options: RustParserOptions,
+    item_regex: Regex,
+    use_regex: Regex,
+    fn_regex: Regex,
+    type_regex: Regex,
+    import_regex: Regex,
+}
+
+impl Default for RustParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RustParser {
+    pub fn new() -> Self {
+        Self {
+            options: RustParserOptions::default(),
+            item_regex: Regex::new(r"^\s*(?:pub(?:\s*\([^)]*\))?\s+)?(?:unsafe\s+)?(struct|enum|union|trait)\s+([A-Za-z_]\w*)").unwrap(),
+            use_regex: Regex::new(r"^\s*(?:pub(?:\s*\([^)]*\))?\s+)?use\s").unwrap(),
+            fn_regex: Regex::new(r"\bfn\s+\w+").unwrap(),
+            type_regex: Regex::new(r"\b[A-Z]\w*").unwrap(),
+            import_regex: Regex::new(r"\b([A-Z]\w*)(?:\s+as\s+(\w+))?").unwrap(),
+        }
+    }
+
+    pub fn with_options(options: RustParserOptions) -> Self {
+        Self { options, ..Self::new() }
+    }
+
+    // Capitalized names of a type or signature, with whether each is written
+    // as a path like `billing::Invoice`. Single letters are taken for
+    // generic parameters.
+    fn type_names(&self, text: &str) -> Vec<(String, bool)> {
+        self.type_regex
+            .find_iter(text)
+            .filter(|m| m.len() > 1 && !NOT_TYPES.contains(&m.as_str()) && !text[..m.start()].ends_with("Self::"))
+            .map(|m| (m.as_str().to_string(), text[..m.start()].ends_with("::")))
+            .collect()
+    }
+
+    // Bounds of an item's `<...>` generics and of the `where` clause that
+    // may follow them on the declaration line, as usage references
+    fn bound_names(&self, generics: &str, rest: &str) -> Vec<(String, bool, LinkKind)> {
+        let clause = where_clause(rest).unwrap_or_default();
+        let generics = generics.trim_start().strip_prefix('<').unwrap_or_default();
+        [generics, clause]
+            .iter()
+            .flat_map(|text| self.type_names(text))
+            .map(|(name, qualified)| (name, qualified, LinkKind::Usage))
+            .collect()
+    }
+
+    // Types of the fields or variants in a piece of a struct or enum body,
+    // like `pub lines: Vec<OrderLine>, total: Money` or
+    // `Shipped(Tracking), Lost`
+    fn member_names(&self, body: &str) -> Vec<(String, bool, LinkKind)> {
+        members(body)
+            .into_iter()
+            .flat_map(|member| {
+                let member = member.trim();
+                let types = match field_colon(member) {
+                    Some(colon) => &member[colon + 1..],
+                    None => member.split_once(['(', '{']).map_or("", |(_, rest)| rest),
+                };
+                self.type_names(types)
+            })
+            .map(|(name, qualified)| (name, qualified, LinkKind::Composition))
+            .collect()
+    }
+
+    // Local names a `use` declaration brings into scope and the type each
+    // stands for: `use billing::{Invoice, Payment as Charge};` brings in
+    // `Invoice` and `Charge` for `Payment`
+    fn imported_names(&self, text: &str) -> Vec<(String, String)> {
+        self.import_regex
+            .captures_iter(text)
+            .map(|caps| {
+                let name = caps[1].to_string();
+                let local = caps.get(2).map_or_else(|| name.clone(), |m| m.as_str().to_string());
+                (local, name)
+            })
+            .collect()
+    }
+}
+
+// A line without its `//` comment and with the contents of its string
+// literals dropped, so braces and names in them do not count. `'` starts
+// lifetimes more often than character literals, so it is left alone.
+fn code(line: &str) -> String {
+    let mut out = String::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut previous = '\0';
+    for c in line.chars() {
+        if in_string {
+            if c == '"' && !escaped {
+                in_string = false;
+                out.push(c);
+            }
+            escaped = c == '\\' && !escaped;
+        } else if c == '/' && previous == '/' {
+            out.pop();
+            break;
+        } else {
+            in_string = c == '"';
+            out.push(c);
+        }
+        previous = c;
+    }
+    out
+}
+
+// Last line of the item declared on `start`: the brace closing its body, or
+// the `;` ending a unit or tuple struct
+fn block_end(lines: &[&str], start: usize) -> usize {
+    let mut depth = 0usize;
+    for (index, line) in lines.iter().enumerate().skip(start) {
+        for c in code(line).chars() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth <= 1 => return index,
+                '}' => depth -= 1,
+                ';' if depth == 0 => return index,
+                _ => {}
+            }
+        }
+    }
+    lines.len().saturating_sub(1)
+}
+
+// `text` after the `<...>` generics it starts with, which may nest and hold
+// `Fn() -> T` bounds
+fn skip_generics(text: &str) -> &str {
+    let text = text.trim_start();
+    if !text.starts_with('<') {
+        return text;
+    }
+    let mut depth = 0usize;
+    let mut previous = '\0';
+    for (index, c) in text.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' if previous != '-' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &text[index + 1..];
+                }
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    ""
+}
+
+// What is inside the bracket `text` starts with, up to the bracket closing
+// it or the end of the line
+fn enclosed(text: &str) -> &str {
+    let mut depth = 0usize;
+    for (index, c) in text.char_indices() {
+        match c {
+            '(' | '{' | '[' => depth += 1,
+            ')' | '}' | ']' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return &text[1..index];
+                }
+            }
+            _ => {}
+        }
+    }
+    text.get(1..).unwrap_or_default()
+}
+
+// `text` split at the commas outside brackets and generics
+fn members(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut previous = '\0';
+    for (index, c) in text.char_indices() {
+        match c {
+            '(' | '{' | '[' | '<' => depth += 1,
+            ')' | '}' | ']' => depth = depth.saturating_sub(1),
+            '>' if previous != '-' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&text[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+// The bounds of a `where` clause in `text`, up to the body or the `;`
+fn where_clause(text: &str) -> Option<&str> {
+    let head = text.split(['{', ';']).next().unwrap_or_default();
+    let start = if head.trim_start().starts_with("where ") {
+        head.find("where ")?
+    } else {
+        head.find(" where ")? + 1
+    };
+    Some(&head[start + "where ".len()..])
+}
+
+// The type a type expression names: `&'a mut dyn shop::Cart<T>` is `Cart`
+fn base_name(text: &str) -> Option<String> {
+    let mut text = text.trim().trim_start_matches(['&', '!']).trim_start();
+    if text.starts_with('\'') {
+        text = text.split_once(' ').map_or("", |(_, rest)| rest).trim_start();
+    }
+    for prefix in ["mut ", "dyn "] {
+        text = text.strip_prefix(prefix).unwrap_or(text).trim_start();
+    }
+    let path = text.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':')).next()?;
+    let name = path.rsplit("::").next()?;
+    name.starts_with(|c: char| c.is_alphabetic() || c == '_').then(|| name.to_string())
+}
+
+// `impl Trait for Type` and `impl Type`: the implementing type and the
+// trait, if any
+fn impl_header(line: &str) -> Option<(String, Option<String>)> {
+    let rest = line.trim_start();
+    let rest = rest.strip_prefix("unsafe ").unwrap_or(rest).trim_start();
+    let rest = rest.strip_prefix("impl")?;
+    if !rest.starts_with([' ', '<']) {
+        return None;
+    }
+    let header = skip_generics(rest).split(['{', ';']).next().unwrap_or_default();
+    let header = header.split(" where").next().unwrap_or_default();
+    match header.split_once(" for ") {
+        Some((trait_name, type_name)) => Some((base_name(type_name)?, base_name(trait_name))),
+        None => Some((base_name(header)?, None)),
+    }
+}
+
+// The `:` between a field's name and its type, not part of a `::` path
+fn field_colon(line: &str) -> Option<usize> {
+    let bytes = line.as_bytes();
+    (0..bytes.len()).find(|&i| {
+        bytes[i] == b':' && bytes.get(i + 1) != Some(&b':') && (i == 0 || bytes[i - 1] != b':')
+    })
+}
+
+impl FileAnalyzer for RustParser {
+    fn analyze(&self, content: &str, _file_path: &str) -> AnalysisResult {
+        let lines: Vec<&str> = content.lines().collect();
+        let code_lines: Vec<String> = lines.iter().map(|line| code(line)).collect();
+        // Types are often implemented or used above their definition
+        let classes: HashSet<String> = code_lines
+            .iter()
+            .filter_map(|line| self.item_regex.captures(line))
+            .map(|caps| caps[2].to_string())
+            .collect();
+        let mut imported: HashMap<String, String> = HashMap::new();
+        let mut links: Vec<ClassLink> = Vec::new();
+        let mut class_starts = Vec::new();
+        let mut impl_starts = Vec::new();
+        let mut item: Option<Item> = None;
+        let mut depth = 0usize;
+        let mut pending_use: Option<String> = None;
+        let mut in_signature = false;
+
+        for (index, line) in code_lines.iter().enumerate() {
+            let trimmed = line.trim();
+            let mut references: Vec<(String, bool, LinkKind)> = Vec::new();
+
+            // `use` declarations, which may span lines; inside an impl or a
+            // function of one they link the type to what they import
+            if pending_use.is_some() || self.use_regex.is_match(line) {
+                let text = pending_use.get_or_insert_with(String::new);
+                text.push_str(line);
+                if line.contains(';') {
+                    let text = pending_use.take().unwrap_or_default();
+                    for (local, name) in self.imported_names(&text) {
+                        if item.is_some() {
+                            references.push((local.clone(), true, LinkKind::Usage));
+                        }
+                        imported.insert(local, name);
+                    }
+                }
+            } else if item.is_none() {
+                if let Some(caps) = self.item_regex.captures(line) {
+                    let name = caps[2].to_string();
+                    let kind = if &caps[1] == "trait" { ItemKind::Trait } else { ItemKind::Data };
+                    let after = &line[caps.get(0).map_or(0, |m| m.end())..];
+                    let rest = skip_generics(after);
+                    references.extend(self.bound_names(&after[..after.len() - rest.len()], rest));
+                    // Tuple structs like `struct OrderId(Uuid);`, bodies
+                    // opened on the declaration line like
+                    // `struct LineItem { price: Money }`, and supertraits
+                    // like `trait Repository: Storage + Send {`, which name
+                    // their trait whether it is imported or not
+                    if kind == ItemKind::Data && rest.starts_with('(') {
+                        references.extend(self.type_names(enclosed(rest)).into_iter().map(|(n, q)| (n, q, LinkKind::Composition)));
+                    } else if let (ItemKind::Data, Some(body)) = (kind, rest.find('{')) {
+                        references.extend(self.member_names(enclosed(&rest[body..])));
+                    } else if let (ItemKind::Trait, Some(bounds)) = (kind, rest.strip_prefix(':')) {
+                        let bounds = bounds.split(['{', ';']).next().unwrap_or_default();
+                        let bounds = bounds.split(" where").next().unwrap_or_default();
+                        references.extend(self.type_names(bounds).into_iter().map(|(n, _)| (n, true, LinkKind::Inheritance)));
+                    }
+                    class_starts.push((name.clone(), index));
+                    item = Some(Item { name, kind, depth, opened: false });
+                } else if let Some((name, trait_name)) = impl_header(line) {
+                    references.extend(trait_name.map(|trait_name| (trait_name, true, LinkKind::Inheritance)));
+                    let after = line.trim_start().trim_start_matches("unsafe ").trim_start().trim_start_matches("impl");
+                    let rest = skip_generics(after);
+                    references.extend(self.bound_names(&after[..after.len() - rest.len()], rest));
+                    impl_starts.push((name.clone(), index));
+                    item = Some(Item { name, kind: ItemKind::Impl, depth, opened: false });
+                }
+            } else if let Some(current) = &item {
+                match current.kind {
+                    // Lines before the body: `where` bounds, which have a
+                    // `:`, or the fields of a tuple struct spanning lines
+                    ItemKind::Data if !current.opened => {
+                        let (head, body) = trimmed.split_once('{').unwrap_or((trimmed, ""));
+                        let kind = if head.starts_with("where") || field_colon(head).is_some() {
+                            LinkKind::Usage
+                        } else {
+                            LinkKind::Composition
+                        };
+                        let head = head.strip_prefix("where").unwrap_or(head);
+                        references.extend(self.type_names(head).into_iter().map(|(n, q)| (n, q, kind)));
+                        references.extend(self.member_names(body));
+                    }
+                    // Fields like `pub lines: Vec<OrderLine>,` and variants
+                    // like `Shipped(Tracking),`; attributes are skipped
+                    ItemKind::Data if !trimmed.starts_with('#') => {
+                        references.extend(self.member_names(trimmed));
+                    }
+                    ItemKind::Data => {}
+                    // Signatures of methods, which may span lines up to the
+                    // body or the `;` of a trait method
+                    ItemKind::Trait | ItemKind::Impl => {
+                        let signature = match self.fn_regex.find(line) {
+                            Some(found) => {
+                                in_signature = true;
+                                &line[found.end()..]
+                            }
+                            None => line.as_str(),
+                        };
+                        if in_signature && self.options.include_type_only {
+                            let signature = signature.split('{').next().unwrap_or_default();
+                            references.extend(self.type_names(signature).into_iter().map(|(n, q)| (n, q, LinkKind::Usage)));
+                        }
+                        if line.contains('{') || line.contains(';') {
+                            in_signature = false;
+                        }
+                    }
+                }
+                if self.options.any_capitalized {
+                    for name in preset::capitalized_names(line, NOT_TYPES) {
+                        references.push((name.to_string(), false, LinkKind::Usage));
+                    }
+                }
+            }
+
+            if let Some(current) = &item {
+                for (name, qualified, kind) in references {
+                    let known = qualified || classes.contains(&name) || imported.contains_key(&name);
+                    let to_class = imported.get(&name).cloned().unwrap_or(name);
+                    if to_class == current.name || (!known && self.options.known_targets_only) {
+                        continue;
+                    }
+                    // One link per place, the strongest kind first
+                    let already_exists = links
+                        .iter()
+                        .any(|l| l.from_class == current.name && l.to_class == to_class && l.line_number == index + 1);
+                    if !already_exists {
+                        links.push(ClassLink {
+                            from_class: current.name.clone(),
+                            to_class,
+                            line_number: index + 1,
+                            kind,
+                            position: None,
+                        });
+                    }
+                }
+            }
+
+            for c in line.chars() {
+                match c {
+                    '{' => {
+                        depth += 1;
+                        if let Some(item) = &mut item {
+                            item.opened = true;
+                        }
+                    }
+                    '}' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+            }
+            let ended = item.as_ref().is_some_and(|item| {
+                if item.opened {
+                    depth <= item.depth
+                } else {
+                    trimmed.ends_with(';')
+                }
+            });
+            if ended {
+                item = None;
+                in_signature = false;
+            }
+        }
+
+        let class_tags = annotations::rust_tags(&lines, &class_starts);
+        let dependency_budgets = annotations::dependency_budgets(&lines, &class_starts);
+        // Impl blocks count towards the size of types the file defines
+        let mut starts = class_starts;
+        starts.extend(impl_starts.into_iter().filter(|(name, _)| classes.contains(name)));
+        let class_metrics = metrics::measure_classes(&lines, starts, block_end, |line| self.fn_regex.is_match(&code(line)));
+
+        AnalysisResult {
+            classes,
+            links,
+            class_metrics,
+            class_tags,
+            dependency_budgets,
+            ..Default::default()
+        }
+    }
+}
+
+impl LanguageParser for RustParser {
+    fn language(&self) -> &str {
+        "rust"
+    }
+
+    fn extensions(&self) -> Vec<String> {
+        vec!["rs".to_string()]
+    }
+}
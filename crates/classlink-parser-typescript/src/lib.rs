@@ -0,0 +1,373 @@
+pub mod template_parser;
+
+use regex::Regex;
+use std::collections::HashSet;
+use classlink_core::{annotations, endpoints, metrics};
+use classlink_core::link::{pascal_case, ClassLink, LinkKind};
+use classlink_core::analysis::{AnalysisResult, FileAnalyzer};
+use classlink_core::preset::{self, ExtractionPreset};
+use classlink_core::registry::LanguageParser;
+use crate::template_parser::{TemplateParser, TemplateParserOptions};
+use serde::{Deserialize, Serialize};
+
+// Goes into the parse cache key, so a parser change re-parses its files
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "TypeScriptParserConfig")]
+pub struct TypeScriptParserOptions {
+    // Type annotations and generic arguments, which are erased at runtime
+    pub include_type_only: bool,
+    // Only link to classes the file imports or defines; off links any
+    // capitalized type name
+    pub known_targets_only: bool,
+    // Every capitalized name in a class body, subject to `known_targets_only`
+    pub any_capitalized: bool,
+}
+
+impl Default for TypeScriptParserOptions {
+    fn default() -> Self {
+        Self::preset(ExtractionPreset::Balanced)
+    }
+}
+
+impl TypeScriptParserOptions {
+    pub fn preset(preset: ExtractionPreset) -> Self {
+        Self {
+            include_type_only: preset != ExtractionPreset::Strict,
+            known_targets_only: preset != ExtractionPreset::Greedy,
+            any_capitalized: preset == ExtractionPreset::Greedy,
+        }
+    }
+}
+
+// Options as written in a config file: a preset, with any field given
+// overriding it
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct TypeScriptParserConfig {
+    preset: ExtractionPreset,
+    include_type_only: Option<bool>,
+    known_targets_only: Option<bool>,
+    any_capitalized: Option<bool>,
+}
+
+impl From<TypeScriptParserConfig> for TypeScriptParserOptions {
+    fn from(config: TypeScriptParserConfig) -> Self {
+        let preset = Self::preset(config.preset);
+        Self {
+            include_type_only: config.include_type_only.unwrap_or(preset.include_type_only),
+            known_targets_only: config.known_targets_only.unwrap_or(preset.known_targets_only),
+            any_capitalized: config.any_capitalized.unwrap_or(preset.any_capitalized),
+        }
+    }
+}
+
+pub struct TypeScriptParser {
+    options: TypeScriptParserOptions,
+    class_regex: Regex,
+    interface_regex: Regex,
+    heritage_regex: Regex,
+    member_regex: Regex,
+    import_regex: Regex,
+    method_regex: Regex,
+    provide_regex: Regex,
+    use_class_regex: Regex,
+    event_handler_regex: Regex,
+    emit_regex: Regex,
+    controller_regex: Regex,
+    route_regex: Regex,
+    http_client_regex: Regex,
+    templates: TemplateParser,
+}
+
+impl Default for TypeScriptParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeScriptParser {
+    pub fn new() -> Self {
+        Self {
+            options: TypeScriptParserOptions::default(),
+            class_regex: Regex::new(r"^\s*(?:export\s+)?(?:default\s+)?(?:abstract\s+)?class\s+(\w+)").unwrap(),
+            interface_regex: Regex::new(r"^\s*(?:export\s+)?interface\s+(\w+)").unwrap(),
+            heritage_regex: Regex::new(r"\b(?:extends|implements)\s+([A-Z][\w.]*(?:\s*,\s*[A-Z][\w.]*)*)").unwrap(),
+            member_regex: Regex::new(r"\b(?:private|public|protected|readonly)\s+(?:readonly\s+)?\w+\??\s*:\s*([A-Z]\w+)").unwrap(),
+            import_regex: Regex::new(r"import\s*\{([^}]+)\}\s*from").unwrap(),
+            method_regex: Regex::new(r"^\s*(?:(?:public|private|protected|static|async|readonly|abstract|override|get|set)\s+)*(\w+)\s*(?:<[^>]*>)?\s*\([^;]*$").unwrap(),
+            provide_regex: Regex::new(r"\bprovide\s*:\s*([A-Z]\w*)").unwrap(),
+            use_class_regex: Regex::new(r"\buseClass\s*:\s*([A-Z]\w*)").unwrap(),
+            event_handler_regex: Regex::new(r#"@(?:EventPattern|MessagePattern|OnEvent)\(\s*['"]([\w.-]+)['"]"#).unwrap(),
+            emit_regex: Regex::new(r#"\.emit\(\s*['"]([\w.-]+)['"]"#).unwrap(),
+            controller_regex: Regex::new(r#"@Controller\(\s*(?:['"]([^'"]*)['"])?"#).unwrap(),
+            route_regex: Regex::new(r#"@(?:Get|Post|Put|Patch|Delete|All)\(\s*(?:['"]([^'"]*)['"])?\s*\)"#).unwrap(),
+            http_client_regex: Regex::new(
+                r#"\b(?:fetch|\w*(?:[Hh]ttp|axios|api|[Cc]lient)\w*\.(?:get|post|put|patch|delete|request))\s*(?:<[^>]*>)?\(\s*['"`]([^'"`]+)['"`]"#,
+            )
+            .unwrap(),
+            templates: TemplateParser::new(),
+        }
+    }
+
+    pub fn with_options(options: TypeScriptParserOptions) -> Self {
+        Self { options, ..Self::new() }
+    }
+
+    pub fn with_templates(mut self, options: TemplateParserOptions) -> Self {
+        self.templates = TemplateParser::with_options(options);
+        self
+    }
+    
+    // Method and constructor declarations; control statements look the same
+    // to the regex and are filtered out by name
+    fn is_method(&self, line: &str) -> bool {
+        self.method_regex
+            .captures(line)
+            .is_some_and(|caps| !["if", "for", "while", "switch", "catch", "return", "function"].contains(&&caps[1]))
+    }
+
+    // Stronger kinds come first so they win when duplicate links are dropped
+    fn extract_class_references(&self, line: &str, is_declaration: bool) -> Vec<(String, LinkKind)> {
+        let mut references = Vec::new();
+
+        // Heritage clauses like `class Admin extends User implements Auditable`
+        if is_declaration {
+            for caps in self.heritage_regex.captures_iter(line) {
+                for parent in caps[1].split(',') {
+                    let name = parent.trim().rsplit('.').next().unwrap_or_default();
+                    references.push((name.to_string(), LinkKind::Inheritance));
+                }
+            }
+        }
+
+        // Members and injected dependencies like `private db: DatabaseService`
+        for caps in self.member_regex.captures_iter(line) {
+            references.push((caps[1].to_string(), LinkKind::Composition));
+        }
+        
+        if self.options.any_capitalized {
+            for name in preset::capitalized_names(line, &[]) {
+                references.push((name.to_string(), LinkKind::Usage));
+            }
+        }
+
+        if !self.options.include_type_only {
+            return references;
+        }
+
+        // Type annotations like : User, : DatabaseService
+        let type_annotation_regex = Regex::new(r":\s*([A-Z]\w+)").unwrap();
+        for caps in type_annotation_regex.captures_iter(line) {
+            references.push((caps[1].to_string(), LinkKind::Usage));
+        }
+        
+        // Constructor injections like constructor(private db: DatabaseService)
+        let constructor_regex = Regex::new(r"(?:private|public|protected)?\s*\w+:\s*([A-Z]\w+)").unwrap();
+        for caps in constructor_regex.captures_iter(line) {
+            references.push((caps[1].to_string(), LinkKind::Usage));
+        }
+        
+        // Generic types like Array<User>, Observable<User>
+        let generic_regex = Regex::new(r"<([A-Z]\w+)>").unwrap();
+        for caps in generic_regex.captures_iter(line) {
+            references.push((caps[1].to_string(), LinkKind::Usage));
+        }
+        
+        references
+    }
+}
+
+impl FileAnalyzer for TypeScriptParser {
+    fn analyze(&self, content: &str, file_path: &str) -> AnalysisResult {
+        let jsx = file_path.ends_with(".tsx") || file_path.ends_with(".jsx");
+        let mut classes = HashSet::new();
+        let mut links = Vec::new();
+        let mut current_class = None;
+        let mut imported_classes = HashSet::new();
+        let mut class_starts = Vec::new();
+        // Components an Angular `@Component({ template })` decorator renders,
+        // held until the class it decorates is declared
+        let mut in_decorator = false;
+        let mut decorator_usages = Vec::new();
+        // NestJS and Angular providers, `{ provide: UserRepository, useClass: SqlUserRepository }`,
+        // possibly spread over several lines
+        let mut provided: Option<String> = None;
+        // NestJS `@Controller('users')` prefixes the routes of the class below it
+        let mut pending_prefix: Option<String> = None;
+        let mut route_prefix = String::new();
+        let lines: Vec<&str> = content.lines().collect();
+        
+        for (line_num, line) in lines.iter().copied().enumerate() {
+            let is_declaration = self.class_regex.is_match(line) || self.interface_regex.is_match(line);
+
+            if let Some(caps) = self.controller_regex.captures(line) {
+                pending_prefix = Some(caps.get(1).map_or("", |m| m.as_str()).to_string());
+            }
+            if let Some(caps) = self.provide_regex.captures(line) {
+                provided = Some(caps[1].to_string());
+            }
+            if let Some(caps) = self.use_class_regex.captures(line) {
+                if let Some(token) = provided.take().filter(|token| *token != caps[1]) {
+                    links.push(ClassLink {
+                        from_class: token,
+                        to_class: caps[1].to_string(),
+                        line_number: line_num + 1,
+                        kind: LinkKind::Binding,
+                        position: None,
+                    });
+                }
+            }
+
+            // Check for class definitions
+            if let Some(caps) = self.class_regex.captures(line) {
+                let class_name = caps[1].to_string();
+                classes.insert(class_name.clone());
+                class_starts.push((class_name.clone(), line_num));
+                route_prefix = pending_prefix.take().unwrap_or_default();
+                for (to_class, line_number) in decorator_usages.drain(..) {
+                    if to_class != class_name {
+                        links.push(ClassLink {
+                            from_class: class_name.clone(),
+                            to_class,
+                            line_number,
+                            kind: LinkKind::Composition,
+                            position: None,
+                        });
+                    }
+                }
+                in_decorator = false;
+                current_class = Some(class_name);
+            } else if line.trim_start().starts_with("@Component(") {
+                in_decorator = true;
+            }
+            if in_decorator {
+                let usages = self.templates.component_usages(line, false);
+                decorator_usages.extend(usages.into_iter().map(|to_class| (to_class, line_num + 1)));
+                continue;
+            }
+            
+            // Check for interface definitions
+            if let Some(caps) = self.interface_regex.captures(line) {
+                let interface_name = caps[1].to_string();
+                classes.insert(interface_name.clone());
+                class_starts.push((interface_name.clone(), line_num));
+                current_class = Some(interface_name);
+            }
+            
+            // Check for imports
+            if let Some(caps) = self.import_regex.captures(line) {
+                let imports = &caps[1];
+                for import in imports.split(',') {
+                    let class_name = import.trim();
+                    if class_name.chars().next().is_some_and(|c| c.is_uppercase()) {
+                        imported_classes.insert(class_name.to_string());
+                    }
+                }
+            }
+            
+            // Endpoints: route handlers are bound to their routes, and `fetch`,
+            // Angular `HttpClient` or axios calls use them
+            if let Some(ref class) = current_class {
+                for caps in self.route_regex.captures_iter(line) {
+                    links.push(ClassLink {
+                        from_class: endpoints::join(&route_prefix, caps.get(1).map_or("", |m| m.as_str()), class),
+                        to_class: class.clone(),
+                        line_number: line_num + 1,
+                        kind: LinkKind::Binding,
+                        position: None,
+                    });
+                }
+                for caps in self.http_client_regex.captures_iter(line) {
+                    if let Some(endpoint) = endpoints::client_endpoint(&caps[1]) {
+                        links.push(ClassLink {
+                            from_class: class.clone(),
+                            to_class: endpoint,
+                            line_number: line_num + 1,
+                            kind: LinkKind::Usage,
+                            position: None,
+                        });
+                    }
+                }
+            }
+
+            // Topics name their event class by convention, `order.created` is
+            // `OrderCreated`: handlers are bound to it and emitters use it
+            if let Some(ref class) = current_class {
+                for caps in self.event_handler_regex.captures_iter(line) {
+                    links.push(ClassLink {
+                        from_class: pascal_case(&caps[1]),
+                        to_class: class.clone(),
+                        line_number: line_num + 1,
+                        kind: LinkKind::Binding,
+                        position: None,
+                    });
+                }
+                for caps in self.emit_regex.captures_iter(line) {
+                    links.push(ClassLink {
+                        from_class: class.clone(),
+                        to_class: pascal_case(&caps[1]),
+                        line_number: line_num + 1,
+                        kind: LinkKind::Usage,
+                        position: None,
+                    });
+                }
+            }
+
+            // Find class references. Components rendered in JSX or an inline
+            // Angular template are matched by naming convention, not imports.
+            if let Some(ref from_class) = current_class {
+                let rendered = self.templates.component_usages(line, jsx);
+                let references = self
+                    .extract_class_references(line, is_declaration)
+                    .into_iter()
+                    .filter(|(to_class, _)| {
+                        let is_known = imported_classes.contains(to_class) || classes.contains(to_class);
+                        is_known || !self.options.known_targets_only
+                    })
+                    .chain(rendered.into_iter().map(|to_class| (to_class, LinkKind::Composition)));
+                for (to_class, kind) in references {
+                    if &to_class != from_class {
+                        // One link per place: several patterns can match the same
+                        // reference, but every line it occurs on is kept
+                        let already_exists = links.iter().any(|l: &ClassLink| l.from_class == *from_class && l.to_class == to_class && l.line_number == line_num + 1);
+                        if !already_exists {
+                            links.push(ClassLink {
+                                from_class: from_class.clone(),
+                                to_class,
+                                line_number: line_num + 1,
+                                kind,
+                                position: None,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        
+        let class_tags = annotations::typescript_tags(&lines, &class_starts);
+        let dependency_budgets = annotations::dependency_budgets(&lines, &class_starts);
+        let class_metrics =
+            metrics::measure_classes(&lines, class_starts, metrics::brace_block_end, |line| self.is_method(line));
+
+        AnalysisResult {
+            classes,
+            links,
+            class_metrics,
+            class_tags,
+            dependency_budgets,
+            ..Default::default()
+        }
+    }
+}
+
+impl LanguageParser for TypeScriptParser {
+    fn language(&self) -> &str {
+        "typescript"
+    }
+
+    fn extensions(&self) -> Vec<String> {
+        vec!["ts".to_string(), "tsx".to_string(), "jsx".to_string()]
+    }
+}
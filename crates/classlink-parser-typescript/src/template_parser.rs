@@ -0,0 +1,160 @@
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::Path;
+use classlink_core::link::{pascal_case, ClassLink, LinkKind};
+use classlink_core::analysis::{AnalysisResult, FileAnalyzer};
+use classlink_core::registry::LanguageParser;
+use serde::{Deserialize, Serialize};
+
+// How component tags in markup map to component classes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TemplateParserOptions {
+    // Selector prefixes of the project's own custom elements; `app` maps
+    // `<app-user-card>` to `UserCardComponent`. Elements with other
+    // prefixes belong to libraries and are ignored. Empty maps them all.
+    pub component_prefixes: Vec<String>,
+    pub component_suffix: String,
+}
+
+impl Default for TemplateParserOptions {
+    fn default() -> Self {
+        Self {
+            component_prefixes: vec!["app".to_string()],
+            component_suffix: "Component".to_string(),
+        }
+    }
+}
+
+// Component usages in HTML templates, Angular inline templates and JSX.
+// Rendering a child component is treated as composition.
+pub struct TemplateParser {
+    options: TemplateParserOptions,
+    jsx_regex: Regex,
+    element_regex: Regex,
+}
+
+impl Default for TemplateParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TemplateParser {
+    pub fn new() -> Self {
+        Self {
+            options: TemplateParserOptions::default(),
+            // Not after an identifier, so generics like `useState<User>()` stay out
+            jsx_regex: Regex::new(r"(?:^|[^\w.$])<([A-Z][\w.]*)(?:[\s/>]|$)").unwrap(),
+            element_regex: Regex::new(r"<([a-z][a-z0-9]*(?:-[a-z0-9]+)+)(?:[\s/>]|$)").unwrap(),
+        }
+    }
+
+    pub fn with_options(options: TemplateParserOptions) -> Self {
+        Self { options, ..Self::new() }
+    }
+
+    // `<app-user-card>` becomes `UserCardComponent` with the default options
+    pub fn element_class(&self, tag: &str) -> Option<String> {
+        let name = if self.options.component_prefixes.is_empty() {
+            tag
+        } else {
+            self.options
+                .component_prefixes
+                .iter()
+                .find_map(|prefix| tag.strip_prefix(prefix.as_str())?.strip_prefix('-'))?
+        };
+        Some(format!("{}{}", pascal_case(name), self.options.component_suffix))
+    }
+
+    // Custom elements always; capitalized JSX tags only when `jsx` is set
+    pub fn component_usages(&self, line: &str, jsx: bool) -> Vec<String> {
+        let mut usages: Vec<String> = self
+            .element_regex
+            .captures_iter(line)
+            .filter_map(|caps| self.element_class(&caps[1]))
+            .collect();
+        if jsx {
+            for caps in self.jsx_regex.captures_iter(line) {
+                // `<Layout.Header>` renders `Header`
+                usages.push(caps[1].rsplit('.').next().unwrap_or_default().to_string());
+            }
+        }
+        usages
+    }
+
+    // Angular names a template after its component file, so
+    // `user-card.component.html` belongs to `UserCardComponent`
+    pub fn owner(path: &str) -> Option<String> {
+        let stem = Path::new(path).file_stem()?.to_str()?;
+        let owner = pascal_case(stem);
+        (!owner.is_empty()).then_some(owner)
+    }
+}
+
+impl FileAnalyzer for TemplateParser {
+    // Templates define no classes; their links start at the owning component
+    fn analyze(&self, content: &str, file_path: &str) -> AnalysisResult {
+        let Some(owner) = Self::owner(file_path) else {
+            return AnalysisResult::default();
+        };
+        let mut links = Vec::new();
+        let mut seen = HashSet::new();
+        for (line_num, line) in content.lines().enumerate() {
+            for to_class in self.component_usages(line, true) {
+                if to_class != owner && seen.insert((to_class.clone(), line_num)) {
+                    links.push(ClassLink {
+                        from_class: owner.clone(),
+                        to_class,
+                        line_number: line_num + 1,
+                        kind: LinkKind::Composition,
+                        position: None,
+                    });
+                }
+            }
+        }
+        AnalysisResult {
+            links,
+            ..Default::default()
+        }
+    }
+}
+
+impl LanguageParser for TemplateParser {
+    fn language(&self) -> &str {
+        "html"
+    }
+
+    fn extensions(&self) -> Vec<String> {
+        vec!["html".to_string()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_component_usages() {
+        let template = "<div class=\"card\">\n  <app-user-card [user]=\"user\"></app-user-card>\n  <mat-icon>home</mat-icon>\n  <app-avatar/>\n</div>\n";
+
+        let result = TemplateParser::new().analyze(template, "src/app/user-list.component.html");
+
+        let links: Vec<(&str, &str, usize)> =
+            result.links.iter().map(|l| (l.from_class.as_str(), l.to_class.as_str(), l.line_number)).collect();
+        assert_eq!(
+            links,
+            vec![("UserListComponent", "UserCardComponent", 2), ("UserListComponent", "AvatarComponent", 4)]
+        );
+        assert!(result.classes.is_empty());
+
+        let parser = TemplateParser::new();
+        assert_eq!(parser.component_usages("return <Layout.Header title={t}><UserCard /></Layout.Header>;", true), vec!["Header", "UserCard"]);
+        assert!(parser.component_usages("const [user] = useState<User>(null);", true).is_empty());
+        let any_prefix = TemplateParser::with_options(TemplateParserOptions {
+            component_prefixes: Vec::new(),
+            component_suffix: String::new(),
+        });
+        assert_eq!(any_prefix.element_class("mat-icon"), Some("MatIcon".to_string()));
+    }
+}
@@ -0,0 +1,289 @@
+use crate::file_analyzer::{FileType, UnifiedAnalyzer};
+use crate::i18n::{Catalog, Message};
+use crate::manifest::relative_path;
+use crate::project::FileAnalysis;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt::Write;
+use std::fs;
+
+// Hand-checked links for part of a project. Only the files named here are
+// scored: every file a link comes from, plus `files` for those that should
+// produce no links at all.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GroundTruth {
+    pub files: Vec<String>,
+    pub links: Vec<TruthLink>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TruthLink {
+    // Root-relative path of the file the link is written in
+    pub file: String,
+    pub from: String,
+    pub to: String,
+}
+
+impl GroundTruth {
+    pub fn from_file(path: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    fn scored_files(&self) -> BTreeSet<String> {
+        self.files
+            .iter()
+            .chain(self.links.iter().map(|link| &link.file))
+            .map(|file| file.replace('\\', "/"))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Score {
+    pub files: usize,
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+}
+
+impl Score {
+    fn add(&mut self, other: &Score) {
+        self.files += other.files;
+        self.true_positives += other.true_positives;
+        self.false_positives += other.false_positives;
+        self.false_negatives += other.false_negatives;
+    }
+
+    // Nothing predicted means nothing wrong, and nothing expected means
+    // nothing missed, so empty denominators score 1
+    fn finish(&mut self) {
+        let ratio = |hits: usize, total: usize| if total == 0 { 1.0 } else { hits as f64 / total as f64 };
+        self.precision = ratio(self.true_positives, self.true_positives + self.false_positives);
+        self.recall = ratio(self.true_positives, self.true_positives + self.false_negatives);
+        self.f1 = if self.precision + self.recall == 0.0 {
+            0.0
+        } else {
+            2.0 * self.precision * self.recall / (self.precision + self.recall)
+        };
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AccuracyReport {
+    pub languages: BTreeMap<String, Score>,
+    pub overall: Score,
+    // Extracted but not in the ground truth
+    pub spurious: Vec<TruthLink>,
+    // In the ground truth but not extracted
+    pub missed: Vec<TruthLink>,
+    // Scored files the analyzer did not see (unsupported, skipped, or absent)
+    pub unanalyzed_files: Vec<String>,
+}
+
+pub fn language_of(path: &str) -> &'static str {
+    match UnifiedAnalyzer::detect_file_type(path) {
+        FileType::Markdown => "markdown",
+        FileType::Python => "python",
+        FileType::TypeScript => "typescript",
+        FileType::CSharp => "csharp",
+        FileType::Rust => "rust",
+        FileType::Java => "java",
+        FileType::Go => "go",
+        FileType::JavaScript => "javascript",
+        FileType::Template => "html",
+        FileType::Unknown => "other",
+    }
+}
+
+// Links are compared as (file, from, to); line numbers and kinds are not
+// scored, since hand-written ground truth rarely pins them down
+pub fn evaluate(root: &str, files: &[FileAnalysis], truth: &GroundTruth) -> AccuracyReport {
+    let scored = truth.scored_files();
+    let mut extracted: BTreeMap<String, BTreeSet<TruthLink>> = BTreeMap::new();
+    for file in files {
+        let relative = relative_path(root, &file.path);
+        if !scored.contains(&relative) {
+            continue;
+        }
+        let links = extracted.entry(relative.clone()).or_default();
+        for link in &file.result.links {
+            links.insert(TruthLink {
+                file: relative.clone(),
+                from: link.from_class.clone(),
+                to: link.to_class.clone(),
+            });
+        }
+    }
+
+    let mut report = AccuracyReport::default();
+    for file in &scored {
+        let Some(found) = extracted.get(file) else {
+            report.unanalyzed_files.push(file.clone());
+            continue;
+        };
+        let expected: BTreeSet<TruthLink> = truth
+            .links
+            .iter()
+            .filter(|link| link.file.replace('\\', "/") == *file)
+            .map(|link| TruthLink {
+                file: file.clone(),
+                ..link.clone()
+            })
+            .collect();
+
+        let score = report.languages.entry(language_of(file).to_string()).or_default();
+        score.files += 1;
+        for link in found {
+            if expected.contains(link) {
+                score.true_positives += 1;
+            } else {
+                score.false_positives += 1;
+                report.spurious.push(link.clone());
+            }
+        }
+        for link in expected.difference(found) {
+            score.false_negatives += 1;
+            report.missed.push(link.clone());
+        }
+    }
+
+    for score in report.languages.values_mut() {
+        score.finish();
+        report.overall.add(score);
+    }
+    report.overall.finish();
+    report
+}
+
+impl AccuracyReport {
+    pub fn render_text(&self, catalog: &Catalog) -> String {
+        let mut out = String::new();
+        writeln!(out, "{}\n", catalog.text(Message::AccuracyTitle, &[])).unwrap();
+        let line = |name: &str, score: &Score| {
+            catalog.text(
+                Message::AccuracyLine,
+                &[
+                    &name,
+                    &format!("{:.3}", score.precision),
+                    &format!("{:.3}", score.recall),
+                    &format!("{:.3}", score.f1),
+                    &score.files,
+                    &score.true_positives,
+                    &score.false_positives,
+                    &score.false_negatives,
+                ],
+            )
+        };
+        for (language, score) in &self.languages {
+            writeln!(out, "{}", line(language, score)).unwrap();
+        }
+        writeln!(out, "{}", line(&catalog.text(Message::AccuracyOverall, &[]), &self.overall)).unwrap();
+
+        let sections = [
+            (Message::AccuracyMissed, &self.missed),
+            (Message::AccuracySpurious, &self.spurious),
+        ];
+        for (header, links) in sections {
+            if !links.is_empty() {
+                writeln!(out, "\n{}", catalog.text(header, &[])).unwrap();
+                for link in links {
+                    writeln!(out, "  - {}: {} -> {}", link.file, link.from, link.to).unwrap();
+                }
+            }
+        }
+        if !self.unanalyzed_files.is_empty() {
+            writeln!(out, "\n{}", catalog.text(Message::AccuracyUnanalyzed, &[])).unwrap();
+            for file in &self.unanalyzed_files {
+                writeln!(out, "  - {}", file).unwrap();
+            }
+        }
+        out
+    }
+}
+
+#[cfg(all(test, feature = "python"))]
+mod tests {
+    use super::*;
+    use crate::file_analyzer::AnalysisResult;
+    use crate::parser::ClassLink;
+
+    fn file(path: &str, links: &[(&str, &str)]) -> FileAnalysis {
+        FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                links: links
+                    .iter()
+                    .map(|(from, to)| ClassLink {
+                        from_class: from.to_string(),
+                        to_class: to.to_string(),
+                        line_number: 1,
+                        kind: Default::default(),
+                        position: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        }
+    }
+
+    fn truth_link(file: &str, from: &str, to: &str) -> TruthLink {
+        TruthLink {
+            file: file.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "typescript")]
+    fn test_precision_and_recall_per_language() {
+        let files = vec![
+            file("/repo/app/order.py", &[("Order", "Money"), ("Order", "Order"), ("Order", "Money")]),
+            file("/repo/web/cart.ts", &[("Cart", "Order")]),
+            file("/repo/web/unscored.ts", &[("Stray", "Order")]),
+        ];
+        let truth = GroundTruth {
+            files: vec!["web/cart.ts".to_string(), "docs/Missing.md".to_string()],
+            links: vec![
+                truth_link("app/order.py", "Order", "Money"),
+                truth_link("app/order.py", "Order", "Customer"),
+            ],
+        };
+
+        let report = evaluate("/repo", &files, &truth);
+
+        let python = &report.languages["python"];
+        assert_eq!((python.true_positives, python.false_positives, python.false_negatives), (1, 1, 1));
+        assert_eq!(python.precision, 0.5);
+        assert_eq!(python.recall, 0.5);
+        let typescript = &report.languages["typescript"];
+        assert_eq!(typescript.precision, 0.0);
+        assert_eq!(typescript.recall, 1.0);
+        assert_eq!(report.overall.files, 2);
+        assert_eq!(report.overall.true_positives, 1);
+        assert_eq!(report.missed, vec![truth_link("app/order.py", "Order", "Customer")]);
+        assert_eq!(report.spurious.len(), 2);
+        assert_eq!(report.unanalyzed_files, vec!["docs/Missing.md"]);
+    }
+
+    #[test]
+    fn test_render_text_lists_mistakes() {
+        let files = vec![file("/repo/a.py", &[("A", "C")])];
+        let truth = GroundTruth {
+            files: vec![],
+            links: vec![truth_link("a.py", "A", "B")],
+        };
+
+        let text = evaluate("/repo", &files, &truth).render_text(&Catalog::default());
+
+        assert!(text.contains("python: precision 0.000, recall 0.000"));
+        assert!(text.contains("  - a.py: A -> B"));
+        assert!(text.contains("  - a.py: A -> C"));
+    }
+}
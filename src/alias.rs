@@ -0,0 +1,182 @@
+use crate::annotations::ClassTags;
+use crate::metrics::ClassMetrics;
+use crate::project::FileAnalysis;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+// Name equivalences across languages, e.g. a proto `User` that TypeScript
+// calls `UserDto` and C# calls `UserModel`. Every alias is renamed to its
+// canonical name so the data model is one node in the graph.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AliasMap {
+    canonical: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AliasConflict {
+    pub alias: String,
+    pub first: String,
+    pub second: String,
+}
+
+impl fmt::Display for AliasConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "alias '{}' is mapped to both '{}' and '{}'", self.alias, self.first, self.second)
+    }
+}
+
+impl std::error::Error for AliasConflict {}
+
+impl AliasMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // `aliases` maps each canonical name to the names that mean the same class
+    pub fn from_config(aliases: &BTreeMap<String, Vec<String>>) -> Result<Self, AliasConflict> {
+        let mut map = Self::new();
+        for (canonical, names) in aliases {
+            for name in names.iter().filter(|name| *name != canonical) {
+                match map.canonical.get(name) {
+                    Some(first) if first != canonical => {
+                        return Err(AliasConflict {
+                            alias: name.clone(),
+                            first: first.clone(),
+                            second: canonical.clone(),
+                        })
+                    }
+                    _ => {
+                        map.canonical.insert(name.clone(), canonical.clone());
+                    }
+                }
+            }
+        }
+        if let Some(name) = aliases.keys().find(|name| map.canonical.contains_key(*name)) {
+            return Err(AliasConflict {
+                alias: name.clone(),
+                first: map.canonical[name].clone(),
+                second: name.clone(),
+            });
+        }
+        Ok(map)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.canonical.is_empty()
+    }
+
+    pub fn canonical<'a>(&'a self, name: &'a str) -> &'a str {
+        self.canonical.get(name).map_or(name, String::as_str)
+    }
+
+    // Links that become self-references once both ends are renamed are dropped
+    pub fn apply(&self, files: Vec<FileAnalysis>) -> Vec<FileAnalysis> {
+        if self.is_empty() {
+            return files;
+        }
+        files
+            .into_iter()
+            .map(|mut file| {
+                let result = &mut file.result;
+                result.classes = result.classes.iter().map(|class| self.canonical(class).to_string()).collect();
+                for link in &mut result.links {
+                    link.from_class = self.canonical(&link.from_class).to_string();
+                    link.to_class = self.canonical(&link.to_class).to_string();
+                }
+                result.links.retain(|link| link.from_class != link.to_class);
+                let mut seen = HashSet::new();
+                result
+                    .links
+                    .retain(|link| seen.insert((link.from_class.clone(), link.to_class.clone(), link.line_number)));
+
+                let mut metrics = HashMap::new();
+                for (class, size) in result.class_metrics.drain() {
+                    metrics
+                        .entry(self.canonical(&class).to_string())
+                        .and_modify(|existing: &mut ClassMetrics| existing.combine(&size))
+                        .or_insert(size);
+                }
+                result.class_metrics = metrics;
+                let mut tags = ClassTags::new();
+                for (class, class_tags) in result.class_tags.drain() {
+                    tags.entry(self.canonical(&class).to_string()).or_default().extend(class_tags);
+                }
+                result.class_tags = tags;
+                let mut budgets = HashMap::new();
+                for (class, max) in result.dependency_budgets.drain() {
+                    budgets
+                        .entry(self.canonical(&class).to_string())
+                        .and_modify(|existing: &mut usize| *existing = (*existing).min(max))
+                        .or_insert(max);
+                }
+                result.dependency_budgets = budgets;
+                result.namespaces = result
+                    .namespaces
+                    .drain()
+                    .map(|(class, namespace)| (self.canonical(&class).to_string(), namespace))
+                    .collect();
+                file
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_analyzer::AnalysisResult;
+    use crate::parser::ClassLink;
+
+    fn file(path: &str, classes: &[&str], links: &[(&str, &str)]) -> FileAnalysis {
+        FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|s| s.to_string()).collect(),
+                links: links
+                    .iter()
+                    .map(|(from, to)| ClassLink {
+                        from_class: from.to_string(),
+                        to_class: to.to_string(),
+                        line_number: 1,
+                        kind: Default::default(),
+                        position: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        }
+    }
+
+    fn aliases(entries: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        entries
+            .iter()
+            .map(|(canonical, names)| (canonical.to_string(), names.iter().map(|s| s.to_string()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_aliases_connect_cross_language_models() {
+        let map = AliasMap::from_config(&aliases(&[("User", &["UserDto", "UserModel"])])).unwrap();
+        let files = vec![
+            file("web/user.ts", &["UserDto", "Profile"], &[("Profile", "UserDto")]),
+            file("api/User.cs", &["UserModel"], &[("UserModel", "UserDto")]),
+        ];
+
+        let files = map.apply(files);
+
+        assert!(files[0].result.classes.contains("User"));
+        assert_eq!(files[0].result.links[0].to_class, "User");
+        assert!(files[1].result.classes.contains("User"));
+        assert!(files[1].result.links.is_empty());
+        assert_eq!(map.canonical("Profile"), "Profile");
+    }
+
+    #[test]
+    fn test_conflicting_aliases_are_rejected() {
+        let error = AliasMap::from_config(&aliases(&[("Account", &["UserDto"]), ("User", &["UserDto"])])).unwrap_err();
+        assert_eq!(error.to_string(), "alias 'UserDto' is mapped to both 'Account' and 'User'");
+
+        assert!(AliasMap::from_config(&aliases(&[("User", &["Account"]), ("Account", &["Login"])])).is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use crate::file_analyzer::AnalysisResult;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Where `--cache` keeps parse results when no file is given, relative to
+// the working directory like `--last-run`
+pub const DEFAULT_CACHE_PATH: &str = ".classlink/parse-cache.json";
+
+// Parse results of the previous run, by file path. An entry is reused while
+// both the file's content and the parser key match. The key names the
+// parser, its version and its options, so upgrading the checker or changing
+// one language's options re-parses the files it affects and nothing else.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParseCache {
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    parser: String,
+    // `sha256:` and the hex digest of the decoded content
+    hash: String,
+    result: AnalysisResult,
+}
+
+impl ParseCache {
+    // Empty on the first run; a file that no longer parses, e.g. one left
+    // by an older version, is treated as no cache
+    pub fn load(path: &str) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        if let Some(parent) = Path::new(path).parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self).map_err(io::Error::other)?)
+    }
+
+    pub fn get(&self, path: &str, parser: &str, hash: &str) -> Option<&AnalysisResult> {
+        self.entries
+            .get(path)
+            .filter(|entry| entry.parser == parser && entry.hash == hash)
+            .map(|entry| &entry.result)
+    }
+
+    pub fn insert(&mut self, path: &str, parser: String, hash: String, result: AnalysisResult) {
+        self.entries.insert(path.to_string(), CacheEntry { parser, hash, result });
+    }
+
+    // Carries over the previous run's entries for files this run did not
+    // reach, so a stopped scan does not evict them
+    pub fn keep_unvisited(&mut self, previous: &ParseCache) {
+        for (path, entry) in &previous.entries {
+            self.entries.entry(path.clone()).or_insert_with(|| entry.clone());
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+// Shared flag another thread (an IDE's cancel request, a CI watchdog) sets to
+// stop a running scan. Clones share the flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+// Why a scan stopped before visiting every file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Interruption {
+    Cancelled,
+    TimedOut,
+}
+
+impl fmt::Display for Interruption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Interruption::Cancelled => "cancelled",
+            Interruption::TimedOut => "timed out",
+        })
+    }
+}
+
+// Checked between files and directories; the default never stops
+#[derive(Debug, Clone, Default)]
+pub struct StopCondition {
+    pub token: Option<CancellationToken>,
+    pub deadline: Option<Instant>,
+}
+
+impl StopCondition {
+    pub fn check(&self) -> Option<Interruption> {
+        if self.token.as_ref().is_some_and(CancellationToken::is_cancelled) {
+            Some(Interruption::Cancelled)
+        } else if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            Some(Interruption::TimedOut)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_stop_condition() {
+        let token = CancellationToken::new();
+        let stop = StopCondition {
+            token: Some(token.clone()),
+            deadline: Some(Instant::now() + Duration::from_secs(3600)),
+        };
+        assert_eq!(stop.check(), None);
+        token.cancel();
+        assert_eq!(stop.check(), Some(Interruption::Cancelled));
+
+        let expired = StopCondition {
+            token: None,
+            deadline: Some(Instant::now()),
+        };
+        assert_eq!(expired.check(), Some(Interruption::TimedOut));
+        assert_eq!(StopCondition::default().check(), None);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::coupling::CouplingReport;
+use crate::i18n::{Catalog, Message};
+use crate::manifest::relative_path;
+use crate::project::FileAnalysis;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::io;
+use std::process::Command;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChurnHotspot {
+    pub class: String,
+    // Commits that touched a file defining the class
+    pub commits: usize,
+    pub coupling: f64,
+    // Change frequency times weighted coupling: code that changes often AND
+    // drags many classes along ranks first
+    pub score: f64,
+}
+
+// Files changed by each commit reachable from HEAD, relative to `root`,
+// newest first. `since` is anything `git log --since` takes, e.g.
+// `2024-01-01` or `6 months ago`.
+pub fn commits(root: &str, since: Option<&str>) -> io::Result<Vec<BTreeSet<String>>> {
+    let mut command = Command::new("git");
+    command.args(["-C", root, "log", "--format=%x1e", "--name-only", "--relative"]);
+    if let Some(since) = since {
+        command.arg(format!("--since={}", since));
+    }
+    let output = command
+        .args(["--", "."])
+        .output()
+        .map_err(|e| io::Error::new(e.kind(), format!("failed to run git log: {}", e)))?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "git log failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\x1e')
+        .map(|commit| commit.lines().filter(|line| !line.is_empty()).map(str::to_string).collect())
+        .filter(|files: &BTreeSet<String>| !files.is_empty())
+        .collect())
+}
+
+// Commits per defined class; a class defined in several files counts each
+// commit touching any of them once
+pub fn class_churn(root: &str, files: &[FileAnalysis], commits: &[BTreeSet<String>]) -> BTreeMap<String, usize> {
+    let mut defined_in: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for file in files {
+        for class in &file.result.classes {
+            defined_in.entry(class).or_default().push(relative_path(root, &file.path));
+        }
+    }
+    defined_in
+        .into_iter()
+        .map(|(class, paths)| {
+            let touched = commits.iter().filter(|changed| paths.iter().any(|path| changed.contains(path)));
+            (class.to_string(), touched.count())
+        })
+        .collect()
+}
+
+pub fn churn_hotspots(coupling: &CouplingReport, churn: &BTreeMap<String, usize>) -> Vec<ChurnHotspot> {
+    let mut hotspots: Vec<ChurnHotspot> = coupling
+        .classes
+        .iter()
+        .filter_map(|score| {
+            let commits = *churn.get(&score.name)?;
+            Some(ChurnHotspot {
+                class: score.name.clone(),
+                commits,
+                coupling: score.total,
+                score: commits as f64 * score.total,
+            })
+        })
+        .filter(|hotspot| hotspot.score > 0.0)
+        .collect();
+    hotspots.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.class.cmp(&b.class)));
+    hotspots
+}
+
+const CHURN_HOTSPOTS_TOP: usize = 5;
+
+pub fn render_churn_hotspots(hotspots: &[ChurnHotspot], catalog: &Catalog) -> String {
+    let mut out = String::new();
+    if hotspots.is_empty() {
+        return out;
+    }
+    writeln!(out, "\n{}", catalog.text(Message::ChurnHotspotsHeader, &[&CHURN_HOTSPOTS_TOP])).unwrap();
+    for hotspot in hotspots.iter().take(CHURN_HOTSPOTS_TOP) {
+        let line = catalog.text(
+            Message::ChurnHotspotLine,
+            &[&hotspot.class, &hotspot.commits, &format!("{:.1}", hotspot.coupling), &format!("{:.1}", hotspot.score)],
+        );
+        writeln!(out, "{}", line).unwrap();
+    }
+    out
+}
+
+#[cfg(all(test, feature = "python"))]
+mod tests {
+    use super::*;
+    use crate::coupling::{self, LinkWeights};
+    use crate::manifest::Grouping;
+    use crate::project::ProjectAnalyzer;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_churn_hotspots_rank_commits_times_coupling() {
+        let dir = TempDir::new().unwrap();
+        let git = |args: &[&str], date: &str| {
+            let mut command = Command::new("git");
+            command.args(["-c", "user.name=test", "-c", "user.email=test@example.com"]).args(args);
+            command.env("GIT_COMMITTER_DATE", date).current_dir(dir.path()).output().unwrap()
+        };
+        git(&["init", "--quiet"], "");
+        let commit = |path: &str, content: &str, date: &str| {
+            fs::write(dir.path().join(path), content).unwrap();
+            git(&["add", "."], date);
+            git(&["commit", "--quiet", "-m", path], date);
+        };
+        commit("cart.py", "class Cart:\n    pass\n", "2020-01-01T00:00:00");
+        commit("shop.py", "class Shop:\n    def __init__(self):\n        self.cart = Cart()\n", "2024-01-01T00:00:00");
+        commit("cart.py", "class Cart:\n    items = []\n", "2024-02-01T00:00:00");
+        commit("cart.py", "class Cart:\n    items = {}\n", "2024-03-01T00:00:00");
+        commit("notes.txt", "not analyzed\n", "2024-04-01T00:00:00");
+
+        let root = dir.path().to_str().unwrap();
+        let files = ProjectAnalyzer::new().analyze_files(root).unwrap();
+        let history = commits(root, None).unwrap();
+        assert_eq!(history.len(), 5);
+        let churn = class_churn(root, &files, &history);
+        assert_eq!((churn["Cart"], churn["Shop"]), (3, 1));
+        let recent = commits(root, Some("2023-01-01")).unwrap();
+        assert_eq!(class_churn(root, &files, &recent)["Cart"], 2);
+
+        let hotspots = churn_hotspots(&coupling::weighted_coupling(root, &files, &LinkWeights::default(), Grouping::Directory), &churn);
+        assert_eq!(hotspots[0].class, "Cart");
+        assert_eq!(hotspots[0].score, 3.0 * hotspots[0].coupling);
+        assert!(render_churn_hotspots(&hotspots, &Catalog::default()).contains("  Cart: 3 commits"));
+    }
+}
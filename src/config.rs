@@ -0,0 +1,309 @@
+use crate::coupling::LinkWeights;
+use crate::custom_analyzer::CustomAnalyzerConfig;
+use crate::exceptions::RuleException;
+use crate::external_types::ExternalTypes;
+use crate::file_analyzer::ParserOptions;
+use crate::findings;
+use crate::export::{ColorMetric, DiagramLimits, ExportFilter};
+use crate::i18n::Locale;
+use crate::limits::ResourceLimits;
+use crate::manifest::Grouping;
+use crate::plugin::PluginConfig;
+use crate::position::ColumnEncoding;
+use crate::scan_io::IoOptions;
+use crate::impact;
+use crate::subgraph::Focus;
+use crate::walker;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    Manifest,
+    NetworkX,
+    // Neo4j Cypher statements
+    Cypher,
+    // Graphviz and Mermaid diagrams
+    Dot,
+    Mermaid,
+    Sarif,
+    // Class definitions for editors: a ctags `tags` file, or the same index
+    // as JSON
+    Ctags,
+    Symbols,
+    // The user's `template`, see `template`
+    Template,
+    // Classes and links as JSON lines while the scan runs, see `stream`
+    #[serde(rename = "jsonl")]
+    JsonLines,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "manifest" => Ok(OutputFormat::Manifest),
+            "networkx" => Ok(OutputFormat::NetworkX),
+            "cypher" => Ok(OutputFormat::Cypher),
+            "dot" => Ok(OutputFormat::Dot),
+            "mermaid" => Ok(OutputFormat::Mermaid),
+            "sarif" => Ok(OutputFormat::Sarif),
+            "ctags" => Ok(OutputFormat::Ctags),
+            "symbols" => Ok(OutputFormat::Symbols),
+            "template" => Ok(OutputFormat::Template),
+            "jsonl" => Ok(OutputFormat::JsonLines),
+            _ => Err(format!("Unknown output format: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+            OutputFormat::Manifest => "manifest",
+            OutputFormat::NetworkX => "networkx",
+            OutputFormat::Cypher => "cypher",
+            OutputFormat::Dot => "dot",
+            OutputFormat::Mermaid => "mermaid",
+            OutputFormat::Sarif => "sarif",
+            OutputFormat::Ctags => "ctags",
+            OutputFormat::Symbols => "symbols",
+            OutputFormat::Template => "template",
+            OutputFormat::JsonLines => "jsonl",
+        };
+        f.write_str(name)
+    }
+}
+
+// Everything the pipeline needs, so embedders (xtask, build scripts) can drive
+// a full run without going through the CLI. Loadable from a JSON file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub path: String,
+    pub recursive: bool,
+    // Directory levels below `path` a recursive scan enters; `None` for no
+    // limit
+    pub max_depth: Option<usize>,
+    pub follow_symlinks: bool,
+    // Files above this size are skipped; `None` disables the limit
+    pub max_file_size_mb: Option<u64>,
+    pub skip_binary: bool,
+    pub skip_vendored: bool,
+    // Globs over root-relative files and directories left out of the scan;
+    // build output, virtualenv and `.git` directories by default
+    pub exclude: Vec<String>,
+    pub respect_gitignore: bool,
+    // Globs identifying test files for impact analysis and test selection
+    pub test_patterns: Vec<String>,
+    // Globs over class names and root-relative files of classes started
+    // from outside, whose isolation is expected
+    pub entry_points: Vec<String>,
+    pub link_weights: LinkWeights,
+    // Canonical class name -> names that mean the same class in other
+    // languages, e.g. `{"User": ["UserDto", "UserModel"]}`
+    pub aliases: BTreeMap<String, Vec<String>>,
+    // Keep the source line of every link (shown in manifest locations)
+    pub capture_snippets: bool,
+    // How link columns are counted: `utf-16` (LSP and SARIF), `utf-8` or
+    // `utf-32` (characters)
+    pub column_encoding: ColumnEncoding,
+    // Restricts the report and exports to part of the graph
+    pub focus: Option<Focus>,
+    // Graph exports show packages instead of classes
+    pub collapse_packages: bool,
+    // Whether packages are directories or namespaces, for package coupling,
+    // package exports and offenders
+    pub group_by: Grouping,
+    // Split each class's links into ones on interfaces/abstract classes and
+    // ones on concrete classes in the statistics
+    pub abstraction_coupling: bool,
+    // Links kept by the graph exports (NetworkX, Cypher, DOT, Mermaid,
+    // manifest)
+    pub export_filter: ExportFilter,
+    // DOT and Mermaid graphs past these sizes fold leaf classes into summary
+    // nodes; `None` draws every class
+    pub diagram_limits: Option<DiagramLimits>,
+    // Metric filling DOT and Mermaid nodes from green to red
+    pub diagram_color: Option<ColorMetric>,
+    // Documentation URL template for class names; `{class}` is substituted
+    pub doc_url: Option<String>,
+    // Documented exceptions to rules; expired ones fail the run
+    pub exceptions: Vec<RuleException>,
+    // Regex analyzers for languages without a built-in parser
+    pub custom_analyzers: Vec<CustomAnalyzerConfig>,
+    // What the built-in parsers count as links, per language
+    pub parsers: ParserOptions,
+    // Resolve references against the classes of the whole project instead
+    // of each file's own declarations and imports, see `resolution`
+    pub global_resolution: bool,
+    // Record classes imported from files outside `path`, e.g. a sibling
+    // package, as external nodes
+    pub follow_imports: bool,
+    // Standard-library and framework types not linked to, per language
+    pub external_types: ExternalTypes,
+    // Report links to Django, Angular, ASP.NET and configured framework
+    // types per class and package
+    pub external_coupling: bool,
+    // Keep classes the project does not define, like third-party package
+    // types, apart from the graph as external nodes with their own
+    // statistics
+    pub external_nodes: bool,
+    // External analyzer and rule plugins, see `plugin`
+    pub plugins: Vec<PluginConfig>,
+    // Layer name -> globs over root-relative file paths, for scripted rules
+    pub layers: BTreeMap<String, Vec<String>>,
+    // Tag name -> globs over class names and root-relative file paths, for
+    // tag-level statistics
+    pub tags: BTreeMap<String, Vec<String>>,
+    // Layer tags from entry to data, e.g. `["ui", "service", "data"]`, for
+    // travel distances and layer-skip findings
+    pub layer_order: Vec<String>,
+    // Per-author statistics from `git blame`, see `ownership`
+    pub ownership: bool,
+    // Team name -> globs over author names and emails, e.g. `*@payments.io`
+    pub teams: BTreeMap<String, Vec<String>>,
+    // Rank classes by commits from `git log` times coupling, see `churn`
+    pub churn: bool,
+    // Only count commits since this date, in any form `git log --since` takes
+    pub churn_since: Option<String>,
+    // Files of scripted rules, see `script`; needs the `scripting` feature
+    pub rule_scripts: Vec<String>,
+    pub output: OutputFormat,
+    pub output_path: Option<String>,
+    // Handlebars-style template file for the `template` output format
+    pub template: Option<String>,
+    // Where to write the `AnalysisDiagnostics` record as JSON
+    pub diagnostics_path: Option<String>,
+    // Where parse results are kept between runs so unchanged files are not
+    // parsed again, see `cache`
+    pub cache_path: Option<String>,
+    // Threads, read size and prefetching for reading and parsing files
+    pub io: IoOptions,
+    // Stop analyzing after this many seconds and report what was found
+    pub timeout_secs: Option<u64>,
+    // Where to write Prometheus textfile-format metrics
+    pub metrics_path: Option<String>,
+    // Caps on files, classes and links; the scan is truncated past them
+    pub limits: ResourceLimits,
+    // OSC 8 hyperlinks on file locations in the text report, for terminals
+    // that support them; only applied when stdout is a terminal
+    pub hyperlinks: bool,
+    // URL template for those links, e.g. `vscode://file/{path}:{line}`;
+    // defaults to `file://{path}`
+    pub editor_url: Option<String>,
+    pub locale: Locale,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            path: ".".to_string(),
+            recursive: true,
+            max_depth: None,
+            follow_symlinks: false,
+            max_file_size_mb: Some(10),
+            skip_binary: true,
+            skip_vendored: true,
+            exclude: walker::DEFAULT_EXCLUDES.iter().map(|p| p.to_string()).collect(),
+            respect_gitignore: true,
+            test_patterns: impact::default_test_patterns(),
+            entry_points: findings::default_entry_points(),
+            link_weights: LinkWeights::default(),
+            aliases: BTreeMap::new(),
+            capture_snippets: false,
+            column_encoding: ColumnEncoding::default(),
+            focus: None,
+            collapse_packages: false,
+            group_by: Grouping::Directory,
+            abstraction_coupling: false,
+            export_filter: ExportFilter::default(),
+            diagram_limits: Some(DiagramLimits::default()),
+            diagram_color: None,
+            doc_url: None,
+            exceptions: Vec::new(),
+            custom_analyzers: Vec::new(),
+            parsers: ParserOptions::default(),
+            global_resolution: false,
+            follow_imports: false,
+            external_types: ExternalTypes::default(),
+            external_coupling: false,
+            external_nodes: false,
+            plugins: Vec::new(),
+            layers: BTreeMap::new(),
+            tags: BTreeMap::new(),
+            layer_order: Vec::new(),
+            ownership: false,
+            teams: BTreeMap::new(),
+            churn: false,
+            churn_since: None,
+            rule_scripts: Vec::new(),
+            output: OutputFormat::Text,
+            output_path: None,
+            template: None,
+            diagnostics_path: None,
+            cache_path: None,
+            io: IoOptions::default(),
+            timeout_secs: None,
+            metrics_path: None,
+            limits: ResourceLimits::default(),
+            hyperlinks: false,
+            editor_url: None,
+            locale: Locale::En,
+        }
+    }
+}
+
+impl Config {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, Box<dyn Error>> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let config: Config = serde_json::from_str(r#"{ "path": "src", "output": "json" }"#).unwrap();
+
+        assert_eq!(config.path, "src");
+        assert_eq!(config.output, OutputFormat::Json);
+        assert!(config.recursive);
+        assert!(!config.follow_symlinks);
+        assert_eq!(config.max_file_size_mb, Some(10));
+        assert!(config.test_patterns.contains(&"*.spec.*".to_string()));
+        assert_eq!(config.output_path, None);
+        assert_eq!(config.locale, Locale::En);
+    }
+
+    #[test]
+    fn test_output_format_round_trip() {
+        for format in ["text", "json", "manifest", "networkx", "cypher", "sarif"] {
+            assert_eq!(format.parse::<OutputFormat>().unwrap().to_string(), format);
+        }
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+}
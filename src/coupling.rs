@@ -0,0 +1,172 @@
+use crate::manifest::{self, Grouping};
+use crate::parser::LinkKind;
+use crate::project::FileAnalysis;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LinkWeights {
+    pub inheritance: f64,
+    pub composition: f64,
+    pub usage: f64,
+    pub binding: f64,
+    pub doc: f64,
+}
+
+impl Default for LinkWeights {
+    fn default() -> Self {
+        Self {
+            inheritance: 3.0,
+            composition: 2.0,
+            usage: 1.0,
+            binding: 1.0,
+            doc: 0.1,
+        }
+    }
+}
+
+impl LinkWeights {
+    pub fn weight(&self, kind: LinkKind) -> f64 {
+        match kind {
+            LinkKind::Inheritance => self.inheritance,
+            LinkKind::Composition => self.composition,
+            LinkKind::Usage => self.usage,
+            LinkKind::Binding => self.binding,
+            LinkKind::Doc => self.doc,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CouplingScore {
+    pub name: String,
+    // Weighted incoming (afferent) and outgoing (efferent) dependencies
+    pub afferent: f64,
+    pub efferent: f64,
+    pub total: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CouplingReport {
+    pub classes: Vec<CouplingScore>,
+    pub packages: Vec<CouplingScore>,
+}
+
+fn scores(pairs: &BTreeMap<(String, String), f64>) -> Vec<CouplingScore> {
+    let mut totals: BTreeMap<&str, (f64, f64)> = BTreeMap::new();
+    for ((from, to), weight) in pairs {
+        totals.entry(from).or_default().1 += weight;
+        totals.entry(to).or_default().0 += weight;
+    }
+
+    let mut scores: Vec<CouplingScore> = totals
+        .into_iter()
+        .map(|(name, (afferent, efferent))| CouplingScore {
+            name: name.to_string(),
+            afferent,
+            efferent,
+            total: afferent + efferent,
+        })
+        .collect();
+    scores.sort_by(|a, b| b.total.total_cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+    scores
+}
+
+// A pair of classes linked several ways counts once, at its most costly
+// kind, so repeating a usage does not outweigh a single inheritance.
+// Package scores only count links that cross package boundaries.
+pub fn weighted_coupling(root: &str, files: &[FileAnalysis], weights: &LinkWeights, grouping: Grouping) -> CouplingReport {
+    let package_of_class = manifest::class_groups(root, files, grouping);
+
+    let mut class_pairs: BTreeMap<(String, String), f64> = BTreeMap::new();
+    for link in files.iter().flat_map(|file| &file.result.links) {
+        if link.from_class == link.to_class {
+            continue;
+        }
+        let weight = class_pairs
+            .entry((link.from_class.clone(), link.to_class.clone()))
+            .or_insert(0.0);
+        *weight = weight.max(weights.weight(link.kind));
+    }
+
+    let mut package_pairs: BTreeMap<(String, String), f64> = BTreeMap::new();
+    for ((from, to), weight) in &class_pairs {
+        let (Some(from), Some(to)) = (package_of_class.get(from), package_of_class.get(to)) else {
+            continue;
+        };
+        if from != to {
+            *package_pairs.entry((from.clone(), to.clone())).or_default() += weight;
+        }
+    }
+
+    CouplingReport {
+        classes: scores(&class_pairs),
+        packages: scores(&package_pairs),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_analyzer::AnalysisResult;
+    use crate::parser::ClassLink;
+
+    fn file(path: &str, classes: &[&str], links: &[(&str, &str, LinkKind)]) -> FileAnalysis {
+        FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|s| s.to_string()).collect(),
+                links: links
+                    .iter()
+                    .map(|(from, to, kind)| ClassLink {
+                        from_class: from.to_string(),
+                        to_class: to.to_string(),
+                        line_number: 1,
+                        kind: *kind,
+                        position: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_weighted_coupling_per_class_and_package() {
+        let files = vec![
+            file(
+                "/repo/web/admin.py",
+                &["Admin"],
+                &[
+                    ("Admin", "User", LinkKind::Inheritance),
+                    ("Admin", "User", LinkKind::Usage),
+                    ("Admin", "Audit", LinkKind::Usage),
+                ],
+            ),
+            file("/repo/domain/user.py", &["User", "Audit"], &[("User", "Audit", LinkKind::Composition)]),
+            file("/repo/docs/User.md", &[], &[("User", "Admin", LinkKind::Doc)]),
+        ];
+
+        let report = weighted_coupling("/repo", &files, &LinkWeights::default(), Grouping::Directory);
+
+        assert_eq!(report.classes[0].name, "User");
+        assert_eq!(report.classes[0].afferent, 3.0);
+        assert_eq!(report.classes[0].efferent, 2.1);
+        let admin = report.classes.iter().find(|c| c.name == "Admin").unwrap();
+        assert_eq!(admin.efferent, 4.0);
+
+        let web = report.packages.iter().find(|p| p.name == "web").unwrap();
+        assert_eq!(web.efferent, 4.0);
+        assert_eq!(web.afferent, 0.1);
+    }
+
+    #[test]
+    fn test_partial_weights_fall_back_to_defaults() {
+        let weights: LinkWeights = serde_json::from_str(r#"{ "doc": 0.0 }"#).unwrap();
+
+        assert_eq!(weights.weight(LinkKind::Doc), 0.0);
+        assert_eq!(weights.weight(LinkKind::Inheritance), 3.0);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::file_analyzer::{AnalysisResult, FileAnalyzer};
+use crate::glob;
+use crate::parser::{ClassLink, LinkKind};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+// A regex-only analyzer declared in the config file, for in-house DSLs and
+// languages without a built-in parser. Each regex takes its name from the
+// first capture group.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomAnalyzerConfig {
+    pub name: String,
+    // Globs selecting the files this analyzer handles, e.g. `*.flow`
+    pub files: Vec<String>,
+    pub class_regex: String,
+    pub reference_regex: String,
+    // Captures a comma-separated list of imported class names
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import_regex: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct CustomAnalyzerError {
+    pub analyzer: String,
+    pub field: &'static str,
+    pub source: regex::Error,
+}
+
+impl fmt::Display for CustomAnalyzerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "custom analyzer '{}': invalid {}: {}", self.analyzer, self.field, self.source)
+    }
+}
+
+impl std::error::Error for CustomAnalyzerError {}
+
+#[derive(Debug, Clone)]
+pub struct CustomAnalyzer {
+    name: String,
+    files: Vec<String>,
+    class_regex: Regex,
+    reference_regex: Regex,
+    import_regex: Option<Regex>,
+}
+
+impl CustomAnalyzer {
+    pub fn new(config: &CustomAnalyzerConfig) -> Result<Self, CustomAnalyzerError> {
+        let compile = |field: &'static str, pattern: &str| {
+            Regex::new(pattern).map_err(|source| CustomAnalyzerError {
+                analyzer: config.name.clone(),
+                field,
+                source,
+            })
+        };
+
+        Ok(Self {
+            name: config.name.clone(),
+            files: config.files.clone(),
+            class_regex: compile("class_regex", &config.class_regex)?,
+            reference_regex: compile("reference_regex", &config.reference_regex)?,
+            import_regex: config
+                .import_regex
+                .as_deref()
+                .map(|pattern| compile("import_regex", pattern))
+                .transpose()?,
+        })
+    }
+
+    pub fn from_configs(configs: &[CustomAnalyzerConfig]) -> Result<Vec<Self>, CustomAnalyzerError> {
+        configs.iter().map(Self::new).collect()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn file_patterns(&self) -> &[String] {
+        &self.files
+    }
+
+    pub fn handles(&self, path: &str) -> bool {
+        self.files.iter().any(|pattern| glob::matches_tail(pattern, path))
+    }
+}
+
+impl FileAnalyzer for CustomAnalyzer {
+    fn analyze(&self, content: &str, _file_path: &str) -> AnalysisResult {
+        let mut classes = HashSet::new();
+        let mut links: Vec<ClassLink> = Vec::new();
+        let mut current_class: Option<String> = None;
+        let mut imports = Vec::new();
+
+        for (line_num, line) in content.lines().enumerate() {
+            if let Some(caps) = self.class_regex.captures(line) {
+                if let Some(name) = caps.get(1) {
+                    classes.insert(name.as_str().to_string());
+                    current_class = Some(name.as_str().to_string());
+                }
+            }
+
+            if let Some(caps) = self.import_regex.as_ref().and_then(|regex| regex.captures(line)) {
+                if let Some(names) = caps.get(1) {
+                    for name in names.as_str().split(',').map(str::trim).filter(|name| !name.is_empty()) {
+                        imports.push((name.to_string(), line_num + 1));
+                    }
+                }
+                continue;
+            }
+
+            let Some(ref from_class) = current_class else {
+                continue;
+            };
+            for caps in self.reference_regex.captures_iter(line) {
+                let Some(to_class) = caps.get(1).map(|m| m.as_str()) else {
+                    continue;
+                };
+                let already_exists = links
+                    .iter()
+                    .any(|l| l.from_class == *from_class && l.to_class == to_class && l.line_number == line_num + 1);
+                if to_class != from_class && !already_exists {
+                    links.push(ClassLink {
+                        from_class: from_class.clone(),
+                        to_class: to_class.to_string(),
+                        line_number: line_num + 1,
+                        kind: LinkKind::Usage,
+                        position: None,
+                    });
+                }
+            }
+        }
+
+        // Imports apply to the whole file: every class defined in it depends
+        // on what it imports, unless it already references it directly
+        let mut file_classes: Vec<&String> = classes.iter().collect();
+        file_classes.sort();
+        for (imported, line_number) in imports {
+            for class in &file_classes {
+                let already_linked = links.iter().any(|l| l.from_class == **class && l.to_class == imported);
+                if **class != imported && !already_linked {
+                    links.push(ClassLink {
+                        from_class: class.to_string(),
+                        to_class: imported.clone(),
+                        line_number,
+                        kind: LinkKind::Usage,
+                        position: None,
+                    });
+                }
+            }
+        }
+
+        AnalysisResult {
+            classes,
+            links,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flow_config() -> CustomAnalyzerConfig {
+        serde_json::from_str(
+            r#"{
+                "name": "flow",
+                "files": ["*.flow", "workflows/*.yaml"],
+                "class_regex": "^flow\\s+(\\w+)",
+                "reference_regex": "call\\s+(\\w+)",
+                "import_regex": "^use\\s+(.+)"
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_custom_analyzer_extracts_classes_and_links() {
+        let analyzer = CustomAnalyzer::new(&flow_config()).unwrap();
+        let content = "use Mailer, Audit\nflow Checkout\n  call Payment\n  call Payment\n  call Audit\nflow Payment\n";
+
+        let result = analyzer.analyze(content, "checkout.flow");
+
+        assert_eq!(result.classes.len(), 2);
+        let links: Vec<(&str, &str, usize)> = result
+            .links
+            .iter()
+            .map(|l| (l.from_class.as_str(), l.to_class.as_str(), l.line_number))
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                ("Checkout", "Payment", 3),
+                ("Checkout", "Payment", 4),
+                ("Checkout", "Audit", 5),
+                ("Checkout", "Mailer", 1),
+                ("Payment", "Mailer", 1),
+                ("Payment", "Audit", 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_analyzer_file_selection_and_errors() {
+        let analyzer = CustomAnalyzer::new(&flow_config()).unwrap();
+        assert!(analyzer.handles("/repo/checkout.flow"));
+        assert!(analyzer.handles("/repo/ci/workflows/build.yaml"));
+        assert!(!analyzer.handles("/repo/config.yaml"));
+
+        let mut broken = flow_config();
+        broken.reference_regex = "call\\s+(".to_string();
+        let error = CustomAnalyzer::new(&broken).unwrap_err();
+        assert_eq!(error.field, "reference_regex");
+        assert!(error.to_string().starts_with("custom analyzer 'flow': invalid reference_regex"));
+    }
+}
@@ -0,0 +1,314 @@
+use crate::annotations;
+use crate::analyzer::LinkAnalyzer;
+use crate::encoding;
+use crate::file_analyzer::{AnalysisResult, FileType, UnifiedAnalyzer};
+use crate::graph::ClassGraph;
+use crate::position::ColumnEncoding;
+use crate::jsonrpc::{self, Handler, RpcError, INTERNAL_ERROR, METHOD_NOT_FOUND};
+use crate::project::ProjectAnalyzer;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+// Lightweight JSON-RPC daemon for editor extensions. It keeps per-file
+// results in memory, re-analyzes only the files it is told about, and pushes
+// a `graph/didChange` notification after each update.
+pub struct Daemon {
+    root: String,
+    recursive: bool,
+    analyzer: UnifiedAnalyzer,
+    files: BTreeMap<String, AnalysisResult>,
+    exit_requested: bool,
+}
+
+impl Daemon {
+    pub fn new(root: &str, recursive: bool) -> Self {
+        Self {
+            root: root.to_string(),
+            recursive,
+            analyzer: UnifiedAnalyzer::new(),
+            files: BTreeMap::new(),
+            exit_requested: false,
+        }
+    }
+
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let mut outgoing = Vec::new();
+
+        let result = match method {
+            "initialize" => self.initialize(&params),
+            "initialized" => Ok(Value::Null),
+            "files/didChange" => self.did_change(&params, &mut outgoing),
+            "files/didDelete" => self.did_delete(&params, &mut outgoing),
+            "graph/dependencies" => self.dependencies(&params),
+            "graph/cycles" => Ok(json!({ "cycles": self.graph().find_cycles() })),
+            "graph/isolated" => Ok(json!({ "isolated_classes": self.graph().isolated_classes() })),
+            "graph/statistics" => self.statistics(),
+            "graph/classesInFile" => self.classes_in_file(&params),
+            "graph/linksInFile" => self.links_in_file(&params),
+            "graph/filesOfClass" => self.files_of_class(&params),
+            "shutdown" => Ok(Value::Null),
+            "exit" => {
+                self.exit_requested = true;
+                Ok(Value::Null)
+            }
+            _ => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+        };
+
+        // Responses are only sent for requests; notifications stay silent
+        if let Some(id) = message.get("id") {
+            outgoing.insert(0, jsonrpc::response(id.clone(), result));
+        }
+
+        outgoing
+    }
+
+    // Columns are counted in the first encoding the client lists under
+    // `capabilities.general.positionEncodings`, as in LSP 3.17; UTF-16
+    // when it lists none
+    fn initialize(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let encoding = params
+            .pointer("/capabilities/general/positionEncodings")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|encoding| encoding.as_str()?.parse::<ColumnEncoding>().ok())
+            .next()
+            .unwrap_or_default();
+        self.analyzer = UnifiedAnalyzer::new().with_column_encoding(encoding);
+        let files = ProjectAnalyzer::new()
+            .recursive(self.recursive)
+            .find_supported_files(&self.root)
+            .map_err(|e| (INTERNAL_ERROR, format!("Error scanning project: {}", e)))?;
+
+        self.files.clear();
+        for file in files {
+            if let Ok(source) = encoding::read_source(&file) {
+                let result = self.analyzer.analyze_file(&source.content, &file);
+                self.files.insert(file, result);
+            }
+        }
+
+        Ok(json!({
+            "files": self.files.len(),
+            "classes": self.merged().classes.len(),
+            "positionEncoding": encoding,
+        }))
+    }
+
+    fn did_change(&mut self, params: &Value, outgoing: &mut Vec<Value>) -> Result<Value, RpcError> {
+        let path = jsonrpc::string_param(params, "path")?;
+        if UnifiedAnalyzer::detect_file_type(path) == FileType::Unknown {
+            return Ok(json!({ "analyzed": false }));
+        }
+
+        // Editors may send unsaved buffer contents; fall back to disk otherwise
+        let content = match params.get("content").and_then(Value::as_str) {
+            Some(content) => content.to_string(),
+            None => encoding::read_source(path)
+                .map_err(|e| (INTERNAL_ERROR, format!("Error reading {}: {}", path, e)))?
+                .content,
+        };
+
+        let result = self.analyzer.analyze_file(&content, path);
+        let classes: BTreeSet<String> = result.classes.iter().cloned().collect();
+        let link_count = result.links.len();
+        self.files.insert(path.to_string(), result);
+
+        outgoing.push(jsonrpc::notification(
+            "graph/didChange",
+            json!({ "path": path, "classes": classes, "links": link_count }),
+        ));
+        Ok(json!({ "analyzed": true }))
+    }
+
+    fn did_delete(&mut self, params: &Value, outgoing: &mut Vec<Value>) -> Result<Value, RpcError> {
+        let path = jsonrpc::string_param(params, "path")?;
+        let removed = self.files.remove(path).is_some();
+
+        if removed {
+            outgoing.push(jsonrpc::notification(
+                "graph/didChange",
+                json!({ "path": path, "classes": [], "links": 0 }),
+            ));
+        }
+        Ok(json!({ "removed": removed }))
+    }
+
+    fn dependencies(&self, params: &Value) -> Result<Value, RpcError> {
+        let class = jsonrpc::string_param(params, "class")?;
+        let graph = self.graph();
+
+        Ok(json!({
+            "class": class,
+            "known": graph.contains(class),
+            "dependencies": graph.dependencies(class),
+            "dependents": graph.dependents(class),
+        }))
+    }
+
+    fn statistics(&self) -> Result<Value, RpcError> {
+        let merged = self.merged();
+        let stats = LinkAnalyzer::new().analyze(merged.classes, merged.links);
+        serde_json::to_value(stats).map_err(|e| (INTERNAL_ERROR, e.to_string()))
+    }
+
+    fn classes_in_file(&self, params: &Value) -> Result<Value, RpcError> {
+        let path = jsonrpc::string_param(params, "path")?;
+        let classes: BTreeSet<String> = self
+            .files
+            .get(path)
+            .map(|result| result.classes.iter().cloned().collect())
+            .unwrap_or_default();
+
+        Ok(json!({ "path": path, "classes": classes }))
+    }
+
+    // Positions are 0-based like LSP's, in the negotiated encoding
+    fn links_in_file(&self, params: &Value) -> Result<Value, RpcError> {
+        let path = jsonrpc::string_param(params, "path")?;
+        let links: Vec<Value> = self
+            .files
+            .get(path)
+            .into_iter()
+            .flat_map(|result| &result.links)
+            .map(|link| {
+                let position = link.position.unwrap_or_default();
+                json!({
+                    "from": link.from_class,
+                    "to": link.to_class,
+                    "kind": link.kind,
+                    "position": {
+                        "line": link.line_number.saturating_sub(1),
+                        "character": position.column.saturating_sub(1),
+                    },
+                })
+            })
+            .collect();
+
+        Ok(json!({ "path": path, "links": links }))
+    }
+
+    fn files_of_class(&self, params: &Value) -> Result<Value, RpcError> {
+        let class = jsonrpc::string_param(params, "class")?;
+        let files: Vec<&String> = self
+            .files
+            .iter()
+            .filter(|(_, result)| result.classes.contains(class))
+            .map(|(path, _)| path)
+            .collect();
+
+        Ok(json!({ "class": class, "files": files }))
+    }
+
+    fn merged(&self) -> AnalysisResult {
+        let mut merged = AnalysisResult::default();
+        for result in self.files.values() {
+            merged.classes.extend(result.classes.iter().cloned());
+            merged.links.extend(result.links.iter().cloned());
+            for (class, size) in &result.class_metrics {
+                merged
+                    .class_metrics
+                    .entry(class.clone())
+                    .and_modify(|existing| existing.combine(size))
+                    .or_insert(*size);
+            }
+            annotations::merge_tags(&mut merged.class_tags, result.class_tags.clone());
+            annotations::merge_budgets(&mut merged.dependency_budgets, result.dependency_budgets.clone());
+        }
+        merged
+    }
+
+    fn graph(&self) -> ClassGraph {
+        let merged = self.merged();
+        ClassGraph::new(&merged.classes, &merged.links)
+    }
+}
+
+impl Handler for Daemon {
+    fn handle_message(&mut self, message: &Value) -> Vec<Value> {
+        self.handle(message)
+    }
+
+    fn should_exit(&self) -> bool {
+        self.exit_requested
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn request(id: u64, method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+    }
+
+    #[test]
+    fn test_initialize_indexes_project_files() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("Alpha.md"), "# Alpha\n\n[Beta](Beta.md)\n").unwrap();
+        fs::write(temp_dir.path().join("Beta.md"), "# Beta\n").unwrap();
+        let mut daemon = Daemon::new(&temp_dir.path().to_string_lossy(), false);
+
+        let responses = daemon.handle(&request(1, "initialize", json!({})));
+        assert_eq!(responses[0]["result"]["files"], 2);
+
+        let responses = daemon.handle(&request(2, "graph/dependencies", json!({ "class": "Beta" })));
+        assert_eq!(responses[0]["result"]["dependents"], json!(["Alpha"]));
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_change_feed_updates_graph_and_notifies() {
+        let mut daemon = Daemon::new("unused", false);
+        let change = json!({
+            "jsonrpc": "2.0",
+            "method": "files/didChange",
+            "params": { "path": "src/a.py", "content": "class Alpha:\n    def run(self):\n        Beta()\n" },
+        });
+
+        let messages = daemon.handle(&change);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["method"], "graph/didChange");
+        assert_eq!(messages[0]["params"]["classes"], json!(["Alpha"]));
+
+        let responses = daemon.handle(&request(2, "graph/filesOfClass", json!({ "class": "Alpha" })));
+        assert_eq!(responses[0]["result"]["files"], json!(["src/a.py"]));
+
+        let messages = daemon.handle(&request(3, "files/didDelete", json!({ "path": "src/a.py" })));
+        assert_eq!(messages[0]["result"]["removed"], true);
+        assert_eq!(messages[1]["params"]["classes"], json!([]));
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_links_in_file_use_the_negotiated_encoding() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut daemon = Daemon::new(&temp_dir.path().to_string_lossy(), false);
+        let capabilities = json!({ "capabilities": { "general": { "positionEncodings": ["utf-32", "utf-16"] } } });
+        let responses = daemon.handle(&request(1, "initialize", capabilities));
+        assert_eq!(responses[0]["result"]["positionEncoding"], "utf-32");
+
+        let change = json!({ "path": "a.py", "content": "class Alpha:\n    def run(self):\n        é = \"🛒\"; Beta()\n" });
+        daemon.handle(&request(2, "files/didChange", change));
+        let responses = daemon.handle(&request(3, "graph/linksInFile", json!({ "path": "a.py" })));
+
+        let link = &responses[0]["result"]["links"][0];
+        assert_eq!(link["to"], "Beta");
+        assert_eq!(link["position"], json!({ "line": 2, "character": 17 }));
+    }
+
+    #[test]
+    fn test_exit_stops_serving() {
+        let mut daemon = Daemon::new("unused", false);
+        let input = "{\"jsonrpc\":\"2.0\",\"method\":\"exit\"}\n{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"shutdown\"}\n";
+        let mut output = Vec::new();
+
+        jsonrpc::serve(&mut daemon, input.as_bytes(), &mut output).unwrap();
+
+        assert!(output.is_empty());
+    }
+}
@@ -0,0 +1,169 @@
+use crate::encoding::{DecodedText, Encoding};
+use crate::file_analyzer::{AnalysisResult, FileType, UnifiedAnalyzer};
+use crate::walker::SkippedFile;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+// Everything a scan skipped or could not interpret, for judging how well the
+// parsers cover a codebase
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AnalysisDiagnostics {
+    // Files without an analyzer, counted by extension (`""` for none)
+    pub unsupported_extensions: BTreeMap<String, usize>,
+    pub unsupported_files: Vec<String>,
+    pub skipped: Vec<SkippedFile>,
+    pub encoding_fallbacks: Vec<EncodingFallback>,
+    #[serde(default)]
+    pub parser_fallbacks: Vec<ParserFallback>,
+    pub unmatched_lines: Vec<UnmatchedLine>,
+}
+
+// A file that was not plain UTF-8, or that decoded with replacements
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EncodingFallback {
+    pub path: String,
+    pub encoding: Encoding,
+    pub lossy: bool,
+}
+
+// A file a registered parser failed on, analyzed by the built-in regex
+// parser instead, so its links may be incomplete
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParserFallback {
+    pub path: String,
+    // Language of the parser that failed
+    pub parser: String,
+    pub line: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+// A source line naming known classes that produced no link
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnmatchedLine {
+    pub path: String,
+    pub line: usize,
+    pub tokens: Vec<String>,
+    pub text: String,
+}
+
+impl AnalysisDiagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_unsupported(&mut self, path: &str) {
+        let extension = Path::new(path)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        *self.unsupported_extensions.entry(extension).or_default() += 1;
+        self.unsupported_files.push(path.to_string());
+    }
+
+    pub fn record_encoding(&mut self, path: &str, source: &DecodedText) {
+        if source.encoding != Encoding::Utf8 || source.lossy {
+            self.encoding_fallbacks.push(EncodingFallback {
+                path: path.to_string(),
+                encoding: source.encoding,
+                lossy: source.lossy,
+            });
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.unsupported_files.is_empty()
+            && self.skipped.is_empty()
+            && self.encoding_fallbacks.is_empty()
+            && self.parser_fallbacks.is_empty()
+            && self.unmatched_lines.is_empty()
+    }
+}
+
+// Lines of a code file that mention a known class without the parser
+// recording a link there. Definition lines and a class naming itself inside
+// its own body are not counted; Markdown is left out since its links are
+// explicit.
+pub fn unmatched_lines(path: &str, content: &str, result: &AnalysisResult, known: &HashSet<String>) -> Vec<UnmatchedLine> {
+    if UnifiedAnalyzer::detect_file_type(path) == FileType::Markdown {
+        return Vec::new();
+    }
+    let token_regex = Regex::new(r"\b[A-Z][A-Za-z0-9_]*\b").unwrap();
+    let linked_lines: HashSet<usize> = result.links.iter().map(|link| link.line_number).collect();
+
+    let mut unmatched = Vec::new();
+    for (index, text) in content.lines().enumerate() {
+        let line = index + 1;
+        if linked_lines.contains(&line) {
+            continue;
+        }
+        let mut tokens: Vec<String> = Vec::new();
+        for token in token_regex.find_iter(text).map(|m| m.as_str()) {
+            let own_block = result
+                .class_metrics
+                .get(token)
+                .is_some_and(|size| (size.start_line..=size.end_line).contains(&line));
+            if known.contains(token) && !own_block && !tokens.iter().any(|t| t == token) {
+                tokens.push(token.to_string());
+            }
+        }
+        if !tokens.is_empty() {
+            unmatched.push(UnmatchedLine {
+                path: path.to_string(),
+                line,
+                tokens,
+                text: text.trim().to_string(),
+            });
+        }
+    }
+    unmatched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding;
+    #[cfg(feature = "python")]
+    use crate::file_analyzer::UnifiedAnalyzer;
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_unmatched_lines_report_missed_references() {
+        let content = "class Order:\n    def total(self) -> Money:\n        return Order.sum(Money)\n\nregistry = [Order, Invoice]\n";
+        let result = UnifiedAnalyzer::new().analyze_file(content, "order.py");
+        let known: HashSet<String> = ["Order", "Money", "Invoice"].iter().map(|s| s.to_string()).collect();
+
+        let unmatched = unmatched_lines("order.py", content, &result, &known);
+
+        let lines: Vec<(usize, Vec<&str>)> = unmatched
+            .iter()
+            .map(|u| (u.line, u.tokens.iter().map(String::as_str).collect()))
+            .collect();
+        assert!(!lines.iter().any(|(line, _)| *line == 1));
+        assert_eq!(lines.last().unwrap(), &(5, vec!["Order", "Invoice"]));
+        assert_eq!(unmatched.last().unwrap().text, "registry = [Order, Invoice]");
+        assert!(unmatched_lines("Order.md", "See Invoice", &AnalysisResult::default(), &known).is_empty());
+    }
+
+    #[test]
+    fn test_records_unsupported_and_encoding() {
+        let mut diagnostics = AnalysisDiagnostics::new();
+        diagnostics.record_unsupported("src/app.rb");
+        diagnostics.record_unsupported("src/lib.RB");
+        diagnostics.record_unsupported("Makefile");
+        diagnostics.record_encoding("a.py", &encoding::decode(b"class A: pass"));
+        diagnostics.record_encoding("b.py", &encoding::decode(&[0xEF, 0xBB, 0xBF, b'x']));
+        diagnostics.record_encoding("c.cs", &encoding::decode(&[b'/', b'/', 0x83, 0x86, b'\n']));
+
+        assert_eq!(diagnostics.unsupported_extensions.get("rb"), Some(&2));
+        assert_eq!(diagnostics.unsupported_extensions.get(""), Some(&1));
+        assert_eq!(diagnostics.encoding_fallbacks.len(), 2);
+        assert_eq!(diagnostics.encoding_fallbacks[0].encoding, Encoding::Utf8Bom);
+        assert_eq!(diagnostics.encoding_fallbacks[1].encoding, Encoding::ShiftJis);
+        assert!(diagnostics.encoding_fallbacks[1].lossy);
+        assert!(!diagnostics.is_empty());
+    }
+}
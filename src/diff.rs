@@ -0,0 +1,440 @@
+use crate::i18n::{Catalog, Message};
+use crate::manifest::{ArchitectureManifest, ManifestClass};
+use crate::project::ProjectAnalyzer;
+use crate::snapshot::{self, Snapshot};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Pairs scoring below this are reported as a plain removal plus addition
+pub const RENAME_THRESHOLD: f64 = 0.6;
+
+// Keeps the PR comment readable and well under GitHub's comment size limit
+pub const MARKDOWN_MAX_EDGES: usize = 40;
+pub const MARKDOWN_MAX_ROWS: usize = 100;
+
+const STRUCTURE_WEIGHT: f64 = 0.6;
+const LOCATION_WEIGHT: f64 = 0.4;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Rename {
+    pub from: String,
+    pub to: String,
+    pub similarity: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct LinkChange {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GraphDiff {
+    pub added_classes: Vec<String>,
+    pub removed_classes: Vec<String>,
+    pub renamed_classes: Vec<Rename>,
+    pub added_links: Vec<LinkChange>,
+    pub removed_links: Vec<LinkChange>,
+}
+
+// A snapshot is either a committed manifest (`-o manifest` output), a
+// binary graph snapshot (`snapshot` output) or a directory that is analyzed
+// on the spot
+pub fn load_snapshot(path: &str) -> io::Result<ArchitectureManifest> {
+    if Path::new(path).is_file() {
+        let bytes = fs::read(path)?;
+        if snapshot::is_snapshot(&bytes) {
+            return Ok(Snapshot::from_bytes(&bytes)?.to_manifest());
+        }
+        return Ok(serde_json::from_slice(&bytes)?);
+    }
+    let files = ProjectAnalyzer::new().recursive(true).analyze_files(path)?;
+    Ok(ArchitectureManifest::build(path, &files))
+}
+
+fn class_name(id: &str) -> &str {
+    id.strip_prefix("class:").unwrap_or(id)
+}
+
+fn links(manifest: &ArchitectureManifest) -> BTreeSet<(String, String)> {
+    manifest
+        .links
+        .iter()
+        .map(|link| (class_name(&link.from).to_string(), class_name(&link.to).to_string()))
+        .collect()
+}
+
+// Neighbours tagged with direction, so `A -> X` and `X -> A` are different
+// structure
+fn neighbours(links: &BTreeSet<(String, String)>, class: &str) -> BTreeSet<String> {
+    let mut result = BTreeSet::new();
+    for (from, to) in links {
+        if from == class && to != class {
+            result.insert(format!("out:{}", to));
+        }
+        if to == class && from != class {
+            result.insert(format!("in:{}", from));
+        }
+    }
+    result
+}
+
+fn jaccard(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        // No links on either side tells us nothing either way
+        return 0.5;
+    }
+    a.intersection(b).count() as f64 / a.union(b).count() as f64
+}
+
+fn location_similarity(old: &ManifestClass, new: &ManifestClass) -> f64 {
+    if old.files.iter().any(|file| new.files.contains(file)) {
+        1.0
+    } else if old.package == new.package {
+        0.5
+    } else {
+        0.0
+    }
+}
+
+impl GraphDiff {
+    pub fn between(old: &ArchitectureManifest, new: &ArchitectureManifest) -> Self {
+        let old_classes: BTreeMap<&str, &ManifestClass> =
+            old.classes.iter().map(|c| (c.name.as_str(), c)).collect();
+        let new_classes: BTreeMap<&str, &ManifestClass> =
+            new.classes.iter().map(|c| (c.name.as_str(), c)).collect();
+        let old_links = links(old);
+        let new_links = links(new);
+
+        let removed: Vec<&str> = old_classes
+            .keys()
+            .filter(|name| !new_classes.contains_key(*name))
+            .copied()
+            .collect();
+        let added: Vec<&str> = new_classes
+            .keys()
+            .filter(|name| !old_classes.contains_key(*name))
+            .copied()
+            .collect();
+
+        let mut candidates = Vec::new();
+        for from in &removed {
+            let old_neighbours = neighbours(&old_links, from);
+            for to in &added {
+                let structure = jaccard(&old_neighbours, &neighbours(&new_links, to));
+                let location = location_similarity(old_classes[from], new_classes[to]);
+                let similarity = STRUCTURE_WEIGHT * structure + LOCATION_WEIGHT * location;
+                if similarity >= RENAME_THRESHOLD {
+                    candidates.push(Rename {
+                        from: from.to_string(),
+                        to: to.to_string(),
+                        similarity,
+                    });
+                }
+            }
+        }
+
+        // Greedy best-first matching; ties fall back to name order so the
+        // result is deterministic
+        candidates.sort_by(|a, b| {
+            b.similarity
+                .total_cmp(&a.similarity)
+                .then_with(|| a.from.cmp(&b.from))
+                .then_with(|| a.to.cmp(&b.to))
+        });
+        let mut renamed_from = BTreeMap::new();
+        let mut renamed_to = BTreeSet::new();
+        let mut renamed_classes = Vec::new();
+        for candidate in candidates {
+            if renamed_from.contains_key(&candidate.from) || renamed_to.contains(&candidate.to) {
+                continue;
+            }
+            renamed_from.insert(candidate.from.clone(), candidate.to.clone());
+            renamed_to.insert(candidate.to.clone());
+            renamed_classes.push(candidate);
+        }
+        renamed_classes.sort_by(|a, b| a.from.cmp(&b.from));
+
+        // Old links are rewritten through the renames before comparing, so a
+        // renamed class keeps its unchanged links
+        let rename = |name: &String| renamed_from.get(name).unwrap_or(name).clone();
+        let mapped_old: BTreeSet<(String, String)> = old_links
+            .iter()
+            .map(|(from, to)| (rename(from), rename(to)))
+            .collect();
+        let link_changes = |a: &BTreeSet<(String, String)>, b: &BTreeSet<(String, String)>| {
+            a.difference(b)
+                .map(|(from, to)| LinkChange {
+                    from: from.clone(),
+                    to: to.clone(),
+                })
+                .collect()
+        };
+
+        Self {
+            added_classes: added
+                .iter()
+                .filter(|name| !renamed_to.contains(**name))
+                .map(|name| name.to_string())
+                .collect(),
+            removed_classes: removed
+                .iter()
+                .filter(|name| !renamed_from.contains_key(**name))
+                .map(|name| name.to_string())
+                .collect(),
+            renamed_classes,
+            added_links: link_changes(&new_links, &mapped_old),
+            removed_links: link_changes(&mapped_old, &new_links),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_classes.is_empty()
+            && self.removed_classes.is_empty()
+            && self.renamed_classes.is_empty()
+            && self.added_links.is_empty()
+            && self.removed_links.is_empty()
+    }
+
+    pub fn render_text(&self, catalog: &Catalog) -> String {
+        let mut out = String::new();
+        writeln!(out, "{}\n", catalog.text(Message::DiffTitle, &[])).unwrap();
+        if self.is_empty() {
+            writeln!(out, "{}", catalog.text(Message::DiffNoChanges, &[])).unwrap();
+            return out;
+        }
+
+        for rename in &self.renamed_classes {
+            let percent = (rename.similarity * 100.0).round();
+            let line = catalog.text(Message::DiffRenamedClass, &[&rename.from, &rename.to, &percent]);
+            writeln!(out, "{}", line).unwrap();
+        }
+        for class in &self.added_classes {
+            writeln!(out, "{}", catalog.text(Message::DiffAddedClass, &[class])).unwrap();
+        }
+        for class in &self.removed_classes {
+            writeln!(out, "{}", catalog.text(Message::DiffRemovedClass, &[class])).unwrap();
+        }
+        for link in &self.added_links {
+            writeln!(out, "{}", catalog.text(Message::DiffAddedLink, &[&link.from, &link.to])).unwrap();
+        }
+        for link in &self.removed_links {
+            writeln!(out, "{}", catalog.text(Message::DiffRemovedLink, &[&link.from, &link.to])).unwrap();
+        }
+        out
+    }
+
+    // One markdown block for a PR comment: a summary line, a mermaid graph of
+    // the changed classes and links only, and a table of link changes
+    pub fn render_markdown(&self, catalog: &Catalog) -> String {
+        let mut out = String::new();
+        writeln!(out, "{}\n", catalog.text(Message::DiffMarkdownTitle, &[])).unwrap();
+        if self.is_empty() {
+            writeln!(out, "{}", catalog.text(Message::DiffNoChanges, &[])).unwrap();
+            return out;
+        }
+        let summary = catalog.text(
+            Message::DiffMarkdownSummary,
+            &[
+                &self.added_classes.len(),
+                &self.removed_classes.len(),
+                &self.renamed_classes.len(),
+                &self.added_links.len(),
+                &self.removed_links.len(),
+            ],
+        );
+        writeln!(out, "{}\n", summary).unwrap();
+        self.write_mermaid(&mut out, catalog);
+
+        let rows: Vec<(&str, &LinkChange)> = self
+            .added_links
+            .iter()
+            .map(|link| ("➕", link))
+            .chain(self.removed_links.iter().map(|link| ("➖", link)))
+            .collect();
+        if !rows.is_empty() {
+            writeln!(out, "\n{}", catalog.text(Message::DiffMarkdownTableHeader, &[])).unwrap();
+            writeln!(out, "|---|---|---|").unwrap();
+            for (marker, link) in rows.iter().take(MARKDOWN_MAX_ROWS) {
+                writeln!(out, "| {} | `{}` | `{}` |", marker, link.from, link.to).unwrap();
+            }
+            if rows.len() > MARKDOWN_MAX_ROWS {
+                let more = rows.len() - MARKDOWN_MAX_ROWS;
+                writeln!(out, "\n{}", catalog.text(Message::DiffMarkdownMore, &[&more])).unwrap();
+            }
+        }
+        out
+    }
+
+    // Added links are thick, removed ones dotted; renamed classes are drawn
+    // under their new name
+    fn write_mermaid(&self, out: &mut String, catalog: &Catalog) {
+        fn id<'a>(name: &'a str, ids: &mut BTreeMap<&'a str, String>) -> String {
+            let next = format!("n{}", ids.len());
+            ids.entry(name).or_insert(next).clone()
+        }
+        let mut ids: BTreeMap<&str, String> = BTreeMap::new();
+        let renamed: BTreeMap<&str, &str> =
+            self.renamed_classes.iter().map(|r| (r.to.as_str(), r.from.as_str())).collect();
+        let edges: Vec<(&LinkChange, &str)> = self
+            .added_links
+            .iter()
+            .map(|link| (link, "==>"))
+            .chain(self.removed_links.iter().map(|link| (link, "-.->")))
+            .collect();
+
+        writeln!(out, "```mermaid\ngraph LR").unwrap();
+        let mut lines = Vec::new();
+        for (link, arrow) in edges.iter().take(MARKDOWN_MAX_EDGES) {
+            let (from, to) = (id(&link.from, &mut ids), id(&link.to, &mut ids));
+            lines.push(format!("    {} {} {}", from, arrow, to));
+        }
+        for class in self
+            .added_classes
+            .iter()
+            .chain(&self.removed_classes)
+            .chain(self.renamed_classes.iter().map(|r| &r.to))
+        {
+            id(class, &mut ids);
+        }
+        for (name, node) in &ids {
+            let label = match renamed.get(name) {
+                Some(old) => format!("{}<br/>({})", name, catalog.text(Message::DiffMarkdownWas, &[old])),
+                None => name.to_string(),
+            };
+            writeln!(out, "    {}[\"{}\"]", node, label.replace('"', "#quot;")).unwrap();
+        }
+        for line in lines {
+            writeln!(out, "{}", line).unwrap();
+        }
+        let styles = [
+            ("added", "fill:#e6ffed,stroke:#28a745", &self.added_classes),
+            ("removed", "fill:#ffeef0,stroke:#d73a49", &self.removed_classes),
+        ];
+        for (class, style, names) in styles {
+            let nodes: Vec<&str> = names.iter().filter_map(|name| ids.get(name.as_str())).map(String::as_str).collect();
+            if !nodes.is_empty() {
+                writeln!(out, "    classDef {} {}\n    class {} {}", class, style, nodes.join(","), class).unwrap();
+            }
+        }
+        let nodes: Vec<&str> = renamed.keys().filter_map(|name| ids.get(name)).map(String::as_str).collect();
+        if !nodes.is_empty() {
+            writeln!(out, "    classDef renamed fill:#fff5b1,stroke:#b08800\n    class {} renamed", nodes.join(",")).unwrap();
+        }
+        writeln!(out, "```").unwrap();
+        if edges.len() > MARKDOWN_MAX_EDGES {
+            let more = edges.len() - MARKDOWN_MAX_EDGES;
+            writeln!(out, "\n{}", catalog.text(Message::DiffMarkdownMore, &[&more])).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_analyzer::AnalysisResult;
+    use crate::parser::ClassLink;
+    use crate::project::FileAnalysis;
+
+    fn file(path: &str, classes: &[&str], links: &[(&str, &str)]) -> FileAnalysis {
+        FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|s| s.to_string()).collect(),
+                links: links
+                    .iter()
+                    .map(|(from, to)| ClassLink {
+                        from_class: from.to_string(),
+                        to_class: to.to_string(),
+                        line_number: 1,
+                        kind: Default::default(),
+                        position: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        }
+    }
+
+    fn snapshot(files: &[FileAnalysis]) -> ArchitectureManifest {
+        ArchitectureManifest::build("/repo", files)
+    }
+
+    #[test]
+    fn test_rename_keeps_links_intact() {
+        let old = snapshot(&[
+            file("/repo/app/user.py", &["UserMgr"], &[("UserMgr", "Database")]),
+            file("/repo/app/api.py", &["Api"], &[("Api", "UserMgr")]),
+            file("/repo/db/database.py", &["Database"], &[]),
+        ]);
+        let new = snapshot(&[
+            file("/repo/app/user.py", &["UserManager"], &[("UserManager", "Database")]),
+            file("/repo/app/api.py", &["Api"], &[("Api", "UserManager")]),
+            file("/repo/db/database.py", &["Database"], &[]),
+        ]);
+
+        let diff = GraphDiff::between(&old, &new);
+
+        assert_eq!(diff.renamed_classes.len(), 1);
+        assert_eq!(diff.renamed_classes[0].from, "UserMgr");
+        assert_eq!(diff.renamed_classes[0].to, "UserManager");
+        assert!(diff.added_classes.is_empty());
+        assert!(diff.removed_classes.is_empty());
+        assert!(diff.added_links.is_empty());
+        assert!(diff.removed_links.is_empty());
+    }
+
+    #[test]
+    fn test_unrelated_classes_are_not_renames() {
+        let old = snapshot(&[
+            file("/repo/app/legacy.py", &["Legacy"], &[("Legacy", "Logger")]),
+            file("/repo/app/logger.py", &["Logger"], &[]),
+        ]);
+        let new = snapshot(&[
+            file("/repo/billing/invoice.py", &["Invoice"], &[("Invoice", "Tax")]),
+            file("/repo/app/logger.py", &["Logger"], &[]),
+        ]);
+
+        let diff = GraphDiff::between(&old, &new);
+
+        assert!(diff.renamed_classes.is_empty());
+        assert_eq!(diff.added_classes, vec!["Invoice"]);
+        assert_eq!(diff.removed_classes, vec!["Legacy"]);
+        assert_eq!(diff.removed_links, vec![LinkChange { from: "Legacy".into(), to: "Logger".into() }]);
+
+        let text = diff.render_text(&Catalog::default());
+        assert!(text.contains("+ class Invoice"));
+        assert!(text.contains("- link Legacy -> Logger"));
+    }
+
+    #[test]
+    fn test_render_markdown_draws_changed_neighbourhood() {
+        let old = snapshot(&[
+            file("/repo/app/user.py", &["UserMgr"], &[("UserMgr", "Database")]),
+            file("/repo/app/legacy.py", &["Legacy"], &[("Legacy", "Database")]),
+            file("/repo/db/database.py", &["Database"], &[]),
+            file("/repo/app/api.py", &["Api"], &[("Api", "Untouched")]),
+        ]);
+        let new = snapshot(&[
+            file("/repo/app/user.py", &["UserManager"], &[("UserManager", "Database"), ("UserManager", "Cache")]),
+            file("/repo/db/database.py", &["Database"], &[]),
+            file("/repo/app/api.py", &["Api"], &[("Api", "Untouched")]),
+        ]);
+
+        let markdown = GraphDiff::between(&old, &new).render_markdown(&Catalog::default());
+
+        assert!(markdown.contains("```mermaid\ngraph LR\n"));
+        assert!(markdown.contains("[\"UserManager<br/>(was UserMgr)\"]"));
+        assert!(markdown.contains(" ==> "));
+        assert!(markdown.contains(" -.-> "));
+        assert!(markdown.contains("    classDef removed ") && markdown.contains("    classDef renamed "));
+        assert!(!markdown.contains("Untouched"));
+        assert!(markdown.contains("| ➕ | `UserManager` | `Cache` |"));
+        assert!(markdown.contains("| ➖ | `Legacy` | `Database` |"));
+    }
+}
@@ -0,0 +1,364 @@
+use crate::file_analyzer::{FileType, UnifiedAnalyzer};
+use crate::i18n::{Catalog, Message};
+use crate::manifest::relative_path;
+use crate::parser::LinkKind;
+use crate::project::FileAnalysis;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// A page to create for a class that no Markdown header declares, with the
+// links the code analysis found for it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DocStub {
+    pub class: String,
+    // Root-relative path the page is proposed at
+    pub path: String,
+    pub outgoing: Vec<String>,
+    pub incoming: Vec<String>,
+}
+
+// Used when `scaffold-docs` is given no template
+pub const DEFAULT_TEMPLATE: &str = "# {class}\n\n## Dependencies\n\n{dependencies}\n## Used By\n\n{used_by}";
+
+impl DocStub {
+    // Same shape the Markdown parser reads back: a level-1 header and
+    // `[Name](Name.md)` links, so the new page is in sync from the start
+    pub fn markdown(&self) -> String {
+        let mut out = format!("# {}\n", self.class);
+        let sections = [("Dependencies", &self.outgoing), ("Used By", &self.incoming)];
+        for (title, classes) in sections {
+            if classes.is_empty() {
+                continue;
+            }
+            write!(out, "\n## {}\n\n", title).unwrap();
+            out.push_str(&link_list(classes));
+        }
+        out
+    }
+
+    // `{class}` is the class name, `{dependencies}` and `{used_by}` are
+    // lists of `[Name](Name.md)` links, `-` when empty
+    pub fn render(&self, template: &str) -> String {
+        let list = |classes: &[String]| if classes.is_empty() { "-\n".to_string() } else { link_list(classes) };
+        template
+            .replace("{class}", &self.class)
+            .replace("{dependencies}", &list(&self.outgoing))
+            .replace("{used_by}", &list(&self.incoming))
+    }
+}
+
+fn link_list(classes: &[String]) -> String {
+    classes.iter().map(|class| format!("- [{0}]({0}.md)\n", class)).collect()
+}
+
+// Writes each stub under `root`, rendered with `template`, and returns the
+// pages created. Pages that already exist are never overwritten.
+pub fn scaffold(root: &str, stubs: &[DocStub], template: &str) -> io::Result<Vec<String>> {
+    let mut created = Vec::new();
+    for stub in stubs {
+        let path = Path::new(root).join(&stub.path);
+        if path.exists() {
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, stub.render(template))?;
+        created.push(stub.path.clone());
+    }
+    Ok(created)
+}
+
+// A documented class whose page lists other dependencies than the code has
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DocMismatch {
+    pub class: String,
+    pub docs: Vec<String>,
+    // Linked in code but not mentioned in the docs
+    pub undocumented: Vec<String>,
+    // Mentioned in the docs but not linked in code
+    pub stale: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DocSyncReport {
+    pub stubs: Vec<DocStub>,
+    pub mismatches: Vec<DocMismatch>,
+}
+
+// Compares Markdown pages with the code in both directions. Only links
+// between classes the code defines are compared, so library types and
+// prose mentions of other projects do not count as drift.
+pub fn sync(root: &str, files: &[FileAnalysis], docs_dir: &str) -> DocSyncReport {
+    let mut defined: BTreeSet<&str> = BTreeSet::new();
+    let mut pages: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
+    let mut documented: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    let mut outgoing: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    let mut incoming: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for file in files {
+        if UnifiedAnalyzer::detect_file_type(&file.path) == FileType::Markdown {
+            for class in &file.result.classes {
+                pages.entry(class).or_default().insert(relative_path(root, &file.path));
+            }
+            for link in &file.result.links {
+                documented.entry(&link.from_class).or_default().insert(&link.to_class);
+            }
+        } else {
+            defined.extend(file.result.classes.iter().map(String::as_str));
+            for link in file.result.links.iter().filter(|link| link.kind != LinkKind::Doc) {
+                outgoing.entry(&link.from_class).or_default().insert(&link.to_class);
+                incoming.entry(&link.to_class).or_default().insert(&link.from_class);
+            }
+        }
+    }
+    let project_links = |links: Option<&BTreeSet<&str>>, class: &str| -> BTreeSet<String> {
+        links
+            .into_iter()
+            .flatten()
+            .filter(|other| **other != class && defined.contains(*other))
+            .map(|other| other.to_string())
+            .collect()
+    };
+
+    let mut report = DocSyncReport::default();
+    for &class in &defined {
+        let code = project_links(outgoing.get(class), class);
+        let Some(docs) = pages.get(class) else {
+            let docs_dir = docs_dir.trim_end_matches('/');
+            report.stubs.push(DocStub {
+                class: class.to_string(),
+                path: if docs_dir.is_empty() { format!("{}.md", class) } else { format!("{}/{}.md", docs_dir, class) },
+                outgoing: code.into_iter().collect(),
+                incoming: project_links(incoming.get(class), class).into_iter().collect(),
+            });
+            continue;
+        };
+        let listed: BTreeSet<String> = documented
+            .get(class)
+            .into_iter()
+            .flatten()
+            .filter(|other| **other != class)
+            .map(|other| other.to_string())
+            .collect();
+        if code != listed {
+            report.mismatches.push(DocMismatch {
+                class: class.to_string(),
+                docs: docs.iter().cloned().collect(),
+                undocumented: code.difference(&listed).cloned().collect(),
+                stale: listed.difference(&code).cloned().collect(),
+            });
+        }
+    }
+    report
+}
+
+// Documentation coverage of one source file: each class it defines, at its
+// first line, and whether a Markdown header declares it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileDocCoverage {
+    pub path: String,
+    pub classes: Vec<(String, usize, bool)>,
+}
+
+// Root-relative source files in path order; classes without a measured
+// body count at line 1
+pub fn coverage(root: &str, files: &[FileAnalysis]) -> Vec<FileDocCoverage> {
+    let is_page = |file: &&FileAnalysis| UnifiedAnalyzer::detect_file_type(&file.path) == FileType::Markdown;
+    let documented: BTreeSet<&str> =
+        files.iter().filter(is_page).flat_map(|file| file.result.classes.iter().map(String::as_str)).collect();
+    let mut coverage: Vec<FileDocCoverage> = files
+        .iter()
+        .filter(|file| !is_page(file) && !file.result.classes.is_empty())
+        .map(|file| {
+            let mut classes: Vec<(String, usize, bool)> = file
+                .result
+                .classes
+                .iter()
+                .map(|class| {
+                    let line = file.result.class_metrics.get(class).map_or(1, |metrics| metrics.start_line);
+                    (class.clone(), line, documented.contains(class.as_str()))
+                })
+                .collect();
+            classes.sort_by(|a, b| (a.1, &a.0).cmp(&(b.1, &b.0)));
+            FileDocCoverage {
+                path: relative_path(root, &file.path),
+                classes,
+            }
+        })
+        .collect();
+    coverage.sort_by(|a, b| a.path.cmp(&b.path));
+    coverage
+}
+
+// LCOV tracefile for coverage dashboards (genhtml, Codecov, Coveralls,
+// SonarQube): every class is a function and its first line a line, hit once
+// when documented
+pub fn to_lcov(coverage: &[FileDocCoverage]) -> String {
+    let mut out = String::new();
+    for file in coverage {
+        writeln!(out, "TN:documentation\nSF:{}", file.path).unwrap();
+        for (class, line, _) in &file.classes {
+            writeln!(out, "FN:{},{}", line, class).unwrap();
+        }
+        for (class, _, documented) in &file.classes {
+            writeln!(out, "FNDA:{},{}", u8::from(*documented), class).unwrap();
+        }
+        let hit = file.classes.iter().filter(|(_, _, documented)| *documented).count();
+        writeln!(out, "FNF:{}\nFNH:{}", file.classes.len(), hit).unwrap();
+        // Classes sharing a line, like several without a measured body, are
+        // one line, covered when any of them is documented
+        let mut lines: BTreeMap<usize, bool> = BTreeMap::new();
+        for (_, line, documented) in &file.classes {
+            *lines.entry(*line).or_default() |= documented;
+        }
+        for (line, documented) in &lines {
+            writeln!(out, "DA:{},{}", line, u8::from(*documented)).unwrap();
+        }
+        let lines_hit = lines.values().filter(|documented| **documented).count();
+        writeln!(out, "LF:{}\nLH:{}\nend_of_record", lines.len(), lines_hit).unwrap();
+    }
+    out
+}
+
+impl DocSyncReport {
+    pub fn render_text(&self, catalog: &Catalog) -> String {
+        let mut out = String::new();
+        writeln!(out, "{}", catalog.text(Message::DocSyncTitle, &[])).unwrap();
+        if self.stubs.is_empty() && self.mismatches.is_empty() {
+            writeln!(out, "\n{}", catalog.text(Message::DocSyncInSync, &[])).unwrap();
+            return out;
+        }
+        if !self.stubs.is_empty() {
+            writeln!(out, "\n{}", catalog.text(Message::DocSyncStubsHeader, &[&self.stubs.len()])).unwrap();
+            for stub in &self.stubs {
+                writeln!(out, "  + {} ({})", stub.path, stub.class).unwrap();
+            }
+        }
+        if !self.mismatches.is_empty() {
+            writeln!(out, "\n{}", catalog.text(Message::DocSyncMismatchHeader, &[&self.mismatches.len()])).unwrap();
+            for mismatch in &self.mismatches {
+                writeln!(out, "  {} ({})", mismatch.class, mismatch.docs.join(", ")).unwrap();
+                if !mismatch.undocumented.is_empty() {
+                    let line = catalog.text(Message::DocSyncUndocumented, &[&mismatch.undocumented.join(", ")]);
+                    writeln!(out, "    {}", line).unwrap();
+                }
+                if !mismatch.stale.is_empty() {
+                    let line = catalog.text(Message::DocSyncStale, &[&mismatch.stale.join(", ")]);
+                    writeln!(out, "    {}", line).unwrap();
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_analyzer::AnalysisResult;
+    use crate::parser::ClassLink;
+
+    fn file(path: &str, classes: &[&str], links: &[(&str, &str)], kind: LinkKind) -> FileAnalysis {
+        FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|s| s.to_string()).collect(),
+                links: links
+                    .iter()
+                    .map(|(from, to)| ClassLink {
+                        from_class: from.to_string(),
+                        to_class: to.to_string(),
+                        line_number: 1,
+                        kind,
+                        position: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_sync_proposes_stubs_and_flags_drift() {
+        let files = vec![
+            file(
+                "/repo/src/shop.py",
+                &["Shop", "Cart", "Db", "Mailer"],
+                &[("Shop", "Cart"), ("Shop", "Mailer"), ("Shop", "Optional"), ("Cart", "Db")],
+                LinkKind::Usage,
+            ),
+            file("/repo/docs/Shop.md", &["Shop"], &[("Shop", "Cart"), ("Shop", "OldBasket")], LinkKind::Doc),
+            file("/repo/docs/Cart.md", &["Cart"], &[("Cart", "Db")], LinkKind::Doc),
+        ];
+
+        let report = sync("/repo", &files, "docs/");
+
+        let stubs: Vec<&str> = report.stubs.iter().map(|stub| stub.path.as_str()).collect();
+        assert_eq!(stubs, vec!["docs/Db.md", "docs/Mailer.md"]);
+        assert_eq!(report.stubs[0].incoming, vec!["Cart"]);
+        assert_eq!(report.mismatches.len(), 1);
+        let mismatch = &report.mismatches[0];
+        assert_eq!(mismatch.class, "Shop");
+        assert_eq!(mismatch.docs, vec!["docs/Shop.md"]);
+        assert_eq!(mismatch.undocumented, vec!["Mailer"]);
+        assert_eq!(mismatch.stale, vec!["OldBasket"]);
+        assert_eq!(report.stubs[0].markdown(), "# Db\n\n## Used By\n\n- [Cart](Cart.md)\n");
+    }
+
+    #[test]
+    fn test_coverage_as_lcov_tracefile() {
+        let mut code = file("/repo/src/shop.py", &["Shop", "Cart"], &[], LinkKind::Usage);
+        code.result.class_metrics.insert(
+            "Cart".to_string(),
+            crate::metrics::ClassMetrics {
+                start_line: 9,
+                end_line: 12,
+                lines: 4,
+                methods: 1,
+            },
+        );
+        let files = vec![
+            code,
+            file("/repo/src/empty.py", &[], &[], LinkKind::Usage),
+            file("/repo/docs/Cart.md", &["Cart"], &[], LinkKind::Doc),
+        ];
+
+        let coverage = coverage("/repo", &files);
+
+        assert_eq!(coverage.len(), 1);
+        assert_eq!(coverage[0].classes, vec![("Shop".to_string(), 1, false), ("Cart".to_string(), 9, true)]);
+        assert_eq!(
+            to_lcov(&coverage),
+            "TN:documentation\nSF:src/shop.py\nFN:1,Shop\nFN:9,Cart\nFNDA:0,Shop\nFNDA:1,Cart\nFNF:2\nFNH:1\nDA:1,0\nDA:9,1\nLF:2\nLH:1\nend_of_record\n"
+        );
+    }
+
+    #[test]
+    fn test_scaffold_renders_template_and_keeps_existing_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_str().unwrap();
+        fs::create_dir_all(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("docs/Cart.md"), "hand written").unwrap();
+        let stub = |class: &str, outgoing: &[&str]| DocStub {
+            class: class.to_string(),
+            path: format!("docs/{}.md", class),
+            outgoing: outgoing.iter().map(|s| s.to_string()).collect(),
+            incoming: Vec::new(),
+        };
+        let stubs = vec![stub("Shop", &["Cart", "Db"]), stub("Cart", &[])];
+
+        let created = scaffold(root, &stubs, "# {class}\n\nTODO\n\n{dependencies}---\n{used_by}").unwrap();
+
+        assert_eq!(created, vec!["docs/Shop.md"]);
+        let page = fs::read_to_string(dir.path().join("docs/Shop.md")).unwrap();
+        assert_eq!(page, "# Shop\n\nTODO\n\n- [Cart](Cart.md)\n- [Db](Db.md)\n---\n-\n");
+        assert_eq!(fs::read_to_string(dir.path().join("docs/Cart.md")).unwrap(), "hand written");
+        let analyzed = crate::parser::MarkdownParser::new().parse_file(&page);
+        assert_eq!(analyzed.len(), 2);
+    }
+}
@@ -0,0 +1,276 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    ShiftJis,
+    Windows1252,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedText {
+    pub content: String,
+    pub encoding: Encoding,
+    // True when some characters could not be represented exactly. Identifiers
+    // are ASCII in practice, so analysis still works on lossy text.
+    pub lossy: bool,
+}
+
+impl DecodedText {
+    // Text decoders never produce NUL for real source, so any NUL left after
+    // decoding means the file is an image, archive or other binary blob
+    pub fn is_binary(&self) -> bool {
+        self.content.contains('\0')
+    }
+}
+
+pub fn read_source<P: AsRef<Path>>(path: P) -> io::Result<DecodedText> {
+    Ok(decode(&fs::read(path)?))
+}
+
+pub fn decode(bytes: &[u8]) -> DecodedText {
+    if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        let content = String::from_utf8_lossy(rest);
+        let lossy = matches!(content, std::borrow::Cow::Owned(_));
+        return decoded(content.into_owned(), Encoding::Utf8Bom, lossy);
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        return decode_utf16(rest, Encoding::Utf16Le);
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        return decode_utf16(rest, Encoding::Utf16Be);
+    }
+    // ASCII-only UTF-16 is also valid UTF-8, so check the zero-byte pattern first
+    if let Some(encoding) = guess_bomless_utf16(bytes) {
+        return decode_utf16(bytes, encoding);
+    }
+    if let Ok(content) = std::str::from_utf8(bytes) {
+        return decoded(content.to_string(), Encoding::Utf8, false);
+    }
+    if looks_like_shift_jis(bytes) {
+        return decode_shift_jis(bytes);
+    }
+    decode_windows_1252(bytes)
+}
+
+// `\r\n` and lone `\r` (classic Mac OS) become `\n`, and a BOM left at the
+// start of text passed in directly is dropped. Parsers then never capture a
+// trailing `\r`, and line numbers count the same for every line ending.
+pub fn normalize(text: &str) -> Cow<'_, str> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    if text.contains('\r') {
+        Cow::Owned(text.replace("\r\n", "\n").replace('\r', "\n"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+fn decoded(content: String, encoding: Encoding, lossy: bool) -> DecodedText {
+    DecodedText {
+        content: normalize(&content).into_owned(),
+        encoding,
+        lossy,
+    }
+}
+
+fn decode_utf16(bytes: &[u8], encoding: Encoding) -> DecodedText {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| match encoding {
+            Encoding::Utf16Be => u16::from_be_bytes([pair[0], pair[1]]),
+            _ => u16::from_le_bytes([pair[0], pair[1]]),
+        })
+        .collect();
+
+    let mut lossy = bytes.len() % 2 == 1;
+    let content: String = char::decode_utf16(units)
+        .map(|unit| {
+            unit.unwrap_or_else(|_| {
+                lossy = true;
+                char::REPLACEMENT_CHARACTER
+            })
+        })
+        .collect();
+
+    decoded(content, encoding, lossy)
+}
+
+// Source code is mostly ASCII, so UTF-16 without a BOM shows up as a zero
+// byte in every other position
+fn guess_bomless_utf16(bytes: &[u8]) -> Option<Encoding> {
+    if bytes.len() < 4 {
+        return None;
+    }
+    let pairs = bytes.len() / 2;
+    let zeros_at = |offset: usize| (0..pairs).filter(|i| bytes[i * 2 + offset] == 0).count();
+    let (even, odd) = (zeros_at(0), zeros_at(1));
+
+    if odd * 10 >= pairs * 4 && even == 0 {
+        Some(Encoding::Utf16Le)
+    } else if even * 10 >= pairs * 4 && odd == 0 {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+fn is_shift_jis_lead(byte: u8) -> bool {
+    (0x81..=0x9F).contains(&byte) || (0xE0..=0xFC).contains(&byte)
+}
+
+fn is_shift_jis_trail(byte: u8) -> bool {
+    (0x40..=0x7E).contains(&byte) || (0x80..=0xFC).contains(&byte)
+}
+
+fn looks_like_shift_jis(bytes: &[u8]) -> bool {
+    let mut i = 0;
+    let mut double_byte = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        if byte < 0x80 || (0xA1..=0xDF).contains(&byte) {
+            i += 1;
+        } else if is_shift_jis_lead(byte) && bytes.get(i + 1).is_some_and(|b| is_shift_jis_trail(*b)) {
+            double_byte += 1;
+            i += 2;
+        } else {
+            return false;
+        }
+    }
+    double_byte > 0
+}
+
+// Without the JIS tables we keep ASCII and half-width katakana exact and
+// replace double-byte characters (comments, string literals) with U+FFFD.
+// Detection needs at least one double-byte character, so every Shift-JIS
+// decode is lossy and shows up as such in the diagnostics.
+fn decode_shift_jis(bytes: &[u8]) -> DecodedText {
+    let mut content = String::with_capacity(bytes.len());
+    let mut lossy = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        if byte < 0x80 {
+            content.push(byte as char);
+            i += 1;
+        } else if (0xA1..=0xDF).contains(&byte) {
+            content.push(char::from_u32(0xFF61 + (byte - 0xA1) as u32).unwrap());
+            i += 1;
+        } else {
+            content.push(char::REPLACEMENT_CHARACTER);
+            lossy = true;
+            i += 2;
+        }
+    }
+    decoded(content, Encoding::ShiftJis, lossy)
+}
+
+const WINDOWS_1252_HIGH: [u32; 32] = [
+    0x20AC, 0xFFFD, 0x201A, 0x0192, 0x201E, 0x2026, 0x2020, 0x2021, 0x02C6, 0x2030, 0x0160, 0x2039,
+    0x0152, 0xFFFD, 0x017D, 0xFFFD, 0xFFFD, 0x2018, 0x2019, 0x201C, 0x201D, 0x2022, 0x2013, 0x2014,
+    0x02DC, 0x2122, 0x0161, 0x203A, 0x0153, 0xFFFD, 0x017E, 0x0178,
+];
+
+fn decode_windows_1252(bytes: &[u8]) -> DecodedText {
+    let mut lossy = false;
+    let content = bytes
+        .iter()
+        .map(|&byte| match byte {
+            0x80..=0x9F => {
+                let code = WINDOWS_1252_HIGH[(byte - 0x80) as usize];
+                lossy |= code == 0xFFFD;
+                char::from_u32(code).unwrap()
+            }
+            _ => byte as char,
+        })
+        .collect();
+    decoded(content, Encoding::Windows1252, lossy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str, bom: bool) -> Vec<u8> {
+        let mut bytes = if bom { vec![0xFF, 0xFE] } else { vec![] };
+        for unit in text.encode_utf16() {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_decode_utf8_with_and_without_bom() {
+        let plain = decode("class A {}".as_bytes());
+        assert_eq!(plain.encoding, Encoding::Utf8);
+
+        let with_bom = decode(b"\xEF\xBB\xBFclass A {}");
+        assert_eq!(with_bom.encoding, Encoding::Utf8Bom);
+        assert_eq!(with_bom.content, "class A {}");
+        assert!(!with_bom.lossy);
+    }
+
+    #[test]
+    fn test_decode_normalizes_line_endings() {
+        assert_eq!(decode(b"class A:\r\n    pass\r\n").content, "class A:\n    pass\n");
+        assert_eq!(decode(b"class A:\r    pass\r").content, "class A:\n    pass\n");
+        assert_eq!(decode(&utf16le("class A {}\r\n", true)).content, "class A {}\n");
+        assert_eq!(normalize("\u{feff}class A {}"), "class A {}");
+    }
+
+    #[test]
+    fn test_decode_utf16() {
+        let source = "public class Légacy : IService {}";
+
+        let with_bom = decode(&utf16le(source, true));
+        assert_eq!(with_bom.encoding, Encoding::Utf16Le);
+        assert_eq!(with_bom.content, source);
+
+        let without_bom = decode(&utf16le(source, false));
+        assert_eq!(without_bom.encoding, Encoding::Utf16Le);
+        assert_eq!(without_bom.content, source);
+
+        let mut big_endian = vec![0xFE, 0xFF];
+        for unit in source.encode_utf16() {
+            big_endian.extend_from_slice(&unit.to_be_bytes());
+        }
+        assert_eq!(decode(&big_endian).content, source);
+    }
+
+    #[test]
+    fn test_binary_detection_ignores_utf16_zero_bytes() {
+        assert!(!decode(&utf16le("class A {}", false)).is_binary());
+        assert!(decode(b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR").is_binary());
+    }
+
+    #[test]
+    fn test_decode_shift_jis_keeps_ascii_identifiers() {
+        // "// ユーザー" in Shift-JIS followed by a class declaration
+        let mut bytes = b"// ".to_vec();
+        bytes.extend_from_slice(&[0x83, 0x86, 0x81, 0x5B, 0x83, 0x55, 0x81, 0x5B]);
+        bytes.extend_from_slice(b"\npublic class UserService {}\n");
+
+        let decoded = decode(&bytes);
+
+        assert_eq!(decoded.encoding, Encoding::ShiftJis);
+        assert!(decoded.lossy);
+        assert!(decoded.content.contains("public class UserService {}"));
+    }
+
+    #[test]
+    fn test_decode_windows_1252_fallback() {
+        let decoded = decode(b"// caf\xE9 \x93quoted\x94\nclass Menu:\n");
+
+        assert_eq!(decoded.encoding, Encoding::Windows1252);
+        assert!(decoded.content.starts_with("// café “quoted”"));
+        assert!(!decoded.lossy);
+    }
+}
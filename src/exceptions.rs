@@ -0,0 +1,245 @@
+use crate::findings::{Finding, Severity};
+use crate::glob;
+use crate::i18n::{Catalog, Message};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const EXPIRED_EXCEPTION_RULE: &str = "expired-exception";
+
+// A documented, reviewable escape hatch from an architecture rule.
+// `pattern` is a class glob (`Legacy*`: every class of the finding must
+// match) or a link pattern (`Legacy*->Logger`: the finding must involve a
+// class matching each side).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleException {
+    pub rule: String,
+    pub pattern: String,
+    pub reason: String,
+    // Last day the exception applies, as `YYYY-MM-DD`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SuppressedFinding {
+    pub finding: Finding,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.trim().splitn(3, '-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        ((1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day)).then_some(Self { year, month, day })
+    }
+
+    // Civil date (UTC) of a day count since 1970-01-01, after Howard
+    // Hinnant's `civil_from_days`
+    pub fn from_days(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        Self { year, month, day }
+    }
+
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default();
+        Self::from_days(seconds.div_euclid(86_400))
+    }
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl RuleException {
+    pub fn covers(&self, finding: &Finding) -> bool {
+        if finding.rule != self.rule || finding.classes.is_empty() {
+            return false;
+        }
+        match self.pattern.split_once("->") {
+            Some((from, to)) => {
+                let (from, to) = (from.trim(), to.trim());
+                finding.classes.iter().any(|class| glob::matches(from, class))
+                    && finding.classes.iter().any(|class| glob::matches(to, class))
+            }
+            None => finding.classes.iter().all(|class| glob::matches(&self.pattern, class)),
+        }
+    }
+
+    // Expired, or carrying an expiry that cannot be read: either way it no
+    // longer suppresses anything and is itself reported
+    fn problem(&self, today: Date, catalog: &Catalog) -> Option<Finding> {
+        let expires = self.expires.as_deref()?;
+        let message = match Date::parse(expires) {
+            Some(date) if date >= today => return None,
+            Some(_) => catalog.text(
+                Message::ExpiredException,
+                &[&self.rule, &self.pattern, &expires, &self.reason],
+            ),
+            None => catalog.text(
+                Message::InvalidExceptionExpiry,
+                &[&self.rule, &self.pattern, &expires],
+            ),
+        };
+        Some(Finding {
+            rule: EXPIRED_EXCEPTION_RULE.to_string(),
+            severity: Severity::Error,
+            message,
+            classes: vec![],
+        })
+    }
+}
+
+// Splits findings into those still reported and those covered by a live
+// exception; expired exceptions are reported as errors so they get renewed
+// or removed instead of silently rotting in the config
+pub fn apply(
+    exceptions: &[RuleException],
+    findings: Vec<Finding>,
+    today: Date,
+    catalog: &Catalog,
+) -> (Vec<Finding>, Vec<SuppressedFinding>) {
+    let mut problems = Vec::new();
+    let mut live = Vec::new();
+    for exception in exceptions {
+        match exception.problem(today, catalog) {
+            Some(problem) => problems.push(problem),
+            None => live.push(exception),
+        }
+    }
+
+    let mut reported = Vec::new();
+    let mut suppressed = Vec::new();
+    for finding in findings {
+        match live.iter().find(|exception| exception.covers(&finding)) {
+            Some(exception) => suppressed.push(SuppressedFinding {
+                finding,
+                reason: exception.reason.clone(),
+            }),
+            None => reported.push(finding),
+        }
+    }
+    reported.extend(problems);
+    (reported, suppressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(rule: &str, classes: &[&str]) -> Finding {
+        Finding {
+            rule: rule.to_string(),
+            severity: Severity::Warning,
+            message: String::new(),
+            classes: classes.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn exception(rule: &str, pattern: &str, expires: Option<&str>) -> RuleException {
+        RuleException {
+            rule: rule.to_string(),
+            pattern: pattern.to_string(),
+            reason: "migrating in Q3".to_string(),
+            expires: expires.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(Date::from_days(0).to_string(), "1970-01-01");
+        assert_eq!(Date::from_days(19_782).to_string(), "2024-02-29");
+        assert_eq!(Date::parse("2024-02-29"), Some(Date::from_days(19_782)));
+        assert!(Date::parse("2024-13-01").is_none());
+        assert!(Date::parse("next year").is_none());
+        assert!(Date::parse("2025-02-29").is_none());
+        assert!(Date::parse("2100-02-29").is_none());
+        assert!(Date::parse("2000-02-29").is_some());
+        assert!(Date::parse("2025-04-31").is_none());
+        assert!(Date::parse("2025-12-31").is_some());
+    }
+
+    #[test]
+    fn test_exception_patterns() {
+        let cycle = finding("circular-dependency", &["LegacyA", "LegacyB"]);
+
+        assert!(exception("circular-dependency", "Legacy*", None).covers(&cycle));
+        assert!(exception("circular-dependency", "LegacyA -> LegacyB", None).covers(&cycle));
+        assert!(!exception("circular-dependency", "Legacy*->Logger", None).covers(&cycle));
+        assert!(!exception("isolated-class", "Legacy*", None).covers(&cycle));
+        assert!(!exception("circular-dependency", "LegacyA", None).covers(&cycle));
+    }
+
+    #[test]
+    fn test_apply_suppresses_and_reports_expired() {
+        let today = Date::parse("2025-06-01").unwrap();
+        let findings = vec![
+            finding("isolated-class", &["OldHelper"]),
+            finding("isolated-class", &["Orphan"]),
+            finding("circular-dependency", &["A", "B"]),
+        ];
+        let exceptions = vec![
+            exception("isolated-class", "Old*", Some("2025-06-01")),
+            exception("circular-dependency", "A->B", Some("2025-05-31")),
+        ];
+
+        let (reported, suppressed) = apply(&exceptions, findings, today, &Catalog::default());
+
+        assert_eq!(suppressed.len(), 1);
+        assert_eq!(suppressed[0].finding.classes, vec!["OldHelper"]);
+        assert_eq!(suppressed[0].reason, "migrating in Q3");
+        let rules: Vec<&str> = reported.iter().map(|f| f.rule.as_str()).collect();
+        assert_eq!(rules, vec!["isolated-class", "circular-dependency", EXPIRED_EXCEPTION_RULE]);
+        assert_eq!(reported[2].severity, Severity::Error);
+        assert!(reported[2].message.contains("expired on 2025-05-31"));
+    }
+
+    #[test]
+    fn test_apply_reports_impossible_expiry_dates() {
+        let today = Date::parse("2025-01-01").unwrap();
+        let exceptions = vec![exception("isolated-class", "Old*", Some("2025-02-30"))];
+
+        let (reported, suppressed) =
+            apply(&exceptions, vec![finding("isolated-class", &["OldHelper"])], today, &Catalog::default());
+
+        assert!(suppressed.is_empty());
+        assert_eq!(reported.len(), 2);
+        assert_eq!(reported[1].rule, EXPIRED_EXCEPTION_RULE);
+        assert_eq!(reported[1].severity, Severity::Error);
+        assert_eq!(
+            reported[1].message,
+            "Exception for isolated-class (Old*) has an invalid expiry date '2025-02-30' (expected an existing YYYY-MM-DD date)"
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use crate::annotations::ClassTag;
+use crate::file_analyzer::{FileType, UnifiedAnalyzer};
+use crate::manifest::relative_path;
+use crate::project::FileAnalysis;
+use serde::Serialize;
+use std::fmt::Write;
+
+// Where a class is defined, for editor navigation. Paths are root-relative;
+// documentation pages and classes without a measured body point at line 1.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Symbol {
+    pub name: String,
+    pub file: String,
+    pub line: usize,
+    pub kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<&'static str>,
+}
+
+fn language(path: &str) -> Option<&'static str> {
+    match UnifiedAnalyzer::detect_file_type(path) {
+        FileType::Markdown => Some("Markdown"),
+        FileType::Python => Some("Python"),
+        FileType::TypeScript => Some("TypeScript"),
+        FileType::CSharp => Some("C#"),
+        FileType::Rust => Some("Rust"),
+        FileType::Java => Some("Java"),
+        FileType::Go => Some("Go"),
+        FileType::JavaScript => Some("JavaScript"),
+        FileType::Template => Some("HTML"),
+        FileType::Unknown => None,
+    }
+}
+
+// One symbol per defining file, sorted by name as `ctags` sorts, so partial
+// classes and classes documented next to their code get every location
+pub fn symbol_index(root: &str, files: &[FileAnalysis]) -> Vec<Symbol> {
+    let mut symbols = Vec::new();
+    for file in files {
+        for class in &file.result.classes {
+            let is_interface = file.result.class_tags.get(class).is_some_and(|tags| tags.contains(&ClassTag::Interface));
+            symbols.push(Symbol {
+                name: class.clone(),
+                file: relative_path(root, &file.path),
+                line: file.result.class_metrics.get(class).map_or(1, |metrics| metrics.start_line),
+                kind: if is_interface { "interface" } else { "class" },
+                language: language(&file.path),
+            });
+        }
+    }
+    symbols.sort_by(|a, b| (&a.name, &a.file, a.line).cmp(&(&b.name, &b.file, b.line)));
+    symbols
+}
+
+// Exuberant/Universal Ctags extended format, loadable as a `tags` file at
+// the project root. Tab characters cannot occur in class names or paths the
+// walker finds, so no escaping is needed.
+pub fn to_ctags(symbols: &[Symbol]) -> String {
+    let mut out = String::new();
+    writeln!(out, "!_TAG_FILE_FORMAT\t2\t/extended format/").unwrap();
+    writeln!(out, "!_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted, 2=foldcase/").unwrap();
+    writeln!(out, "!_TAG_PROGRAM_NAME\tclasslink-checker\t//").unwrap();
+    writeln!(out, "!_TAG_PROGRAM_VERSION\t{}\t//", env!("CARGO_PKG_VERSION")).unwrap();
+    for symbol in symbols {
+        let kind = if symbol.kind == "interface" { "i" } else { "c" };
+        write!(out, "{}\t{}\t{};\"\t{}\tline:{}", symbol.name, symbol.file, symbol.line, kind, symbol.line).unwrap();
+        if let Some(language) = symbol.language {
+            write!(out, "\tlanguage:{}", language).unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+    out
+}
+
+#[cfg(all(test, feature = "typescript"))]
+mod tests {
+    use super::*;
+    use crate::file_analyzer::AnalysisResult;
+    use crate::metrics::ClassMetrics;
+
+    #[test]
+    fn test_symbol_index_in_ctags_format() {
+        let mut code = AnalysisResult::default();
+        for (class, start) in [("Shop", 3), ("Repository", 12)] {
+            code.classes.insert(class.to_string());
+            code.class_metrics.insert(
+                class.to_string(),
+                ClassMetrics {
+                    start_line: start,
+                    end_line: start + 5,
+                    lines: 6,
+                    methods: 1,
+                },
+            );
+        }
+        code.class_tags.entry("Repository".to_string()).or_default().insert(ClassTag::Interface);
+        let mut docs = AnalysisResult::default();
+        docs.classes.insert("Shop".to_string());
+        let files = vec![
+            FileAnalysis {
+                path: "/repo/src/shop.ts".to_string(),
+                result: code,
+                encoding: Default::default(),
+            },
+            FileAnalysis {
+                path: "/repo/docs/Shop.md".to_string(),
+                result: docs,
+                encoding: Default::default(),
+            },
+        ];
+
+        let symbols = symbol_index("/repo", &files);
+
+        let names: Vec<(&str, &str, usize)> = symbols.iter().map(|s| (s.name.as_str(), s.file.as_str(), s.line)).collect();
+        assert_eq!(names, vec![("Repository", "src/shop.ts", 12), ("Shop", "docs/Shop.md", 1), ("Shop", "src/shop.ts", 3)]);
+        let tags = to_ctags(&symbols);
+        assert!(tags.starts_with("!_TAG_FILE_FORMAT\t2\t/extended format/\n"));
+        assert!(tags.contains("\nRepository\tsrc/shop.ts\t12;\"\ti\tline:12\tlanguage:TypeScript\n"));
+        assert!(tags.contains("\nShop\tdocs/Shop.md\t1;\"\tc\tline:1\tlanguage:Markdown\n"));
+    }
+}
@@ -0,0 +1,149 @@
+use crate::churn::ChurnHotspot;
+use crate::manifest::{self, relative_path};
+use crate::parser::LinkKind;
+use crate::project::FileAnalysis;
+use crate::query::rel_type_name;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+// Cypher statements loading the class graph into Neo4j, e.g. with
+// `cypher-shell -f graph.cypher`. Everything is `MERGE`d on the class name
+// and package path, so re-running an export updates the graph in place and
+// exports of several repositories join up on the classes they share. Each
+// (from, to, kind) becomes one relationship whose `weight` is the number of
+// occurrences.
+pub fn to_cypher(root: &str, files: &[FileAnalysis]) -> String {
+    let packages = manifest::class_packages(root, files);
+    let namespaces = manifest::class_namespaces(root, files);
+    let mut referenced: BTreeSet<&str> = BTreeSet::new();
+    let mut weights: BTreeMap<(&str, &str, LinkKind), usize> = BTreeMap::new();
+    for link in files.iter().flat_map(|file| &file.result.links) {
+        referenced.insert(&link.from_class);
+        referenced.insert(&link.to_class);
+        *weights.entry((&link.from_class, &link.to_class, link.kind)).or_default() += 1;
+    }
+    let mut defined_in: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
+    for file in files {
+        for class in &file.result.classes {
+            defined_in.entry(class).or_default().insert(relative_path(root, &file.path));
+        }
+    }
+
+    let mut out = String::new();
+    writeln!(out, "// classlink-checker {}", env!("CARGO_PKG_VERSION")).unwrap();
+    writeln!(out, "CREATE CONSTRAINT classlink_class IF NOT EXISTS FOR (c:Class) REQUIRE c.name IS UNIQUE;").unwrap();
+    writeln!(out, "CREATE CONSTRAINT classlink_package IF NOT EXISTS FOR (p:Package) REQUIRE p.path IS UNIQUE;").unwrap();
+    for package in packages.values().collect::<BTreeSet<_>>() {
+        writeln!(out, "MERGE (:Package {{path: {}}});", literal(package)).unwrap();
+    }
+    for (class, files) in &defined_in {
+        let files: Vec<String> = files.iter().map(|file| literal(file)).collect();
+        writeln!(
+            out,
+            "MERGE (c:Class {{name: {}}}) SET c.defined = true, c.namespace = {}, c.files = [{}];",
+            literal(class),
+            literal(&namespaces[*class]),
+            files.join(", ")
+        )
+        .unwrap();
+    }
+    // Another repository may define what this one only links to
+    for class in referenced.iter().filter(|class| !defined_in.contains_key(*class)) {
+        writeln!(out, "MERGE (c:Class {{name: {}}}) ON CREATE SET c.defined = false;", literal(class)).unwrap();
+    }
+    for (class, package) in &packages {
+        writeln!(
+            out,
+            "MATCH (p:Package {{path: {}}}), (c:Class {{name: {}}}) MERGE (p)-[:CONTAINS]->(c);",
+            literal(package),
+            literal(class)
+        )
+        .unwrap();
+    }
+    for ((from, to, kind), weight) in weights {
+        writeln!(
+            out,
+            "MATCH (a:Class {{name: {}}}), (b:Class {{name: {}}}) MERGE (a)-[r:{}]->(b) SET r.weight = {};",
+            literal(from),
+            literal(to),
+            rel_type_name(kind),
+            weight
+        )
+        .unwrap();
+    }
+    out
+}
+
+// Sets `commits` and `churn_score` on ranked classes, after `to_cypher`
+pub fn churn_properties(hotspots: &[ChurnHotspot]) -> String {
+    let mut out = String::new();
+    for hotspot in hotspots {
+        writeln!(
+            out,
+            "MATCH (c:Class {{name: {}}}) SET c.commits = {}, c.churn_score = {};",
+            literal(&hotspot.class),
+            hotspot.commits,
+            hotspot.score
+        )
+        .unwrap();
+    }
+    out
+}
+
+// A single-quoted Cypher string
+fn literal(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+#[cfg(all(test, feature = "python"))]
+mod tests {
+    use super::*;
+    use crate::file_analyzer::AnalysisResult;
+    use crate::parser::ClassLink;
+
+    fn file(path: &str, classes: &[&str], links: &[(&str, &str, LinkKind)]) -> FileAnalysis {
+        FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|s| s.to_string()).collect(),
+                links: links
+                    .iter()
+                    .map(|(from, to, kind)| ClassLink {
+                        from_class: from.to_string(),
+                        to_class: to.to_string(),
+                        line_number: 1,
+                        kind: *kind,
+                        position: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_cypher_merges_classes_packages_and_weighted_links() {
+        let files = vec![
+            file(
+                "/repo/src/shop.py",
+                &["Shop", "O'Brien"],
+                &[("Shop", "Cart", LinkKind::Usage), ("Shop", "Cart", LinkKind::Usage), ("O'Brien", "Shop", LinkKind::Inheritance)],
+            ),
+            file("/repo/src/cart.py", &["Cart"], &[("Cart", "Decimal", LinkKind::Composition)]),
+        ];
+
+        let cypher = to_cypher("/repo", &files);
+        let lines: Vec<&str> = cypher.lines().collect();
+
+        assert!(lines.contains(&"MERGE (:Package {path: 'src'});"));
+        assert!(lines.contains(&"MERGE (c:Class {name: 'Cart'}) SET c.defined = true, c.namespace = 'src.cart', c.files = ['src/cart.py'];"));
+        assert!(lines.contains(&"MERGE (c:Class {name: 'Decimal'}) ON CREATE SET c.defined = false;"));
+        assert!(lines.contains(&"MATCH (p:Package {path: 'src'}), (c:Class {name: 'O\\'Brien'}) MERGE (p)-[:CONTAINS]->(c);"));
+        assert!(lines.contains(&"MATCH (a:Class {name: 'Shop'}), (b:Class {name: 'Cart'}) MERGE (a)-[r:USES]->(b) SET r.weight = 2;"));
+        assert!(lines.contains(&"MATCH (a:Class {name: 'O\\'Brien'}), (b:Class {name: 'Shop'}) MERGE (a)-[r:INHERITS]->(b) SET r.weight = 1;"));
+        // Nodes exist before the relationships matching them
+        let last_node = lines.iter().rposition(|line| line.starts_with("MERGE")).unwrap();
+        assert!(lines.iter().position(|line| line.starts_with("MATCH")).unwrap() > last_node);
+    }
+}
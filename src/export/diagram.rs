@@ -0,0 +1,594 @@
+use crate::file_analyzer::AnalysisResult;
+use crate::graph::ClassGraph;
+use crate::metrics::Hotspot;
+use crate::parser::ClassLink;
+use crate::subgraph;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write;
+use std::str::FromStr;
+
+// Sizes above which Graphviz and Mermaid layouts stop being readable (or,
+// for Mermaid, stop rendering at all)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiagramLimits {
+    pub max_nodes: usize,
+    pub max_edges: usize,
+}
+
+impl Default for DiagramLimits {
+    fn default() -> Self {
+        Self {
+            max_nodes: 150,
+            max_edges: 300,
+        }
+    }
+}
+
+// A node standing for several folded leaves
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    // True for leaves the hub depends on, false for leaves depending on it
+    pub dependencies: bool,
+}
+
+impl Summary {
+    pub fn label(&self) -> String {
+        let kind = if self.dependencies { "dependencies" } else { "dependents" };
+        format!("{} more {}…", self.count, kind)
+    }
+}
+
+// Node metrics diagrams can be colored by, from green for healthy values to
+// red for the worst one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorMetric {
+    // Distinct classes depending on the node
+    FanIn,
+    // Distinct dependencies over all distinct neighbours, 0 (stable) to 1
+    Instability,
+    // Size times weighted coupling, see `metrics::hotspots`
+    Hotspot,
+}
+
+impl FromStr for ColorMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "fan-in" | "fanin" => Ok(ColorMetric::FanIn),
+            "instability" => Ok(ColorMetric::Instability),
+            "hotspot" => Ok(ColorMetric::Hotspot),
+            _ => Err(format!("Unknown color metric: {} (expected fan-in, instability, hotspot)", s)),
+        }
+    }
+}
+
+impl ColorMetric {
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorMetric::FanIn => "fan-in",
+            ColorMetric::Instability => "instability",
+            ColorMetric::Hotspot => "hotspot score",
+        }
+    }
+
+    // Value of every node the metric is defined for; classes without links
+    // have no instability, and only ranked hotspots have a score
+    pub fn values(self, graph: &AnalysisResult, hotspots: &[Hotspot]) -> BTreeMap<String, f64> {
+        if self == ColorMetric::Hotspot {
+            return hotspots.iter().map(|hotspot| (hotspot.class.clone(), hotspot.score)).collect();
+        }
+        let mut dependents: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        let mut dependencies: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for link in graph.links.iter().filter(|link| link.from_class != link.to_class) {
+            dependents.entry(&link.to_class).or_default().insert(&link.from_class);
+            dependencies.entry(&link.from_class).or_default().insert(&link.to_class);
+        }
+        let count = |map: &BTreeMap<&str, BTreeSet<&str>>, class: &str| map.get(class).map_or(0, BTreeSet::len) as f64;
+        ClassGraph::new(&graph.classes, &graph.links)
+            .classes()
+            .filter_map(|class| {
+                let (incoming, outgoing) = (count(&dependents, class), count(&dependencies, class));
+                let value = match self {
+                    ColorMetric::FanIn => incoming,
+                    _ if incoming + outgoing == 0.0 => return None,
+                    _ => outgoing / (incoming + outgoing),
+                };
+                Some((class.clone(), value))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coloring {
+    pub metric: ColorMetric,
+    // The value drawn red; instability is always scaled to 1
+    pub max: f64,
+    // Node id -> `#rrggbb` fill
+    pub fills: BTreeMap<String, String>,
+}
+
+// Green, yellow at the midpoint, then red, as spreadsheet color scales go
+fn gradient(t: f64) -> String {
+    const STOPS: [(f64, f64, f64); 3] = [(99.0, 190.0, 123.0), (255.0, 235.0, 132.0), (248.0, 105.0, 107.0)];
+    let t = t.clamp(0.0, 1.0) * 2.0;
+    let (from, to, t) = if t <= 1.0 { (STOPS[0], STOPS[1], t) } else { (STOPS[1], STOPS[2], t - 1.0) };
+    let mix = |a: f64, b: f64| (a + (b - a) * t).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
+
+// What DOT and Mermaid draw: repeated (from, to) pairs are one edge whose
+// weight is the number of occurrences
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diagram {
+    pub classes: BTreeSet<String>,
+    // Node id -> folded leaves
+    pub summaries: BTreeMap<String, Summary>,
+    pub edges: BTreeMap<(String, String), usize>,
+    pub coloring: Option<Coloring>,
+}
+
+impl Diagram {
+    pub fn new(result: &AnalysisResult) -> Self {
+        let graph = ClassGraph::new(&result.classes, &result.links);
+        let mut edges = BTreeMap::new();
+        for link in &result.links {
+            *edges.entry((link.from_class.clone(), link.to_class.clone())).or_default() += 1;
+        }
+        Self {
+            classes: graph.classes().cloned().collect(),
+            summaries: BTreeMap::new(),
+            edges,
+            coloring: None,
+        }
+    }
+
+    pub fn exceeds(&self, limits: &DiagramLimits) -> bool {
+        self.classes.len() + self.summaries.len() > limits.max_nodes || self.edges.len() > limits.max_edges
+    }
+
+    // Folds leaves, classes with a single edge, into one summary node per
+    // hub and direction wherever a hub has at least two of them
+    pub fn summarize_leaves(mut self) -> Self {
+        let mut degree: BTreeMap<&str, usize> = BTreeMap::new();
+        for (from, to) in self.edges.keys() {
+            *degree.entry(from).or_default() += 1;
+            *degree.entry(to).or_default() += 1;
+        }
+        let is_leaf = |class: &str| degree.get(class) == Some(&1);
+        let mut groups: BTreeMap<(String, bool), Vec<(String, String)>> = BTreeMap::new();
+        for (from, to) in self.edges.keys() {
+            if from == to {
+                continue;
+            }
+            // A lone pair of leaves has no hub to fold into
+            match (is_leaf(from), is_leaf(to)) {
+                (false, true) => groups.entry((from.clone(), true)).or_default().push((from.clone(), to.clone())),
+                (true, false) => groups.entry((to.clone(), false)).or_default().push((from.clone(), to.clone())),
+                _ => {}
+            }
+        }
+
+        for ((hub, dependencies), edges) in groups.into_iter().filter(|(_, edges)| edges.len() >= 2) {
+            let id = format!("{} ({})", hub, if dependencies { "more dependencies" } else { "more dependents" });
+            let mut weight = 0;
+            for edge in &edges {
+                weight += self.edges.remove(edge).unwrap_or_default();
+                self.classes.remove(if dependencies { &edge.1 } else { &edge.0 });
+            }
+            let key = if dependencies { (hub, id.clone()) } else { (id.clone(), hub) };
+            self.edges.insert(key, weight);
+            self.summaries.insert(
+                id,
+                Summary {
+                    count: edges.len(),
+                    dependencies,
+                },
+            );
+        }
+        self
+    }
+
+    // Fills the drawn classes the metric has a value for; summary nodes and
+    // the rest stay unfilled
+    pub fn color_by(mut self, metric: ColorMetric, values: &BTreeMap<String, f64>) -> Self {
+        let max = match metric {
+            ColorMetric::Instability => 1.0,
+            _ => values.values().copied().fold(0.0, f64::max),
+        };
+        let fills = values
+            .iter()
+            .filter(|(class, _)| self.classes.contains(*class))
+            .map(|(class, value)| (class.clone(), gradient(if max > 0.0 { value / max } else { 0.0 })))
+            .collect();
+        self.coloring = Some(Coloring { metric, max, fills });
+        self
+    }
+
+    fn fill(&self, class: &str) -> Option<&str> {
+        self.coloring.as_ref()?.fills.get(class).map(String::as_str)
+    }
+
+    // Summarizes only past the limits; `None` never summarizes
+    pub fn build(result: &AnalysisResult, limits: Option<&DiagramLimits>) -> Self {
+        let diagram = Self::new(result);
+        match limits {
+            Some(limits) if diagram.exceeds(limits) => diagram.summarize_leaves(),
+            _ => diagram,
+        }
+    }
+}
+
+fn dot_id(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// Graphviz DOT, e.g. `dot -Tsvg graph.dot`; summary nodes are dashed notes.
+// Built with `new()` and the option methods, for callers that start from
+// classes and links rather than from a report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DotExporter {
+    limits: Option<DiagramLimits>,
+    highlight_isolated: bool,
+    edge_labels: bool,
+}
+
+impl Default for DotExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DotExporter {
+    pub fn new() -> Self {
+        Self {
+            limits: None,
+            highlight_isolated: false,
+            edge_labels: true,
+        }
+    }
+
+    // Folds leaves past the limits, see `Diagram::build`
+    pub fn limits(mut self, limits: Option<DiagramLimits>) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    // Draws classes without any edge dashed and grey
+    pub fn highlight_isolated(mut self, highlight_isolated: bool) -> Self {
+        self.highlight_isolated = highlight_isolated;
+        self
+    }
+
+    // Labels edges occurring more than once with their weight
+    pub fn edge_labels(mut self, edge_labels: bool) -> Self {
+        self.edge_labels = edge_labels;
+        self
+    }
+
+    pub fn export(&self, classes: &HashSet<String>, links: &[ClassLink]) -> String {
+        let result = AnalysisResult {
+            classes: classes.clone(),
+            links: links.to_vec(),
+            ..Default::default()
+        };
+        self.render(&Diagram::build(&result, self.limits.as_ref()))
+    }
+
+    pub fn render(&self, diagram: &Diagram) -> String {
+        let mut out = String::new();
+        writeln!(out, "// classlink-checker {}", env!("CARGO_PKG_VERSION")).unwrap();
+        if let Some(coloring) = &diagram.coloring {
+            writeln!(out, "// nodes colored by {}, green 0 to red {:.2}", coloring.metric.label(), coloring.max).unwrap();
+        }
+        writeln!(out, "digraph classlink {{\n    rankdir=LR;\n    node [shape=box];").unwrap();
+        let linked: BTreeSet<&str> = diagram.edges.keys().flat_map(|(from, to)| [from.as_str(), to.as_str()]).collect();
+        for class in &diagram.classes {
+            let isolated = self.highlight_isolated && !linked.contains(class.as_str());
+            let fill = diagram.fill(class);
+            let style: Vec<&str> = [fill.map(|_| "filled"), isolated.then_some("dashed")].into_iter().flatten().collect();
+            let mut attributes = Vec::new();
+            match style[..] {
+                [] => {}
+                [style] => attributes.push(format!("style={}", style)),
+                _ => attributes.push(format!("style=\"{}\"", style.join(","))),
+            }
+            if let Some(fill) = fill {
+                attributes.push(format!("fillcolor=\"{}\"", fill));
+            }
+            if isolated {
+                attributes.push("color=\"gray50\", fontcolor=\"gray50\"".to_string());
+            }
+            if attributes.is_empty() {
+                writeln!(out, "    {};", dot_id(class)).unwrap();
+            } else {
+                writeln!(out, "    {} [{}];", dot_id(class), attributes.join(", ")).unwrap();
+            }
+        }
+        for (id, summary) in &diagram.summaries {
+            writeln!(out, "    {} [label={}, shape=note, style=dashed];", dot_id(id), dot_id(&summary.label())).unwrap();
+        }
+        for ((from, to), weight) in &diagram.edges {
+            match weight {
+                2.. if self.edge_labels => writeln!(out, "    {} -> {} [label=\"{}\"];", dot_id(from), dot_id(to), weight).unwrap(),
+                _ => writeln!(out, "    {} -> {};", dot_id(from), dot_id(to)).unwrap(),
+            }
+        }
+        writeln!(out, "}}").unwrap();
+        out
+    }
+}
+
+pub fn to_dot(diagram: &Diagram) -> String {
+    DotExporter::new().render(diagram)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MermaidStyle {
+    #[default]
+    Flowchart,
+    // UML-style boxes, as class documentation usually shows them
+    ClassDiagram,
+}
+
+// A Mermaid flowchart or class diagram, e.g. for a ```` ```mermaid ```` block
+// in a README; nodes get generated ids since class names may hold characters
+// Mermaid does not accept there
+#[derive(Debug, Clone, PartialEq)]
+pub struct MermaidExporter {
+    style: MermaidStyle,
+    top_down: bool,
+    around: Option<(String, usize)>,
+    limits: Option<DiagramLimits>,
+    fenced: bool,
+}
+
+impl Default for MermaidExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MermaidExporter {
+    pub fn new() -> Self {
+        Self {
+            style: MermaidStyle::default(),
+            top_down: false,
+            around: None,
+            limits: None,
+            fenced: false,
+        }
+    }
+
+    pub fn style(mut self, style: MermaidStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    // `TD` instead of `LR`
+    pub fn top_down(mut self, top_down: bool) -> Self {
+        self.top_down = top_down;
+        self
+    }
+
+    // Only draws classes within `depth` links of `class`, see
+    // `subgraph::subgraph_around`
+    pub fn around(mut self, class: &str, depth: usize) -> Self {
+        self.around = Some((class.to_string(), depth));
+        self
+    }
+
+    // Folds leaves past the limits, see `Diagram::build`
+    pub fn limits(mut self, limits: Option<DiagramLimits>) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    // Wraps the diagram in a ```` ```mermaid ```` fence, ready to paste into
+    // Markdown that GitHub renders
+    pub fn fenced(mut self, fenced: bool) -> Self {
+        self.fenced = fenced;
+        self
+    }
+
+    pub fn export(&self, result: &AnalysisResult) -> String {
+        let diagram = match &self.around {
+            Some((class, depth)) => Diagram::build(&subgraph::subgraph_around(result, class, *depth), self.limits.as_ref()),
+            None => Diagram::build(result, self.limits.as_ref()),
+        };
+        self.render(&diagram)
+    }
+
+    pub fn render(&self, diagram: &Diagram) -> String {
+        let direction = if self.top_down { "TD" } else { "LR" };
+        let class_diagram = self.style == MermaidStyle::ClassDiagram;
+        let mut ids: BTreeMap<&str, String> = BTreeMap::new();
+        let mut out = String::new();
+        if self.fenced {
+            out.push_str("```mermaid\n");
+        }
+        if class_diagram {
+            writeln!(out, "classDiagram\n    direction {}", direction).unwrap();
+        } else {
+            writeln!(out, "graph {}", direction).unwrap();
+        }
+        if let Some(coloring) = &diagram.coloring {
+            writeln!(out, "    %% nodes colored by {}, green 0 to red {:.2}", coloring.metric.label(), coloring.max).unwrap();
+        }
+        let label = |text: &str| text.replace('"', "#quot;");
+        for class in &diagram.classes {
+            let id = format!("n{}", ids.len());
+            if class_diagram {
+                writeln!(out, "    class {}[\"{}\"]", id, label(class)).unwrap();
+            } else {
+                writeln!(out, "    {}[\"{}\"]", id, label(class)).unwrap();
+            }
+            ids.insert(class, id);
+        }
+        for (name, summary) in &diagram.summaries {
+            let id = format!("n{}", ids.len());
+            if class_diagram {
+                writeln!(out, "    class {}[\"{}\"]\n    <<summary>> {}", id, label(&summary.label()), id).unwrap();
+            } else {
+                writeln!(out, "    {}([\"{}\"])", id, label(&summary.label())).unwrap();
+            }
+            ids.insert(name, id);
+        }
+        for ((from, to), weight) in &diagram.edges {
+            let (from, to) = (&ids[from.as_str()], &ids[to.as_str()]);
+            match (weight, class_diagram) {
+                (1, _) => writeln!(out, "    {} --> {}", from, to).unwrap(),
+                (_, true) => writeln!(out, "    {} --> {} : {}", from, to, weight).unwrap(),
+                (_, false) => writeln!(out, "    {} -->|{}| {}", from, weight, to).unwrap(),
+            }
+        }
+        let summaries: Vec<&str> = diagram.summaries.keys().map(|name| ids[name.as_str()].as_str()).collect();
+        if !summaries.is_empty() && !class_diagram {
+            writeln!(out, "    classDef summary stroke-dasharray:4 2,fill:#f6f8fa").unwrap();
+            writeln!(out, "    class {} summary", summaries.join(",")).unwrap();
+        }
+        for class in &diagram.classes {
+            if let Some(fill) = diagram.fill(class) {
+                writeln!(out, "    style {} fill:{}", ids[class.as_str()], fill).unwrap();
+            }
+        }
+        if self.fenced {
+            out.push_str("```\n");
+        }
+        out
+    }
+}
+
+pub fn to_mermaid(diagram: &Diagram) -> String {
+    MermaidExporter::new().render(diagram)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(from: &str, to: &str) -> ClassLink {
+        ClassLink {
+            from_class: from.to_string(),
+            to_class: to.to_string(),
+            line_number: 1,
+            kind: Default::default(),
+            position: None,
+        }
+    }
+
+    #[test]
+    fn test_leaves_fold_into_summary_nodes_past_the_limits() {
+        let mut links = vec![link("Shop", "Cart"), link("Cart", "Shop"), link("Admin", "Shop")];
+        for leaf in ["Logger", "Clock", "Config"] {
+            links.push(link("Shop", leaf));
+        }
+        links.extend([link("Audit", "Cart"), link("Report", "Cart"), link("Report", "Cart")]);
+        let result = AnalysisResult {
+            links,
+            ..Default::default()
+        };
+
+        let small = Diagram::build(&result, Some(&DiagramLimits::default()));
+        assert!(small.summaries.is_empty());
+        let limits = DiagramLimits {
+            max_nodes: 5,
+            ..Default::default()
+        };
+        let diagram = Diagram::build(&result, Some(&limits));
+
+        // Admin is Shop's only dependent leaf, so it stays
+        assert_eq!(diagram.classes, ["Admin", "Cart", "Shop"].map(String::from).into());
+        assert_eq!(diagram.summaries["Shop (more dependencies)"].label(), "3 more dependencies…");
+        assert_eq!(diagram.edges[&("Cart (more dependents)".to_string(), "Cart".to_string())], 3);
+        assert_eq!(Diagram::build(&result, None), Diagram::new(&result));
+
+        let dot = to_dot(&diagram);
+        assert!(dot.contains("    \"Shop (more dependencies)\" [label=\"3 more dependencies…\", shape=note, style=dashed];"));
+        assert!(dot.contains("    \"Cart (more dependents)\" -> \"Cart\" [label=\"3\"];"));
+        let mermaid = to_mermaid(&diagram);
+        assert!(mermaid.contains("    n3([\"2 more dependents…\"])"));
+        assert!(mermaid.contains("    n3 -->|3| n1"));
+        assert!(mermaid.contains("    class n3,n4 summary"));
+    }
+
+    #[test]
+    fn test_nodes_are_colored_by_metric() {
+        let result = AnalysisResult {
+            links: vec![link("Shop", "Cart"), link("Admin", "Cart"), link("Admin", "Cart"), link("Cart", "Db")],
+            ..Default::default()
+        };
+        let fan_in = ColorMetric::FanIn.values(&result, &[]);
+        assert_eq!((fan_in["Cart"], fan_in["Db"], fan_in["Shop"]), (2.0, 1.0, 0.0));
+        let instability = ColorMetric::Instability.values(&result, &[]);
+        assert_eq!((instability["Shop"], instability["Cart"], instability["Db"]), (1.0, 1.0 / 3.0, 0.0));
+        assert_eq!("Fan-In".parse::<ColorMetric>(), Ok(ColorMetric::FanIn));
+        assert!("churn".parse::<ColorMetric>().is_err());
+
+        let diagram = Diagram::new(&result).color_by(ColorMetric::FanIn, &fan_in);
+
+        let dot = to_dot(&diagram);
+        assert!(dot.contains("// nodes colored by fan-in, green 0 to red 2.00\n"));
+        assert!(dot.contains("    \"Cart\" [style=filled, fillcolor=\"#f8696b\"];"));
+        assert!(dot.contains("    \"Db\" [style=filled, fillcolor=\"#ffeb84\"];"));
+        assert!(dot.contains("    \"Shop\" [style=filled, fillcolor=\"#63be7b\"];"));
+        let mermaid = to_mermaid(&diagram);
+        assert!(mermaid.contains("    style n1 fill:#f8696b\n"));
+        assert!(!to_dot(&Diagram::new(&result)).contains("fillcolor"));
+    }
+
+    #[test]
+    fn test_dot_exporter_options() {
+        let classes: HashSet<String> = ["Shop", "Cart", "Lonely"].map(String::from).into();
+        let links = vec![link("Shop", "Cart"), link("Shop", "Cart")];
+
+        let plain = DotExporter::new().export(&classes, &links);
+        assert!(plain.contains("    \"Lonely\";\n"));
+        assert!(plain.contains("    \"Shop\" -> \"Cart\" [label=\"2\"];"));
+
+        let styled = DotExporter::new().highlight_isolated(true).edge_labels(false).export(&classes, &links);
+        assert!(styled.contains("    \"Lonely\" [style=dashed, color=\"gray50\", fontcolor=\"gray50\"];"));
+        assert!(styled.contains("    \"Cart\";\n"));
+        assert!(styled.contains("    \"Shop\" -> \"Cart\";"));
+        let fan_in = BTreeMap::from([("Lonely".to_string(), 0.0), ("Cart".to_string(), 1.0)]);
+        let diagram = Diagram::new(&AnalysisResult {
+            classes,
+            links,
+            ..Default::default()
+        });
+        let colored = DotExporter::new().highlight_isolated(true).render(&diagram.color_by(ColorMetric::FanIn, &fan_in));
+        assert!(colored.contains("    \"Lonely\" [style=\"filled,dashed\", fillcolor=\"#63be7b\", color=\"gray50\""));
+    }
+
+    #[test]
+    fn test_mermaid_exporter_options() {
+        let result = AnalysisResult {
+            links: vec![link("Shop", "Cart"), link("Shop", "Cart"), link("Cart", "Db"), link("Db", "Pool")],
+            ..Default::default()
+        };
+
+        assert_eq!(MermaidExporter::new().export(&result), to_mermaid(&Diagram::new(&result)));
+        let markdown = MermaidExporter::new()
+            .style(MermaidStyle::ClassDiagram)
+            .top_down(true)
+            .around("Shop", 2)
+            .fenced(true)
+            .export(&result);
+
+        assert!(markdown.starts_with("```mermaid\nclassDiagram\n    direction TD\n"));
+        assert!(markdown.ends_with("```\n"));
+        // Pool is three links away from Shop
+        assert!(!markdown.contains("Pool"));
+        assert!(markdown.contains("    class n2[\"Shop\"]\n"));
+        assert!(markdown.contains("    n2 --> n0 : 2\n"));
+        assert!(markdown.contains("    n0 --> n1\n"));
+        let flowchart = MermaidExporter::new().top_down(true).export(&result);
+        assert!(flowchart.starts_with("graph TD\n"));
+        assert!(flowchart.contains("    n3 -->|2| n0\n"));
+    }
+}
@@ -0,0 +1,161 @@
+use crate::file_analyzer::AnalysisResult;
+use crate::parser::{ClassLink, LinkKind};
+use crate::project::FileAnalysis;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+// Confidence that a link points at a real class: its target is defined in
+// the project, or it is only a name the parser matched (a library type, or a
+// false positive)
+pub const DEFINED_TARGET_CONFIDENCE: f64 = 1.0;
+pub const UNDEFINED_TARGET_CONFIDENCE: f64 = 0.5;
+
+// Which links exporters keep, e.g. inheritance only for a UML-style diagram.
+// The defaults keep everything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportFilter {
+    // Link kinds to keep; empty keeps every kind
+    pub kinds: Vec<LinkKind>,
+    pub min_confidence: f64,
+    // Minimum number of occurrences of a (from, to) pair
+    pub min_weight: usize,
+}
+
+pub fn confidence(link: &ClassLink, defined: &HashSet<String>) -> f64 {
+    if defined.contains(&link.to_class) {
+        DEFINED_TARGET_CONFIDENCE
+    } else {
+        UNDEFINED_TARGET_CONFIDENCE
+    }
+}
+
+impl ExportFilter {
+    pub fn is_empty(&self) -> bool {
+        self.kinds.is_empty() && self.min_confidence <= 0.0 && self.min_weight <= 1
+    }
+
+    // Kind and confidence checks, which look at one link at a time
+    pub fn apply_links(&self, files: Vec<FileAnalysis>) -> Vec<FileAnalysis> {
+        let defined: HashSet<String> = files.iter().flat_map(|f| f.result.classes.iter().cloned()).collect();
+        files
+            .into_iter()
+            .map(|mut file| {
+                file.result.links.retain(|link| {
+                    (self.kinds.is_empty() || self.kinds.contains(&link.kind))
+                        && confidence(link, &defined) >= self.min_confidence
+                });
+                file
+            })
+            .collect()
+    }
+
+    // Every check; pair weights count occurrences across all files
+    pub fn apply(&self, files: Vec<FileAnalysis>) -> Vec<FileAnalysis> {
+        if self.is_empty() {
+            return files;
+        }
+        let mut files = self.apply_links(files);
+        let weights = pair_weights(files.iter().flat_map(|f| &f.result.links));
+        for file in &mut files {
+            file.result
+                .links
+                .retain(|link| weights[&(link.from_class.clone(), link.to_class.clone())] >= self.min_weight);
+        }
+        files
+    }
+
+    // The weight check on an already built graph, such as the package view
+    pub fn retain_weight(&self, mut graph: AnalysisResult) -> AnalysisResult {
+        let weights = pair_weights(graph.links.iter());
+        graph
+            .links
+            .retain(|link| weights[&(link.from_class.clone(), link.to_class.clone())] >= self.min_weight);
+        graph
+    }
+}
+
+fn pair_weights<'a>(links: impl Iterator<Item = &'a ClassLink>) -> BTreeMap<(String, String), usize> {
+    let mut weights = BTreeMap::new();
+    for link in links {
+        *weights.entry((link.from_class.clone(), link.to_class.clone())).or_default() += 1;
+    }
+    weights
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(from: &str, to: &str, kind: LinkKind) -> ClassLink {
+        ClassLink {
+            from_class: from.to_string(),
+            to_class: to.to_string(),
+            line_number: 1,
+            kind,
+            position: None,
+        }
+    }
+
+    fn files() -> Vec<FileAnalysis> {
+        let file = |path: &str, classes: &[&str], links: Vec<ClassLink>| FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|s| s.to_string()).collect(),
+                links,
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        };
+        vec![
+            file(
+                "/repo/a.py",
+                &["A", "B"],
+                vec![
+                    link("A", "B", LinkKind::Inheritance),
+                    link("A", "B", LinkKind::Usage),
+                    link("A", "Json", LinkKind::Usage),
+                ],
+            ),
+            file("/repo/c.py", &["C"], vec![link("C", "B", LinkKind::Usage), link("C", "A", LinkKind::Usage)]),
+        ]
+    }
+
+    fn pairs(files: &[FileAnalysis]) -> Vec<(String, String, LinkKind)> {
+        files
+            .iter()
+            .flat_map(|f| &f.result.links)
+            .map(|l| (l.from_class.clone(), l.to_class.clone(), l.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_by_kind_and_confidence() {
+        let inheritance = ExportFilter {
+            kinds: vec![LinkKind::Inheritance],
+            ..Default::default()
+        };
+        assert_eq!(pairs(&inheritance.apply(files())), vec![("A".into(), "B".into(), LinkKind::Inheritance)]);
+
+        let confident = ExportFilter {
+            min_confidence: 0.9,
+            ..Default::default()
+        };
+        assert!(!pairs(&confident.apply(files())).iter().any(|(_, to, _)| to == "Json"));
+        assert!(ExportFilter::default().is_empty());
+    }
+
+    #[test]
+    fn test_filter_by_weight() {
+        let heavy = ExportFilter {
+            min_weight: 2,
+            ..Default::default()
+        };
+        let kept = pairs(&heavy.apply(files()));
+        assert_eq!(kept.len(), 2);
+        assert!(kept.iter().all(|(from, to, _)| from == "A" && to == "B"));
+
+        let graph = crate::project::merge(files());
+        assert_eq!(heavy.retain_weight(graph).links.len(), 2);
+    }
+}
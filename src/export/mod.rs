@@ -0,0 +1,14 @@
+pub mod ctags;
+pub mod cypher;
+pub mod diagram;
+pub mod filter;
+pub mod networkx;
+pub mod packages;
+pub mod prometheus;
+
+pub use ctags::{symbol_index, to_ctags};
+pub use cypher::to_cypher;
+pub use diagram::{to_dot, to_mermaid, ColorMetric, Diagram, DiagramLimits, DotExporter, MermaidExporter, MermaidStyle};
+pub use filter::ExportFilter;
+pub use networkx::to_node_link;
+pub use packages::collapse_to_packages;
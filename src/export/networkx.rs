@@ -0,0 +1,115 @@
+use crate::churn::ChurnHotspot;
+use crate::file_analyzer::AnalysisResult;
+use crate::graph::ClassGraph;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+// NetworkX node-link format, loadable with `networkx.node_link_graph(data)`.
+// Repeated (from, to) pairs become a single edge whose `weight` is the number
+// of occurrences.
+pub fn to_node_link(result: &AnalysisResult) -> Value {
+    let graph = ClassGraph::new(&result.classes, &result.links);
+
+    let nodes: Vec<Value> = graph
+        .classes()
+        .map(|class| {
+            let fan_in = graph.dependents(class).len();
+            let fan_out = graph.dependencies(class).len();
+            json!({
+                "id": class,
+                "defined": result.classes.contains(class),
+                "fan_in": fan_in,
+                "fan_out": fan_out,
+                "isolated": fan_in == 0 && fan_out == 0,
+            })
+        })
+        .collect();
+
+    let mut weights: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+    for link in &result.links {
+        *weights
+            .entry((link.from_class.as_str(), link.to_class.as_str()))
+            .or_default() += 1;
+    }
+
+    let links: Vec<Value> = weights
+        .into_iter()
+        .map(|((source, target), weight)| {
+            json!({ "source": source, "target": target, "weight": weight })
+        })
+        .collect();
+
+    json!({
+        "directed": true,
+        "multigraph": false,
+        "graph": { "generator": format!("classlink-checker {}", env!("CARGO_PKG_VERSION")) },
+        "nodes": nodes,
+        "links": links,
+    })
+}
+
+// Sets `commits` and `churn_score` on the nodes of ranked classes
+pub fn add_churn(graph: &mut Value, hotspots: &[ChurnHotspot]) {
+    let Some(nodes) = graph["nodes"].as_array_mut() else {
+        return;
+    };
+    for node in nodes {
+        if let Some(hotspot) = hotspots.iter().find(|hotspot| node["id"] == hotspot.class.as_str()) {
+            node["commits"] = json!(hotspot.commits);
+            node["churn_score"] = json!(hotspot.score);
+        }
+    }
+}
+
+// Sets every registered metric on the nodes of the classes it has a value for
+pub fn add_custom_metrics(graph: &mut Value, metrics: &BTreeMap<String, BTreeMap<String, f64>>) {
+    let Some(nodes) = graph["nodes"].as_array_mut() else {
+        return;
+    };
+    for node in nodes {
+        for (name, values) in metrics {
+            if let Some(value) = node["id"].as_str().and_then(|class| values.get(class)) {
+                node[name.as_str()] = json!(value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ClassLink;
+
+    #[test]
+    fn test_node_link_format() {
+        let link = |from: &str, to: &str| ClassLink {
+            from_class: from.to_string(),
+            to_class: to.to_string(),
+            line_number: 1,
+            kind: Default::default(),
+            position: None,
+        };
+        let result = AnalysisResult {
+            classes: ["A", "B", "Lonely"].iter().map(|s| s.to_string()).collect(),
+            links: vec![link("A", "B"), link("A", "B"), link("B", "External")],
+            ..Default::default()
+        };
+
+        let data = to_node_link(&result);
+
+        assert_eq!(data["directed"], true);
+        assert_eq!(data["multigraph"], false);
+        let nodes = data["nodes"].as_array().unwrap();
+        assert_eq!(nodes.len(), 4);
+        let external = nodes.iter().find(|n| n["id"] == "External").unwrap();
+        assert_eq!(external["defined"], false);
+        let lonely = nodes.iter().find(|n| n["id"] == "Lonely").unwrap();
+        assert_eq!(lonely["isolated"], true);
+
+        assert_eq!(
+            data["links"][0],
+            json!({ "source": "A", "target": "B", "weight": 2 })
+        );
+        assert_eq!(data["links"].as_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::file_analyzer::AnalysisResult;
+use crate::manifest::{self, Grouping};
+use crate::parser::ClassLink;
+use crate::project::FileAnalysis;
+
+// Package-level view for exporters: each package (directory or namespace,
+// see `manifest::Grouping`) becomes a node
+// and every class link crossing packages becomes a link between them, so
+// exporters that count repeated pairs label package edges with the number of
+// class links they bundle. Links within a package, and links to classes not
+// defined in the project, are dropped.
+pub fn collapse_to_packages(root: &str, files: &[FileAnalysis], grouping: Grouping) -> AnalysisResult {
+    let packages = manifest::class_groups(root, files, grouping);
+    let links: Vec<ClassLink> = files
+        .iter()
+        .flat_map(|file| &file.result.links)
+        .filter_map(|link| {
+            let from = packages.get(&link.from_class)?;
+            let to = packages.get(&link.to_class)?;
+            (from != to).then(|| ClassLink {
+                from_class: from.clone(),
+                to_class: to.clone(),
+                ..link.clone()
+            })
+        })
+        .collect();
+
+    AnalysisResult {
+        classes: packages.into_values().collect(),
+        links,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::to_node_link;
+    use serde_json::json;
+
+    fn file(path: &str, classes: &[&str], links: &[(&str, &str)]) -> FileAnalysis {
+        FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|s| s.to_string()).collect(),
+                links: links
+                    .iter()
+                    .map(|(from, to)| ClassLink {
+                        from_class: from.to_string(),
+                        to_class: to.to_string(),
+                        line_number: 1,
+                        kind: Default::default(),
+                        position: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_collapse_counts_cross_package_links() {
+        let files = vec![
+            file("/repo/web/api.py", &["Api", "Views"], &[("Api", "Repo"), ("Views", "Repo"), ("Api", "Views")]),
+            file("/repo/web/admin.py", &["Admin"], &[("Admin", "Repo"), ("Admin", "Stripe")]),
+            file("/repo/db/repo.py", &["Repo"], &[]),
+        ];
+
+        let collapsed = collapse_to_packages("/repo", &files, Grouping::Directory);
+
+        assert_eq!(collapsed.classes.len(), 2);
+        assert_eq!(collapsed.links.len(), 3);
+        let data = to_node_link(&collapsed);
+        assert_eq!(data["links"], json!([{ "source": "web", "target": "db", "weight": 3 }]));
+    }
+}
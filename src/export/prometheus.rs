@@ -0,0 +1,128 @@
+use crate::analyzer::LinkStatistics;
+use crate::churn::ChurnHotspot;
+use crate::coupling::{CouplingReport, CouplingScore};
+use crate::findings::{Finding, Severity};
+use std::fmt::Write;
+
+const PREFIX: &str = "classlink";
+
+type Metric = (&'static str, &'static str, fn(&CouplingScore) -> f64);
+
+// Prometheus textfile-collector format, so nightly runs can be scraped by
+// node_exporter and graphed over time
+pub fn to_textfile(
+    stats: &LinkStatistics,
+    cycles: usize,
+    findings: &[Finding],
+    coupling: &CouplingReport,
+    churn_hotspots: &[ChurnHotspot],
+) -> String {
+    let mut out = String::new();
+    let unlabelled = |value: usize| vec![(String::new(), value as f64)];
+    let max_fan_in = stats.class_link_counts.values().map(|count| count.incoming_links).max();
+    let max_fan_out = stats.class_link_counts.values().map(|count| count.outgoing_links).max();
+    gauge(&mut out, "total_classes", "Classes defined in the project", unlabelled(stats.total_classes));
+    gauge(&mut out, "isolated_classes", "Classes with no links", unlabelled(stats.isolated_classes.len()));
+    gauge(&mut out, "cycle_count", "Dependency cycles between classes", unlabelled(cycles));
+    gauge(&mut out, "max_fan_in", "Most incoming links of any class", unlabelled(max_fan_in.unwrap_or(0)));
+    gauge(&mut out, "max_fan_out", "Most outgoing links of any class", unlabelled(max_fan_out.unwrap_or(0)));
+
+    let severities = [(Severity::Error, "error"), (Severity::Warning, "warning"), (Severity::Info, "info")]
+        .into_iter()
+        .map(|(severity, name)| {
+            let count = findings.iter().filter(|finding| finding.severity == severity).count();
+            (format!("severity=\"{}\"", name), count as f64)
+        })
+        .collect();
+    gauge(&mut out, "findings", "Findings by severity, after exceptions", severities);
+
+    let metrics: [Metric; 3] = [
+        ("package_afferent_coupling", "Weighted links into each package", |s| s.afferent),
+        ("package_efferent_coupling", "Weighted links out of each package", |s| s.efferent),
+        ("package_coupling", "Weighted afferent plus efferent coupling of each package", |s| s.total),
+    ];
+    for (name, help, value) in metrics {
+        let samples = coupling
+            .packages
+            .iter()
+            .map(|score| (format!("package=\"{}\"", escape(&score.name)), value(score)))
+            .collect();
+        gauge(&mut out, name, help, samples);
+    }
+    if !churn_hotspots.is_empty() {
+        let samples = churn_hotspots
+            .iter()
+            .map(|hotspot| (format!("class=\"{}\"", escape(&hotspot.class)), hotspot.score))
+            .collect();
+        gauge(&mut out, "churn_hotspot_score", "Commits times weighted coupling of each changed class", samples);
+    }
+    if !stats.custom_metrics.is_empty() {
+        let samples = stats
+            .custom_metrics
+            .iter()
+            .flat_map(|(name, values)| {
+                values
+                    .iter()
+                    .map(move |(class, value)| (format!("metric=\"{}\",class=\"{}\"", escape(name), escape(class)), *value))
+            })
+            .collect();
+        gauge(&mut out, "custom_metric", "Registered metrics per class", samples);
+    }
+    out
+}
+
+// Each sample is (labels, value); empty labels print a bare metric
+fn gauge(out: &mut String, name: &str, help: &str, samples: Vec<(String, f64)>) {
+    writeln!(out, "# HELP {}_{} {}", PREFIX, name, help).unwrap();
+    writeln!(out, "# TYPE {}_{} gauge", PREFIX, name).unwrap();
+    for (labels, value) in samples {
+        if labels.is_empty() {
+            writeln!(out, "{}_{} {}", PREFIX, name, value).unwrap();
+        } else {
+            writeln!(out, "{}_{}{{{}}} {}", PREFIX, name, labels, value).unwrap();
+        }
+    }
+}
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::LinkAnalyzer;
+    use crate::parser::ClassLink;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_textfile_gauges() {
+        let classes: HashSet<String> = ["A", "B", "C"].iter().map(|s| s.to_string()).collect();
+        let links = vec![ClassLink {
+            from_class: "A".to_string(),
+            to_class: "B".to_string(),
+            line_number: 1,
+            kind: Default::default(),
+            position: None,
+        }];
+        let stats = LinkAnalyzer::new().analyze(classes, links);
+        let coupling = CouplingReport {
+            classes: vec![],
+            packages: vec![CouplingScore {
+                name: "web\"app".to_string(),
+                afferent: 0.0,
+                efferent: 2.5,
+                total: 2.5,
+            }],
+        };
+
+        let text = to_textfile(&stats, 2, &[], &coupling, &[]);
+
+        assert!(text.contains("# TYPE classlink_total_classes gauge\nclasslink_total_classes 3\n"));
+        assert!(text.contains("classlink_isolated_classes 1\n"));
+        assert!(text.contains("classlink_cycle_count 2\n"));
+        assert!(text.contains("classlink_max_fan_in 1\n"));
+        assert!(text.contains("classlink_findings{severity=\"error\"} 0\n"));
+        assert!(text.contains("classlink_package_coupling{package=\"web\\\"app\"} 2.5\n"));
+    }
+}
@@ -0,0 +1,206 @@
+use crate::annotations::ClassTag;
+use crate::file_analyzer::{FileType, UnifiedAnalyzer};
+use crate::i18n::{Catalog, Message};
+use crate::imports;
+use crate::manifest::{package_of, relative_path};
+use crate::project::FileAnalysis;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write;
+
+// A class the project uses but does not define: from an installed package
+// (node_modules, site-packages, NuGet) or, with `follow_imports`, from a
+// file outside the root
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExternalNode {
+    pub name: String,
+    // The package it is imported from, or the file outside the root;
+    // unknown for C# and for names used without an import
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    // Project classes depending on it
+    pub dependents: usize,
+    pub links: usize,
+}
+
+// External classes one project class or package depends on, with the
+// number of links to each
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExternalFanOut {
+    pub name: String,
+    pub fan_out: usize,
+    pub targets: BTreeMap<String, usize>,
+}
+
+// Most used nodes and highest fan-out first
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ExternalNodeReport {
+    pub nodes: Vec<ExternalNode>,
+    pub classes: Vec<ExternalFanOut>,
+    pub packages: Vec<ExternalFanOut>,
+}
+
+fn ranked(fan_outs: BTreeMap<String, BTreeMap<String, usize>>) -> Vec<ExternalFanOut> {
+    let mut ranked: Vec<ExternalFanOut> = fan_outs
+        .into_iter()
+        .map(|(name, targets)| ExternalFanOut {
+            name,
+            fan_out: targets.len(),
+            targets,
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.fan_out.cmp(&a.fan_out).then_with(|| a.name.cmp(&b.name)));
+    ranked
+}
+
+// Moves links to external classes out of the graph, so the internal
+// statistics only count project classes. A class a code file links to
+// without the project defining it is external, and so is a class tagged
+// `external`, whose file is dropped. Markdown links document rather than
+// depend, so they stay. `sources` holds the contents of the leading files,
+// for looking up the package each name is imported from.
+pub fn split_external_nodes(root: &str, files: Vec<FileAnalysis>, sources: &[String]) -> (Vec<FileAnalysis>, ExternalNodeReport) {
+    let mut origins: BTreeMap<String, String> = BTreeMap::new();
+    for file in &files {
+        for (class, tags) in &file.result.class_tags {
+            if tags.contains(&ClassTag::External) {
+                origins.insert(class.clone(), file.path.clone());
+            }
+        }
+    }
+    let defined: HashSet<String> = files
+        .iter()
+        .flat_map(|file| &file.result.classes)
+        .filter(|class| !origins.contains_key(*class))
+        .cloned()
+        .collect();
+
+    let mut nodes: BTreeMap<String, (Option<String>, BTreeSet<String>, usize)> = BTreeMap::new();
+    let mut classes: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+    let mut packages: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+    let mut kept = Vec::new();
+    for (index, mut file) in files.into_iter().enumerate() {
+        let outside = !file.result.classes.is_empty() && file.result.classes.iter().all(|class| origins.contains_key(class));
+        if outside {
+            continue;
+        }
+        if UnifiedAnalyzer::detect_file_type(&file.path) == FileType::Markdown {
+            kept.push(file);
+            continue;
+        }
+        let imported = sources
+            .get(index)
+            .map(|content| imports::package_imports(&file.path, content))
+            .unwrap_or_default();
+        let package = package_of(&relative_path(root, &file.path));
+        file.result.links.retain(|link| {
+            if defined.contains(&link.to_class) {
+                return true;
+            }
+            let node = nodes.entry(link.to_class.clone()).or_default();
+            if node.0.is_none() {
+                node.0 = imported.get(&link.to_class).or(origins.get(&link.to_class)).cloned();
+            }
+            node.1.insert(link.from_class.clone());
+            node.2 += 1;
+            *classes.entry(link.from_class.clone()).or_default().entry(link.to_class.clone()).or_default() += 1;
+            *packages.entry(package.clone()).or_default().entry(link.to_class.clone()).or_default() += 1;
+            false
+        });
+        kept.push(file);
+    }
+
+    let mut nodes: Vec<ExternalNode> = nodes
+        .into_iter()
+        .map(|(name, (source, dependents, links))| ExternalNode {
+            name,
+            source,
+            dependents: dependents.len(),
+            links,
+        })
+        .collect();
+    nodes.sort_by(|a, b| (b.dependents, b.links).cmp(&(a.dependents, a.links)).then_with(|| a.name.cmp(&b.name)));
+    let report = ExternalNodeReport {
+        nodes,
+        classes: ranked(classes),
+        packages: ranked(packages),
+    };
+    (kept, report)
+}
+
+const EXTERNAL_NODES_TOP: usize = 10;
+
+pub fn render_external_nodes(report: &ExternalNodeReport, catalog: &Catalog) -> String {
+    let mut out = String::new();
+    if report.nodes.is_empty() {
+        return out;
+    }
+    writeln!(out, "\n{}", catalog.text(Message::ExternalNodesHeader, &[&report.nodes.len(), &EXTERNAL_NODES_TOP])).unwrap();
+    for node in report.nodes.iter().take(EXTERNAL_NODES_TOP) {
+        let line = match &node.source {
+            Some(source) => catalog.text(Message::ExternalNodeLine, &[&node.name, source, &node.dependents, &node.links]),
+            None => catalog.text(Message::ExternalNodeUnknownLine, &[&node.name, &node.dependents, &node.links]),
+        };
+        writeln!(out, "{}", line).unwrap();
+    }
+    let sections = [
+        (Message::ExternalFanOutHeader, &report.classes),
+        (Message::ExternalPackageFanOutHeader, &report.packages),
+    ];
+    for (header, fan_outs) in sections {
+        writeln!(out, "\n{}", catalog.text(header, &[&EXTERNAL_NODES_TOP])).unwrap();
+        for fan_out in fan_outs.iter().take(EXTERNAL_NODES_TOP) {
+            let targets: Vec<&str> = fan_out.targets.keys().map(String::as_str).collect();
+            let line = catalog.text(Message::ExternalFanOutLine, &[&fan_out.name, &fan_out.fan_out, &targets.join(", ")]);
+            writeln!(out, "{}", line).unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(all(test, feature = "python", feature = "typescript"))]
+mod tests {
+    use super::*;
+    use crate::project::ProjectAnalyzer;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_external_classes_are_split_from_the_graph() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join("web")).unwrap();
+        fs::write(
+            temp_dir.path().join("shop.py"),
+            "from requests import Session\nfrom .cart import Cart\n\nclass Shop:\n    def __init__(self, http: Session):\n        self.cart = Cart()\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("cart.py"), "class Cart:\n    def load(self, http: Session):\n        pass\n").unwrap();
+        fs::write(
+            temp_dir.path().join("web/view.ts"),
+            "import { Store } from '@ngrx/store';\nexport class View {\n  private store: Store;\n}\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("Shop.md"), "# Shop\n\nSee [Session](Session.md).\n").unwrap();
+        let root = temp_dir.path().to_string_lossy().to_string();
+        let scan = ProjectAnalyzer::new().recursive(true).external_nodes(true).scan(&root).unwrap();
+
+        let links: Vec<(&str, &str)> = scan
+            .files
+            .iter()
+            .flat_map(|file| &file.result.links)
+            .map(|link| (link.from_class.as_str(), link.to_class.as_str()))
+            .collect();
+        assert_eq!(links, vec![("Shop", "Session"), ("Shop", "Cart")]);
+        let report = scan.external_nodes.unwrap();
+        let nodes: Vec<(&str, Option<&str>, usize)> = report
+            .nodes
+            .iter()
+            .map(|node| (node.name.as_str(), node.source.as_deref(), node.dependents))
+            .collect();
+        // `cart.py` uses `Session` without importing it
+        assert_eq!(nodes, vec![("Session", Some("requests"), 2), ("Store", Some("@ngrx/store"), 1)]);
+        assert_eq!(report.packages.iter().map(|p| (p.name.as_str(), p.fan_out)).collect::<Vec<_>>(), vec![(".", 1), ("web", 1)]);
+        let text = render_external_nodes(&report, &Catalog::default());
+        assert!(text.contains("  Session (requests): 2 dependents, 2 links"));
+    }
+}
@@ -0,0 +1,344 @@
+use crate::file_analyzer::{FileType, UnifiedAnalyzer};
+use crate::manifest::{package_of, relative_path};
+use crate::project::FileAnalysis;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+// Standard-library and framework types the regex parsers pick up as if they
+// were project classes. Only names starting with an uppercase letter can be
+// matched, so lowercase builtins are left out.
+pub const PYTHON: &[&str] = &[
+    "Any", "AsyncIterator", "Awaitable", "Callable", "ClassVar", "Coroutine", "Counter", "Decimal", "DefaultDict",
+    "Dict", "Enum", "Exception", "Final", "FrozenSet", "Generator", "Generic", "IntEnum", "Iterable", "Iterator",
+    "KeyError", "List", "Literal", "Logger", "Mapping", "MutableMapping", "NamedTuple", "NoReturn", "None",
+    "NotImplementedError", "Optional", "OrderedDict", "Path", "Pattern", "Protocol", "RuntimeError", "Self",
+    "Sequence", "Set", "Thread", "Tuple", "Type", "TypeError", "TypeVar", "TypedDict", "UUID", "Union",
+    "ValueError",
+];
+
+pub const TYPESCRIPT: &[&str] = &[
+    "Array", "ArrayBuffer", "BehaviorSubject", "Boolean", "Date", "Error", "Event", "EventEmitter", "Function",
+    "HTMLElement", "HttpClient", "HttpHeaders", "HttpParams", "Map", "Number", "Object", "Observable", "Omit",
+    "Partial", "Pick", "Promise", "ReadonlyArray", "Readonly", "Record", "RegExp", "Required", "ReturnType",
+    "Set", "String", "Subject", "Subscription", "Symbol", "Uint8Array", "WeakMap", "WeakSet",
+];
+
+pub const CSHARP: &[&str] = &[
+    "Action", "ArgumentException", "ArgumentNullException", "CancellationToken", "Console", "DateTime",
+    "DateTimeOffset", "Dictionary", "Exception", "Func", "Guid", "HashSet", "HttpClient", "ICollection",
+    "IConfiguration", "IDictionary", "IDisposable", "IEnumerable", "IList", "ILogger", "IQueryable",
+    "IReadOnlyCollection", "IReadOnlyList", "IServiceProvider", "Int32", "Int64", "InvalidOperationException",
+    "Lazy", "List", "Math", "Nullable", "Object", "Stream", "String", "StringBuilder", "Task", "TimeSpan", "Type",
+    "ValueTask",
+];
+
+pub const RUST: &[&str] = &[
+    "Arc", "AsRef", "BTreeMap", "BTreeSet", "Box", "Cell", "Clone", "Cow", "Debug", "Default", "Deref",
+    "Deserialize", "Display", "Drop", "Duration", "Eq", "Error", "File", "Formatter", "From", "FromStr", "Hash", "HashMap",
+    "HashSet", "Instant", "Into", "IntoIterator", "Iterator", "Mutex", "Option", "Ord", "Ordering", "PartialEq", "PartialOrd",
+    "Path", "PathBuf", "PhantomData", "Rc", "RefCell", "Result", "RwLock", "Send", "Serialize", "Sized", "String",
+    "Sync", "TryFrom", "Vec", "VecDeque",
+];
+
+pub const JAVA: &[&str] = &[
+    "ArrayList", "Arrays", "BigDecimal", "BigInteger", "Boolean", "Collection", "Collections", "Comparable",
+    "Comparator", "CompletableFuture", "Consumer", "Double", "Duration", "Exception", "Function", "HashMap", "HashSet",
+    "IllegalArgumentException", "IllegalStateException", "Instant", "Integer", "Iterable", "Iterator", "LinkedList",
+    "List", "LocalDate", "LocalDateTime", "Logger", "Long", "Map", "Object", "Objects", "Optional", "Predicate",
+    "Runnable", "RuntimeException", "Serializable", "Set", "Stream", "String", "StringBuilder", "Supplier", "Thread",
+    "Throwable", "TreeMap", "UUID", "Void",
+];
+
+pub const JAVASCRIPT: &[&str] = &[
+    "AbortController", "Array", "ArrayBuffer", "Blob", "Boolean", "Buffer", "Date", "Error", "EventEmitter",
+    "FormData", "Function", "Headers", "Intl", "JSON", "Map", "Math", "Number", "Object", "Promise", "Proxy",
+    "RangeError", "Reflect", "RegExp", "Request", "Response", "Set", "String", "Symbol", "TypeError", "URL",
+    "URLSearchParams", "WeakMap", "WeakSet",
+];
+
+pub const GO: &[&str] = &[
+    "Buffer", "Builder", "Context", "DB", "Decoder", "Duration", "Encoder", "File", "Group", "Handler", "Logger",
+    "Mutex", "Once", "Pool", "Reader", "Request", "ResponseWriter", "RWMutex", "Server", "Ticker", "Time", "Timer",
+    "Tx", "URL", "WaitGroup", "Writer",
+];
+
+// Framework base classes and services, by the language that uses them.
+// Depending on these is vendor lock-in rather than project coupling.
+pub const FRAMEWORKS: &[(FileType, &str, &[&str])] = &[
+    (
+        FileType::Python,
+        "django",
+        &[
+            "APIView", "AppConfig", "BaseCommand", "CharField", "ForeignKey", "Form", "GenericAPIView",
+            "HttpRequest", "HttpResponse", "JsonResponse", "ListView", "Model", "ModelAdmin", "ModelForm",
+            "ModelSerializer", "ModelViewSet", "QuerySet", "Serializer", "TemplateView", "View", "ViewSet",
+        ],
+    ),
+    (
+        FileType::TypeScript,
+        "angular",
+        &[
+            "ActivatedRoute", "AfterViewInit", "ChangeDetectorRef", "ElementRef", "EventEmitter", "FormBuilder",
+            "FormControl", "FormGroup", "HttpClient", "HttpInterceptor", "NgModule", "NgZone", "OnChanges",
+            "OnDestroy", "OnInit", "Renderer2", "Router", "TemplateRef", "ViewContainerRef",
+        ],
+    ),
+    (
+        FileType::CSharp,
+        "aspnet",
+        &[
+            "ActionResult", "ApiController", "Controller", "ControllerBase", "DbContext", "DbSet", "HttpContext",
+            "IActionResult", "IApplicationBuilder", "IConfiguration", "IHostEnvironment", "ILogger", "IMiddleware",
+            "IServiceCollection", "ModelBuilder", "PageModel", "RequestDelegate",
+        ],
+    ),
+];
+
+// Framework links of one class or package, counted per occurrence
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExternalCoupling {
+    pub name: String,
+    pub total: usize,
+    pub frameworks: BTreeMap<String, usize>,
+}
+
+// Most framework-dependent classes and packages first
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ExternalCouplingReport {
+    pub classes: Vec<ExternalCoupling>,
+    pub packages: Vec<ExternalCoupling>,
+}
+
+fn ranked(counts: BTreeMap<String, BTreeMap<String, usize>>) -> Vec<ExternalCoupling> {
+    let mut ranked: Vec<ExternalCoupling> = counts
+        .into_iter()
+        .map(|(name, frameworks)| ExternalCoupling {
+            name,
+            total: frameworks.values().sum(),
+            frameworks,
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+    ranked
+}
+
+// One language's list: the built-in names, adjusted from the config file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TypeList {
+    // Off starts from an empty list, so only `add` applies
+    pub builtin: bool,
+    pub add: Vec<String>,
+    // Built-in names that are real project concepts here
+    pub remove: Vec<String>,
+}
+
+impl Default for TypeList {
+    fn default() -> Self {
+        Self {
+            builtin: true,
+            add: Vec::new(),
+            remove: Vec::new(),
+        }
+    }
+}
+
+impl TypeList {
+    fn names(&self, builtin: &[&str]) -> HashSet<String> {
+        let builtin = builtin.iter().filter(|_| self.builtin).map(|name| name.to_string());
+        let mut names: HashSet<String> = builtin.chain(self.add.iter().cloned()).collect();
+        for name in &self.remove {
+            names.remove(name);
+        }
+        names
+    }
+}
+
+// Links to these types are dropped unless the project defines a class of
+// the same name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExternalTypes {
+    pub python: TypeList,
+    pub typescript: TypeList,
+    pub javascript: TypeList,
+    pub csharp: TypeList,
+    pub rust: TypeList,
+    pub java: TypeList,
+    pub go: TypeList,
+    // More framework types for the external coupling report, by framework
+    // name; they apply to every language
+    pub frameworks: BTreeMap<String, Vec<String>>,
+}
+
+impl ExternalTypes {
+    pub fn names(&self, file_type: &FileType) -> HashSet<String> {
+        match file_type {
+            FileType::Python => self.python.names(PYTHON),
+            FileType::TypeScript => self.typescript.names(TYPESCRIPT),
+            FileType::JavaScript => self.javascript.names(JAVASCRIPT),
+            FileType::CSharp => self.csharp.names(CSHARP),
+            FileType::Rust => self.rust.names(RUST),
+            FileType::Java => self.java.names(JAVA),
+            FileType::Go => self.go.names(GO),
+            FileType::Markdown | FileType::Template | FileType::Unknown => HashSet::new(),
+        }
+    }
+
+    // Targets `apply` drops from a file of this language, and with
+    // `frameworks` also `split_frameworks`, unless the project defines them
+    pub fn droppable(&self, file_type: &FileType, frameworks: bool) -> HashSet<String> {
+        let mut names = self.names(file_type);
+        if frameworks {
+            names.extend(self.framework_types(file_type).into_keys().map(str::to_string));
+        }
+        names
+    }
+
+    // Framework of each framework type a file of this language can use
+    fn framework_types(&self, file_type: &FileType) -> HashMap<&str, &str> {
+        let builtin = FRAMEWORKS
+            .iter()
+            .filter(|(language, _, _)| language == file_type)
+            .flat_map(|(_, framework, types)| types.iter().map(move |name| (*name, *framework)));
+        let configured = self
+            .frameworks
+            .iter()
+            .flat_map(|(framework, types)| types.iter().map(move |name| (name.as_str(), framework.as_str())));
+        builtin.chain(configured).collect()
+    }
+
+    // Moves links to framework types out of the graph and into a report of
+    // who depends on which framework. Classes the project defines stay
+    // linked, as in `apply`.
+    pub fn split_frameworks(&self, root: &str, files: Vec<FileAnalysis>) -> (Vec<FileAnalysis>, ExternalCouplingReport) {
+        let defined: HashSet<String> = files.iter().flat_map(|f| f.result.classes.iter().cloned()).collect();
+        let mut classes: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+        let mut packages: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+        let files = files
+            .into_iter()
+            .map(|mut file| {
+                let types = self.framework_types(&UnifiedAnalyzer::detect_file_type(&file.path));
+                let package = package_of(&relative_path(root, &file.path));
+                file.result.links.retain(|link| {
+                    let framework = types.get(link.to_class.as_str()).filter(|_| !defined.contains(&link.to_class));
+                    let Some(framework) = framework else {
+                        return true;
+                    };
+                    *classes.entry(link.from_class.clone()).or_default().entry(framework.to_string()).or_default() += 1;
+                    *packages.entry(package.clone()).or_default().entry(framework.to_string()).or_default() += 1;
+                    false
+                });
+                file
+            })
+            .collect();
+        let report = ExternalCouplingReport {
+            classes: ranked(classes),
+            packages: ranked(packages),
+        };
+        (files, report)
+    }
+
+    pub fn apply(&self, files: Vec<FileAnalysis>) -> Vec<FileAnalysis> {
+        let defined: HashSet<String> = files.iter().flat_map(|f| f.result.classes.iter().cloned()).collect();
+        let lists = [FileType::Python, FileType::TypeScript, FileType::JavaScript, FileType::CSharp, FileType::Rust, FileType::Java, FileType::Go].map(|file_type| {
+            let mut names = self.names(&file_type);
+            names.retain(|name| !defined.contains(name));
+            (file_type, names)
+        });
+        files
+            .into_iter()
+            .map(|mut file| {
+                let file_type = UnifiedAnalyzer::detect_file_type(&file.path);
+                if let Some((_, external)) = lists.iter().find(|(list_type, _)| *list_type == file_type) {
+                    file.result.links.retain(|link| !external.contains(&link.to_class));
+                }
+                file
+            })
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "python", feature = "csharp"))]
+mod tests {
+    use super::*;
+    use crate::file_analyzer::AnalysisResult;
+    use crate::parser::ClassLink;
+
+    fn file(path: &str, classes: &[&str], targets: &[&str]) -> FileAnalysis {
+        FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|s| s.to_string()).collect(),
+                links: targets
+                    .iter()
+                    .map(|to| ClassLink {
+                        from_class: classes[0].to_string(),
+                        to_class: to.to_string(),
+                        line_number: 1,
+                        kind: Default::default(),
+                        position: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        }
+    }
+
+    fn targets(files: &[FileAnalysis]) -> Vec<&str> {
+        files.iter().flat_map(|f| &f.result.links).map(|l| l.to_class.as_str()).collect()
+    }
+
+    #[test]
+    #[cfg(feature = "typescript")]
+    fn test_builtin_types_are_dropped_per_language() {
+        let files = vec![
+            file("a.py", &["Service"], &["Optional", "Repo", "Task"]),
+            file("b.cs", &["Job"], &["Task", "ILogger", "Repo"]),
+            file("c.ts", &["Repo"], &["Observable"]),
+        ];
+
+        let kept = ExternalTypes::default().apply(files.clone());
+        assert_eq!(targets(&kept), vec!["Repo", "Task", "Repo"]);
+
+        let config = ExternalTypes {
+            csharp: TypeList {
+                add: vec!["Repo".to_string()],
+                remove: vec!["Task".to_string()],
+                ..Default::default()
+            },
+            typescript: TypeList {
+                builtin: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        // `Repo` is defined by the project, so it stays even when listed
+        assert_eq!(targets(&config.apply(files)), vec!["Repo", "Task", "Task", "Repo", "Observable"]);
+    }
+
+    #[test]
+    fn test_framework_links_are_reported_separately() {
+        let files = vec![
+            file("/repo/shop/views.py", &["CartView"], &["View", "HttpResponse", "Cart", "Optional"]),
+            file("/repo/shop/models.py", &["Cart"], &["Model"]),
+            file("/repo/api/Orders.cs", &["OrdersController"], &["ControllerBase", "ILogger", "Model"]),
+        ];
+        let external = ExternalTypes {
+            frameworks: BTreeMap::from([("mediatr".to_string(), vec!["IMediator".to_string()])]),
+            ..Default::default()
+        };
+
+        let (kept, report) = external.split_frameworks("/repo", files);
+
+        assert_eq!(targets(&kept), vec!["Cart", "Optional", "Model"]);
+        assert_eq!(report.classes[0].name, "CartView");
+        assert_eq!(report.classes[0].frameworks, BTreeMap::from([("django".to_string(), 2)]));
+        assert_eq!(report.classes[1].name, "OrdersController");
+        assert_eq!(report.classes[1].frameworks["aspnet"], 2);
+        let shop = report.packages.iter().find(|p| p.name == "shop").unwrap();
+        assert_eq!(shop.total, 3);
+        assert!(external.framework_types(&FileType::Python).contains_key("IMediator"));
+    }
+}
@@ -0,0 +1,214 @@
+use crate::i18n::{Catalog, Message};
+use crate::project::FileAnalysis;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+// Joins a repository name and a class name, e.g. `billing::User`
+pub const QUALIFIER: &str = "::";
+
+// One separately analyzed repository or service taking part in a merge
+#[derive(Debug, Clone)]
+pub struct Repository {
+    pub name: String,
+    pub files: Vec<FileAnalysis>,
+}
+
+// A link from one repository that only resolves to a class defined in
+// another
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct CrossRepoLink {
+    pub from_repo: String,
+    pub to_repo: String,
+    pub from: String,
+    pub to: String,
+    pub occurrences: usize,
+}
+
+// Coupling across one service boundary, in one direction
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RepoCoupling {
+    pub from: String,
+    pub to: String,
+    // Distinct class pairs linked across the boundary
+    pub links: usize,
+    pub occurrences: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CrossRepoReport {
+    pub links: Vec<CrossRepoLink>,
+    // Most coupled boundaries first
+    pub boundaries: Vec<RepoCoupling>,
+    // Unresolved targets that several other repositories define
+    pub ambiguous: Vec<String>,
+}
+
+// Resolves each repository's references against its own classes first and
+// then against the classes of the others. Classes defined in several
+// repositories are qualified with the repository name, so two unrelated
+// `User` classes stay apart; names defined once keep their plain name.
+// Targets defined in no repository are left as they are.
+pub fn stitch(repositories: Vec<Repository>) -> (Vec<FileAnalysis>, CrossRepoReport) {
+    let mut defined: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for repository in &repositories {
+        for file in &repository.files {
+            for class in &file.result.classes {
+                defined.entry(class.clone()).or_default().insert(repository.name.clone());
+            }
+        }
+    }
+    let qualify = |repository: &str, class: &str| {
+        if defined.get(class).is_some_and(|repos| repos.len() > 1 && repos.contains(repository)) {
+            format!("{}{}{}", repository, QUALIFIER, class)
+        } else {
+            class.to_string()
+        }
+    };
+
+    let mut crossings: BTreeMap<(String, String, String, String), usize> = BTreeMap::new();
+    let mut ambiguous = BTreeSet::new();
+    let mut files = Vec::new();
+    for repository in repositories {
+        let own = repository.name.as_str();
+        for mut file in repository.files {
+            let result = &mut file.result;
+            result.classes = result.classes.iter().map(|class| qualify(own, class)).collect();
+            result.class_metrics = result.class_metrics.drain().map(|(class, size)| (qualify(own, &class), size)).collect();
+            result.class_tags = result.class_tags.drain().map(|(class, tags)| (qualify(own, &class), tags)).collect();
+            result.dependency_budgets =
+                result.dependency_budgets.drain().map(|(class, max)| (qualify(own, &class), max)).collect();
+            result.namespaces = result.namespaces.drain().map(|(class, ns)| (qualify(own, &class), ns)).collect();
+            for link in &mut result.links {
+                let from = qualify(own, &link.from_class);
+                let target = link.to_class.clone();
+                let owners = defined.get(&target);
+                if owners.is_some_and(|repos| repos.contains(own)) {
+                    link.to_class = qualify(own, &target);
+                } else if let Some(repos) = owners {
+                    if repos.len() > 1 {
+                        ambiguous.insert(target);
+                    } else {
+                        let other = repos.iter().next().unwrap();
+                        link.to_class = qualify(other, &target);
+                        let key = (own.to_string(), other.clone(), from.clone(), link.to_class.clone());
+                        *crossings.entry(key).or_default() += 1;
+                    }
+                }
+                link.from_class = from;
+            }
+            files.push(file);
+        }
+    }
+
+    let links: Vec<CrossRepoLink> = crossings
+        .into_iter()
+        .map(|((from_repo, to_repo, from, to), occurrences)| CrossRepoLink {
+            from_repo,
+            to_repo,
+            from,
+            to,
+            occurrences,
+        })
+        .collect();
+    let mut boundaries: BTreeMap<(&str, &str), RepoCoupling> = BTreeMap::new();
+    for link in &links {
+        let boundary = boundaries
+            .entry((&link.from_repo, &link.to_repo))
+            .or_insert_with(|| RepoCoupling {
+                from: link.from_repo.clone(),
+                to: link.to_repo.clone(),
+                links: 0,
+                occurrences: 0,
+            });
+        boundary.links += 1;
+        boundary.occurrences += link.occurrences;
+    }
+    let mut boundaries: Vec<RepoCoupling> = boundaries.into_values().collect();
+    boundaries.sort_by(|a, b| b.occurrences.cmp(&a.occurrences).then_with(|| (&a.from, &a.to).cmp(&(&b.from, &b.to))));
+
+    let report = CrossRepoReport {
+        links,
+        boundaries,
+        ambiguous: ambiguous.into_iter().collect(),
+    };
+    (files, report)
+}
+
+pub fn render_cross_repo(report: &CrossRepoReport, catalog: &Catalog) -> String {
+    let mut out = String::new();
+    if !report.boundaries.is_empty() {
+        writeln!(out, "\n{}", catalog.text(Message::CrossRepoHeader, &[])).unwrap();
+        for boundary in &report.boundaries {
+            let pairs: Vec<String> = report
+                .links
+                .iter()
+                .filter(|link| link.from_repo == boundary.from && link.to_repo == boundary.to)
+                .map(|link| format!("{} -> {}", link.from, link.to))
+                .collect();
+            let line = catalog.text(
+                Message::CrossRepoLine,
+                &[&boundary.from, &boundary.to, &boundary.occurrences, &pairs.join(", ")],
+            );
+            writeln!(out, "{}", line).unwrap();
+        }
+    }
+    if !report.ambiguous.is_empty() {
+        writeln!(out, "\n{}", catalog.text(Message::CrossRepoAmbiguous, &[&report.ambiguous.join(", ")])).unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_analyzer::AnalysisResult;
+    use crate::parser::ClassLink;
+
+    fn repository(name: &str, classes: &[&str], links: &[(&str, &str)]) -> Repository {
+        Repository {
+            name: name.to_string(),
+            files: vec![FileAnalysis {
+                path: format!("{}/src/app.py", name),
+                result: AnalysisResult {
+                    classes: classes.iter().map(|s| s.to_string()).collect(),
+                    links: links
+                        .iter()
+                        .map(|(from, to)| ClassLink {
+                            from_class: from.to_string(),
+                            to_class: to.to_string(),
+                            line_number: 1,
+                            kind: Default::default(),
+                            position: None,
+                        })
+                        .collect(),
+                    ..Default::default()
+                },
+                encoding: Default::default(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_stitch_resolves_across_repositories() {
+        let repositories = vec![
+            repository("billing", &["Invoice", "User"], &[("Invoice", "User")]),
+            repository("shop", &["Cart", "User"], &[("Cart", "Invoice"), ("Cart", "Invoice"), ("Cart", "User")]),
+            repository("admin", &["Panel"], &[("Panel", "User"), ("Panel", "Logger")]),
+        ];
+
+        let (files, report) = stitch(repositories);
+
+        let targets = |index: usize| -> Vec<&str> { files[index].result.links.iter().map(|l| l.to_class.as_str()).collect() };
+        assert_eq!(targets(0), vec!["billing::User"]);
+        assert_eq!(targets(1), vec!["Invoice", "Invoice", "shop::User"]);
+        assert_eq!(targets(2), vec!["User", "Logger"]);
+        assert!(files[1].result.classes.contains("shop::User"));
+        assert_eq!(report.links.len(), 1);
+        assert_eq!(report.links[0].from_repo, "shop");
+        assert_eq!(report.links[0].to, "Invoice");
+        assert_eq!(report.links[0].occurrences, 2);
+        assert_eq!(report.boundaries[0].to, "billing");
+        assert_eq!(report.ambiguous, vec!["User"]);
+    }
+}
@@ -1,9 +1,29 @@
 use std::path::Path;
-use crate::parser::{ClassLink, MarkdownParser};
-use crate::python_parser::PythonParser;
-use crate::typescript_parser::TypeScriptParser;
-use crate::csharp_parser::CSharpParser;
-use std::collections::HashSet;
+use crate::parser::{MarkdownParser, MarkdownParserOptions};
+#[cfg(feature = "python")]
+use crate::python_parser::{PythonParser, PythonParserOptions};
+#[cfg(feature = "typescript")]
+use crate::typescript_parser::{TypeScriptParser, TypeScriptParserOptions};
+#[cfg(feature = "typescript")]
+use crate::template_parser::{TemplateParser, TemplateParserOptions};
+#[cfg(feature = "csharp")]
+use crate::csharp_parser::{CSharpParser, CSharpParserOptions};
+#[cfg(feature = "rust")]
+use crate::rust_parser::{RustParser, RustParserOptions};
+#[cfg(feature = "java")]
+use crate::java_parser::{JavaParser, JavaParserOptions};
+#[cfg(feature = "go")]
+use crate::go_parser::{GoParser, GoParserOptions};
+#[cfg(feature = "javascript")]
+use crate::javascript_parser::{JavaScriptParser, JavaScriptParserOptions};
+use crate::custom_analyzer::CustomAnalyzer;
+use crate::encoding;
+use crate::position::{self, ColumnEncoding};
+use crate::diagnostics::ParserFallback;
+use crate::registry::ParserRegistry;
+use serde::{Deserialize, Serialize};
+
+pub use classlink_core::analysis::{AnalysisResult, FileAnalyzer};
 
 #[derive(Debug, PartialEq)]
 pub enum FileType {
@@ -11,72 +31,257 @@ pub enum FileType {
     Python,
     TypeScript,
     CSharp,
+    Rust,
+    Java,
+    Go,
+    JavaScript,
+    // HTML component templates
+    Template,
     Unknown,
 }
 
-pub trait FileAnalyzer {
-    fn analyze(&self, content: &str, file_path: &str) -> AnalysisResult;
+// What each built-in parser counts as a link; the defaults match the
+// parsers' standalone behaviour. Options of parsers left out of the build
+// are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParserOptions {
+    #[cfg(feature = "python")]
+    pub python: PythonParserOptions,
+    #[cfg(feature = "typescript")]
+    pub typescript: TypeScriptParserOptions,
+    #[cfg(feature = "csharp")]
+    pub csharp: CSharpParserOptions,
+    #[cfg(feature = "rust")]
+    pub rust: RustParserOptions,
+    #[cfg(feature = "java")]
+    pub java: JavaParserOptions,
+    #[cfg(feature = "go")]
+    pub go: GoParserOptions,
+    #[cfg(feature = "javascript")]
+    pub javascript: JavaScriptParserOptions,
+    pub markdown: MarkdownParserOptions,
+    #[cfg(feature = "typescript")]
+    pub templates: TemplateParserOptions,
 }
 
-#[derive(Debug)]
-pub struct AnalysisResult {
-    pub classes: HashSet<String>,
-    pub links: Vec<ClassLink>,
+pub struct UnifiedAnalyzer {
+    custom: Vec<CustomAnalyzer>,
+    registry: ParserRegistry,
+    options: ParserOptions,
+    column_encoding: ColumnEncoding,
 }
 
-pub struct UnifiedAnalyzer;
+impl Default for UnifiedAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl UnifiedAnalyzer {
     pub fn new() -> Self {
-        Self
+        Self {
+            custom: Vec::new(),
+            registry: ParserRegistry::new(),
+            options: ParserOptions::default(),
+            column_encoding: ColumnEncoding::default(),
+        }
+    }
+
+    // Custom analyzers are tried first, in order, so they can also take over
+    // files a built-in parser would handle
+    pub fn with_custom_analyzers(mut self, analyzers: Vec<CustomAnalyzer>) -> Self {
+        self.custom = analyzers;
+        self
+    }
+
+    // Parser plugins come after custom analyzers and before the built-in
+    // parsers
+    pub fn with_registry(mut self, registry: ParserRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    pub fn with_parser_options(mut self, options: ParserOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_column_encoding(mut self, column_encoding: ColumnEncoding) -> Self {
+        self.column_encoding = column_encoding;
+        self
     }
 
     pub fn detect_file_type(path: &str) -> FileType {
         let path = Path::new(path);
         match path.extension().and_then(|ext| ext.to_str()) {
             Some("md") => FileType::Markdown,
-            Some("py") => FileType::Python,
-            Some("ts") | Some("tsx") => FileType::TypeScript,
-            Some("cs") => FileType::CSharp,
+            // Jupyter notebooks are analyzed as the Python of their code cells
+            Some("py") | Some("ipynb") if cfg!(feature = "python") => FileType::Python,
+            Some("js") | Some("jsx") | Some("mjs") if cfg!(feature = "javascript") => FileType::JavaScript,
+            // Without the JavaScript parser JSX goes to the TypeScript one
+            Some("ts") | Some("tsx") | Some("jsx") if cfg!(feature = "typescript") => FileType::TypeScript,
+            Some("cs") if cfg!(feature = "csharp") => FileType::CSharp,
+            Some("rs") if cfg!(feature = "rust") => FileType::Rust,
+            Some("java") if cfg!(feature = "java") => FileType::Java,
+            Some("go") if cfg!(feature = "go") => FileType::Go,
+            Some("html") if cfg!(feature = "typescript") => FileType::Template,
             _ => FileType::Unknown,
         }
     }
 
+    // What decides a built-in parser's output for the file besides its
+    // content: the parser, its crate's version and its options, and the
+    // column encoding. None for files custom analyzers or registered parsers
+    // take, which carry no version.
+    pub fn parser_key(&self, path: &str) -> Option<String> {
+        if self.custom.iter().any(|custom| custom.handles(path)) || self.registry.find(path).is_some() {
+            return None;
+        }
+        let (parser, version) = match Self::detect_file_type(path) {
+            FileType::Markdown => ("markdown", classlink_parser_markdown::VERSION),
+            #[cfg(feature = "python")]
+            FileType::Python => ("python", crate::python_parser::VERSION),
+            #[cfg(feature = "typescript")]
+            FileType::TypeScript => ("typescript", crate::typescript_parser::VERSION),
+            #[cfg(feature = "typescript")]
+            FileType::Template => ("templates", crate::typescript_parser::VERSION),
+            #[cfg(feature = "csharp")]
+            FileType::CSharp => ("csharp", crate::csharp_parser::VERSION),
+            #[cfg(feature = "rust")]
+            FileType::Rust => ("rust", crate::rust_parser::VERSION),
+            #[cfg(feature = "java")]
+            FileType::Java => ("java", crate::java_parser::VERSION),
+            #[cfg(feature = "go")]
+            FileType::Go => ("go", crate::go_parser::VERSION),
+            #[cfg(feature = "javascript")]
+            FileType::JavaScript => ("javascript", crate::javascript_parser::VERSION),
+            _ => return None,
+        };
+        let options = serde_json::to_value(&self.options).ok()?;
+        let options = options.get(parser).cloned().unwrap_or_default();
+        Some(format!("{} {} {} {:?}", parser, version, options, self.column_encoding))
+    }
+
+    // Whichever parser handles the file, its links get positions
     pub fn analyze_file(&self, content: &str, path: &str) -> AnalysisResult {
+        self.analyze_file_with_fallback(content, path).0
+    }
+
+    // Also tells when a registered parser failed on the file and the
+    // built-in one stood in
+    pub fn analyze_file_with_fallback(&self, content: &str, path: &str) -> (AnalysisResult, Option<ParserFallback>) {
+        let content = encoding::normalize(content);
+        let (mut result, fallback) = self.parse(&content, path);
+        position::locate_links(&content, &mut result.links, self.column_encoding);
+        (result, fallback)
+    }
+
+    fn parse(&self, content: &str, path: &str) -> (AnalysisResult, Option<ParserFallback>) {
+        if let Some(custom) = self.custom.iter().find(|custom| custom.handles(path)) {
+            return (custom.analyze(content, path), None);
+        }
+        let Some(parser) = self.registry.find(path) else {
+            return (self.parse_builtin(content, path), None);
+        };
+        match parser.try_parse(content, path) {
+            Ok(result) => (result, None),
+            Err(error) => {
+                let fallback = ParserFallback {
+                    path: path.to_string(),
+                    parser: parser.language().to_string(),
+                    line: error.line,
+                    column: error.column,
+                    message: error.message,
+                };
+                (self.parse_builtin(content, path), Some(fallback))
+            }
+        }
+    }
+
+    fn parse_builtin(&self, content: &str, path: &str) -> AnalysisResult {
         let file_type = Self::detect_file_type(path);
         
         match file_type {
             FileType::Markdown => {
-                let parser = MarkdownParser::new();
-                let classes = parser.extract_classes(content);
-                let links = parser.parse_file(content);
-                AnalysisResult { classes, links }
+                let parser = MarkdownParser::with_options(self.options.markdown.clone());
+                parser.analyze(content, path)
+            }
+            #[cfg(feature = "python")]
+            FileType::Python if crate::notebook::is_notebook(path) => {
+                let parser = PythonParser::with_options(self.options.python.clone());
+                parser.analyze_notebook(content, path)
             }
+            #[cfg(feature = "python")]
             FileType::Python => {
-                let parser = PythonParser::new();
+                let parser = PythonParser::with_options(self.options.python.clone());
                 parser.analyze(content, path)
             }
+            #[cfg(feature = "typescript")]
             FileType::TypeScript => {
-                let parser = TypeScriptParser::new();
+                let parser = TypeScriptParser::with_options(self.options.typescript.clone())
+                    .with_templates(self.options.templates.clone());
                 parser.analyze(content, path)
             }
+            #[cfg(feature = "csharp")]
             FileType::CSharp => {
-                let parser = CSharpParser::new();
+                let parser = CSharpParser::with_options(self.options.csharp.clone());
                 parser.analyze(content, path)
             }
-            FileType::Unknown => {
-                AnalysisResult {
-                    classes: HashSet::new(),
-                    links: vec![],
-                }
+            #[cfg(feature = "rust")]
+            FileType::Rust => {
+                let parser = RustParser::with_options(self.options.rust.clone());
+                parser.analyze(content, path)
+            }
+            #[cfg(feature = "java")]
+            FileType::Java => {
+                let parser = JavaParser::with_options(self.options.java.clone());
+                parser.analyze(content, path)
+            }
+            #[cfg(feature = "go")]
+            FileType::Go => {
+                let parser = GoParser::with_options(self.options.go.clone());
+                parser.analyze(content, path)
+            }
+            #[cfg(feature = "javascript")]
+            FileType::JavaScript => {
+                let parser = JavaScriptParser::with_options(self.options.javascript.clone());
+                parser.analyze(content, path)
             }
+            #[cfg(feature = "typescript")]
+            FileType::Template => {
+                let parser = TemplateParser::with_options(self.options.templates.clone());
+                parser.analyze(content, path)
+            }
+            FileType::Unknown => AnalysisResult::default(),
+            // Only detected for parsers in the build
+            #[cfg(not(all(feature = "python", feature = "typescript", feature = "csharp", feature = "rust", feature = "java", feature = "go", feature = "javascript")))]
+            _ => AnalysisResult::default(),
         }
     }
 }
 
 #[cfg(test)]
+// Tests are gated on the parsers they exercise, so a build with only some
+// of them leaves imports unused
+#[cfg_attr(
+    not(all(
+        feature = "python",
+        feature = "typescript",
+        feature = "csharp",
+        feature = "rust",
+        feature = "java",
+        feature = "go",
+        feature = "javascript"
+    )),
+    allow(unused_imports)
+)]
 mod tests {
     use super::*;
+    use crate::parser::LinkKind;
+    #[cfg(all(feature = "python", feature = "typescript"))]
+    use crate::preset::ExtractionPreset;
+    use std::collections::{BTreeSet, HashSet};
 
     #[test]
     fn test_detect_markdown_files() {
@@ -85,23 +290,56 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "python")]
     fn test_detect_python_files() {
         assert_eq!(UnifiedAnalyzer::detect_file_type("main.py"), FileType::Python);
         assert_eq!(UnifiedAnalyzer::detect_file_type("src/models.py"), FileType::Python);
     }
 
     #[test]
+    #[cfg(feature = "typescript")]
     fn test_detect_typescript_files() {
         assert_eq!(UnifiedAnalyzer::detect_file_type("app.ts"), FileType::TypeScript);
         assert_eq!(UnifiedAnalyzer::detect_file_type("component.tsx"), FileType::TypeScript);
     }
 
     #[test]
+    #[cfg(feature = "csharp")]
     fn test_detect_csharp_files() {
         assert_eq!(UnifiedAnalyzer::detect_file_type("Program.cs"), FileType::CSharp);
         assert_eq!(UnifiedAnalyzer::detect_file_type("Models/User.cs"), FileType::CSharp);
     }
 
+    #[test]
+    #[cfg(feature = "rust")]
+    fn test_detect_rust_files() {
+        assert_eq!(UnifiedAnalyzer::detect_file_type("src/main.rs"), FileType::Rust);
+        assert_eq!(UnifiedAnalyzer::detect_file_type("crates/core/src/graph.rs"), FileType::Rust);
+    }
+
+    #[test]
+    #[cfg(feature = "java")]
+    fn test_detect_java_files() {
+        assert_eq!(UnifiedAnalyzer::detect_file_type("Order.java"), FileType::Java);
+        assert_eq!(UnifiedAnalyzer::detect_file_type("src/main/java/com/shop/Order.java"), FileType::Java);
+    }
+
+    #[test]
+    #[cfg(feature = "go")]
+    fn test_detect_go_files() {
+        assert_eq!(UnifiedAnalyzer::detect_file_type("order.go"), FileType::Go);
+        assert_eq!(UnifiedAnalyzer::detect_file_type("internal/orders/order_test.go"), FileType::Go);
+    }
+
+    #[test]
+    #[cfg(all(feature = "typescript", feature = "javascript"))]
+    fn test_detect_javascript_files() {
+        assert_eq!(UnifiedAnalyzer::detect_file_type("src/order.js"), FileType::JavaScript);
+        assert_eq!(UnifiedAnalyzer::detect_file_type("src/OrderView.jsx"), FileType::JavaScript);
+        assert_eq!(UnifiedAnalyzer::detect_file_type("src/order.mjs"), FileType::JavaScript);
+        assert_eq!(UnifiedAnalyzer::detect_file_type("src/OrderView.tsx"), FileType::TypeScript);
+    }
+
     #[test]
     fn test_detect_unknown_files() {
         assert_eq!(UnifiedAnalyzer::detect_file_type("config.json"), FileType::Unknown);
@@ -109,6 +347,33 @@ mod tests {
     }
 
     #[test]
+    #[cfg(all(feature = "python", feature = "typescript", feature = "csharp"))]
+    fn test_line_endings_do_not_change_results() {
+        let analyzer = UnifiedAnalyzer::new();
+        let sources = [
+            ("Shop.md", "# Shop\n\nUses [Cart](Cart.md)\nand [Order](Order.md)\n"),
+            ("shop.py", "class Shop(Base):\n    def add(self):\n        self.cart = Cart()\n"),
+            ("shop.ts", "import { Cart, Order } from './m';\nexport class Shop {\n  private cart: Cart;\n  find(): Order {}\n}\n"),
+            ("Shop.cs", "public class Cart\n{\n}\n\npublic class Shop\n{\n    private readonly Cart _cart;\n}\n"),
+        ];
+        let summary = |result: AnalysisResult| {
+            let classes: BTreeSet<String> = result.classes.into_iter().collect();
+            let links: BTreeSet<(String, usize)> = result.links.into_iter().map(|l| (l.to_class, l.line_number)).collect();
+            (classes, links)
+        };
+
+        for (path, content) in sources {
+            let expected = summary(analyzer.analyze_file(content, path));
+            assert!(!expected.1.is_empty(), "{}", path);
+            for ending in ["\r\n", "\r"] {
+                let converted = format!("\u{feff}{}", content.replace('\n', ending));
+                assert_eq!(summary(analyzer.analyze_file(&converted, path)), expected, "{} with {:?}", path, ending);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
     fn test_analyze_python_classes() {
         let analyzer = UnifiedAnalyzer::new();
         let content = r#"
@@ -136,6 +401,7 @@ from db import DatabaseConnection
     }
 
     #[test]
+    #[cfg(feature = "typescript")]
     fn test_analyze_typescript_classes() {
         let analyzer = UnifiedAnalyzer::new();
         let content = r#"
@@ -171,6 +437,7 @@ interface IAuthProvider {
     }
 
     #[test]
+    #[cfg(feature = "csharp")]
     fn test_analyze_csharp_classes() {
         let analyzer = UnifiedAnalyzer::new();
         let content = r#"
@@ -206,7 +473,8 @@ namespace Application.Services
         
         assert!(result.classes.contains("UserService"));
         assert!(result.classes.contains("IUserService"));
-        assert_eq!(result.links.len(), 4); // UserService -> IUserService, UserService -> DatabaseContext, UserService -> User, IUserService -> User
+        // UserService -> IUserService, UserService -> DatabaseContext (field and constructor), UserService -> User, IUserService -> User
+        assert_eq!(result.links.len(), 5);
         
         let link_targets: HashSet<String> = result.links.iter()
             .map(|l| l.to_class.clone())
@@ -215,4 +483,629 @@ namespace Application.Services
         assert!(link_targets.contains("DatabaseContext"));
         assert!(link_targets.contains("IUserService"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    #[cfg(feature = "rust")]
+    fn test_analyze_rust_types() {
+        let analyzer = UnifiedAnalyzer::new();
+        let content = r#"use crate::billing::{Invoice, Payment as Charge};
+use std::fmt;
+
+pub struct Order {
+    pub id: OrderId,
+    invoice: Option<Invoice>,
+    audit: crate::audit::AuditLog,
+}
+
+pub(crate) struct OrderId(u64);
+
+pub enum OrderState {
+    Pending,
+    Paid(Charge),
+}
+
+pub trait Repository: Storage {
+    fn find(&self, id: &OrderId) -> Option<Order>;
+    fn save(&mut self, order: &Order, mailer: Mailer);
+}
+
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", "}")
+    }
+}
+
+impl Ledger for OrderId {}
+"#;
+
+        let result = analyzer.analyze_file(content, "src/orders.rs");
+
+        let classes: BTreeSet<&str> = result.classes.iter().map(String::as_str).collect();
+        assert_eq!(classes, BTreeSet::from(["Order", "OrderId", "OrderState", "Repository"]));
+        let links: BTreeSet<(&str, &str, LinkKind)> =
+            result.links.iter().map(|l| (l.from_class.as_str(), l.to_class.as_str(), l.kind)).collect();
+        // `Mailer` is neither defined nor imported; `Formatter` and
+        // `Result` are paths into `std`, dropped later with the other
+        // standard types
+        let expected = [
+            ("Order", "AuditLog", LinkKind::Composition),
+            ("Order", "Display", LinkKind::Inheritance),
+            ("Order", "Formatter", LinkKind::Usage),
+            ("Order", "Invoice", LinkKind::Composition),
+            ("Order", "OrderId", LinkKind::Composition),
+            ("Order", "Result", LinkKind::Usage),
+            ("OrderId", "Ledger", LinkKind::Inheritance),
+            ("OrderState", "Payment", LinkKind::Composition),
+            ("Repository", "Order", LinkKind::Usage),
+            ("Repository", "OrderId", LinkKind::Usage),
+            ("Repository", "Storage", LinkKind::Inheritance),
+        ];
+        assert_eq!(links, BTreeSet::from(expected));
+
+        let tags = |class: &str| result.class_tags[class].iter().map(|tag| tag.to_string()).collect::<Vec<_>>();
+        assert_eq!(tags("OrderId"), vec!["internal"]);
+        assert_eq!(tags("Repository"), vec!["interface", "exported"]);
+        let order = result.class_metrics["Order"];
+        // The struct and its `Display` impl
+        assert_eq!((order.start_line, order.end_line, order.lines, order.methods), (4, 26, 10, 1));
+        assert_eq!(result.class_metrics["OrderId"].lines, 2);
+    }
+
+    #[test]
+    #[cfg(feature = "rust")]
+    fn test_analyze_rust_single_line_items() {
+        let analyzer = UnifiedAnalyzer::new();
+        let content = r#"use crate::refunds::Refund;
+use uuid::Uuid;
+
+pub struct LineItem { price: Money, tax: Option<Money> }
+pub struct Id(Uuid);
+pub enum Status { Open(Order), Closed { refund: Refund }, Void }
+pub struct Cache<T: Repo> where T: Clock { items: Vec<T> }
+pub struct Index<T>
+where
+    T: Repo,
+{
+    entries: Vec<T>,
+}
+pub struct Wrapper(
+    pub Order,
+);
+impl<T: Repo> Service for Cache<T> {}
+
+pub struct Money;
+pub struct Order;
+pub trait Repo {}
+pub trait Clock {}
+pub trait Service {}
+"#;
+
+        let result = analyzer.analyze_file(content, "src/orders.rs");
+
+        let links: BTreeSet<(&str, &str, LinkKind)> =
+            result.links.iter().map(|l| (l.from_class.as_str(), l.to_class.as_str(), l.kind)).collect();
+        let expected = [
+            ("Cache", "Clock", LinkKind::Usage),
+            ("Cache", "Repo", LinkKind::Usage),
+            ("Cache", "Service", LinkKind::Inheritance),
+            ("Id", "Uuid", LinkKind::Composition),
+            ("Index", "Repo", LinkKind::Usage),
+            ("LineItem", "Money", LinkKind::Composition),
+            ("Status", "Order", LinkKind::Composition),
+            ("Status", "Refund", LinkKind::Composition),
+            ("Wrapper", "Order", LinkKind::Composition),
+        ];
+        assert_eq!(links, BTreeSet::from(expected));
+    }
+
+    #[test]
+    #[cfg(feature = "java")]
+    fn test_analyze_java_classes() {
+        let analyzer = UnifiedAnalyzer::new();
+        let content = r#"package com.shop.orders;
+
+import com.shop.billing.Invoice;
+import com.shop.catalog.*;
+import java.util.List;
+import static com.shop.Money.ZERO;
+
+/**
+ * Orders placed by a {@link Customer}.
+ * @deprecated use PurchaseOrder
+ */
+@Entity
+public class Order extends AggregateRoot<OrderId> implements Comparable<Order>, Auditable {
+    private final OrderId id;
+    @OneToMany
+    private List<OrderLine> lines = new ArrayList<>();
+    private String note = "{ Customer }";
+
+    public Order(OrderId id) {
+        this.id = id;
+    }
+
+    public Invoice bill(PaymentTerms terms) {
+        Invoice invoice = new com.shop.billing.Invoice(id);
+        Mailer mailer = new Mailer();
+        return invoice;
+    }
+
+    record OrderLine(com.shop.catalog.Product product, int quantity) {}
+}
+
+interface OrderRepository extends Repository<Order, OrderId> {
+    Optional<Order> findById(OrderId id);
+}
+
+record OrderId(long value) {}
+"#;
+
+        let result = analyzer.analyze_file(content, "src/main/java/com/shop/orders/Order.java");
+
+        let classes: BTreeSet<&str> = result.classes.iter().map(String::as_str).collect();
+        assert_eq!(classes, BTreeSet::from(["Order", "OrderId", "OrderLine", "OrderRepository"]));
+        let links: BTreeSet<(&str, &str, LinkKind)> =
+            result.links.iter().map(|l| (l.from_class.as_str(), l.to_class.as_str(), l.kind)).collect();
+        // Type positions link without an import, like `PaymentTerms` and
+        // `Mailer` from the same package; `String`, `ArrayList` and
+        // `Optional` are dropped later with the other standard types
+        let expected = [
+            ("Order", "AggregateRoot", LinkKind::Inheritance),
+            ("Order", "ArrayList", LinkKind::Usage),
+            ("Order", "Auditable", LinkKind::Inheritance),
+            ("Order", "Comparable", LinkKind::Inheritance),
+            ("Order", "Invoice", LinkKind::Usage),
+            ("Order", "List", LinkKind::Composition),
+            ("Order", "Mailer", LinkKind::Usage),
+            ("Order", "OrderId", LinkKind::Composition),
+            ("Order", "OrderId", LinkKind::Usage),
+            ("Order", "OrderLine", LinkKind::Composition),
+            ("Order", "PaymentTerms", LinkKind::Usage),
+            ("Order", "String", LinkKind::Composition),
+            ("OrderLine", "Product", LinkKind::Composition),
+            ("OrderRepository", "Optional", LinkKind::Usage),
+            ("OrderRepository", "Order", LinkKind::Usage),
+            ("OrderRepository", "OrderId", LinkKind::Usage),
+            ("OrderRepository", "Repository", LinkKind::Inheritance),
+        ];
+        assert_eq!(links, BTreeSet::from(expected));
+
+        assert_eq!(result.namespaces["OrderLine"], "com.shop.orders");
+        let tags = |class: &str| result.class_tags[class].iter().map(|tag| tag.to_string()).collect::<Vec<_>>();
+        assert_eq!(tags("Order"), vec!["deprecated", "exported"]);
+        assert_eq!(tags("OrderRepository"), vec!["internal", "interface"]);
+        let order = result.class_metrics["Order"];
+        assert_eq!((order.start_line, order.end_line, order.methods), (13, 30, 2));
+    }
+
+    #[test]
+    #[cfg(feature = "go")]
+    fn test_analyze_go_types() {
+        let analyzer = UnifiedAnalyzer::new();
+        let content = r#"package orders
+
+import (
+	"context"
+	"time"
+
+	"github.com/acme/shop/billing"
+	legacy "github.com/acme/shop/customers/v2"
+)
+
+// Order is the aggregate root.
+//
+// Deprecated: use Purchase.
+type Order struct {
+	Base
+	ID       OrderID `json:"id"`
+	Lines    []*OrderLine
+	Invoice  billing.Invoice
+	Customer *legacy.Customer
+	Placed   time.Time
+}
+
+func (o *Order) Total(ctx context.Context, rates billing.Rates) (Money, error) {
+	type line struct{ Amount Money }
+	return Money{}, nil
+}
+
+type (
+	orderStore interface {
+		Find(ctx context.Context, id OrderID) (*Order, error)
+		Saver
+	}
+
+	OrderLine struct {
+		Product Product
+		Qty     int
+	}
+)
+"#;
+        let result = analyzer.analyze_file(content, "internal/orders/order.go");
+
+        assert_eq!(result.classes, HashSet::from(["Order".to_string(), "orderStore".to_string(), "OrderLine".to_string()]));
+        let links: BTreeSet<(&str, &str, LinkKind)> =
+            result.links.iter().map(|l| (l.from_class.as_str(), l.to_class.as_str(), l.kind)).collect();
+        // `OrderID`, `Money` and `Product` are neither defined nor qualified
+        // by a project import, and `time.Time` is the standard library's;
+        // embedded types count either way
+        let expected = [
+            ("Order", "Base", LinkKind::Inheritance),
+            ("Order", "Customer", LinkKind::Composition),
+            ("Order", "Invoice", LinkKind::Composition),
+            ("Order", "OrderLine", LinkKind::Composition),
+            ("Order", "Rates", LinkKind::Usage),
+            ("orderStore", "Order", LinkKind::Usage),
+            ("orderStore", "Saver", LinkKind::Inheritance),
+        ];
+        assert_eq!(links, BTreeSet::from(expected));
+
+        let tags = |class: &str| result.class_tags[class].iter().map(|tag| tag.to_string()).collect::<Vec<_>>();
+        assert_eq!(tags("Order"), vec!["deprecated", "exported"]);
+        assert_eq!(tags("orderStore"), vec!["internal", "interface"]);
+        let order = result.class_metrics["Order"];
+        assert_eq!((order.start_line, order.end_line, order.lines, order.methods), (14, 26, 12, 1));
+        assert_eq!(result.class_metrics["orderStore"].methods, 1);
+    }
+
+    #[test]
+    #[cfg(feature = "go")]
+    fn test_analyze_go_single_line_types() {
+        let analyzer = UnifiedAnalyzer::new();
+        let content = r#"package orders
+
+type Money struct{ Cents int64 }
+type Buf struct{}
+type LineItem struct{ Price Money; Qty int }
+type Reader interface{ Read() Buf }
+type Cart struct{ *LineItem; Items []LineItem }
+"#;
+
+        let result = analyzer.analyze_file(content, "orders/types.go");
+
+        let links: BTreeSet<(&str, &str, LinkKind)> =
+            result.links.iter().map(|l| (l.from_class.as_str(), l.to_class.as_str(), l.kind)).collect();
+        let expected = [
+            ("Cart", "LineItem", LinkKind::Inheritance),
+            ("LineItem", "Money", LinkKind::Composition),
+            ("Reader", "Buf", LinkKind::Usage),
+        ];
+        assert_eq!(links, BTreeSet::from(expected));
+    }
+
+    #[test]
+    #[cfg(feature = "javascript")]
+    fn test_analyze_javascript_classes() {
+        let analyzer = UnifiedAnalyzer::new();
+        let content = r#"import React from 'react';
+import { Invoice as Bill } from './billing';
+const models = require('./models');
+const { Mailer } = require('./mail');
+
+/**
+ * @deprecated use Purchase
+ */
+class Order extends React.Component {
+  repository = new models.OrderRepository();
+
+  constructor(customer) {
+    super();
+    this.mailer = new Mailer();
+    this.note = `new Shipment()`;
+  }
+
+  /** @param {Bill} bill */
+  pay(bill) {
+    const receipt = new Receipt(bill);
+    return new Bill();
+  }
+
+  render = () => <LineList order={this} />;
+}
+
+const OrderLine = class {
+  total() {
+    return new Order();
+  }
+};
+
+module.exports = { Order };
+"#;
+        let result = analyzer.analyze_file(content, "src/order.jsx");
+
+        assert_eq!(result.classes, HashSet::from(["Order".to_string(), "OrderLine".to_string()]));
+        let links: BTreeSet<(&str, &str, LinkKind)> =
+            result.links.iter().map(|l| (l.from_class.as_str(), l.to_class.as_str(), l.kind)).collect();
+        // `Receipt` and `LineList` are neither imported nor defined, and the
+        // `Shipment` in the template literal is not code
+        let expected = [
+            ("Order", "Component", LinkKind::Inheritance),
+            ("Order", "Invoice", LinkKind::Usage),
+            ("Order", "Mailer", LinkKind::Composition),
+            ("Order", "OrderRepository", LinkKind::Composition),
+            ("OrderLine", "Order", LinkKind::Usage),
+        ];
+        assert_eq!(links, BTreeSet::from(expected));
+
+        let tags = |class: &str| result.class_tags[class].iter().map(|tag| tag.to_string()).collect::<Vec<_>>();
+        assert_eq!(tags("Order"), vec!["deprecated", "exported"]);
+        assert!(!result.class_tags.contains_key("OrderLine"));
+        let order = result.class_metrics["Order"];
+        assert_eq!((order.start_line, order.end_line, order.methods), (9, 25, 3));
+    }
+
+    #[test]
+    #[cfg(all(feature = "python", feature = "typescript", feature = "csharp"))]
+    fn test_links_are_classified_by_kind() {
+        let analyzer = UnifiedAnalyzer::new();
+        let kind_of = |result: &AnalysisResult, to: &str| {
+            result.links.iter().find(|l| l.to_class == to).map(|l| l.kind)
+        };
+
+        let python = analyzer.analyze_file(
+            "class Admin(models.User):\n    def __init__(self):\n        self.audit = AuditLog()\n        Mailer().send()\n",
+            "admin.py",
+        );
+        assert_eq!(kind_of(&python, "User"), Some(LinkKind::Inheritance));
+        assert_eq!(kind_of(&python, "AuditLog"), Some(LinkKind::Composition));
+        assert_eq!(kind_of(&python, "Mailer"), Some(LinkKind::Usage));
+
+        let typescript = analyzer.analyze_file(
+            "import { Base, Repo, User } from './m';\nexport class Admin extends Base {\n  constructor(private repo: Repo) {}\n  find(): User {}\n}\n",
+            "admin.ts",
+        );
+        assert_eq!(kind_of(&typescript, "Base"), Some(LinkKind::Inheritance));
+        assert_eq!(kind_of(&typescript, "Repo"), Some(LinkKind::Composition));
+        assert_eq!(kind_of(&typescript, "User"), Some(LinkKind::Usage));
+
+        let csharp = analyzer.analyze_file(
+            "public class UserService : IUserService\n{\n    private readonly DatabaseContext _context;\n    public User GetUser(int id) {}\n}\n",
+            "UserService.cs",
+        );
+        assert_eq!(kind_of(&csharp, "IUserService"), Some(LinkKind::Inheritance));
+        assert_eq!(kind_of(&csharp, "DatabaseContext"), Some(LinkKind::Composition));
+        assert_eq!(kind_of(&csharp, "User"), Some(LinkKind::Usage));
+
+        let markdown = analyzer.analyze_file("# Admin\n\n[User](User.md)\n", "Admin.md");
+        assert_eq!(kind_of(&markdown, "User"), Some(LinkKind::Doc));
+    }
+
+    #[test]
+    #[cfg(all(feature = "python", feature = "typescript", feature = "csharp"))]
+    fn test_parser_options() {
+        let strict = UnifiedAnalyzer::new().with_parser_options(ParserOptions {
+            python: PythonParserOptions {
+                count_imports_as_links: false,
+                include_type_only: false,
+                ..Default::default()
+            },
+            typescript: TypeScriptParserOptions {
+                include_type_only: false,
+                ..Default::default()
+            },
+            csharp: CSharpParserOptions {
+                known_targets_only: false,
+                ..Default::default()
+            },
+            markdown: MarkdownParserOptions {
+                include_code_references: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let targets = |result: AnalysisResult| -> HashSet<String> {
+            result.links.into_iter().map(|l| l.to_class).collect()
+        };
+
+        let python = "from models import Audit\n\nclass Service:\n    def run(self, user: User):\n        Mailer()\n";
+        assert_eq!(targets(UnifiedAnalyzer::new().analyze_file(python, "s.py")).len(), 3);
+        assert_eq!(targets(strict.analyze_file(python, "s.py")), HashSet::from(["Mailer".to_string()]));
+
+        let typescript = "import { Repo, User } from './m';\nexport class Service {\n  private repo: Repo;\n  find(): User {}\n}\n";
+        assert_eq!(targets(strict.analyze_file(typescript, "s.ts")), HashSet::from(["Repo".to_string()]));
+
+        let csharp = "public class Service\n{\n    private readonly Clock _clock;\n}\n";
+        assert!(targets(UnifiedAnalyzer::new().analyze_file(csharp, "S.cs")).is_empty());
+        assert!(targets(strict.analyze_file(csharp, "S.cs")).contains("Clock"));
+
+        let markdown = "# Service\n\nUses HelperClass and [User](User.md)\n";
+        assert_eq!(targets(strict.analyze_file(markdown, "Service.md")), HashSet::from(["User".to_string()]));
+    }
+
+    #[test]
+    #[cfg(all(feature = "python", feature = "typescript"))]
+    fn test_extraction_presets() {
+        let options: ParserOptions = serde_json::from_str(
+            r#"{ "python": { "preset": "strict" }, "typescript": { "preset": "greedy", "known_targets_only": true } }"#,
+        )
+        .unwrap();
+        assert_eq!(options.python, PythonParserOptions::preset(ExtractionPreset::Strict));
+        assert!(options.typescript.any_capitalized && options.typescript.known_targets_only);
+        let configured = UnifiedAnalyzer::new().with_parser_options(options);
+        let greedy = UnifiedAnalyzer::new().with_parser_options(ParserOptions {
+            python: PythonParserOptions::preset(ExtractionPreset::Greedy),
+            typescript: TypeScriptParserOptions::preset(ExtractionPreset::Greedy),
+            ..Default::default()
+        });
+        let targets = |result: AnalysisResult| -> BTreeSet<String> { result.links.into_iter().map(|l| l.to_class).collect() };
+
+        let python = "from models import Audit\n\nclass Service:\n    def run(self, user: User):\n        Mailer()\n        return Cache.get(None)\n";
+        assert_eq!(targets(configured.analyze_file(python, "s.py")), BTreeSet::from(["Audit".to_string()]));
+        assert_eq!(targets(UnifiedAnalyzer::new().analyze_file(python, "s.py")).len(), 3);
+        let all = ["Audit", "Cache", "Mailer", "User"].map(String::from);
+        assert_eq!(targets(greedy.analyze_file(python, "s.py")), BTreeSet::from(all));
+
+        let typescript = "import { Repo } from './m';\nexport class Service {\n  run() { return Repo.find(Date.now()); }\n}\n";
+        assert_eq!(targets(configured.analyze_file(typescript, "s.ts")), BTreeSet::from(["Repo".to_string()]));
+        assert_eq!(targets(greedy.analyze_file(typescript, "s.ts")).len(), 2);
+    }
+
+    #[test]
+    #[cfg(all(feature = "python", feature = "typescript", feature = "csharp"))]
+    fn test_class_size_metrics() {
+        let analyzer = UnifiedAnalyzer::new();
+
+        let python = analyzer.analyze_file(
+            "class Repo:\n    def get(self):\n        pass\n\n    async def save(self):\n        pass\n\nclass Other:\n    pass\n",
+            "repo.py",
+        );
+        let repo = python.class_metrics["Repo"];
+        assert_eq!((repo.start_line, repo.end_line, repo.methods), (1, 6, 2));
+
+        let typescript = analyzer.analyze_file(
+            "export class Repo {\n  constructor() {}\n  find(id: string): User {\n    if (id) {\n      return load(id);\n    }\n  }\n}\n",
+            "repo.ts",
+        );
+        let repo = typescript.class_metrics["Repo"];
+        assert_eq!((repo.lines, repo.methods), (8, 2));
+
+        let csharp = analyzer.analyze_file(
+            "public interface IRepo\n{\n    User Get(int id);\n}\npublic class Repo : IRepo\n{\n    public Repo() {}\n    public User Get(int id)\n    {\n        return null;\n    }\n}\n",
+            "Repo.cs",
+        );
+        assert_eq!(csharp.class_metrics["IRepo"].methods, 1);
+        assert_eq!(csharp.class_metrics["Repo"].lines, 8);
+        assert_eq!(csharp.class_metrics["Repo"].methods, 2);
+
+        assert!(analyzer.analyze_file("# Repo\n", "Repo.md").class_metrics.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_notebook_code_cells_are_analyzed_as_python() {
+        let notebook = r#"{
+ "cells": [
+  { "cell_type": "code", "source": ["class Cart:\n", "    pass\n"] },
+  { "cell_type": "markdown", "source": ["Uses [Price](Price.md)\n"] },
+  { "cell_type": "code", "source": ["class Shop(Cart):\n", "    pass\n"] }
+ ]
+}"#;
+        assert_eq!(UnifiedAnalyzer::detect_file_type("proto.ipynb"), FileType::Python);
+
+        let result = UnifiedAnalyzer::new().analyze_file(notebook, "proto.ipynb");
+
+        assert!(result.classes.contains("Cart") && result.classes.contains("Shop"));
+        assert_eq!(result.links.len(), 1);
+        assert_eq!((result.links[0].to_class.as_str(), result.links[0].line_number), ("Cart", 5));
+        assert!(UnifiedAnalyzer::new().analyze_file("{", "broken.ipynb").classes.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "typescript")]
+    fn test_template_usages_link_components() {
+        let analyzer = UnifiedAnalyzer::new();
+        let tsx = "import { UserCard } from './UserCard';\n\nexport class UserList extends React.Component<Props> {\n  render() {\n    return <ul>{this.users.map(u => <UserCard user={u} />)}</ul>;\n  }\n}\n";
+        let angular = "@Component({\n  selector: 'app-shell',\n  template: '<app-nav-bar></app-nav-bar>'\n})\nexport class ShellComponent {}\n";
+
+        let react = analyzer.analyze_file(tsx, "src/UserList.tsx");
+        let inline = analyzer.analyze_file(angular, "src/app/shell.component.ts");
+        let html = analyzer.analyze_file("<app-user-card></app-user-card>\n", "src/app/user-list.component.html");
+
+        assert_eq!(UnifiedAnalyzer::detect_file_type("page.html"), FileType::Template);
+        assert!(react.links.iter().any(|l| l.from_class == "UserList" && l.to_class == "UserCard" && l.line_number == 5));
+        assert_eq!(inline.links.len(), 1);
+        assert_eq!((inline.links[0].from_class.as_str(), inline.links[0].to_class.as_str()), ("ShellComponent", "NavBarComponent"));
+        assert_eq!(inline.links[0].line_number, 3);
+        assert_eq!(html.links[0].from_class, "UserListComponent");
+        assert_eq!(html.links[0].to_class, "UserCardComponent");
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_orm_relationships_link_models() {
+        let models = "class Order(models.Model):\n    customer = models.ForeignKey(Customer, on_delete=models.CASCADE)\n    items = models.ManyToManyField(\"shop.Product\")\n    parent = models.ForeignKey(\"self\", null=True)\n\nclass Invoice(Base):\n    order_id = Column(Integer, ForeignKey(\"orders.id\"))\n    order = relationship(\"Order\", back_populates=\"invoice\")\n";
+
+        let result = UnifiedAnalyzer::new().analyze_file(models, "models.py");
+
+        let relations: Vec<(&str, &str, usize)> = result
+            .links
+            .iter()
+            .filter(|l| l.kind == LinkKind::Composition)
+            .map(|l| (l.from_class.as_str(), l.to_class.as_str(), l.line_number))
+            .collect();
+        assert_eq!(relations, vec![("Order", "Customer", 2), ("Order", "Product", 3), ("Invoice", "Order", 8)]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "python", feature = "typescript", feature = "csharp"))]
+    fn test_container_registrations_bind_interfaces() {
+        let analyzer = UnifiedAnalyzer::new();
+        let bindings = |content: &str, path: &str| -> Vec<(String, String)> {
+            analyzer
+                .analyze_file(content, path)
+                .links
+                .into_iter()
+                .filter(|l| l.kind == LinkKind::Binding)
+                .map(|l| (l.from_class, l.to_class))
+                .collect()
+        };
+        let pair = |from: &str, to: &str| vec![(from.to_string(), to.to_string())];
+
+        let csharp = "var builder = WebApplication.CreateBuilder(args);\nbuilder.Services.AddScoped<IUserService, Services.UserService>();\n";
+        assert_eq!(bindings(csharp, "Program.cs"), pair("IUserService", "UserService"));
+        let nest = "@Module({\n  providers: [\n    UserService,\n    {\n      provide: UserRepository,\n      useClass: SqlUserRepository,\n    },\n  ],\n})\nexport class UsersModule {}\n";
+        assert_eq!(bindings(nest, "users.module.ts"), pair("UserRepository", "SqlUserRepository"));
+        let python = "container = Container()\ncontainer.register(IUserRepo, SqlUserRepo)\nbinder.bind(Mailer, to=SmtpMailer)\n";
+        assert_eq!(
+            bindings(python, "wiring.py"),
+            vec![("IUserRepo".to_string(), "SqlUserRepo".to_string()), ("Mailer".to_string(), "SmtpMailer".to_string())]
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "python", feature = "typescript", feature = "csharp"))]
+    fn test_events_link_publishers_and_handlers() {
+        let analyzer = UnifiedAnalyzer::new();
+        let links = |content: &str, path: &str| -> Vec<(String, String, LinkKind)> {
+            analyzer
+                .analyze_file(content, path)
+                .links
+                .into_iter()
+                .filter(|l| l.kind == LinkKind::Binding || l.to_class.starts_with("Order"))
+                .map(|l| (l.from_class, l.to_class, l.kind))
+                .collect()
+        };
+        let link = |from: &str, to: &str, kind: LinkKind| (from.to_string(), to.to_string(), kind);
+
+        let csharp = "public class Checkout\n{\n    public Task Run() => _bus.Publish(new OrderCreated(id));\n}\npublic class OrderMailer : INotificationHandler<Events.OrderCreated>\n{\n}\n";
+        assert_eq!(
+            links(csharp, "Orders.cs"),
+            vec![link("Checkout", "OrderCreated", LinkKind::Usage), link("OrderCreated", "OrderMailer", LinkKind::Binding)]
+        );
+        let nest = "export class OrdersController {\n  place() {\n    this.client.emit('order.created', dto);\n  }\n\n  @EventPattern('order.created')\n  onCreated(data: unknown) {}\n}\n";
+        assert_eq!(
+            links(nest, "orders.controller.ts"),
+            vec![link("OrdersController", "OrderCreated", LinkKind::Usage), link("OrderCreated", "OrdersController", LinkKind::Binding)]
+        );
+        let python = "class Audit:\n    @receiver(post_save, sender=Order)\n    def on_save(self, sender, **kwargs):\n        pass\n\norder_shipped.connect(Mailer.on_shipped)\n";
+        assert_eq!(
+            links(python, "signals.py"),
+            vec![link("Order", "Audit", LinkKind::Binding), link("OrderShipped", "Mailer", LinkKind::Binding)]
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "python", feature = "typescript", feature = "csharp"))]
+    fn test_endpoints_join_clients_and_controllers() {
+        let analyzer = UnifiedAnalyzer::new();
+        let pairs = |content: &str, path: &str| -> Vec<(String, String)> {
+            analyzer
+                .analyze_file(content, path)
+                .links
+                .into_iter()
+                .filter(|l| crate::endpoints::is_endpoint(&l.from_class) || crate::endpoints::is_endpoint(&l.to_class))
+                .map(|l| (l.from_class, l.to_class))
+                .collect()
+        };
+        let pair = |from: &str, to: &str| (from.to_string(), to.to_string());
+
+        let controller = "[ApiController]\n[Route(\"api/[controller]\")]\npublic class UsersController : ControllerBase\n{\n    [HttpGet(\"{id:int}\")]\n    public User Get(int id) => null;\n}\n";
+        assert_eq!(pairs(controller, "UsersController.cs"), vec![pair("/api/users/{}", "UsersController")]);
+        let client = "export class UserApi {\n  load(id: number) {\n    return this.http.get<User>(`${environment.api}/api/users/${id}`);\n  }\n}\n";
+        assert_eq!(pairs(client, "user-api.ts"), vec![pair("UserApi", "/api/users/{}")]);
+        let nest = "@Controller('orders')\nexport class OrdersController {\n  @Get(':id')\n  find() {}\n}\n";
+        assert_eq!(pairs(nest, "orders.controller.ts"), vec![pair("/orders/{}", "OrdersController")]);
+        let fastapi = "@app.get(\"/health\")\ndef health():\n    pass\n\nurlpatterns = [path(\"users/<int:pk>/\", UserView.as_view())]\n";
+        assert_eq!(pairs(fastapi, "app/main.py"), vec![pair("/health", "main"), pair("/users/{}", "UserView")]);
+    }
+}
@@ -0,0 +1,792 @@
+use crate::analyzer::Isolation;
+use crate::annotations::ClassTag;
+use crate::file_analyzer::{AnalysisResult, FileType, UnifiedAnalyzer};
+use crate::glob;
+use crate::graph::ClassGraph;
+use crate::i18n::{Catalog, Message};
+use crate::impact;
+use crate::manifest::{self, package_of, relative_path, Grouping};
+use crate::parser::LinkKind;
+use crate::project::FileAnalysis;
+use crate::tags;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    // Exit codes for machine consumers; 1 is reserved for analysis failures
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Severity::Info => 0,
+            Severity::Warning => 2,
+            Severity::Error => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Finding {
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+    pub classes: Vec<String>,
+}
+
+// Classes started from outside the code: `main` functions' classes,
+// ASP.NET `Program` and `Startup`, app classes and `main` modules
+const DEFAULT_ENTRY_POINTS: &[&str] = &["Main", "Program", "Startup", "App", "*Application", "main.*", "__main__.py"];
+
+pub fn default_entry_points() -> Vec<String> {
+    DEFAULT_ENTRY_POINTS.iter().map(|p| p.to_string()).collect()
+}
+
+// What kind of isolated class each one is. `entry_points` are globs over
+// class names and root-relative files; a class defined only in files
+// matching `test_patterns` is a test helper. Entry points win over test
+// helpers, which win over exported classes.
+pub fn classify_isolated(
+    root: &str,
+    files: &[FileAnalysis],
+    merged: &AnalysisResult,
+    entry_points: &[String],
+    test_patterns: &[String],
+) -> BTreeMap<String, Isolation> {
+    let mut defined_in: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for file in files {
+        for class in &file.result.classes {
+            defined_in.entry(class).or_default().push(relative_path(root, &file.path));
+        }
+    }
+    let graph = ClassGraph::new(&merged.classes, &merged.links);
+    graph
+        .isolated_classes()
+        .into_iter()
+        .map(|class| {
+            let paths = defined_in.get(class.as_str()).map(Vec::as_slice).unwrap_or_default();
+            let isolation = if glob::matches_any(entry_points, &class)
+                || paths.iter().any(|path| glob::matches_any(entry_points, path))
+            {
+                Isolation::EntryPoint
+            } else if !paths.is_empty() && paths.iter().all(|path| impact::is_test_file(path, test_patterns)) {
+                Isolation::TestHelper
+            } else if merged.class_tags.get(&class).is_some_and(|tags| tags.contains(&ClassTag::Exported)) {
+                Isolation::Exported
+            } else {
+                Isolation::Dead
+            };
+            (class, isolation)
+        })
+        .collect()
+}
+
+// Only dead isolated classes are warnings; the other kinds are reported for
+// review under their own rules
+pub fn collect_findings(graph: &ClassGraph, isolation: &BTreeMap<String, Isolation>, catalog: &Catalog) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for cycle in graph.find_cycles() {
+        let path = graph.cycle_path(&cycle).join(" -> ");
+        findings.push(Finding {
+            rule: "circular-dependency".to_string(),
+            severity: Severity::Error,
+            message: catalog.text(Message::CircularDependency, &[&path]),
+            classes: cycle,
+        });
+    }
+
+    for class in graph.isolated_classes() {
+        let (rule, severity, message) = match isolation.get(&class).copied().unwrap_or(Isolation::Dead) {
+            Isolation::Dead => ("isolated-class", Severity::Warning, Message::IsolatedClass),
+            Isolation::EntryPoint => ("isolated-entry-point", Severity::Info, Message::IsolatedEntryPoint),
+            Isolation::TestHelper => ("isolated-test-helper", Severity::Info, Message::IsolatedTestHelper),
+            Isolation::Exported => ("isolated-export", Severity::Info, Message::IsolatedExport),
+        };
+        findings.push(Finding {
+            rule: rule.to_string(),
+            severity,
+            message: catalog.text(message, &[&class]),
+            classes: vec![class],
+        });
+    }
+
+    findings
+}
+
+// Every rule that needs no configuration, in report order
+pub fn builtin(
+    root: &str,
+    files: &[FileAnalysis],
+    merged: &AnalysisResult,
+    isolation: &BTreeMap<String, Isolation>,
+    catalog: &Catalog,
+) -> Vec<Finding> {
+    let graph = ClassGraph::new(&merged.classes, &merged.links);
+    let mut findings = collect_findings(&graph, isolation, catalog);
+    findings.extend(deprecated_in_use(merged, catalog));
+    findings.extend(over_dependency_budget(merged, catalog));
+    findings.extend(duplicate_docs(root, files, catalog));
+    findings.extend(doc_only_classes(root, files, catalog));
+    findings.extend(doc_assertions(root, files, catalog));
+    findings
+}
+
+// Links that jump over a layer, e.g. UI touching persistence directly.
+// `order` lists layer tags from entry to data; see `tags::class_tags`.
+pub fn layer_skips(
+    root: &str,
+    files: &[FileAnalysis],
+    patterns: &BTreeMap<String, Vec<String>>,
+    order: &[String],
+    catalog: &Catalog,
+) -> Vec<Finding> {
+    let tags = tags::class_tags(root, files, patterns);
+    let mut skips: BTreeMap<(&str, &str), (usize, usize)> = BTreeMap::new();
+    for link in files.iter().flat_map(|file| &file.result.links) {
+        if link.kind == LinkKind::Doc {
+            continue;
+        }
+        let from = tags::layer_of(tags.get(&link.from_class), order);
+        let to = tags::layer_of(tags.get(&link.to_class), order);
+        if let (Some(from), Some(to)) = (from, to) {
+            if to > from + 1 {
+                skips.insert((&link.from_class, &link.to_class), (from, to));
+            }
+        }
+    }
+
+    skips
+        .into_iter()
+        .map(|((from_class, to_class), (from, to))| Finding {
+            rule: "layer-skip".to_string(),
+            severity: Severity::Warning,
+            message: catalog.text(
+                Message::LayerSkip,
+                &[&from_class, &order[from], &to_class, &order[to], &order[from + 1..to].join(", ")],
+            ),
+            classes: vec![from_class.to_string(), to_class.to_string()],
+        })
+        .collect()
+}
+
+// Deprecated classes that non-deprecated code still depends on
+pub fn deprecated_in_use(result: &AnalysisResult, catalog: &Catalog) -> Vec<Finding> {
+    let is_deprecated = |class: &str| {
+        result
+            .class_tags
+            .get(class)
+            .is_some_and(|tags| tags.contains(&ClassTag::Deprecated))
+    };
+    let mut users: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for link in &result.links {
+        if is_deprecated(&link.to_class) && !is_deprecated(&link.from_class) {
+            users.entry(&link.to_class).or_default().insert(&link.from_class);
+        }
+    }
+
+    users
+        .into_iter()
+        .map(|(class, users)| {
+            let users: Vec<&str> = users.into_iter().collect();
+            Finding {
+                rule: "deprecated-in-use".to_string(),
+                severity: Severity::Warning,
+                message: catalog.text(Message::DeprecatedInUse, &[&class, &users.join(", ")]),
+                classes: std::iter::once(class).chain(users).map(str::to_string).collect(),
+            }
+        })
+        .collect()
+}
+
+// Classes depending on more distinct classes than their own
+// `classlink: max-deps=N` marker allows; doc links do not count
+pub fn over_dependency_budget(result: &AnalysisResult, catalog: &Catalog) -> Vec<Finding> {
+    let mut dependencies: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for link in &result.links {
+        if link.kind != LinkKind::Doc && result.dependency_budgets.contains_key(&link.from_class) {
+            dependencies.entry(&link.from_class).or_default().insert(&link.to_class);
+        }
+    }
+
+    dependencies
+        .into_iter()
+        .filter(|(class, targets)| targets.len() > result.dependency_budgets[*class])
+        .map(|(class, targets)| {
+            let targets: Vec<&str> = targets.into_iter().collect();
+            let max = result.dependency_budgets[class];
+            Finding {
+                rule: "dependency-budget".to_string(),
+                severity: Severity::Warning,
+                message: catalog.text(
+                    Message::OverDependencyBudget,
+                    &[&class, &targets.len(), &max, &targets.join(", ")],
+                ),
+                classes: vec![class.to_string()],
+            }
+        })
+        .collect()
+}
+
+// Markdown pages whose level-1 header declares the same class. Names that
+// only differ in case or underscores (`UserService`, `user_service`) count
+// as the same class, since wiki pages drift apart that way.
+pub fn duplicate_docs(root: &str, files: &[FileAnalysis], catalog: &Catalog) -> Vec<Finding> {
+    let mut pages: BTreeMap<String, BTreeMap<&str, BTreeSet<String>>> = BTreeMap::new();
+    for file in files {
+        if UnifiedAnalyzer::detect_file_type(&file.path) != FileType::Markdown {
+            continue;
+        }
+        for class in &file.result.classes {
+            pages
+                .entry(normalized_name(class))
+                .or_default()
+                .entry(class)
+                .or_default()
+                .insert(relative_path(root, &file.path));
+        }
+    }
+
+    pages
+        .into_values()
+        .filter_map(|names| {
+            let paths: BTreeSet<&String> = names.values().flatten().collect();
+            if paths.len() < 2 {
+                return None;
+            }
+            let classes: Vec<String> = names.keys().map(|name| name.to_string()).collect();
+            let paths: Vec<&str> = paths.into_iter().map(String::as_str).collect();
+            let message = if classes.len() == 1 { Message::DuplicateDoc } else { Message::DuplicateDocNear };
+            Some(Finding {
+                rule: "duplicate-doc".to_string(),
+                severity: Severity::Warning,
+                message: catalog.text(message, &[&classes.join(", "), &paths.join(", ")]),
+                classes,
+            })
+        })
+        .collect()
+}
+
+// Link targets that only Markdown pages mention: no code file defines or
+// refers to them, which usually means the class was renamed or deleted and
+// its docs were never updated. Docs-only trees have nothing to compare
+// against and report nothing.
+pub fn doc_only_classes(root: &str, files: &[FileAnalysis], catalog: &Catalog) -> Vec<Finding> {
+    let (docs, code): (Vec<&FileAnalysis>, Vec<&FileAnalysis>) = files
+        .iter()
+        .partition(|file| UnifiedAnalyzer::detect_file_type(&file.path) == FileType::Markdown);
+    if code.is_empty() {
+        return Vec::new();
+    }
+    let in_code: BTreeSet<&str> = code
+        .iter()
+        .flat_map(|file| {
+            let defined = file.result.classes.iter();
+            let linked = file.result.links.iter().flat_map(|link| [&link.from_class, &link.to_class]);
+            defined.chain(linked).map(String::as_str)
+        })
+        .collect();
+    let mut references: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
+    for file in docs {
+        for link in &file.result.links {
+            if !in_code.contains(link.to_class.as_str()) {
+                let location = format!("{}:{}", relative_path(root, &file.path), link.line_number);
+                references.entry(&link.to_class).or_default().insert(location);
+            }
+        }
+    }
+
+    references
+        .into_iter()
+        .map(|(class, locations)| {
+            let locations: Vec<String> = locations.into_iter().collect();
+            Finding {
+                rule: "doc-only-class".to_string(),
+                severity: Severity::Warning,
+                message: catalog.text(Message::DocOnlyClass, &[&class, &locations.join(", ")]),
+                classes: vec![class.to_string()],
+            }
+        })
+        .collect()
+}
+
+// `From -> To` or `From -> !To`; either side may be a glob over class names
+fn parse_assertion(statement: &str) -> Option<(&str, &str, bool)> {
+    let (from, to) = statement.split_once("->")?;
+    let (from, to) = (from.trim(), to.trim());
+    let (to, forbidden) = match to.strip_prefix('!') {
+        Some(to) => (to.trim_start(), true),
+        None => (to, false),
+    };
+    let is_name = |side: &str| !side.is_empty() && side.chars().all(|c| c.is_alphanumeric() || "_.:*?".contains(c));
+    (is_name(from) && is_name(to)).then_some((from, to, forbidden))
+}
+
+// Rules from ```classlink-assert blocks in Markdown pages, checked against
+// the links in code: `From -> To` needs at least one matching link and
+// `From -> !To` reports every one
+pub fn doc_assertions(root: &str, files: &[FileAnalysis], catalog: &Catalog) -> Vec<Finding> {
+    let links: BTreeSet<(&str, &str)> = files
+        .iter()
+        .flat_map(|file| &file.result.links)
+        .filter(|link| link.kind != LinkKind::Doc)
+        .map(|link| (link.from_class.as_str(), link.to_class.as_str()))
+        .collect();
+    let mut findings = Vec::new();
+    for file in files {
+        for (line, statement) in &file.result.assertions {
+            let location = format!("{}:{}", relative_path(root, &file.path), line);
+            let Some((from, to, forbidden)) = parse_assertion(statement) else {
+                findings.push(Finding {
+                    rule: "doc-assert".to_string(),
+                    severity: Severity::Error,
+                    message: catalog.text(Message::DocAssertInvalid, &[statement, &location]),
+                    classes: Vec::new(),
+                });
+                continue;
+            };
+            let mut matching = links.iter().filter(|(a, b)| glob::matches(from, a) && glob::matches(to, b)).peekable();
+            if forbidden {
+                findings.extend(matching.map(|(a, b)| Finding {
+                    rule: "doc-assert".to_string(),
+                    severity: Severity::Error,
+                    message: catalog.text(Message::DocAssertForbidden, &[a, b, statement, &location]),
+                    classes: vec![a.to_string(), b.to_string()],
+                }));
+            } else if matching.peek().is_none() {
+                findings.push(Finding {
+                    rule: "doc-assert".to_string(),
+                    severity: Severity::Error,
+                    message: catalog.text(Message::DocAssertMissing, &[statement, &location]),
+                    classes: [from, to].into_iter().filter(|side| !side.contains(['*', '?'])).map(str::to_string).collect(),
+                });
+            }
+        }
+    }
+    findings
+}
+
+fn normalized_name(class: &str) -> String {
+    class.chars().filter(|c| *c != '_').flat_map(char::to_lowercase).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Offender {
+    pub name: String,
+    pub findings: usize,
+}
+
+// Where one rule's findings are, so cleanup can be split across teams. A
+// finding counts towards the file defining its first class, a code
+// definition winning over a documentation page; findings on classes
+// defined nowhere only count towards the total.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleOffenders {
+    pub rule: String,
+    pub findings: usize,
+    // Most findings first
+    pub files: Vec<Offender>,
+    pub packages: Vec<Offender>,
+}
+
+fn ranked_offenders(counts: BTreeMap<String, usize>) -> Vec<Offender> {
+    let mut offenders: Vec<Offender> = counts.into_iter().map(|(name, findings)| Offender { name, findings }).collect();
+    offenders.sort_by(|a, b| b.findings.cmp(&a.findings).then_with(|| a.name.cmp(&b.name)));
+    offenders
+}
+
+#[derive(Default)]
+struct RuleTally {
+    findings: usize,
+    files: BTreeMap<String, usize>,
+    packages: BTreeMap<String, usize>,
+}
+
+pub fn offenders(root: &str, files: &[FileAnalysis], findings: &[Finding], grouping: Grouping) -> Vec<RuleOffenders> {
+    let namespaces = (grouping == Grouping::Namespace).then(|| manifest::class_namespaces(root, files));
+    let mut defined_in: BTreeMap<&str, (bool, String)> = BTreeMap::new();
+    for file in files {
+        for class in &file.result.classes {
+            let is_code = file.result.class_metrics.contains_key(class);
+            if defined_in.get(class.as_str()).is_none_or(|(known_code, _)| is_code && !known_code) {
+                defined_in.insert(class, (is_code, relative_path(root, &file.path)));
+            }
+        }
+    }
+
+    let mut rules: BTreeMap<&str, RuleTally> = BTreeMap::new();
+    for finding in findings {
+        let tally = rules.entry(&finding.rule).or_default();
+        tally.findings += 1;
+        let Some(class) = finding.classes.first() else {
+            continue;
+        };
+        let Some((_, path)) = defined_in.get(class.as_str()) else {
+            continue;
+        };
+        let package = match &namespaces {
+            Some(namespaces) => namespaces[class].clone(),
+            None => package_of(path),
+        };
+        *tally.files.entry(path.clone()).or_default() += 1;
+        *tally.packages.entry(package).or_default() += 1;
+    }
+    let mut offenders: Vec<RuleOffenders> = rules
+        .into_iter()
+        .map(|(rule, tally)| RuleOffenders {
+            rule: rule.to_string(),
+            findings: tally.findings,
+            files: ranked_offenders(tally.files),
+            packages: ranked_offenders(tally.packages),
+        })
+        .collect();
+    offenders.sort_by(|a, b| b.findings.cmp(&a.findings).then_with(|| a.rule.cmp(&b.rule)));
+    offenders
+}
+
+const OFFENDERS_TOP: usize = 3;
+
+pub fn render_offenders(offenders: &[RuleOffenders], catalog: &Catalog) -> String {
+    let mut out = String::new();
+    if offenders.is_empty() {
+        return out;
+    }
+    writeln!(out, "\n{}", catalog.text(Message::OffendersHeader, &[&OFFENDERS_TOP])).unwrap();
+    let top = |offenders: &[Offender]| -> String {
+        let top: Vec<String> = offenders
+            .iter()
+            .take(OFFENDERS_TOP)
+            .map(|offender| format!("{} ({})", offender.name, offender.findings))
+            .collect();
+        top.join(", ")
+    };
+    for rule in offenders {
+        writeln!(out, "{}", catalog.text(Message::OffenderRuleLine, &[&rule.rule, &rule.findings])).unwrap();
+        if !rule.files.is_empty() {
+            writeln!(out, "{}", catalog.text(Message::OffenderFilesLine, &[&top(&rule.files)])).unwrap();
+            writeln!(out, "{}", catalog.text(Message::OffenderPackagesLine, &[&top(&rule.packages)])).unwrap();
+        }
+    }
+    out
+}
+
+pub fn exit_code(findings: &[Finding]) -> i32 {
+    findings
+        .iter()
+        .map(|finding| finding.severity)
+        .max()
+        .map_or(0, Severity::exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ClassLink;
+    use std::collections::HashSet;
+
+    fn link(from: &str, to: &str) -> ClassLink {
+        ClassLink {
+            from_class: from.to_string(),
+            to_class: to.to_string(),
+            line_number: 1,
+            kind: Default::default(),
+            position: None,
+        }
+    }
+
+    #[test]
+    fn test_collect_findings_for_cycles_and_isolated_classes() {
+        let classes: HashSet<String> = ["A", "B", "C", "Lonely"].iter().map(|s| s.to_string()).collect();
+        let graph = ClassGraph::new(&classes, &[link("A", "C"), link("C", "B"), link("B", "A")]);
+
+        let findings = collect_findings(&graph, &BTreeMap::new(), &Catalog::default());
+
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].rule, "circular-dependency");
+        assert_eq!(findings[0].severity, Severity::Error);
+        // The message follows the links, not the sorted component
+        assert_eq!(findings[0].message, "Circular dependency: A -> C -> B -> A");
+        assert_eq!(findings[0].classes, vec!["A", "B", "C"]);
+        assert_eq!(findings[1].rule, "isolated-class");
+        assert_eq!(findings[1].classes, vec!["Lonely"]);
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_isolated_classes_are_classified() {
+        let sources = [
+            ("src/main.py", "class Runner:\n    pass\n"),
+            ("src/program.py", "class Program:\n    pass\n"),
+            ("tests/test_cart.py", "class CartFixture:\n    pass\n"),
+            ("src/shop.py", "__all__ = [\"Shop\"]\n\nclass Shop:\n    pass\n\nclass Leftover:\n    pass\n"),
+        ];
+        let files: Vec<FileAnalysis> = sources
+            .iter()
+            .map(|(path, content)| FileAnalysis {
+                path: format!("/repo/{}", path),
+                result: UnifiedAnalyzer::new().analyze_file(content, path),
+                encoding: Default::default(),
+            })
+            .collect();
+        let merged = crate::project::merge(files.clone());
+
+        let isolation =
+            classify_isolated("/repo", &files, &merged, &default_entry_points(), &impact::default_test_patterns());
+
+        assert_eq!(isolation["Runner"], Isolation::EntryPoint);
+        assert_eq!(isolation["Program"], Isolation::EntryPoint);
+        assert_eq!(isolation["CartFixture"], Isolation::TestHelper);
+        assert_eq!(isolation["Shop"], Isolation::Exported);
+        assert_eq!(isolation["Leftover"], Isolation::Dead);
+        let graph = ClassGraph::new(&merged.classes, &merged.links);
+        let findings = collect_findings(&graph, &isolation, &Catalog::default());
+        let warnings: Vec<&str> = findings
+            .iter()
+            .filter(|finding| finding.severity == Severity::Warning)
+            .map(|finding| finding.rule.as_str())
+            .collect();
+        assert_eq!(warnings, vec!["isolated-class"]);
+        assert!(findings.iter().any(|finding| finding.rule == "isolated-export" && finding.classes == ["Shop"]));
+    }
+
+    #[test]
+    fn test_deprecated_classes_still_in_use() {
+        let mut result = AnalysisResult {
+            links: vec![link("Shop", "OldCart"), link("Admin", "OldCart"), link("OldCart", "LegacyDb"), link("Shop", "Db")],
+            ..Default::default()
+        };
+        for class in ["OldCart", "LegacyDb"] {
+            result.class_tags.entry(class.to_string()).or_default().insert(ClassTag::Deprecated);
+        }
+
+        let findings = deprecated_in_use(&result, &Catalog::default());
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "deprecated-in-use");
+        assert_eq!(findings[0].classes, vec!["OldCart", "Admin", "Shop"]);
+        assert_eq!(findings[0].message, "OldCart is deprecated but still used by Admin, Shop");
+    }
+
+    #[test]
+    fn test_exit_code_uses_highest_severity() {
+        let finding = |severity| Finding {
+            rule: "test".to_string(),
+            severity,
+            message: String::new(),
+            classes: vec![],
+        };
+
+        assert_eq!(exit_code(&[]), 0);
+        assert_eq!(exit_code(&[finding(Severity::Info)]), 0);
+        assert_eq!(exit_code(&[finding(Severity::Warning)]), 2);
+        assert_eq!(exit_code(&[finding(Severity::Warning), finding(Severity::Error)]), 3);
+    }
+
+    #[test]
+    fn test_duplicate_docs() {
+        let page = |path: &str, classes: &[&str]| FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|s| s.to_string()).collect(),
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        };
+        let files = vec![
+            page("/wiki/User.md", &["User"]),
+            page("/wiki/old/User.md", &["User"]),
+            page("/wiki/OrderService.md", &["OrderService"]),
+            page("/wiki/order_service.md", &["order_service"]),
+            page("/wiki/Cart.md", &["Cart"]),
+            page("/src/cart.py", &["Cart"]),
+        ];
+
+        let findings = duplicate_docs("/wiki", &files, &Catalog::default());
+
+        assert_eq!(findings.len(), 2);
+        assert!(findings.iter().all(|finding| finding.rule == "duplicate-doc"));
+        assert_eq!(findings[0].classes, vec!["OrderService", "order_service"]);
+        assert_eq!(
+            findings[0].message,
+            "OrderService, order_service look like one class documented separately: OrderService.md, order_service.md"
+        );
+        assert_eq!(findings[1].message, "User is documented in several files: User.md, old/User.md");
+    }
+
+    #[test]
+    fn test_doc_only_classes() {
+        let file = |path: &str, classes: &[&str], links: Vec<ClassLink>| FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|s| s.to_string()).collect(),
+                links,
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        };
+        let docs = vec![
+            file("/repo/docs/Shop.md", &["Shop"], vec![link("Shop", "Cart"), link("Shop", "OldBasket"), link("Shop", "Logger")]),
+            file("/repo/docs/Cart.md", &["Cart"], vec![link("Cart", "OldBasket")]),
+        ];
+        let mut files = docs.clone();
+        files.push(file("/repo/src/shop.py", &["Shop", "Cart"], vec![link("Shop", "Logger")]));
+
+        let findings = doc_only_classes("/repo", &files, &Catalog::default());
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "doc-only-class");
+        assert_eq!(findings[0].classes, vec!["OldBasket"]);
+        assert_eq!(
+            findings[0].message,
+            "OldBasket is only referenced from documentation, no code defines it: docs/Cart.md:1, docs/Shop.md:1"
+        );
+        assert!(doc_only_classes("/repo", &docs, &Catalog::default()).is_empty());
+    }
+
+    #[test]
+    fn test_doc_assertions() {
+        let mut docs = AnalysisResult::default();
+        for (line, statement) in [(5, "UserService -> UserRepository"), (6, "*Service -> !Database*"), (7, "Mailer -> Smtp"), (8, "UserService")] {
+            docs.assertions.insert(line, statement.to_string());
+        }
+        let code = AnalysisResult {
+            links: vec![link("UserService", "UserRepository"), link("UserService", "DatabaseContext"), link("OrderService", "DatabaseContext")],
+            ..Default::default()
+        };
+        let files = vec![
+            FileAnalysis {
+                path: "/repo/docs/architecture.md".to_string(),
+                result: docs,
+                encoding: Default::default(),
+            },
+            FileAnalysis {
+                path: "/repo/src/services.py".to_string(),
+                result: code,
+                encoding: Default::default(),
+            },
+        ];
+
+        let findings = doc_assertions("/repo", &files, &Catalog::default());
+
+        let messages: Vec<&str> = findings.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "OrderService uses DatabaseContext, which `*Service -> !Database*` forbids (docs/architecture.md:6)",
+                "UserService uses DatabaseContext, which `*Service -> !Database*` forbids (docs/architecture.md:6)",
+                "No link satisfies `Mailer -> Smtp` (docs/architecture.md:7)",
+                "Cannot parse assertion `UserService` (docs/architecture.md:8), expected `From -> To` or `From -> !To`",
+            ]
+        );
+        assert!(findings.iter().all(|f| f.rule == "doc-assert" && f.severity == Severity::Error));
+        assert_eq!(findings[2].classes, vec!["Mailer", "Smtp"]);
+    }
+
+    #[test]
+    fn test_layer_skips() {
+        let file = |path: &str, classes: &[&str], links: Vec<ClassLink>| FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|s| s.to_string()).collect(),
+                links,
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        };
+        let files = vec![
+            file("/repo/ui/page.py", &["Page"], vec![link("Page", "Service"), link("Page", "Repo"), link("Page", "Repo")]),
+            file("/repo/app/service.py", &["Service"], vec![link("Service", "Repo")]),
+            file("/repo/db/repo.py", &["Repo"], vec![link("Repo", "Page")]),
+        ];
+        let patterns = BTreeMap::from([
+            ("ui".to_string(), vec!["ui/**".to_string()]),
+            ("app".to_string(), vec!["app/**".to_string()]),
+            ("db".to_string(), vec!["db/**".to_string()]),
+        ]);
+        let order = vec!["ui".to_string(), "app".to_string(), "db".to_string()];
+
+        let findings = layer_skips("/repo", &files, &patterns, &order, &Catalog::default());
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "layer-skip");
+        assert_eq!(findings[0].message, "Page (ui) uses Repo (db) directly, skipping app");
+    }
+
+    #[test]
+    fn test_over_dependency_budget() {
+        let mut result = AnalysisResult {
+            links: vec![link("Shop", "Cart"), link("Shop", "Cart"), link("Shop", "Db"), link("Shop", "Mailer"), link("Cart", "Db")],
+            ..Default::default()
+        };
+        result.dependency_budgets.insert("Shop".to_string(), 2);
+        result.dependency_budgets.insert("Cart".to_string(), 1);
+
+        let findings = over_dependency_budget(&result, &Catalog::default());
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "dependency-budget");
+        assert_eq!(findings[0].message, "Shop depends on 3 classes, over its declared max-deps of 2: Cart, Db, Mailer");
+    }
+
+    #[test]
+    fn test_offenders_group_findings_by_file_and_package() {
+        let code = |classes: &[&str]| {
+            let mut result = AnalysisResult::default();
+            for class in classes {
+                result.classes.insert(class.to_string());
+                result.class_metrics.insert(class.to_string(), Default::default());
+            }
+            result
+        };
+        let mut docs = AnalysisResult::default();
+        docs.classes.insert("Cart".to_string());
+        let files = vec![
+            FileAnalysis {
+                path: "/repo/docs/Cart.md".to_string(),
+                result: docs,
+                encoding: Default::default(),
+            },
+            FileAnalysis {
+                path: "/repo/src/shop/cart.py".to_string(),
+                result: code(&["Cart", "Basket"]),
+                encoding: Default::default(),
+            },
+            FileAnalysis {
+                path: "/repo/src/db/repo.py".to_string(),
+                result: code(&["Repo"]),
+                encoding: Default::default(),
+            },
+        ];
+        let finding = |rule: &str, class: &str| Finding {
+            rule: rule.to_string(),
+            severity: Severity::Warning,
+            message: String::new(),
+            classes: vec![class.to_string()],
+        };
+        let findings = vec![
+            finding("isolated-class", "Repo"),
+            finding("isolated-class", "Cart"),
+            finding("isolated-class", "Basket"),
+            finding("isolated-class", "Ghost"),
+            finding("layer-skip", "Repo"),
+        ];
+
+        let offenders = offenders("/repo", &files, &findings, Grouping::Directory);
+
+        assert_eq!(offenders.len(), 2);
+        assert_eq!((offenders[0].rule.as_str(), offenders[0].findings), ("isolated-class", 4));
+        // Cart counts towards its code, not its documentation page
+        let files: Vec<(&str, usize)> = offenders[0].files.iter().map(|o| (o.name.as_str(), o.findings)).collect();
+        assert_eq!(files, vec![("src/shop/cart.py", 2), ("src/db/repo.py", 1)]);
+        assert_eq!((offenders[0].packages[0].name.as_str(), offenders[0].packages[0].findings), ("src/shop", 2));
+        let text = render_offenders(&offenders, &Catalog::default());
+        assert!(text.contains("  isolated-class: 4 findings\n    files: src/shop/cart.py (2), src/db/repo.py (1)\n"));
+        assert!(text.contains("  layer-skip: 1 findings\n    files: src/db/repo.py (1)\n    packages: src/db (1)\n"));
+    }
+}
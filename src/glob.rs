@@ -0,0 +1,79 @@
+// Minimal gitignore-style globs over `/`-separated relative paths:
+// `*` and `?` stay within one path segment, `**` spans directories, and a
+// pattern without `/` is matched against the file name alone.
+pub fn matches(pattern: &str, path: &str) -> bool {
+    let path = path.replace('\\', "/");
+    let pattern: Vec<char> = pattern.chars().collect();
+
+    if !pattern.contains(&'/') {
+        let name = path.rsplit('/').next().unwrap_or_default();
+        return match_from(&pattern, &name.chars().collect::<Vec<_>>());
+    }
+    match_from(&pattern, &path.chars().collect::<Vec<_>>())
+}
+
+pub fn matches_any<S: AsRef<str>>(patterns: &[S], path: &str) -> bool {
+    patterns.iter().any(|pattern| matches(pattern.as_ref(), path))
+}
+
+// For paths that are not relative to a known root: a pattern with `/`
+// matches any trailing run of whole segments, so `workflows/*.yaml` matches
+// `/repo/ci/workflows/build.yaml`
+pub fn matches_tail(pattern: &str, path: &str) -> bool {
+    let path = path.replace('\\', "/");
+    matches(pattern, &path)
+        || path
+            .match_indices('/')
+            .any(|(index, _)| matches(pattern, &path[index + 1..]))
+}
+
+fn match_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', '/', rest @ ..] => {
+            // `**/` matches zero or more leading directories
+            match_from(rest, text)
+                || (0..text.len())
+                    .filter(|&i| text[i] == '/')
+                    .any(|i| match_from(rest, &text[i + 1..]))
+        }
+        ['*', '*', rest @ ..] => (0..=text.len()).any(|i| match_from(rest, &text[i..])),
+        ['*', rest @ ..] => {
+            let segment_end = text.iter().position(|&c| c == '/').unwrap_or(text.len());
+            (0..=segment_end).any(|i| match_from(rest, &text[i..]))
+        }
+        ['?', rest @ ..] => {
+            matches!(text.first(), Some(&c) if c != '/') && match_from(rest, &text[1..])
+        }
+        [literal, rest @ ..] => text.first() == Some(literal) && match_from(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_segment_wildcards() {
+        assert!(matches("*.spec.ts", "src/app/user.spec.ts"));
+        assert!(matches("test_?.py", "test_a.py"));
+        assert!(!matches("src/*.py", "src/nested/a.py"));
+        assert!(matches("src/*.py", "src/a.py"));
+    }
+
+    #[test]
+    fn test_double_star_spans_directories() {
+        assert!(matches("**/tests/**", "tests/test_api.py"));
+        assert!(matches("**/tests/**", "pkg/tests/unit/test_api.py"));
+        assert!(matches("src/**/*.cs", "src/Services/Deep/User.cs"));
+        assert!(matches("src/**/*.cs", "src/User.cs"));
+        assert!(!matches("**/tests/**", "src/contests.py"));
+    }
+
+    #[test]
+    fn test_matches_tail() {
+        assert!(matches_tail("workflows/*.yaml", "/repo/ci/workflows/build.yaml"));
+        assert!(matches_tail("*.flow", "/repo/a.flow"));
+        assert!(!matches_tail("workflows/*.yaml", "/repo/myworkflows/build.yaml"));
+    }
+}
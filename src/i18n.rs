@@ -0,0 +1,495 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Ja,
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "en" => Ok(Locale::En),
+            "ja" => Ok(Locale::Ja),
+            _ => Err(format!("Unsupported locale: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    ReportTitle,
+    OverallStatistics,
+    TotalClasses,
+    IsolatedClassCount,
+    IsolatedClassesHeader,
+    IsolatedEntryPointsHeader,
+    IsolatedTestHelpersHeader,
+    IsolatedExportsHeader,
+    ClassLinkCounts,
+    ClassLinkCountLine,
+    MostLinkingClass,
+    MostLinkedClass,
+    AbstractionHeader,
+    AbstractionLine,
+    CustomMetricHeader,
+    CustomMetricLine,
+    FindingsHeader,
+    OffendersHeader,
+    OffenderRuleLine,
+    OffenderFilesLine,
+    OffenderPackagesLine,
+    CircularDependency,
+    IsolatedClass,
+    IsolatedEntryPoint,
+    IsolatedTestHelper,
+    IsolatedExport,
+    DeprecatedInUse,
+    DuplicateDoc,
+    DuplicateDocNear,
+    DocOnlyClass,
+    DocAssertForbidden,
+    DocAssertMissing,
+    DocAssertInvalid,
+    LayerSkip,
+    OverDependencyBudget,
+    ExpiredException,
+    InvalidExceptionExpiry,
+    ScriptLinkFinding,
+    ScriptClassFinding,
+    SuppressedHeader,
+    SuppressedLine,
+    SkippedFilesHeader,
+    SkippedTooLarge,
+    SkippedBinary,
+    SkippedVendored,
+    SkippedMinified,
+    ScanCancelled,
+    ScanTimedOut,
+    ScanTruncated,
+    DiffTitle,
+    DiffNoChanges,
+    DiffRenamedClass,
+    DiffAddedClass,
+    DiffRemovedClass,
+    DiffAddedLink,
+    DiffRemovedLink,
+    DiffMarkdownTitle,
+    DiffMarkdownSummary,
+    DiffMarkdownWas,
+    DiffMarkdownTableHeader,
+    DiffMarkdownMore,
+    ImpactTitle,
+    ImpactChangedClasses,
+    ImpactNoDependents,
+    ImpactDepth,
+    ImpactTestFiles,
+    AccuracyTitle,
+    AccuracyLine,
+    AccuracyOverall,
+    AccuracyMissed,
+    AccuracySpurious,
+    AccuracyUnanalyzed,
+    DocSyncTitle,
+    DocSyncInSync,
+    DocSyncStubsHeader,
+    DocSyncMismatchHeader,
+    DocSyncUndocumented,
+    DocSyncStale,
+    ScaffoldCreated,
+    ScaffoldNothing,
+    VerifyMatch,
+    VerifyMismatch,
+    VerifyTool,
+    VerifyChanged,
+    VerifyMissing,
+    VerifyAdded,
+    WorkspaceTitle,
+    WorkspaceRootLine,
+    WorkspaceDuplicates,
+    QueryRowCount,
+    CouplingHeader,
+    CouplingLine,
+    ExternalCouplingHeader,
+    ExternalPackagesHeader,
+    ExternalCouplingLine,
+    ExternalNodesHeader,
+    ExternalNodeLine,
+    ExternalNodeUnknownLine,
+    ExternalFanOutHeader,
+    ExternalPackageFanOutHeader,
+    ExternalFanOutLine,
+    TagStatisticsHeader,
+    TagLine,
+    TagMatrixHeader,
+    TagMatrixLine,
+    TravelHeader,
+    TravelLine,
+    TravelAverage,
+    TravelUnreachable,
+    OwnershipTeamsHeader,
+    OwnershipAuthorsHeader,
+    OwnershipLine,
+    CrossRepoHeader,
+    CrossRepoLine,
+    CrossRepoAmbiguous,
+    HotspotsHeader,
+    HotspotLine,
+    ChurnHotspotsHeader,
+    ChurnHotspotLine,
+    CiSummary,
+    CiDelta,
+    CiNoDelta,
+    DeltaClasses,
+    DeltaLinks,
+    DeltaCycles,
+    DeltaIsolated,
+    DeltaErrors,
+    DeltaWarnings,
+}
+
+impl Message {
+    // Templates use positional `{0}`, `{1}`, ... placeholders so translations
+    // can reorder arguments
+    fn template(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::En => match self {
+                Message::ReportTitle => "=== Class Link Analysis Report ===",
+                Message::OverallStatistics => "📊 Overall Statistics:",
+                Message::TotalClasses => "  Total Classes: {0}",
+                Message::IsolatedClassCount => "  Isolated Classes: {0}",
+                Message::IsolatedClassesHeader => "🏝️  Isolated Classes (no links):",
+                Message::IsolatedEntryPointsHeader => "🚪 Isolated Entry Points:",
+                Message::IsolatedTestHelpersHeader => "🧪 Isolated Test Helpers:",
+                Message::IsolatedExportsHeader => "📤 Isolated Exports (unused in the project):",
+                Message::ClassLinkCounts => "🔗 Class Link Counts:",
+                Message::ClassLinkCountLine => "  📦 {0}: {1} outgoing, {2} incoming",
+                Message::MostLinkingClass => "🔝 Most Linking Class: {0} ({1} outgoing links)",
+                Message::MostLinkedClass => "🎯 Most Linked Class: {0} ({1} incoming links)",
+                Message::AbstractionHeader => "🧩 Dependency on Abstractions:",
+                Message::AbstractionLine => "  - {0}: {1} abstract, {2} concrete ({3}% on abstractions)",
+                Message::CustomMetricHeader => "📏 {0} (highest {1}):",
+                Message::CustomMetricLine => "  - {0}: {1}",
+                Message::FindingsHeader => "🚨 Findings:",
+                Message::OffendersHeader => "📍 Findings by File and Package (top {0} per rule):",
+                Message::OffenderRuleLine => "  {0}: {1} findings",
+                Message::OffenderFilesLine => "    files: {0}",
+                Message::OffenderPackagesLine => "    packages: {0}",
+                Message::CircularDependency => "Circular dependency: {0}",
+                Message::IsolatedClass => "{0} has no incoming or outgoing links",
+                Message::IsolatedEntryPoint => "{0} is an entry point with no links",
+                Message::IsolatedTestHelper => "{0} is only defined in test files and has no links",
+                Message::IsolatedExport => "{0} is exported but nothing in the project links to it",
+                Message::DeprecatedInUse => "{0} is deprecated but still used by {1}",
+                Message::DuplicateDoc => "{0} is documented in several files: {1}",
+                Message::DuplicateDocNear => "{0} look like one class documented separately: {1}",
+                Message::DocOnlyClass => "{0} is only referenced from documentation, no code defines it: {1}",
+                Message::DocAssertForbidden => "{0} uses {1}, which `{2}` forbids ({3})",
+                Message::DocAssertMissing => "No link satisfies `{0}` ({1})",
+                Message::DocAssertInvalid => "Cannot parse assertion `{0}` ({1}), expected `From -> To` or `From -> !To`",
+                Message::LayerSkip => "{0} ({1}) uses {2} ({3}) directly, skipping {4}",
+                Message::OverDependencyBudget => "{0} depends on {1} classes, over its declared max-deps of {2}: {3}",
+                Message::ExpiredException => "Exception for {0} ({1}) expired on {2}: {3}",
+                Message::InvalidExceptionExpiry => {
+                    "Exception for {0} ({1}) has an invalid expiry date '{2}' (expected an existing YYYY-MM-DD date)"
+                }
+                Message::ScriptLinkFinding => "{0} -> {1} matches rule {2}",
+                Message::ScriptClassFinding => "{0} matches rule {1}",
+                Message::SuppressedHeader => "🙈 Suppressed by exceptions:",
+                Message::SuppressedLine => "  [{0}] {1} ({2})",
+                Message::SkippedFilesHeader => "⏭️  Skipped Files:",
+                Message::SkippedTooLarge => "  - {0} ({1} bytes, over the {2} byte limit)",
+                Message::SkippedBinary => "  - {0} (binary)",
+                Message::SkippedVendored => "  - {0} (vendored)",
+                Message::SkippedMinified => "  - {0} (minified)",
+                Message::ScanCancelled => "⏹️  Analysis was cancelled; results cover only the files analyzed before that",
+                Message::ScanTimedOut => "⏱️  Analysis timed out; results cover only the files analyzed before the deadline",
+                Message::ScanTruncated => "✂️  Analysis stopped at the limit of {1} {0}; results are partial",
+                Message::DiffTitle => "=== Class Graph Diff ===",
+                Message::DiffNoChanges => "No changes",
+                Message::DiffRenamedClass => "~ class {0} -> {1} ({2}% similar)",
+                Message::DiffAddedClass => "+ class {0}",
+                Message::DiffRemovedClass => "- class {0}",
+                Message::DiffAddedLink => "+ link {0} -> {1}",
+                Message::DiffRemovedLink => "- link {0} -> {1}",
+                Message::DiffMarkdownTitle => "### Class graph changes",
+                Message::DiffMarkdownSummary => {
+                    "**{0}** added, **{1}** removed, **{2}** renamed classes; **{3}** added and **{4}** removed links"
+                }
+                Message::DiffMarkdownWas => "was {0}",
+                Message::DiffMarkdownTableHeader => "| | From | To |",
+                Message::DiffMarkdownMore => "…and {0} more",
+                Message::ImpactTitle => "=== Impact of Changing {0} ===",
+                Message::ImpactChangedClasses => "✏️  Changed: {0}",
+                Message::ImpactNoDependents => "No other classes depend on the target",
+                Message::ImpactDepth => "📶 Depth {0}:",
+                Message::ImpactTestFiles => "🧪 Test Files:",
+                Message::AccuracyTitle => "=== Extraction Accuracy ===",
+                Message::AccuracyLine => "{0}: precision {1}, recall {2}, F1 {3} ({4} files; {5} correct, {6} spurious, {7} missed)",
+                Message::AccuracyOverall => "overall",
+                Message::AccuracyMissed => "❌ Missed links:",
+                Message::AccuracySpurious => "➕ Spurious links:",
+                Message::AccuracyUnanalyzed => "⏭️  Ground-truth files not analyzed:",
+                Message::DocSyncTitle => "=== Docs Sync ===",
+                Message::DocSyncInSync => "✅ Documentation matches the code",
+                Message::DocSyncStubsHeader => "📝 Undocumented classes, proposed pages ({0}):",
+                Message::DocSyncMismatchHeader => "🔀 Pages disagreeing with the code ({0}):",
+                Message::DocSyncUndocumented => "missing from docs: {0}",
+                Message::DocSyncStale => "not linked in code: {0}",
+                Message::ScaffoldCreated => "📝 Created {0}",
+                Message::ScaffoldNothing => "✅ Every class already has a page",
+                Message::VerifyMatch => "✅ The report matches the working tree",
+                Message::VerifyMismatch => "❌ The report no longer matches the working tree:",
+                Message::VerifyTool => "recorded with {0}, running {1}",
+                Message::VerifyChanged => "changed: {0}",
+                Message::VerifyMissing => "missing: {0}",
+                Message::VerifyAdded => "not in the report: {0}",
+                Message::WorkspaceTitle => "=== Workspace ===",
+                Message::WorkspaceRootLine => {
+                    "📁 {0}: {1} files, {2} classes ({3} isolated), {4} links to other roots, {5} from other roots, {6} unresolved"
+                }
+                Message::WorkspaceDuplicates => "⚠️  Classes defined in several roots:",
+                Message::QueryRowCount => "{0} rows",
+                Message::CouplingHeader => "⚖️  Weighted Coupling (top {0}):",
+                Message::CouplingLine => "  {0}: {1} (in {2}, out {3})",
+                Message::ExternalCouplingHeader => "🏗️  Framework Coupling (top {0}):",
+                Message::ExternalPackagesHeader => "🏗️  Framework Coupling by Package (top {0}):",
+                Message::ExternalCouplingLine => "  {0}: {1} links ({2})",
+                Message::ExternalNodesHeader => "📦 External Classes: {0} (top {1}):",
+                Message::ExternalNodeLine => "  {0} ({1}): {2} dependents, {3} links",
+                Message::ExternalNodeUnknownLine => "  {0}: {1} dependents, {2} links",
+                Message::ExternalFanOutHeader => "🔌 External Fan-out (top {0}):",
+                Message::ExternalPackageFanOutHeader => "🔌 External Fan-out by Package (top {0}):",
+                Message::ExternalFanOutLine => "  {0}: {1} external classes ({2})",
+                Message::TagStatisticsHeader => "🏷️  Tags:",
+                Message::TagLine => "  {0}: {1} classes, {2} links within, {3} in, {4} out",
+                Message::TagMatrixHeader => "🏷️  Links Between Tags:",
+                Message::TagMatrixLine => "  {0} -> {1}: {2} links",
+                Message::TravelHeader => "🧭 Travel Distance from {0} to {1}:",
+                Message::TravelLine => "  {0} hops: {1} classes",
+                Message::TravelAverage => "  average: {0} hops",
+                Message::TravelUnreachable => "  no path to {0}: {1}",
+                Message::OwnershipTeamsHeader => "👥 Ownership by Team:",
+                Message::OwnershipAuthorsHeader => "👤 Ownership by Author:",
+                Message::OwnershipLine => "  {0}: {1} classes, fan-in {2}, fan-out {3}, {4} cross-team links",
+                Message::CrossRepoHeader => "🌐 Cross-Repository Links:",
+                Message::CrossRepoLine => "  {0} -> {1}: {2} links ({3})",
+                Message::CrossRepoAmbiguous => "❓ Defined in several other repositories, left unresolved: {0}",
+                Message::HotspotsHeader => "🔥 Refactoring Hotspots (size × coupling, top {0}):",
+                Message::HotspotLine => "  {0}: {1} lines, {2} methods, coupling {3}",
+                Message::ChurnHotspotsHeader => "🌡️  Change Hotspots (commits × coupling, top {0}):",
+                Message::ChurnHotspotLine => "  {0}: {1} commits, coupling {2}, score {3}",
+                Message::CiSummary => {
+                    "classlink: {0} classes, {1} isolated, {2} errors, {3} warnings (report: {4})"
+                }
+                Message::CiDelta => "classlink: {0} since last run",
+                Message::CiNoDelta => "classlink: no change since last run",
+                Message::DeltaClasses => "{0} classes",
+                Message::DeltaLinks => "{0} links",
+                Message::DeltaCycles => "{0} cycles",
+                Message::DeltaIsolated => "{0} isolated classes",
+                Message::DeltaErrors => "{0} errors",
+                Message::DeltaWarnings => "{0} warnings",
+            },
+            Locale::Ja => match self {
+                Message::ReportTitle => "=== クラスリンク分析レポート ===",
+                Message::OverallStatistics => "📊 全体統計:",
+                Message::TotalClasses => "  クラス総数: {0}",
+                Message::IsolatedClassCount => "  孤立クラス数: {0}",
+                Message::IsolatedClassesHeader => "🏝️  孤立クラス（リンクなし）:",
+                Message::IsolatedEntryPointsHeader => "🚪 孤立したエントリーポイント:",
+                Message::IsolatedTestHelpersHeader => "🧪 孤立したテスト用クラス:",
+                Message::IsolatedExportsHeader => "📤 孤立した公開クラス（プロジェクト内で未使用）:",
+                Message::ClassLinkCounts => "🔗 クラス別リンク数:",
+                Message::ClassLinkCountLine => "  📦 {0}: 発リンク {1} 件、被リンク {2} 件",
+                Message::MostLinkingClass => "🔝 最多発リンククラス: {0}（発リンク {1} 件）",
+                Message::MostLinkedClass => "🎯 最多被リンククラス: {0}（被リンク {1} 件）",
+                Message::AbstractionHeader => "🧩 抽象への依存:",
+                Message::AbstractionLine => "  - {0}: 抽象 {1} 件、具象 {2} 件（抽象への依存 {3}%）",
+                Message::CustomMetricHeader => "📏 {0}（上位 {1} 件）:",
+                Message::CustomMetricLine => "  - {0}: {1}",
+                Message::FindingsHeader => "🚨 検出事項:",
+                Message::OffendersHeader => "📍 ファイル・パッケージ別検出事項（ルールごとに上位 {0} 件）:",
+                Message::OffenderRuleLine => "  {0}: {1} 件",
+                Message::OffenderFilesLine => "    ファイル: {0}",
+                Message::OffenderPackagesLine => "    パッケージ: {0}",
+                Message::CircularDependency => "循環依存: {0}",
+                Message::IsolatedClass => "{0} には発リンクも被リンクもありません",
+                Message::IsolatedEntryPoint => "{0} はリンクのないエントリーポイントです",
+                Message::IsolatedTestHelper => "{0} はテストファイルにのみ定義され、リンクがありません",
+                Message::IsolatedExport => "{0} は公開されていますが、プロジェクト内からリンクされていません",
+                Message::DeprecatedInUse => "{0} は非推奨ですが、まだ {1} から使われています",
+                Message::DuplicateDoc => "{0} が複数のファイルで文書化されています: {1}",
+                Message::DuplicateDocNear => "{0} は同じクラスが別々に文書化されているようです: {1}",
+                Message::DocOnlyClass => "{0} はドキュメントからのみ参照され、コードに定義がありません: {1}",
+                Message::DocAssertForbidden => "{0} が {1} を使用しており、`{2}` に違反しています（{3}）",
+                Message::DocAssertMissing => "`{0}` を満たすリンクがありません（{1}）",
+                Message::DocAssertInvalid => "アサーション `{0}` を解析できません（{1}）。`From -> To` または `From -> !To` の形式で記述してください",
+                Message::LayerSkip => "{0}（{1}）が {4} を経由せずに {2}（{3}）を直接使用しています",
+                Message::OverDependencyBudget => "{0} は {1} クラスに依存しており、宣言された max-deps {2} を超えています: {3}",
+                Message::ExpiredException => "{0}（{1}）の例外は {2} に期限切れになりました: {3}",
+                Message::InvalidExceptionExpiry => {
+                    "{0}（{1}）の例外の期限 '{2}' は無効な日付です（実在する日付を YYYY-MM-DD 形式で指定してください）"
+                }
+                Message::ScriptLinkFinding => "{0} -> {1} はルール {2} に該当します",
+                Message::ScriptClassFinding => "{0} はルール {1} に該当します",
+                Message::SuppressedHeader => "🙈 例外により抑制された検出事項:",
+                Message::SuppressedLine => "  [{0}] {1}（{2}）",
+                Message::SkippedFilesHeader => "⏭️  スキップしたファイル:",
+                Message::SkippedTooLarge => "  - {0}（{1} バイト、上限 {2} バイト超過）",
+                Message::SkippedBinary => "  - {0}（バイナリ）",
+                Message::SkippedVendored => "  - {0}（外部ライブラリ）",
+                Message::SkippedMinified => "  - {0}（圧縮済み）",
+                Message::ScanCancelled => "⏹️  解析はキャンセルされました。結果はそれまでに解析したファイルのみです",
+                Message::ScanTimedOut => "⏱️  解析がタイムアウトしました。結果は期限までに解析したファイルのみです",
+                Message::ScanTruncated => "✂️  {0} の上限（{1}）に達したため解析を打ち切りました。結果は一部のみです",
+                Message::DiffTitle => "=== クラスグラフ差分 ===",
+                Message::DiffNoChanges => "変更なし",
+                Message::DiffRenamedClass => "~ クラス {0} -> {1}（類似度 {2}%）",
+                Message::DiffAddedClass => "+ クラス {0}",
+                Message::DiffRemovedClass => "- クラス {0}",
+                Message::DiffAddedLink => "+ リンク {0} -> {1}",
+                Message::DiffRemovedLink => "- リンク {0} -> {1}",
+                Message::DiffMarkdownTitle => "### クラスグラフの変更",
+                Message::DiffMarkdownSummary => {
+                    "クラス: 追加 **{0}**、削除 **{1}**、名前変更 **{2}**／リンク: 追加 **{3}**、削除 **{4}**"
+                }
+                Message::DiffMarkdownWas => "旧 {0}",
+                Message::DiffMarkdownTableHeader => "| | 参照元 | 参照先 |",
+                Message::DiffMarkdownMore => "…ほか {0} 件",
+                Message::ImpactTitle => "=== {0} の変更による影響 ===",
+                Message::ImpactChangedClasses => "✏️  変更対象: {0}",
+                Message::ImpactNoDependents => "対象に依存するクラスはありません",
+                Message::ImpactDepth => "📶 深さ {0}:",
+                Message::ImpactTestFiles => "🧪 テストファイル:",
+                Message::AccuracyTitle => "=== 抽出精度 ===",
+                Message::AccuracyLine => "{0}: 適合率 {1}、再現率 {2}、F1 {3}（{4} ファイル、正解 {5} 件、誤検出 {6} 件、見逃し {7} 件）",
+                Message::AccuracyOverall => "全体",
+                Message::AccuracyMissed => "❌ 見逃したリンク:",
+                Message::AccuracySpurious => "➕ 誤検出したリンク:",
+                Message::AccuracyUnanalyzed => "⏭️  解析されなかった正解データのファイル:",
+                Message::DocSyncTitle => "=== ドキュメント同期 ===",
+                Message::DocSyncInSync => "✅ ドキュメントはコードと一致しています",
+                Message::DocSyncStubsHeader => "📝 ドキュメントのないクラスと提案するページ（{0} 件）:",
+                Message::DocSyncMismatchHeader => "🔀 コードと食い違うページ（{0} 件）:",
+                Message::DocSyncUndocumented => "ドキュメントに記載なし: {0}",
+                Message::DocSyncStale => "コードにリンクなし: {0}",
+                Message::ScaffoldCreated => "📝 {0} を作成しました",
+                Message::ScaffoldNothing => "✅ すべてのクラスにページがあります",
+                Message::VerifyMatch => "✅ レポートは作業ツリーと一致しています",
+                Message::VerifyMismatch => "❌ レポートは作業ツリーと一致しません:",
+                Message::VerifyTool => "{0} で記録、実行中は {1}",
+                Message::VerifyChanged => "変更: {0}",
+                Message::VerifyMissing => "削除: {0}",
+                Message::VerifyAdded => "レポートにない: {0}",
+                Message::WorkspaceTitle => "=== ワークスペース ===",
+                Message::WorkspaceRootLine => {
+                    "📁 {0}: {1} ファイル、{2} クラス（孤立 {3}）、他ルートへのリンク {4} 件、他ルートからのリンク {5} 件、未解決 {6} 件"
+                }
+                Message::WorkspaceDuplicates => "⚠️  複数のルートで定義されたクラス:",
+                Message::QueryRowCount => "{0} 行",
+                Message::CouplingHeader => "⚖️  重み付き結合度（上位 {0} 件）:",
+                Message::CouplingLine => "  {0}: {1}（被依存 {2}、依存 {3}）",
+                Message::ExternalCouplingHeader => "🏗️  フレームワーク結合度（上位 {0} 件）:",
+                Message::ExternalPackagesHeader => "🏗️  パッケージ別フレームワーク結合度（上位 {0} 件）:",
+                Message::ExternalCouplingLine => "  {0}: {1} 件（{2}）",
+                Message::ExternalNodesHeader => "📦 外部クラス: {0} 件（上位 {1} 件）:",
+                Message::ExternalNodeLine => "  {0}（{1}）: 依存クラス {2}、リンク {3}",
+                Message::ExternalNodeUnknownLine => "  {0}: 依存クラス {1}、リンク {2}",
+                Message::ExternalFanOutHeader => "🔌 外部依存数（上位 {0} 件）:",
+                Message::ExternalPackageFanOutHeader => "🔌 パッケージ別外部依存数（上位 {0} 件）:",
+                Message::ExternalFanOutLine => "  {0}: 外部クラス {1} 件（{2}）",
+                Message::TagStatisticsHeader => "🏷️  タグ:",
+                Message::TagLine => "  {0}: {1} クラス、内部リンク {2}、被依存 {3}、依存 {4}",
+                Message::TagMatrixHeader => "🏷️  タグ間のリンク:",
+                Message::TagMatrixLine => "  {0} -> {1}: {2} 件",
+                Message::TravelHeader => "🧭 {0} から {1} までの距離:",
+                Message::TravelLine => "  {0} ホップ: {1} クラス",
+                Message::TravelAverage => "  平均: {0} ホップ",
+                Message::TravelUnreachable => "  {0} への経路なし: {1}",
+                Message::OwnershipTeamsHeader => "👥 チーム別の所有:",
+                Message::OwnershipAuthorsHeader => "👤 作者別の所有:",
+                Message::OwnershipLine => "  {0}: {1} クラス, ファンイン {2}, ファンアウト {3}, チーム間リンク {4}",
+                Message::CrossRepoHeader => "🌐 リポジトリ間のリンク:",
+                Message::CrossRepoLine => "  {0} -> {1}: {2} 件（{3}）",
+                Message::CrossRepoAmbiguous => "❓ 複数の他リポジトリで定義されているため未解決: {0}",
+                Message::HotspotsHeader => "🔥 リファクタリング候補（規模 × 結合度、上位 {0} 件）:",
+                Message::HotspotLine => "  {0}: {1} 行、メソッド {2} 個、結合度 {3}",
+                Message::ChurnHotspotsHeader => "🌡️  変更の多い箇所（コミット数 × 結合度、上位 {0} 件）:",
+                Message::ChurnHotspotLine => "  {0}: コミット {1} 件、結合度 {2}、スコア {3}",
+                Message::CiSummary => {
+                    "classlink: クラス {0} 件、孤立 {1} 件、エラー {2} 件、警告 {3} 件（レポート: {4}）"
+                }
+                Message::CiDelta => "classlink: 前回の実行から {0}",
+                Message::CiNoDelta => "classlink: 前回の実行から変化はありません",
+                Message::DeltaClasses => "クラス {0} 件",
+                Message::DeltaLinks => "リンク {0} 件",
+                Message::DeltaCycles => "循環依存 {0} 件",
+                Message::DeltaIsolated => "孤立クラス {0} 件",
+                Message::DeltaErrors => "エラー {0} 件",
+                Message::DeltaWarnings => "警告 {0} 件",
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Catalog {
+    locale: Locale,
+}
+
+impl Catalog {
+    pub fn new(locale: Locale) -> Self {
+        Self { locale }
+    }
+
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    pub fn text(&self, message: Message, args: &[&dyn Display]) -> String {
+        let mut text = message.template(self.locale).to_string();
+        for (index, arg) in args.iter().enumerate() {
+            text = text.replace(&format!("{{{}}}", index), &arg.to_string());
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_substitutes_positional_arguments() {
+        let catalog = Catalog::new(Locale::En);
+
+        assert_eq!(
+            catalog.text(Message::ClassLinkCountLine, &[&"UserManager", &4, &1]),
+            "  📦 UserManager: 4 outgoing, 1 incoming"
+        );
+    }
+
+    #[test]
+    fn test_japanese_catalog() {
+        let catalog = Catalog::new("ja".parse().unwrap());
+
+        assert_eq!(catalog.text(Message::TotalClasses, &[&6]), "  クラス総数: 6");
+        assert_eq!(
+            catalog.text(Message::CircularDependency, &[&"A -> B"]),
+            "循環依存: A -> B"
+        );
+    }
+
+    #[test]
+    fn test_parse_locale() {
+        assert_eq!("EN".parse::<Locale>().unwrap(), Locale::En);
+        assert!("fr".parse::<Locale>().is_err());
+    }
+}
@@ -0,0 +1,252 @@
+use crate::glob;
+use crate::graph::ClassGraph;
+use crate::i18n::{Catalog, Message};
+use crate::manifest::relative_path;
+use crate::project::{self, FileAnalysis};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImpactLevel {
+    pub depth: usize,
+    pub classes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImpactReport {
+    pub target: String,
+    // Classes the target resolved to: the class itself, or every class
+    // defined in the target file
+    pub changed_classes: Vec<String>,
+    pub levels: Vec<ImpactLevel>,
+    pub test_files: Vec<String>,
+}
+
+// Common naming conventions for test sources across the supported languages
+pub const DEFAULT_TEST_PATTERNS: &[&str] = &[
+    "**/test/**",
+    "**/tests/**",
+    "**/__tests__/**",
+    "test_*",
+    "*_test.*",
+    "*.test.*",
+    "*.spec.*",
+    "*Test.*",
+    "*Tests.*",
+];
+
+pub fn default_test_patterns() -> Vec<String> {
+    DEFAULT_TEST_PATTERNS.iter().map(|p| p.to_string()).collect()
+}
+
+pub fn is_test_file(relative_path: &str, patterns: &[String]) -> bool {
+    glob::matches_any(patterns, relative_path)
+}
+
+fn resolve_target(root: &str, files: &[FileAnalysis], target: &str) -> Vec<String> {
+    let target_path = target.replace('\\', "/");
+    let mut classes: BTreeSet<String> = files
+        .iter()
+        .filter(|file| file.path == target || relative_path(root, &file.path) == target_path)
+        .flat_map(|file| file.result.classes.iter().cloned())
+        .collect();
+
+    if classes.is_empty() && files.iter().any(|file| file.result.classes.contains(target)) {
+        classes.insert(target.to_string());
+    }
+    classes.into_iter().collect()
+}
+
+pub fn analyze_impact(
+    root: &str,
+    files: &[FileAnalysis],
+    target: &str,
+    test_patterns: &[String],
+) -> Option<ImpactReport> {
+    let changed_classes = resolve_target(root, files, target);
+    if changed_classes.is_empty() {
+        return None;
+    }
+    let (levels, test_files) = affected_by(root, files, &changed_classes, test_patterns);
+
+    Some(ImpactReport {
+        target: target.to_string(),
+        changed_classes,
+        levels,
+        test_files,
+    })
+}
+
+// Test files that transitively depend on anything defined in `changed_files`,
+// for CI test filtering. Changed test files are always selected.
+pub fn select_tests(
+    root: &str,
+    files: &[FileAnalysis],
+    changed_files: &[String],
+    test_patterns: &[String],
+) -> Vec<String> {
+    let changed_classes: BTreeSet<String> = changed_files
+        .iter()
+        .flat_map(|file| resolve_target(root, files, file))
+        .collect();
+    let changed_classes: Vec<String> = changed_classes.into_iter().collect();
+    let (_, mut test_files) = affected_by(root, files, &changed_classes, test_patterns);
+
+    for changed in changed_files {
+        let changed = changed.replace('\\', "/");
+        let exists = files.iter().any(|file| relative_path(root, &file.path) == changed);
+        if exists && is_test_file(&changed, test_patterns) && !test_files.contains(&changed) {
+            test_files.push(changed);
+        }
+    }
+    test_files.sort();
+    test_files
+}
+
+fn affected_by(
+    root: &str,
+    files: &[FileAnalysis],
+    changed_classes: &[String],
+    test_patterns: &[String],
+) -> (Vec<ImpactLevel>, Vec<String>) {
+    let merged = project::merge(files.to_vec());
+    let graph = ClassGraph::new(&merged.classes, &merged.links);
+    let levels: Vec<ImpactLevel> = graph
+        .dependents_by_depth(changed_classes)
+        .into_iter()
+        .enumerate()
+        .map(|(index, classes)| ImpactLevel {
+            depth: index + 1,
+            classes,
+        })
+        .collect();
+
+    let affected: BTreeSet<&String> = changed_classes
+        .iter()
+        .chain(levels.iter().flat_map(|level| &level.classes))
+        .collect();
+    let test_files = files
+        .iter()
+        .map(|file| (relative_path(root, &file.path), file))
+        .filter(|(relative, _)| is_test_file(relative, test_patterns))
+        .filter(|(_, file)| {
+            file.result.links.iter().any(|link| affected.contains(&link.to_class))
+                || file.result.classes.iter().any(|class| affected.contains(class))
+        })
+        .map(|(relative, _)| relative)
+        .collect();
+
+    (levels, test_files)
+}
+
+impl ImpactReport {
+    pub fn render_text(&self, catalog: &Catalog) -> String {
+        let mut out = String::new();
+        writeln!(out, "{}\n", catalog.text(Message::ImpactTitle, &[&self.target])).unwrap();
+        writeln!(
+            out,
+            "{}",
+            catalog.text(Message::ImpactChangedClasses, &[&self.changed_classes.join(", ")])
+        )
+        .unwrap();
+
+        if self.levels.is_empty() {
+            writeln!(out, "{}", catalog.text(Message::ImpactNoDependents, &[])).unwrap();
+        }
+        for level in &self.levels {
+            writeln!(out, "\n{}", catalog.text(Message::ImpactDepth, &[&level.depth])).unwrap();
+            for class in &level.classes {
+                writeln!(out, "  - {}", class).unwrap();
+            }
+        }
+
+        if !self.test_files.is_empty() {
+            writeln!(out, "\n{}", catalog.text(Message::ImpactTestFiles, &[])).unwrap();
+            for file in &self.test_files {
+                writeln!(out, "  - {}", file).unwrap();
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_analyzer::AnalysisResult;
+    use crate::parser::ClassLink;
+
+    fn file(path: &str, classes: &[&str], links: &[(&str, &str)]) -> FileAnalysis {
+        FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|s| s.to_string()).collect(),
+                links: links
+                    .iter()
+                    .map(|(from, to)| ClassLink {
+                        from_class: from.to_string(),
+                        to_class: to.to_string(),
+                        line_number: 1,
+                        kind: Default::default(),
+                        position: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        }
+    }
+
+    fn project() -> Vec<FileAnalysis> {
+        vec![
+            file("/repo/src/repo.py", &["Repo"], &[]),
+            file("/repo/src/service.py", &["Service"], &[("Service", "Repo")]),
+            file("/repo/src/api.py", &["Api"], &[("Api", "Service")]),
+            file("/repo/tests/test_api.py", &["TestApi"], &[("TestApi", "Api")]),
+            file("/repo/tests/test_other.py", &["TestOther"], &[("TestOther", "Other")]),
+        ]
+    }
+
+    #[test]
+    fn test_impact_groups_dependents_by_depth() {
+        let report = analyze_impact("/repo", &project(), "Repo", &default_test_patterns()).unwrap();
+
+        assert_eq!(report.changed_classes, vec!["Repo"]);
+        assert_eq!(report.levels[0].classes, vec!["Service"]);
+        assert_eq!(report.levels[1].classes, vec!["Api"]);
+        assert_eq!(report.levels[2].classes, vec!["TestApi"]);
+        assert_eq!(report.test_files, vec!["tests/test_api.py"]);
+    }
+
+    #[test]
+    fn test_impact_of_file_and_unknown_target() {
+        let report = analyze_impact("/repo", &project(), "src/service.py", &default_test_patterns()).unwrap();
+        assert_eq!(report.changed_classes, vec!["Service"]);
+        assert_eq!(report.levels.len(), 2);
+
+        assert!(analyze_impact("/repo", &project(), "Missing", &default_test_patterns()).is_none());
+    }
+
+    #[test]
+    fn test_select_tests_for_changed_files() {
+        let patterns = default_test_patterns();
+        let changed = vec!["src/repo.py".to_string(), "tests/test_other.py".to_string()];
+
+        let selected = select_tests("/repo", &project(), &changed, &patterns);
+
+        assert_eq!(selected, vec!["tests/test_api.py", "tests/test_other.py"]);
+        let custom = select_tests("/repo", &project(), &changed, &["checks/**".to_string()]);
+        assert!(custom.is_empty());
+    }
+
+    #[test]
+    fn test_is_test_file() {
+        let patterns = default_test_patterns();
+        assert!(is_test_file("tests/helpers.py", &patterns));
+        assert!(is_test_file("src/user.spec.ts", &patterns));
+        assert!(is_test_file("Services/UserServiceTests.cs", &patterns));
+        assert!(is_test_file("test_models.py", &patterns));
+        assert!(!is_test_file("src/contest.py", &patterns));
+    }
+}
@@ -0,0 +1,154 @@
+use crate::file_analyzer::{FileType, UnifiedAnalyzer};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const SCRIPT_EXTENSIONS: [&str; 5] = ["ts", "tsx", "js", "jsx", "mjs"];
+
+// Existing files a source imports by relative path: TypeScript and
+// JavaScript `from './cart'` or `require('./cart')`, and Python `from ..shop.cart import Cart`. C# `using`
+// names namespaces rather than files, and absolute imports depend on
+// package settings the checker does not read.
+pub fn relative_imports(path: &str, content: &str) -> Vec<PathBuf> {
+    let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    match UnifiedAnalyzer::detect_file_type(path) {
+        FileType::TypeScript | FileType::JavaScript => {
+            let import_regex = Regex::new(r#"(?:\bfrom|\bimport|\brequire\()\s*['"](\.\.?/[^'"]+)['"]"#).unwrap();
+            for caps in import_regex.captures_iter(content) {
+                let target = dir.join(&caps[1]);
+                candidates.push(target.clone());
+                for extension in SCRIPT_EXTENSIONS {
+                    candidates.push(PathBuf::from(format!("{}.{}", target.display(), extension)));
+                    candidates.push(target.join(format!("index.{}", extension)));
+                }
+            }
+        }
+        FileType::Python if !path.ends_with(".ipynb") => {
+            let import_regex = Regex::new(r"(?m)^\s*from\s+(\.+)([\w.]*)\s+import\b").unwrap();
+            for caps in import_regex.captures_iter(content) {
+                // One dot is the file's own package, each further dot a
+                // parent
+                let mut target = dir.to_path_buf();
+                for _ in 1..caps[1].len() {
+                    target.push("..");
+                }
+                if caps[2].is_empty() {
+                    candidates.push(target.join("__init__.py"));
+                    continue;
+                }
+                target.extend(caps[2].split('.'));
+                candidates.push(target.with_extension("py"));
+                candidates.push(target.join("__init__.py"));
+            }
+        }
+        _ => {}
+    }
+    let mut imports: Vec<PathBuf> = candidates.into_iter().filter(|candidate| candidate.is_file()).collect();
+    imports.sort();
+    imports.dedup();
+    imports
+}
+
+// Names a source imports from installed packages, mapped to the package:
+// `import { Observable } from 'rxjs/internal'` gives `Observable -> rxjs`,
+// `from requests.auth import HTTPBasicAuth as Auth` gives
+// `Auth -> requests`, and JavaScript `const { Router } = require('express')`
+// gives `Router -> express`. Relative imports are left to `relative_imports`;
+// C# `using` names no types, so C# sources give nothing.
+pub fn package_imports(path: &str, content: &str) -> BTreeMap<String, String> {
+    let mut names = BTreeMap::new();
+    let mut add = |list: &str, package: &str| {
+        for name in list.split(',') {
+            let name = name.split(" as ").last().unwrap_or_default().trim().trim_start_matches("type ").trim();
+            if !name.is_empty() && name != "*" {
+                names.insert(name.to_string(), package.to_string());
+            }
+        }
+    };
+    let file_type = UnifiedAnalyzer::detect_file_type(path);
+    match file_type {
+        FileType::TypeScript | FileType::JavaScript => {
+            let mut specifiers: Vec<(String, String)> = Vec::new();
+            let import_regex = Regex::new(r#"import\s+(?:type\s+)?(?:(\w+)\s*,?\s*)?(?:\{([^}]*)\})?\s*from\s*['"]([^'"./][^'"]*)['"]"#).unwrap();
+            for caps in import_regex.captures_iter(content) {
+                for list in [caps.get(1), caps.get(2)].into_iter().flatten() {
+                    specifiers.push((list.as_str().to_string(), caps[3].to_string()));
+                }
+            }
+            if file_type == FileType::JavaScript {
+                let require_regex = Regex::new(r#"(?:const|let|var)\s+(?:(\w+)|\{([^}]*)\})\s*=\s*require\(\s*['"]([^'"./][^'"]*)['"]\s*\)"#).unwrap();
+                for caps in require_regex.captures_iter(content) {
+                    // Destructuring renames with `:` where imports use `as`
+                    let list = caps.get(1).or(caps.get(2)).map_or(String::new(), |list| list.as_str().replace(':', " as "));
+                    specifiers.push((list, caps[3].to_string()));
+                }
+            }
+            for (list, specifier) in specifiers {
+                // Scoped packages keep their scope: `@angular/core`
+                let segments = if specifier.starts_with('@') { 2 } else { 1 };
+                let package: Vec<&str> = specifier.split('/').take(segments).collect();
+                add(&list, &package.join("/"));
+            }
+        }
+        FileType::Python if !path.ends_with(".ipynb") => {
+            let import_regex = Regex::new(r"(?m)^\s*from\s+([A-Za-z_][\w.]*)\s+import\s+(\([^)]*\)|[^\n#]*)").unwrap();
+            for caps in import_regex.captures_iter(content) {
+                let package = caps[1].split('.').next().unwrap_or_default();
+                add(caps[2].trim_matches(|c: char| c == '(' || c == ')' || c.is_whitespace()), package);
+            }
+        }
+        _ => {}
+    }
+    names
+}
+
+#[cfg(all(test, feature = "python", feature = "typescript"))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    #[cfg(feature = "csharp")]
+    fn test_relative_imports_resolve_to_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for dir in ["app/shop", "shared/billing", "web/cart"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in ["shared/money.py", "shared/billing/__init__.py", "web/cart/index.ts", "web/order.tsx"] {
+            fs::write(root.join(file), "").unwrap();
+        }
+        let path = |file: &str| root.join(file).to_string_lossy().to_string();
+
+        let python = "from ...shared.money import Money\nfrom ...shared.billing import Invoice\nfrom .missing import Gone\nimport os\n";
+        let found = relative_imports(&path("app/shop/cart.py"), python);
+        let expected = [root.join("app/shop/../../shared/billing/__init__.py"), root.join("app/shop/../../shared/money.py")];
+        assert_eq!(found, expected);
+
+        let typescript = "import { Cart } from './cart';\nimport { Order } from \"./order\";\nimport { Http } from '@angular/common/http';\n";
+        let found = relative_imports(&path("web/shop.ts"), typescript);
+        assert_eq!(found, [root.join("web/./cart/index.ts"), root.join("web/./order.tsx")]);
+        assert!(relative_imports(&path("Shop.cs"), "using Shared.Money;\n").is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "javascript")]
+    fn test_package_imports_map_names_to_packages() {
+        let typescript = "import Big, { Observable, map as rxMap } from 'rxjs/operators';\nimport { Component } from '@angular/core';\nimport { Cart } from './cart';\n";
+        let found = package_imports("shop.ts", typescript);
+        let expected = [("Big", "rxjs"), ("Component", "@angular/core"), ("Observable", "rxjs"), ("rxMap", "rxjs")];
+        assert_eq!(found, expected.map(|(name, package)| (name.to_string(), package.to_string())).into());
+
+        let javascript = "const express = require('express');\nconst { Router, json: parseJson } = require('express/lib');\nconst { Cart } = require('./cart');\n";
+        let found = package_imports("shop.js", javascript);
+        let expected = [("Router", "express"), ("express", "express"), ("parseJson", "express")];
+        assert_eq!(found, expected.map(|(name, package)| (name.to_string(), package.to_string())).into());
+
+        let python = "from requests.auth import HTTPBasicAuth as Auth, Session\nfrom pydantic import (\n    BaseModel,\n    Field,\n)\nfrom .cart import Cart\n";
+        let found = package_imports("shop.py", python);
+        let expected = [("Auth", "requests"), ("BaseModel", "pydantic"), ("Field", "pydantic"), ("Session", "requests")];
+        assert_eq!(found, expected.map(|(name, package)| (name.to_string(), package.to_string())).into());
+    }
+}
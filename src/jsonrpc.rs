@@ -0,0 +1,116 @@
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+
+pub type RpcError = (i64, String);
+
+// Shared newline-delimited JSON-RPC 2.0 loop used by the MCP server and the
+// editor daemon.
+pub trait Handler {
+    fn handle_message(&mut self, message: &Value) -> Vec<Value>;
+
+    fn should_exit(&self) -> bool {
+        false
+    }
+}
+
+pub fn serve<H: Handler, R: BufRead, W: Write>(
+    handler: &mut H,
+    reader: R,
+    mut writer: W,
+) -> io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let responses = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => handler.handle_message(&message),
+            Err(e) => vec![error_response(Value::Null, PARSE_ERROR, &e.to_string())],
+        };
+
+        for response in responses {
+            writeln!(writer, "{}", response)?;
+        }
+        writer.flush()?;
+
+        if handler.should_exit() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn response(id: Value, result: Result<Value, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error_response(id, code, &message),
+    }
+}
+
+pub fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+pub fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+pub fn string_param<'a>(params: &'a Value, name: &str) -> Result<&'a str, RpcError> {
+    params
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or((INVALID_PARAMS, format!("Missing argument: {}", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Echo {
+        exit_after: usize,
+        seen: usize,
+    }
+
+    impl Handler for Echo {
+        fn handle_message(&mut self, message: &Value) -> Vec<Value> {
+            self.seen += 1;
+            vec![response(message["id"].clone(), Ok(message["params"].clone()))]
+        }
+
+        fn should_exit(&self) -> bool {
+            self.seen >= self.exit_after
+        }
+    }
+
+    #[test]
+    fn test_serve_reports_parse_errors_and_stops_on_exit() {
+        let mut handler = Echo {
+            exit_after: 1,
+            seen: 0,
+        };
+        let input = "garbage\n\n{\"id\":1,\"params\":5}\n{\"id\":2,\"params\":6}\n";
+        let mut output = Vec::new();
+
+        serve(&mut handler, input.as_bytes(), &mut output).unwrap();
+
+        let lines: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["error"]["code"], PARSE_ERROR);
+        assert_eq!(lines[1]["result"], 5);
+    }
+}
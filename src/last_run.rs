@@ -0,0 +1,116 @@
+use crate::analyzer::LinkStatistics;
+use crate::findings::{Finding, Severity};
+use crate::i18n::{Catalog, Message};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Where `--ci` keeps the numbers of its previous run, relative to the
+// working directory like `--report-path`
+pub const DEFAULT_LAST_RUN_PATH: &str = ".classlink/last-run.json";
+
+// The absolute numbers of one CI run, kept between runs so the next one can
+// say what changed without a snapshot and `diff`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunSummary {
+    pub classes: usize,
+    pub links: usize,
+    pub cycles: usize,
+    pub isolated: usize,
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+impl RunSummary {
+    pub fn new(statistics: &LinkStatistics, findings: &[Finding]) -> Self {
+        let count = |severity| findings.iter().filter(|finding| finding.severity == severity).count();
+        Self {
+            classes: statistics.total_classes,
+            links: statistics.class_link_counts.values().map(|counts| counts.outgoing_links).sum(),
+            cycles: statistics.circular_dependencies.len(),
+            isolated: statistics.isolated_classes.len(),
+            errors: count(Severity::Error),
+            warnings: count(Severity::Warning),
+        }
+    }
+
+    // None on the first run; a file left by another version that no longer
+    // parses counts as no previous run
+    pub fn load(path: &str) -> Option<Self> {
+        serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        if let Some(parent) = Path::new(path).parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, json + "\n")
+    }
+
+    // "+2 cycles, −5 isolated classes since last run", listing only the
+    // numbers that moved
+    pub fn delta(&self, previous: &RunSummary, catalog: &Catalog) -> String {
+        let changes: Vec<String> = [
+            (self.classes, previous.classes, Message::DeltaClasses),
+            (self.links, previous.links, Message::DeltaLinks),
+            (self.cycles, previous.cycles, Message::DeltaCycles),
+            (self.isolated, previous.isolated, Message::DeltaIsolated),
+            (self.errors, previous.errors, Message::DeltaErrors),
+            (self.warnings, previous.warnings, Message::DeltaWarnings),
+        ]
+        .into_iter()
+        .filter(|(now, before, _)| now != before)
+        .map(|(now, before, message)| {
+            let change = if now > before { format!("+{}", now - before) } else { format!("−{}", before - now) };
+            catalog.text(message, &[&change])
+        })
+        .collect();
+        if changes.is_empty() {
+            catalog.text(Message::CiNoDelta, &[])
+        } else {
+            catalog.text(Message::CiDelta, &[&changes.join(", ")])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_delta_against_the_saved_run() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("cache/last-run.json");
+        let path = path.to_str().unwrap();
+        assert_eq!(RunSummary::load(path), None);
+
+        let previous = RunSummary {
+            classes: 40,
+            links: 52,
+            cycles: 1,
+            isolated: 9,
+            errors: 1,
+            warnings: 9,
+        };
+        previous.save(path).unwrap();
+        let previous = RunSummary::load(path).unwrap();
+        let current = RunSummary {
+            cycles: 3,
+            isolated: 4,
+            errors: 3,
+            warnings: 4,
+            ..previous
+        };
+
+        let catalog = Catalog::default();
+        assert_eq!(
+            current.delta(&previous, &catalog),
+            "classlink: +2 cycles, −5 isolated classes, +2 errors, −5 warnings since last run"
+        );
+        assert_eq!(previous.delta(&previous, &catalog), "classlink: no change since last run");
+    }
+}
@@ -1,10 +1,79 @@
 pub mod parser;
-pub mod analyzer;
+pub use classlink_core::{analyzer, annotations, endpoints, graph, position, preset, registry};
 pub mod file_analyzer;
-pub mod python_parser;
-pub mod typescript_parser;
-pub mod csharp_parser;
+#[cfg(feature = "python")]
+pub use classlink_parser_python::{self as python_parser, notebook};
+#[cfg(feature = "typescript")]
+pub use classlink_parser_typescript::{self as typescript_parser, template_parser};
+#[cfg(feature = "csharp")]
+pub use classlink_parser_csharp as csharp_parser;
+#[cfg(feature = "rust")]
+pub use classlink_parser_rust as rust_parser;
+#[cfg(feature = "java")]
+pub use classlink_parser_java as java_parser;
+#[cfg(feature = "go")]
+pub use classlink_parser_go as go_parser;
+#[cfg(feature = "javascript")]
+pub use classlink_parser_javascript as javascript_parser;
+pub mod custom_analyzer;
+pub mod external_types;
+pub mod external_nodes;
+pub mod plugin;
+pub mod alias;
+pub mod glob;
+pub mod subgraph;
+pub mod coupling;
+pub mod metrics;
+pub mod tags;
+pub mod ownership;
+pub mod churn;
+pub mod resolution;
+pub mod imports;
+pub mod diff;
+pub mod federation;
+pub mod snapshot;
+pub mod last_run;
+pub mod cache;
+pub mod stream;
+pub mod scan_io;
+pub mod impact;
+pub mod accuracy;
+pub mod docsync;
+pub mod encoding;
+pub mod diagnostics;
+pub mod findings;
+pub mod exceptions;
+#[cfg(feature = "scripting")]
+pub mod script;
+pub mod query;
+pub mod search;
+pub mod manifest;
+pub mod provenance;
+pub mod export;
+pub mod config;
+pub mod i18n;
+pub mod report;
+pub mod reporter;
+pub mod template;
+pub mod pipeline;
+pub mod project;
+pub mod workspace;
+pub mod jsonrpc;
+pub mod mcp;
+pub mod daemon;
+pub mod remote;
+pub mod walker;
+pub mod cancel;
+pub mod limits;
 
-pub use parser::MarkdownParser;
+pub use parser::{LinkKind, MarkdownParser};
 pub use analyzer::{LinkAnalyzer, LinkStatistics, ClassLinkCount};
-pub use file_analyzer::{UnifiedAnalyzer, FileType, AnalysisResult};
\ No newline at end of file
+pub use file_analyzer::{UnifiedAnalyzer, FileType, AnalysisResult};
+pub use graph::ClassGraph;
+pub use registry::{LanguageParser, Metric, MetricRegistry, ParseError, ParserRegistry};
+pub use diff::GraphDiff;
+pub use project::{ProjectAnalysis, ProjectAnalyzer};
+pub use config::Config;
+pub use report::Report;
+pub use pipeline::{check, run, CheckOutcome, Pipeline};
+pub use reporter::Reporter;
@@ -0,0 +1,133 @@
+use crate::file_analyzer::AnalysisResult;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Caps that keep a scan of a pathological monorepo within bounded memory.
+// The scan stops at the first cap reached and reports what it had so far.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceLimits {
+    pub max_files: Option<usize>,
+    // Graph nodes: defined classes
+    pub max_classes: Option<usize>,
+    // Graph edges: link occurrences
+    pub max_links: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Limit {
+    Files,
+    Classes,
+    Links,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Limit::Files => "files",
+            Limit::Classes => "classes",
+            Limit::Links => "links",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitExceeded {
+    pub limit: Limit,
+    pub max: usize,
+}
+
+// Running totals of one scan against its limits
+#[derive(Debug, Clone)]
+pub struct Budget<'a> {
+    limits: &'a ResourceLimits,
+    files: usize,
+    classes: usize,
+    links: usize,
+}
+
+impl<'a> Budget<'a> {
+    pub fn new(limits: &'a ResourceLimits) -> Self {
+        Self {
+            limits,
+            files: 0,
+            classes: 0,
+            links: 0,
+        }
+    }
+
+    // Checked before adding the next analyzed file; skipped files are free
+    pub fn next_file(&self) -> Option<LimitExceeded> {
+        exceeded(Limit::Files, self.limits.max_files, self.files + 1)
+    }
+
+    // Counts one analyzed file. A file that would go over the class cap is
+    // not counted and should be dropped whole; one over the link cap keeps
+    // as many of its links as fit. Either way the scan should stop.
+    pub fn admit(&mut self, result: &mut AnalysisResult) -> Option<LimitExceeded> {
+        if let Some(hit) = exceeded(Limit::Classes, self.limits.max_classes, self.classes + result.classes.len()) {
+            return Some(hit);
+        }
+        self.files += 1;
+        self.classes += result.classes.len();
+        let hit = exceeded(Limit::Links, self.limits.max_links, self.links + result.links.len());
+        if let Some(max) = hit.map(|hit| hit.max) {
+            result.links.truncate(max - self.links);
+        }
+        self.links += result.links.len();
+        hit
+    }
+}
+
+fn exceeded(limit: Limit, max: Option<usize>, total: usize) -> Option<LimitExceeded> {
+    max.filter(|max| total > *max).map(|max| LimitExceeded { limit, max })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ClassLink;
+
+    fn result(classes: &[&str], links: usize) -> AnalysisResult {
+        AnalysisResult {
+            classes: classes.iter().map(|s| s.to_string()).collect(),
+            links: (0..links)
+                .map(|i| ClassLink {
+                    from_class: classes[0].to_string(),
+                    to_class: format!("T{}", i),
+                    line_number: i + 1,
+                    kind: Default::default(),
+                    position: None,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_budget_truncates_links_and_rejects_classes() {
+        let limits = ResourceLimits {
+            max_files: Some(2),
+            max_classes: Some(2),
+            max_links: Some(3),
+        };
+        let mut budget = Budget::new(&limits);
+
+        assert_eq!(budget.admit(&mut result(&["A"], 2)), None);
+        let mut second = result(&["B"], 2);
+        assert_eq!(
+            budget.admit(&mut second),
+            Some(LimitExceeded {
+                limit: Limit::Links,
+                max: 3
+            })
+        );
+        assert_eq!(second.links.len(), 1);
+        assert_eq!(budget.next_file().map(|hit| hit.limit), Some(Limit::Files));
+        assert_eq!(budget.admit(&mut result(&["C"], 0)).map(|hit| hit.limit), Some(Limit::Classes));
+
+        let unlimited = ResourceLimits::default();
+        assert_eq!(Budget::new(&unlimited).admit(&mut result(&["A", "B"], 50)), None);
+    }
+}
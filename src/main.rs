@@ -1,174 +1,1301 @@
-use clap::{Arg, Command};
-use classlink_checker::{LinkAnalyzer, UnifiedAnalyzer};
-use serde_json;
-use std::collections::HashSet;
+use clap::parser::ValueSource;
+use clap::{Arg, ArgMatches, Command};
+use classlink_checker::accuracy::{self, GroundTruth};
+use classlink_checker::cache;
+use classlink_checker::cancel::Interruption;
+use classlink_checker::config::{Config, OutputFormat};
+use classlink_checker::daemon::Daemon;
+use classlink_checker::docsync;
+use classlink_checker::diff::{self, GraphDiff};
+use classlink_checker::impact;
+use classlink_checker::project::FileAnalysis;
+use classlink_checker::findings::Severity;
+use classlink_checker::i18n::{Catalog, Locale, Message};
+use classlink_checker::jsonrpc;
+use classlink_checker::manifest::relative_path;
+use classlink_checker::last_run::{self, RunSummary};
+use classlink_checker::mcp::McpServer;
+use classlink_checker::pipeline::Pipeline;
+use classlink_checker::provenance::Provenance;
+use classlink_checker::query::{self, QueryGraph};
+use classlink_checker::remote::RemoteSource;
+use classlink_checker::report::Report;
+use classlink_checker::reporter::{self, JsonReporter};
+use classlink_checker::search;
+use classlink_checker::snapshot::Snapshot;
+use classlink_checker::subgraph::Focus;
+use classlink_checker::workspace::Workspace;
+use std::collections::BTreeMap;
 use std::fs;
+use std::io::{self, BufRead, IsTerminal};
 use std::path::Path;
 
 fn main() {
     let matches = Command::new("classlink-checker")
         .version("0.2.0")
         .author("Generated by Claude")
-        .about("Analyzes class links in Markdown, Python, TypeScript, and C# files")
-        .arg(
-            Arg::new("path")
-                .help("Path to the directory or file to analyze")
-                .required(true)
-                .index(1),
+        .about("Analyzes class links in Markdown, Python, TypeScript, JavaScript, C#, Rust, Java and Go files")
+        .subcommand_negates_reqs(true)
+        .args_conflicts_with_subcommands(true)
+        .args(analyze_args())
+        .subcommand(
+            Command::new("analyze")
+                .about("Walk a project recursively, analyze every supported file and print the link statistics")
+                .args(analyze_args().into_iter().filter(|arg| arg.get_id() != "recursive")),
         )
-        .arg(
-            Arg::new("output")
-                .short('o')
-                .long("output")
-                .value_name("FORMAT")
-                .help("Output format: text, json")
-                .default_value("text"),
+        .subcommand(
+            Command::new("mcp")
+                .about("Serve the project graph to AI assistants over the Model Context Protocol (stdio)")
+                .arg(path_arg())
+                .arg(recursive_arg()),
         )
-        .arg(
-            Arg::new("recursive")
-                .short('r')
-                .long("recursive")
-                .help("Recursively scan subdirectories")
-                .action(clap::ArgAction::SetTrue),
+        .subcommand(
+            Command::new("daemon")
+                .about("Run a JSON-RPC daemon on stdio that tracks file changes for editor extensions")
+                .arg(path_arg())
+                .arg(recursive_arg()),
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("Compare two snapshots (manifest files or directories), detecting renamed classes")
+                .arg(Arg::new("old").help("Old snapshot").required(true).index(1))
+                .arg(Arg::new("new").help("New snapshot").required(true).index(2))
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FORMAT")
+                        .help("Output format: text, json, markdown (PR comment with a mermaid graph)")
+                        .default_value("text"),
+                )
+                .arg(
+                    Arg::new("locale")
+                        .long("locale")
+                        .value_name("LOCALE")
+                        .help("Language for report text: en, ja")
+                        .default_value("en"),
+                ),
+        )
+        .subcommand(
+            Command::new("snapshot")
+                .about("Save the project graph as a compact binary snapshot that diff accepts")
+                .arg(
+                    Arg::new("path")
+                        .help("Project directory")
+                        .default_value(".")
+                        .index(1),
+                )
+                .args(walk_args())
+                .arg(
+                    Arg::new("file")
+                        .short('f')
+                        .long("file")
+                        .value_name("FILE")
+                        .help("Snapshot file to write")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("merge")
+                .about("Merge reports of separate repositories (manifests, snapshots or directories) into one graph")
+                .arg(
+                    Arg::new("reports")
+                        .help("Manifest files, snapshot files or project directories")
+                        .required(true)
+                        .num_args(1..)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FORMAT")
+                        .help("Output format: text, json, manifest, networkx, cypher, dot, mermaid, sarif, ctags, symbols")
+                        .default_value("text"),
+                )
+                .arg(
+                    Arg::new("locale")
+                        .long("locale")
+                        .value_name("LOCALE")
+                        .help("Language for report text: en, ja")
+                        .default_value("en"),
+                ),
+        )
+        .subcommand(
+            Command::new("verify")
+                .about("Check that a JSON report still matches the working tree it was computed from")
+                .arg(Arg::new("report").help("Full JSON report, e.g. from --ci").required(true).index(1))
+                .arg(
+                    Arg::new("path")
+                        .help("Project directory [default: the recorded one]")
+                        .index(2),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FORMAT")
+                        .help("Output format: text, json")
+                        .default_value("text"),
+                )
+                .arg(
+                    Arg::new("locale")
+                        .long("locale")
+                        .value_name("LOCALE")
+                        .help("Language for report text: en, ja")
+                        .default_value("en"),
+                ),
+        )
+        .subcommand(
+            Command::new("impact")
+                .about("List classes transitively affected by changing a class or file, plus the tests that reference them")
+                .arg(Arg::new("target").help("Class name or file path").required(true).index(1))
+                .arg(
+                    Arg::new("path")
+                        .help("Project directory")
+                        .default_value(".")
+                        .index(2),
+                )
+                .args(walk_args())
+                .arg(test_pattern_arg())
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FORMAT")
+                        .help("Output format: text, json")
+                        .default_value("text"),
+                )
+                .arg(
+                    Arg::new("locale")
+                        .long("locale")
+                        .value_name("LOCALE")
+                        .help("Language for report text: en, ja")
+                        .default_value("en"),
+                ),
+        )
+        .subcommand(
+            Command::new("select-tests")
+                .about("Print the test files that transitively depend on the changed files (read from stdin if none are given)")
+                .arg(
+                    Arg::new("changed")
+                        .help("Changed files, relative to the project directory")
+                        .num_args(0..)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("path")
+                        .long("path")
+                        .value_name("DIR")
+                        .help("Project directory")
+                        .default_value("."),
+                )
+                .args(walk_args())
+                .arg(test_pattern_arg())
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FORMAT")
+                        .help("Output format: text (one path per line), json")
+                        .default_value("text"),
+                ),
+        )
+        .subcommand(
+            Command::new("workspace")
+                .about("Analyze several root directories as one workspace, with per-root and combined statistics")
+                .arg(
+                    Arg::new("roots")
+                        .help("Root directories")
+                        .required(true)
+                        .num_args(1..)
+                        .index(1),
+                )
+                .args(walk_args())
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FORMAT")
+                        .help("Output format: text, json")
+                        .default_value("text"),
+                )
+                .arg(
+                    Arg::new("locale")
+                        .long("locale")
+                        .value_name("LOCALE")
+                        .help("Language for report text: en, ja")
+                        .default_value("en"),
+                ),
+        )
+        .subcommand(
+            Command::new("accuracy")
+                .about("Score link extraction per language against a ground-truth links file (precision/recall)")
+                .arg(Arg::new("truth").help("Ground-truth JSON file").required(true).index(1))
+                .arg(
+                    Arg::new("path")
+                        .help("Project directory")
+                        .default_value(".")
+                        .index(2),
+                )
+                .args(walk_args())
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FORMAT")
+                        .help("Output format: text, json")
+                        .default_value("text"),
+                )
+                .arg(
+                    Arg::new("locale")
+                        .long("locale")
+                        .value_name("LOCALE")
+                        .help("Language for report text: en, ja")
+                        .default_value("en"),
+                ),
+        )
+        .subcommand(
+            Command::new("docs")
+                .about("Keep Markdown class documentation in step with the code")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("sync")
+                        .about("Propose pages for undocumented classes and flag pages whose links disagree with the code")
+                        .arg(
+                            Arg::new("path")
+                                .help("Project directory")
+                                .default_value(".")
+                                .index(1),
+                        )
+                        .args(walk_args())
+                        .arg(
+                            Arg::new("docs-dir")
+                                .long("docs-dir")
+                                .value_name("DIR")
+                                .help("Directory, relative to the project, proposed pages go in")
+                                .default_value("docs"),
+                        )
+                        .arg(
+                            Arg::new("output")
+                                .short('o')
+                                .long("output")
+                                .value_name("FORMAT")
+                                .help("Output format: text, json, lcov (documentation coverage)")
+                                .default_value("text"),
+                        )
+                        .arg(
+                            Arg::new("locale")
+                                .long("locale")
+                                .value_name("LOCALE")
+                                .help("Language for report text: en, ja")
+                                .default_value("en"),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("scaffold-docs")
+                .about("Create Markdown pages for undocumented classes, filled in with their links")
+                .arg(
+                    Arg::new("path")
+                        .help("Project directory")
+                        .default_value(".")
+                        .index(1),
+                )
+                .args(walk_args())
+                .arg(
+                    Arg::new("docs-dir")
+                        .long("docs-dir")
+                        .value_name("DIR")
+                        .help("Directory, relative to the project, new pages go in")
+                        .default_value("docs"),
+                )
+                .arg(
+                    Arg::new("template")
+                        .long("template")
+                        .value_name("FILE")
+                        .help("Page template; {class}, {dependencies} and {used_by} are substituted"),
+                )
+                .arg(
+                    Arg::new("locale")
+                        .long("locale")
+                        .value_name("LOCALE")
+                        .help("Language for report text: en, ja")
+                        .default_value("en"),
+                ),
+        )
+        .subcommand(
+            Command::new("query")
+                .about("Run a Cypher-like query over the class graph, e.g. 'MATCH (a)-[:INHERITS]->(b {name: \"Base\"}) RETURN a'")
+                .arg(Arg::new("query").help("Query text").required(true).index(1))
+                .arg(
+                    Arg::new("path")
+                        .help("Project directory")
+                        .default_value(".")
+                        .index(2),
+                )
+                .args(walk_args())
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FORMAT")
+                        .help("Output format: text, json")
+                        .default_value("text"),
+                )
+                .arg(
+                    Arg::new("locale")
+                        .long("locale")
+                        .value_name("LOCALE")
+                        .help("Language for report text: en, ja")
+                        .default_value("en"),
+                ),
+        )
+        .subcommand(
+            Command::new("find")
+                .about("List classes matching a glob like '*Service' or a fuzzy name like 'ordsvc', best first")
+                .arg(Arg::new("pattern").help("Glob or partial class name").required(true).index(1))
+                .arg(
+                    Arg::new("path")
+                        .help("Project directory")
+                        .default_value(".")
+                        .index(2),
+                )
+                .args(walk_args())
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FORMAT")
+                        .help("Output format: text, json")
+                        .default_value("text"),
+                ),
         )
         .get_matches();
 
-    let path = matches.get_one::<String>("path").unwrap();
-    let output_format = matches.get_one::<String>("output").unwrap();
-    let recursive = matches.get_flag("recursive");
-
-    match analyze_directory(path, recursive) {
-        Ok(stats) => {
-            match output_format.as_str() {
-                "json" => {
-                    match serde_json::to_string_pretty(&stats) {
-                        Ok(json) => println!("{}", json),
-                        Err(e) => eprintln!("Error serializing to JSON: {}", e),
-                    }
+    match matches.subcommand() {
+        Some(("analyze", sub_matches)) => run_analyze(sub_matches, true),
+        Some(("mcp", sub_matches)) => run_mcp(sub_matches),
+        Some(("daemon", sub_matches)) => run_daemon(sub_matches),
+        Some(("diff", sub_matches)) => run_diff(sub_matches),
+        Some(("snapshot", sub_matches)) => run_snapshot(sub_matches),
+        Some(("merge", sub_matches)) => run_merge(sub_matches),
+        Some(("verify", sub_matches)) => run_verify(sub_matches),
+        Some(("impact", sub_matches)) => run_impact(sub_matches),
+        Some(("select-tests", sub_matches)) => run_select_tests(sub_matches),
+        Some(("workspace", sub_matches)) => run_workspace(sub_matches),
+        Some(("accuracy", sub_matches)) => run_accuracy(sub_matches),
+        Some(("query", sub_matches)) => run_query(sub_matches),
+        Some(("find", sub_matches)) => run_find(sub_matches),
+        Some(("scaffold-docs", sub_matches)) => run_scaffold_docs(sub_matches),
+        Some(("docs", sub_matches)) => match sub_matches.subcommand() {
+            Some(("sync", sync_matches)) => run_docs_sync(sync_matches),
+            _ => unreachable!("docs requires a subcommand"),
+        },
+        _ => run_analyze(&matches, matches.get_flag("recursive")),
+    }
+}
+
+// Flags of the top-level scan, shared with `analyze`
+fn analyze_args() -> Vec<Arg> {
+    let output = Arg::new("output")
+        .short('o')
+        .long("output")
+        .value_name("FORMAT")
+        .help("Output format: text, json, manifest, networkx, cypher, dot, mermaid, sarif, ctags, symbols, template, jsonl")
+        .default_value("text");
+    [vec![path_arg(), output], walk_args(), report_args()].concat()
+}
+
+// Flags choosing which files are analyzed and how, shared by every command
+// that scans a project
+fn walk_args() -> Vec<Arg> {
+    vec![
+        recursive_arg(),
+        Arg::new("config")
+            .short('c')
+            .long("config")
+            .value_name("FILE")
+            .help("JSON config file; flags given on the command line override it"),
+        Arg::new("max-depth")
+            .long("max-depth")
+            .value_name("N")
+            .help("Enter at most N directory levels below PATH (implies --recursive)")
+            .value_parser(clap::value_parser!(usize)),
+        Arg::new("follow-symlinks")
+            .long("follow-symlinks")
+            .help("Follow symlinked files and directories while scanning")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("max-file-size")
+            .long("max-file-size")
+            .value_name("MB")
+            .help("Skip files larger than this many megabytes (0 disables the limit)")
+            .value_parser(clap::value_parser!(u64))
+            .default_value("10"),
+        Arg::new("include-binary")
+            .long("include-binary")
+            .help("Analyze files that look binary instead of skipping them")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("include-vendored")
+            .long("include-vendored")
+            .help("Analyze vendored directories (node_modules, vendor, ...) and minified files")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("exclude")
+            .long("exclude")
+            .value_name("GLOB")
+            .help("Leave out files and directories matching the glob, relative to PATH (repeatable)")
+            .action(clap::ArgAction::Append),
+        Arg::new("no-gitignore")
+            .long("no-gitignore")
+            .help("Analyze files that .gitignore files ignore")
+            .action(clap::ArgAction::SetTrue),
+    ]
+}
+
+fn report_args() -> Vec<Arg> {
+    vec![
+        Arg::new("column-encoding")
+            .long("column-encoding")
+            .value_name("ENCODING")
+            .help("Count link columns in utf-8, utf-16 (default, as LSP and SARIF do) or utf-32 units"),
+        Arg::new("snippets")
+            .long("snippets")
+            .help("Keep the source line of every link in the report (manifest locations)")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("abstractions")
+            .long("abstractions")
+            .help("Report how much of each class's coupling is on interfaces and abstract classes")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("global-resolution")
+            .long("global-resolution")
+            .help("Resolve references against every class in the project, in a second pass")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("follow-imports")
+            .long("follow-imports")
+            .help("Record classes imported from files outside PATH as external nodes")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("ownership")
+            .long("ownership")
+            .help("Report owned classes, fan-in/out and cross-team links per author and team, from git blame")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("churn")
+            .long("churn")
+            .help("Rank classes by commits from git log times coupling")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("churn-since")
+            .long("churn-since")
+            .value_name("DATE")
+            .help("Only count commits since DATE, e.g. 2024-01-01 or \"6 months ago\" (implies --churn)"),
+        Arg::new("external-coupling")
+            .long("external-coupling")
+            .help("Report links to framework types (Django, Angular, ASP.NET) per class and package")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("external-nodes")
+            .long("external-nodes")
+            .help("Keep classes the project does not define out of the graph and report external fan-out instead")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("focus")
+            .long("focus")
+            .value_name("CLASS")
+            .help("Only report the neighbourhood of this class")
+            .conflicts_with_all(["match", "tagged"]),
+        Arg::new("depth")
+            .long("depth")
+            .value_name("N")
+            .help("How many links away from --focus to include")
+            .value_parser(clap::value_parser!(usize))
+            .default_value("1"),
+        Arg::new("match")
+            .long("match")
+            .value_name("GLOB")
+            .help("Only report classes whose name matches this glob, e.g. 'Billing*'")
+            .conflicts_with("tagged"),
+        Arg::new("tagged")
+            .long("tagged")
+            .value_name("TAG")
+            .help("Only report classes tagged deprecated, internal, abstract, interface, exported or external, and their direct neighbours"),
+        Arg::new("color-by")
+            .long("color-by")
+            .value_name("METRIC")
+            .help("Fill dot and mermaid nodes from green to red by fan-in, instability or hotspot"),
+        Arg::new("no-summarize")
+            .long("no-summarize")
+            .help("Draw every class in dot and mermaid output instead of folding leaves of large graphs")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("collapse-packages")
+            .long("collapse-packages")
+            .help("Export packages instead of classes, with edge weights counting the bundled class links")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("group-by")
+            .long("group-by")
+            .value_name("GROUPING")
+            .help("Roll classes up into packages by directory (default) or namespace"),
+        Arg::new("kinds")
+            .long("kinds")
+            .value_name("KINDS")
+            .help("Only export these link kinds, e.g. 'inheritance,composition'")
+            .value_delimiter(','),
+        Arg::new("min-confidence")
+            .long("min-confidence")
+            .value_name("C")
+            .help("Only export links at least this confident (1.0 defined target, 0.5 unknown name)")
+            .value_parser(clap::value_parser!(f64)),
+        Arg::new("min-weight")
+            .long("min-weight")
+            .value_name("N")
+            .help("Only export edges occurring at least N times")
+            .value_parser(clap::value_parser!(usize)),
+        Arg::new("doc-url")
+            .long("doc-url")
+            .value_name("TEMPLATE")
+            .help("Link class names to their docs, e.g. 'docs/{class}.md'"),
+        Arg::new("hyperlinks")
+            .long("hyperlinks")
+            .help("Make file locations in the text report clickable (OSC 8) when writing to a terminal")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("editor-url")
+            .long("editor-url")
+            .value_name("TEMPLATE")
+            .help("URL for --hyperlinks, e.g. 'vscode://file/{path}:{line}'; implies --hyperlinks"),
+        Arg::new("diagnostics")
+            .long("diagnostics")
+            .value_name("FILE")
+            .help("Write a JSON record of skipped files, unsupported extensions, encoding fallbacks and unmatched lines"),
+        Arg::new("cache")
+            .long("cache")
+            .value_name("FILE")
+            .num_args(0..=1)
+            .default_missing_value(cache::DEFAULT_CACHE_PATH)
+            .help("Reuse parse results of files unchanged since the last run (default file: .classlink/parse-cache.json)"),
+        Arg::new("threads")
+            .long("threads")
+            .value_name("N")
+            .help("Read and parse N files at once (default: one per CPU core)")
+            .value_parser(clap::value_parser!(usize)),
+        Arg::new("read-buffer-kb")
+            .long("read-buffer-kb")
+            .value_name("KB")
+            .help("Read files in chunks of KB kibibytes (default: 64)")
+            .value_parser(clap::value_parser!(usize)),
+        Arg::new("prefetch")
+            .long("prefetch")
+            .help("Read every file before parsing, for slow or network storage")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("metrics-out")
+            .long("metrics-out")
+            .value_name("FILE")
+            .help("Write Prometheus textfile-format gauges (class counts, cycles, fan-in, package coupling)"),
+        Arg::new("timeout")
+            .long("timeout")
+            .value_name("SECS")
+            .help("Stop analyzing after this many seconds and report the partial results")
+            .value_parser(clap::value_parser!(u64)),
+        Arg::new("max-files")
+            .long("max-files")
+            .value_name("N")
+            .help("Stop after analyzing N files")
+            .value_parser(clap::value_parser!(usize)),
+        Arg::new("max-classes")
+            .long("max-classes")
+            .value_name("N")
+            .help("Stop before the graph exceeds N classes")
+            .value_parser(clap::value_parser!(usize)),
+        Arg::new("max-links")
+            .long("max-links")
+            .value_name("N")
+            .help("Stop once the graph holds N links")
+            .value_parser(clap::value_parser!(usize)),
+        Arg::new("ref")
+            .long("ref")
+            .value_name("REF")
+            .help("Branch or tag to check out when PATH is a git URL"),
+        Arg::new("locale")
+            .long("locale")
+            .value_name("LOCALE")
+            .help("Language for report text: en, ja")
+            .default_value("en"),
+        Arg::new("ci")
+            .long("ci")
+            .help("Single-scan CI mode: plain output, JSON report file, exit code from finding severity")
+            .action(clap::ArgAction::SetTrue),
+        Arg::new("template")
+            .long("template")
+            .value_name("FILE")
+            .help("Render the report with a Handlebars-style template (implies -o template)"),
+        Arg::new("report-path")
+            .long("report-path")
+            .value_name("FILE")
+            .help("Where --ci writes the JSON report")
+            .default_value("classlink-report.json"),
+        Arg::new("last-run")
+            .long("last-run")
+            .value_name("FILE")
+            .help("Where --ci keeps the previous run's numbers to print what changed since")
+            .default_value(last_run::DEFAULT_LAST_RUN_PATH),
+        Arg::new("no-last-run")
+            .long("no-last-run")
+            .help("Neither compare --ci results with the previous run nor store them")
+            .action(clap::ArgAction::SetTrue),
+    ]
+}
+
+fn path_arg() -> Arg {
+    Arg::new("path")
+        .help("Path to the directory or file to analyze")
+        .required(true)
+        .index(1)
+}
+
+fn recursive_arg() -> Arg {
+    Arg::new("recursive")
+        .short('r')
+        .long("recursive")
+        .help("Recursively scan subdirectories")
+        .action(clap::ArgAction::SetTrue)
+}
+
+fn test_pattern_arg() -> Arg {
+    Arg::new("test-pattern")
+        .long("test-pattern")
+        .value_name("GLOB")
+        .help("Glob identifying test files (repeatable; replaces the defaults)")
+        .action(clap::ArgAction::Append)
+}
+
+fn test_patterns(matches: &ArgMatches) -> Vec<String> {
+    match matches.get_many::<String>("test-pattern") {
+        Some(patterns) => patterns.cloned().collect(),
+        None => walk_config(matches, false).test_patterns,
+    }
+}
+
+fn analyze_project(matches: &ArgMatches, path: &str) -> Vec<FileAnalysis> {
+    let config = Config {
+        path: path.to_string(),
+        ..walk_config(matches, matches.get_flag("recursive"))
+    };
+    Pipeline::new(config).analyze_files().unwrap_or_else(|e| {
+        eprintln!("Error analyzing directory: {}", e);
+        std::process::exit(1);
+    })
+}
+
+// A config file supplies the defaults; flags given on the command line win
+fn walk_config(matches: &ArgMatches, recursive: bool) -> Config {
+    let mut config = match matches.get_one::<String>("config") {
+        Some(file) => Config::from_file(file).unwrap_or_else(|e| {
+            eprintln!("Error loading config {}: {}", file, e);
+            std::process::exit(1);
+        }),
+        None => Config {
+            recursive: false,
+            ..Config::default()
+        },
+    };
+    config.recursive |= recursive;
+    if let Some(depth) = matches.get_one::<usize>("max-depth") {
+        config.max_depth = Some(*depth);
+        config.recursive = true;
+    }
+    config.follow_symlinks |= matches.get_flag("follow-symlinks");
+    config.skip_binary &= !matches.get_flag("include-binary");
+    config.skip_vendored &= !matches.get_flag("include-vendored");
+    if let Some(patterns) = matches.get_many::<String>("exclude") {
+        config.exclude.extend(patterns.cloned());
+    }
+    config.respect_gitignore &= !matches.get_flag("no-gitignore");
+    if given(matches, "max-file-size") {
+        config.max_file_size_mb =
+            Some(*matches.get_one::<u64>("max-file-size").unwrap()).filter(|mb| *mb > 0);
+    }
+    config
+}
+
+fn run_analyze(matches: &ArgMatches, recursive: bool) {
+    let location = matches.get_one::<String>("path").unwrap();
+    let mut config = Config {
+        path: location.clone(),
+        ..walk_config(matches, recursive)
+    };
+    config.collapse_packages |= matches.get_flag("collapse-packages");
+    if matches.get_flag("no-summarize") {
+        config.diagram_limits = None;
+    }
+    config.capture_snippets |= matches.get_flag("snippets");
+    config.abstraction_coupling |= matches.get_flag("abstractions");
+    config.external_coupling |= matches.get_flag("external-coupling");
+    config.external_nodes |= matches.get_flag("external-nodes");
+    config.global_resolution |= matches.get_flag("global-resolution");
+    config.follow_imports |= matches.get_flag("follow-imports");
+    config.ownership |= matches.get_flag("ownership");
+    if let Some(since) = matches.get_one::<String>("churn-since") {
+        config.churn_since = Some(since.clone());
+        config.churn = true;
+    }
+    config.churn |= matches.get_flag("churn");
+    if let Some(kinds) = matches.get_many::<String>("kinds") {
+        config.export_filter.kinds = kinds
+            .map(|kind| kind.parse())
+            .collect::<Result<_, _>>()
+            .unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            });
+    }
+    if let Some(grouping) = matches.get_one::<String>("group-by") {
+        config.group_by = grouping.parse().unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+    }
+    if let Some(metric) = matches.get_one::<String>("color-by") {
+        config.diagram_color = Some(metric.parse().unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }));
+    }
+    if let Some(encoding) = matches.get_one::<String>("column-encoding") {
+        config.column_encoding = encoding.parse().unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+    }
+    if let Some(confidence) = matches.get_one::<f64>("min-confidence") {
+        config.export_filter.min_confidence = *confidence;
+    }
+    if let Some(weight) = matches.get_one::<usize>("min-weight") {
+        config.export_filter.min_weight = *weight;
+    }
+    if let Some(template) = matches.get_one::<String>("doc-url") {
+        config.doc_url = Some(template.clone());
+    }
+    if let Some(file) = matches.get_one::<String>("diagnostics") {
+        config.diagnostics_path = Some(file.clone());
+    }
+    if let Some(file) = matches.get_one::<String>("cache") {
+        config.cache_path = Some(file.clone());
+    }
+    if let Some(threads) = matches.get_one::<usize>("threads") {
+        config.io.threads = Some(*threads);
+    }
+    if let Some(kb) = matches.get_one::<usize>("read-buffer-kb") {
+        config.io.read_buffer_kb = *kb;
+    }
+    config.io.prefetch |= matches.get_flag("prefetch");
+    if let Some(template) = matches.get_one::<String>("editor-url") {
+        config.editor_url = Some(template.clone());
+        config.hyperlinks = true;
+    }
+    config.hyperlinks |= matches.get_flag("hyperlinks");
+    // Escapes would garble piped output
+    config.hyperlinks &= io::stdout().is_terminal();
+    if let Some(file) = matches.get_one::<String>("metrics-out") {
+        config.metrics_path = Some(file.clone());
+    }
+    if let Some(secs) = matches.get_one::<u64>("timeout") {
+        config.timeout_secs = Some(*secs);
+    }
+    if let Some(max) = matches.get_one::<usize>("max-files") {
+        config.limits.max_files = Some(*max);
+    }
+    if let Some(max) = matches.get_one::<usize>("max-classes") {
+        config.limits.max_classes = Some(*max);
+    }
+    if let Some(max) = matches.get_one::<usize>("max-links") {
+        config.limits.max_links = Some(*max);
+    }
+    if let Some(class) = matches.get_one::<String>("focus") {
+        config.focus = Some(Focus::Around {
+            class: class.clone(),
+            depth: *matches.get_one::<usize>("depth").unwrap(),
+        });
+    } else if let Some(pattern) = matches.get_one::<String>("match") {
+        config.focus = Some(Focus::Matching {
+            pattern: pattern.clone(),
+        });
+    } else if let Some(tag) = matches.get_one::<String>("tagged") {
+        config.focus = Some(Focus::Tagged {
+            tag: tag.parse().unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }),
+        });
+    }
+    if given(matches, "output") || matches.get_one::<String>("config").is_none() {
+        config.output = match matches.get_one::<String>("output").unwrap().parse::<OutputFormat>() {
+            Ok(output) => output,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        };
+    }
+    if let Some(template) = matches.get_one::<String>("template") {
+        config.template = Some(template.clone());
+        config.output = OutputFormat::Template;
+    }
+    if given(matches, "locale") || matches.get_one::<String>("config").is_none() {
+        config.locale = parse_locale(matches);
+    }
+    let output = config.output;
+
+    // Remote sources are checked out into a temp dir that lives until exit;
+    // fetched only once the flags are known good, and dropped before every
+    // exit below since `process::exit` skips destructors
+    let mut checkout = None;
+    if !Path::new(location).exists() {
+        let reference = matches.get_one::<String>("ref").map(String::as_str);
+        if let Some(source) = RemoteSource::parse(location, reference) {
+            match source.fetch() {
+                Ok(dir) => {
+                    config.path = dir.path().to_string_lossy().to_string();
+                    config.recursive = true;
+                    checkout = Some(dir);
                 }
-                "text" | _ => {
-                    print_text_report(&stats);
+                Err(e) => {
+                    eprintln!("Error fetching {}: {}", location, e);
+                    std::process::exit(1);
                 }
             }
         }
+    }
+
+    if matches.get_flag("ci") {
+        let report_path = matches.get_one::<String>("report-path").unwrap();
+        let last_run = (!matches.get_flag("no-last-run")).then(|| matches.get_one::<String>("last-run").unwrap().as_str());
+        let code = run_ci(config, report_path, last_run);
+        drop(checkout);
+        std::process::exit(code);
+    }
+
+    match classlink_checker::run(config) {
+        Ok(report) => print!("{}", with_trailing_newline(report.render(output))),
         Err(e) => {
             eprintln!("Error analyzing directory: {}", e);
+            drop(checkout);
             std::process::exit(1);
         }
     }
 }
 
-fn analyze_directory(path: &str, recursive: bool) -> Result<classlink_checker::LinkStatistics, Box<dyn std::error::Error>> {
-    let unified_analyzer = UnifiedAnalyzer::new();
-    let link_analyzer = LinkAnalyzer::new();
-    
-    let mut all_classes = HashSet::new();
-    let mut all_links = Vec::new();
-    
-    let files = find_supported_files(path, recursive)?;
-    
-    for file_path in files {
-        let content = fs::read_to_string(&file_path)?;
-        let result = unified_analyzer.analyze_file(&content, &file_path);
-        
-        all_classes.extend(result.classes);
-        all_links.extend(result.links);
+fn given(matches: &ArgMatches, id: &str) -> bool {
+    matches.value_source(id) == Some(ValueSource::CommandLine)
+}
+
+fn with_trailing_newline(mut rendered: String) -> String {
+    if !rendered.ends_with('\n') {
+        rendered.push('\n');
     }
-    
-    Ok(link_analyzer.analyze(all_classes, all_links))
+    rendered
 }
 
-fn find_supported_files(path: &str, recursive: bool) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    let mut files = Vec::new();
-    let path = Path::new(path);
-    let supported_extensions = ["md", "py", "ts", "tsx", "cs"];
-    
-    if path.is_file() {
-        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-            if supported_extensions.contains(&ext) {
-                files.push(path.to_string_lossy().to_string());
-            }
+fn run_ci(config: Config, report_path: &str, last_run: Option<&str>) -> i32 {
+    let path = config.path.clone();
+    let report = match classlink_checker::run(config) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("classlink: error analyzing {}: {}", path, e);
+            return 1;
         }
-    } else if path.is_dir() {
-        for entry in fs::read_dir(path)? {
-            let entry = entry?;
-            let entry_path = entry.path();
-            
-            if entry_path.is_file() {
-                if let Some(ext) = entry_path.extension().and_then(|e| e.to_str()) {
-                    if supported_extensions.contains(&ext) {
-                        files.push(entry_path.to_string_lossy().to_string());
-                    }
-                }
-            } else if recursive && entry_path.is_dir() {
-                let sub_files = find_supported_files(&entry_path.to_string_lossy(), recursive)?;
-                files.extend(sub_files);
-            }
+    };
+
+    let written = fs::File::create(report_path)
+        .and_then(|file| reporter::dispatch(&report, &mut JsonReporter::new(file)));
+    if let Err(e) = written {
+        eprintln!("classlink: error writing {}: {}", report_path, e);
+        return 1;
+    }
+
+    let count = |severity| report.findings.iter().filter(|f| f.severity == severity).count();
+    let summary = Catalog::new(report.locale).text(
+        Message::CiSummary,
+        &[
+            &report.statistics.total_classes,
+            &report.statistics.isolated_classes.len(),
+            &count(Severity::Error),
+            &count(Severity::Warning),
+            &report_path,
+        ],
+    );
+    println!("{}", summary);
+    // A partial scan would make the next delta meaningless, so it is neither
+    // compared nor stored
+    if let Some(path) = last_run.filter(|_| report.interrupted.is_none() && report.truncated.is_none()) {
+        let current = RunSummary::new(&report.statistics, &report.findings);
+        if let Some(previous) = RunSummary::load(path) {
+            println!("{}", current.delta(&previous, &Catalog::new(report.locale)));
+        }
+        if let Err(e) = current.save(path) {
+            eprintln!("classlink: error writing {}: {}", path, e);
+        }
+    }
+    if let Some(interruption) = report.interrupted {
+        let message = match interruption {
+            Interruption::Cancelled => Message::ScanCancelled,
+            Interruption::TimedOut => Message::ScanTimedOut,
+        };
+        eprintln!("{}", Catalog::new(report.locale).text(message, &[]));
+    }
+    if let Some(hit) = report.truncated {
+        eprintln!("{}", Catalog::new(report.locale).text(Message::ScanTruncated, &[&hit.limit, &hit.max]));
+    }
+
+    report.exit_code()
+}
+
+fn run_mcp(matches: &ArgMatches) {
+    let path = matches.get_one::<String>("path").unwrap();
+    let mut server = McpServer::new(path, matches.get_flag("recursive"));
+
+    let stdin = io::stdin();
+    if let Err(e) = jsonrpc::serve(&mut server, stdin.lock(), io::stdout()) {
+        eprintln!("Error running MCP server: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run_daemon(matches: &ArgMatches) {
+    let path = matches.get_one::<String>("path").unwrap();
+    let mut daemon = Daemon::new(path, matches.get_flag("recursive"));
+
+    let stdin = io::stdin();
+    if let Err(e) = jsonrpc::serve(&mut daemon, stdin.lock(), io::stdout()) {
+        eprintln!("Error running daemon: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run_diff(matches: &ArgMatches) {
+    let load = |name: &str| {
+        let path = matches.get_one::<String>(name).unwrap();
+        diff::load_snapshot(path).unwrap_or_else(|e| {
+            eprintln!("Error loading snapshot {}: {}", path, e);
+            std::process::exit(1);
+        })
+    };
+    let graph_diff = GraphDiff::between(&load("old"), &load("new"));
+
+    match matches.get_one::<String>("output").unwrap().as_str() {
+        "json" => match serde_json::to_string_pretty(&graph_diff) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("Error serializing to JSON: {}", e),
+        },
+        "text" => print!("{}", graph_diff.render_text(&Catalog::new(parse_locale(matches)))),
+        "markdown" => print!("{}", graph_diff.render_markdown(&Catalog::new(parse_locale(matches)))),
+        other => {
+            eprintln!("Unsupported diff output format: {}", other);
+            std::process::exit(1);
         }
     }
-    
-    Ok(files)
 }
 
-fn print_text_report(stats: &classlink_checker::LinkStatistics) {
-    print_header();
-    print_overall_statistics(stats);
-    print_isolated_classes(stats);
-    print_class_link_counts(stats);
-    print_most_significant_classes(stats);
+fn run_snapshot(matches: &ArgMatches) {
+    let path = matches.get_one::<String>("path").unwrap();
+    let files = analyze_project(matches, path);
+    let file = matches.get_one::<String>("file").unwrap();
+    if let Err(e) = Snapshot::build(path, &files).save(file) {
+        eprintln!("Error writing snapshot {}: {}", file, e);
+        std::process::exit(1);
+    }
 }
 
-fn print_header() {
-    println!("=== Class Link Analysis Report ===\n");
+fn run_merge(matches: &ArgMatches) {
+    let format = matches
+        .get_one::<String>("output")
+        .unwrap()
+        .parse::<OutputFormat>()
+        .unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+    let locale = parse_locale(matches);
+    let reports = matches
+        .get_many::<String>("reports")
+        .unwrap()
+        .map(|input| {
+            let manifest = diff::load_snapshot(input).unwrap_or_else(|e| {
+                eprintln!("Error loading {}: {}", input, e);
+                std::process::exit(1);
+            });
+            // A report is named after its file or directory, e.g. `billing.json`
+            let path = Path::new(input);
+            let root = match path.is_file() {
+                true => path.file_stem().map(|stem| stem.to_string_lossy().to_string()),
+                false => fs::canonicalize(path).ok().map(|dir| dir.to_string_lossy().to_string()),
+            }
+            .unwrap_or_else(|| input.clone());
+            Report::from_files(&root, manifest.files(&root), locale)
+        })
+        .collect();
+    print!("{}", with_trailing_newline(Report::merge(reports).render(format)));
 }
 
-fn print_overall_statistics(stats: &classlink_checker::LinkStatistics) {
-    println!("📊 Overall Statistics:");
-    println!("  Total Classes: {}", stats.total_classes);
-    println!("  Isolated Classes: {}", stats.isolated_classes.len());
-    println!();
+fn run_verify(matches: &ArgMatches) {
+    let report_path = matches.get_one::<String>("report").unwrap();
+    let provenance = Provenance::from_report(report_path).unwrap_or_else(|e| {
+        eprintln!("Error loading report {}: {}", report_path, e);
+        std::process::exit(1);
+    });
+    let path = matches.get_one::<String>("path").map(String::as_str);
+    let verification = provenance.verify(path).unwrap_or_else(|e| {
+        eprintln!("Error verifying report {}: {}", report_path, e);
+        std::process::exit(1);
+    });
+
+    match matches.get_one::<String>("output").unwrap().as_str() {
+        "json" => match serde_json::to_string_pretty(&verification) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("Error serializing to JSON: {}", e),
+        },
+        "text" => print!("{}", verification.render_text(&Catalog::new(parse_locale(matches)))),
+        other => {
+            eprintln!("Unsupported verify output format: {}", other);
+            std::process::exit(1);
+        }
+    }
+    if !verification.is_match() {
+        std::process::exit(2);
+    }
 }
 
-fn print_isolated_classes(stats: &classlink_checker::LinkStatistics) {
-    if !stats.isolated_classes.is_empty() {
-        println!("🏝️  Isolated Classes (no links):");
-        for class in &stats.isolated_classes {
-            println!("  - {}", class);
+fn run_impact(matches: &ArgMatches) {
+    let target = matches.get_one::<String>("target").unwrap();
+    let path = matches.get_one::<String>("path").unwrap();
+    let files = analyze_project(matches, path);
+
+    let Some(report) = impact::analyze_impact(path, &files, target, &test_patterns(matches)) else {
+        let classes = files.iter().flat_map(|file| &file.result.classes);
+        eprintln!("Unknown class or file: {}{}", target, did_you_mean(&search::find_classes(classes, target)));
+        std::process::exit(1);
+    };
+
+    match matches.get_one::<String>("output").unwrap().as_str() {
+        "json" => match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("Error serializing to JSON: {}", e),
+        },
+        "text" => print!("{}", report.render_text(&Catalog::new(parse_locale(matches)))),
+        other => {
+            eprintln!("Unsupported impact output format: {}", other);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_select_tests(matches: &ArgMatches) {
+    let path = matches.get_one::<String>("path").unwrap();
+    let changed: Vec<String> = match matches.get_many::<String>("changed") {
+        Some(changed) => changed.cloned().collect(),
+        None => io::stdin()
+            .lock()
+            .lines()
+            .map_while(Result::ok)
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect(),
+    };
+    let files = analyze_project(matches, path);
+    let selected = impact::select_tests(path, &files, &changed, &test_patterns(matches));
+
+    match matches.get_one::<String>("output").unwrap().as_str() {
+        "json" => println!("{}", serde_json::json!(selected)),
+        "text" => {
+            for file in selected {
+                println!("{}", file);
+            }
+        }
+        other => {
+            eprintln!("Unsupported select-tests output format: {}", other);
+            std::process::exit(1);
         }
-        println!();
     }
 }
 
-fn print_class_link_counts(stats: &classlink_checker::LinkStatistics) {
-    println!("🔗 Class Link Counts:");
-    let sorted_classes = get_sorted_classes_by_total_links(stats);
-    
-    for (class, counts) in sorted_classes {
-        if has_any_links(counts) {
-            println!("  📦 {}: {} outgoing, {} incoming", 
-                     class, counts.outgoing_links, counts.incoming_links);
+fn run_workspace(matches: &ArgMatches) {
+    let roots: Vec<String> = matches.get_many::<String>("roots").unwrap().cloned().collect();
+    let analyzer = Pipeline::new(walk_config(matches, matches.get_flag("recursive")))
+        .analyzer()
+        .unwrap_or_else(|e| {
+            eprintln!("Error analyzing workspace: {}", e);
+            std::process::exit(1);
+        });
+    let workspace = Workspace::scan(&analyzer, &roots).unwrap_or_else(|e| {
+        eprintln!("Error analyzing workspace: {}", e);
+        std::process::exit(1);
+    });
+    let report = workspace.report();
+
+    match matches.get_one::<String>("output").unwrap().as_str() {
+        "json" => match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("Error serializing to JSON: {}", e),
+        },
+        "text" => print!("{}", report.render_text(&Catalog::new(parse_locale(matches)))),
+        other => {
+            eprintln!("Unsupported workspace output format: {}", other);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_accuracy(matches: &ArgMatches) {
+    let truth_path = matches.get_one::<String>("truth").unwrap();
+    let truth = GroundTruth::from_file(truth_path).unwrap_or_else(|e| {
+        eprintln!("Error loading ground truth {}: {}", truth_path, e);
+        std::process::exit(1);
+    });
+    let path = matches.get_one::<String>("path").unwrap();
+    let files = analyze_project(matches, path);
+    let report = accuracy::evaluate(path, &files, &truth);
+
+    match matches.get_one::<String>("output").unwrap().as_str() {
+        "json" => match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("Error serializing to JSON: {}", e),
+        },
+        "text" => print!("{}", report.render_text(&Catalog::new(parse_locale(matches)))),
+        other => {
+            eprintln!("Unsupported accuracy output format: {}", other);
+            std::process::exit(1);
         }
     }
 }
 
-fn get_sorted_classes_by_total_links(stats: &classlink_checker::LinkStatistics) -> Vec<(&String, &classlink_checker::ClassLinkCount)> {
-    let mut sorted_classes: Vec<_> = stats.class_link_counts.iter().collect();
-    sorted_classes.sort_by(|a, b| {
-        (b.1.outgoing_links + b.1.incoming_links).cmp(&(a.1.outgoing_links + a.1.incoming_links))
+fn run_query(matches: &ArgMatches) {
+    let text = matches.get_one::<String>("query").unwrap();
+    let parsed = query::parse(text).unwrap_or_else(|e| {
+        eprintln!("Error parsing query: {}", e);
+        std::process::exit(1);
     });
-    sorted_classes
+    let path = matches.get_one::<String>("path").unwrap();
+    let files = analyze_project(matches, path);
+    let graph = QueryGraph::build(path, &files);
+    let result = graph.run(&parsed);
+    if result.rows.is_empty() {
+        for (name, suggestions) in graph.unknown_names(&parsed) {
+            eprintln!("No class named {}{}", name, did_you_mean(&suggestions));
+        }
+    }
+
+    match matches.get_one::<String>("output").unwrap().as_str() {
+        "json" => match serde_json::to_string_pretty(&result) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("Error serializing to JSON: {}", e),
+        },
+        "text" => print!("{}", result.render_text(&Catalog::new(parse_locale(matches)))),
+        other => {
+            eprintln!("Unsupported query output format: {}", other);
+            std::process::exit(1);
+        }
+    }
 }
 
-fn has_any_links(counts: &classlink_checker::ClassLinkCount) -> bool {
-    counts.outgoing_links > 0 || counts.incoming_links > 0
+fn run_find(matches: &ArgMatches) {
+    let pattern = matches.get_one::<String>("pattern").unwrap();
+    let path = matches.get_one::<String>("path").unwrap();
+    let files = analyze_project(matches, path);
+    let graph = QueryGraph::build(path, &files);
+    // Link targets the project does not define are found too, without a file
+    let mut defined_in: BTreeMap<&str, String> = BTreeMap::new();
+    for file in &files {
+        for class in &file.result.classes {
+            let relative = relative_path(path, &file.path);
+            let entry = defined_in.entry(class.as_str()).or_insert_with(|| relative.clone());
+            if relative < *entry {
+                *entry = relative;
+            }
+        }
+    }
+    let found = graph.find_classes(pattern);
+
+    match matches.get_one::<String>("output").unwrap().as_str() {
+        "json" => {
+            let found: Vec<serde_json::Value> = found
+                .iter()
+                .map(|name| serde_json::json!({ "name": name, "file": defined_in.get(name.as_str()) }))
+                .collect();
+            println!("{}", serde_json::json!(found));
+        }
+        "text" => {
+            let width = found.iter().map(|name| name.chars().count()).max().unwrap_or(0);
+            for name in &found {
+                match defined_in.get(name.as_str()) {
+                    Some(file) => println!("{:<width$}  {}", name, file, width = width),
+                    None => println!("{}", name),
+                }
+            }
+        }
+        other => {
+            eprintln!("Unsupported find output format: {}", other);
+            std::process::exit(1);
+        }
+    }
+    if found.is_empty() {
+        std::process::exit(1);
+    }
 }
 
-fn print_most_significant_classes(stats: &classlink_checker::LinkStatistics) {
-    if let Some((class, count)) = &stats.most_linking_class {
-        println!("\n🔝 Most Linking Class: {} ({} outgoing links)", class, count);
+// "; did you mean OrderService, OrderServices?" for the closest few classes
+fn did_you_mean(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        return String::new();
     }
-    
-    if let Some((class, count)) = &stats.most_linked_class {
-        println!("🎯 Most Linked Class: {} ({} incoming links)", class, count);
+    let closest: Vec<&str> = suggestions.iter().take(3).map(String::as_str).collect();
+    format!("; did you mean {}?", closest.join(", "))
+}
+
+fn run_docs_sync(matches: &ArgMatches) {
+    let path = matches.get_one::<String>("path").unwrap();
+    let files = analyze_project(matches, path);
+    let report = docsync::sync(path, &files, matches.get_one::<String>("docs-dir").unwrap());
+
+    match matches.get_one::<String>("output").unwrap().as_str() {
+        "json" => match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("Error serializing to JSON: {}", e),
+        },
+        "text" => print!("{}", report.render_text(&Catalog::new(parse_locale(matches)))),
+        "lcov" => print!("{}", docsync::to_lcov(&docsync::coverage(path, &files))),
+        other => {
+            eprintln!("Unsupported docs sync output format: {}", other);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run_scaffold_docs(matches: &ArgMatches) {
+    let template = match matches.get_one::<String>("template") {
+        Some(template_path) => fs::read_to_string(template_path).unwrap_or_else(|e| {
+            eprintln!("Error reading template {}: {}", template_path, e);
+            std::process::exit(1);
+        }),
+        None => docsync::DEFAULT_TEMPLATE.to_string(),
+    };
+    let path = matches.get_one::<String>("path").unwrap();
+    let files = analyze_project(matches, path);
+    let report = docsync::sync(path, &files, matches.get_one::<String>("docs-dir").unwrap());
+    let catalog = Catalog::new(parse_locale(matches));
+
+    match docsync::scaffold(path, &report.stubs, &template) {
+        Ok(created) if created.is_empty() => println!("{}", catalog.text(Message::ScaffoldNothing, &[])),
+        Ok(created) => {
+            for page in created {
+                println!("{}", catalog.text(Message::ScaffoldCreated, &[&page]));
+            }
+        }
+        Err(e) => {
+            eprintln!("Error writing documentation pages: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn parse_locale(matches: &ArgMatches) -> Locale {
+    match matches.get_one::<String>("locale").unwrap().parse::<Locale>() {
+        Ok(locale) => locale,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
     }
 }
@@ -0,0 +1,462 @@
+use crate::annotations::ClassTag;
+use crate::file_analyzer::{AnalysisResult, FileType, UnifiedAnalyzer};
+use crate::findings::{self, Finding};
+use crate::graph::ClassGraph;
+use crate::i18n::Catalog;
+use crate::impact;
+use crate::parser::ClassLink;
+use crate::project::{self, FileAnalysis, LinkOccurrence};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::str::FromStr;
+
+pub const MANIFEST_SCHEMA: &str = "classlink-manifest/1";
+
+// Architecture manifest meant to be committed next to the code. Everything is
+// sorted and identified by stable ids (`class:Name`, `package:dir`,
+// `link:From->To`) and carries no timestamps, so regenerating it on an
+// unchanged tree yields an identical file and review diffs stay minimal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchitectureManifest {
+    pub schema: String,
+    pub generator: String,
+    pub classes: Vec<ManifestClass>,
+    pub packages: Vec<ManifestPackage>,
+    pub links: Vec<ManifestLink>,
+    pub metrics: ManifestMetrics,
+    pub rule_results: Vec<Finding>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestClass {
+    pub id: String,
+    pub name: String,
+    pub package: String,
+    pub files: Vec<String>,
+    pub fan_in: usize,
+    pub fan_out: usize,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub tags: BTreeSet<ClassTag>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestPackage {
+    pub id: String,
+    pub path: String,
+    pub classes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestLink {
+    pub id: String,
+    pub from: String,
+    pub to: String,
+    pub occurrences: usize,
+    // Every place the link occurs; absent in manifests written before
+    // locations were recorded
+    #[serde(default)]
+    pub locations: Vec<LinkOccurrence>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestMetrics {
+    pub total_classes: usize,
+    pub total_packages: usize,
+    pub total_links: usize,
+    pub isolated_classes: usize,
+    pub cycles: usize,
+}
+
+pub fn class_id(name: &str) -> String {
+    format!("class:{}", name)
+}
+
+pub fn package_id(path: &str) -> String {
+    format!("package:{}", path)
+}
+
+pub fn link_id(from: &str, to: &str) -> String {
+    format!("link:{}->{}", from, to)
+}
+
+impl ArchitectureManifest {
+    pub fn build(root: &str, files: &[FileAnalysis]) -> Self {
+        let mut class_files: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for file in files {
+            let relative = relative_path(root, &file.path);
+            for class in &file.result.classes {
+                class_files
+                    .entry(class.clone())
+                    .or_default()
+                    .insert(relative.clone());
+            }
+        }
+
+        let merged = project::merge(files.to_vec());
+        let graph = ClassGraph::new(&merged.classes, &merged.links);
+
+        let mut packages: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let classes: Vec<ManifestClass> = class_files
+            .into_iter()
+            .map(|(name, files)| {
+                let package = files.iter().next().map(|f| package_of(f)).unwrap_or_default();
+                packages.entry(package.clone()).or_default().push(class_id(&name));
+                ManifestClass {
+                    id: class_id(&name),
+                    fan_in: graph.dependents(&name).len(),
+                    fan_out: graph.dependencies(&name).len(),
+                    tags: merged.class_tags.get(&name).cloned().unwrap_or_default(),
+                    name,
+                    package: package_id(&package),
+                    files: files.into_iter().collect(),
+                }
+            })
+            .collect();
+
+        let links: Vec<ManifestLink> = project::group_links(root, files)
+            .into_iter()
+            .map(|edge| ManifestLink {
+                id: link_id(&edge.from, &edge.to),
+                from: class_id(&edge.from),
+                to: class_id(&edge.to),
+                occurrences: edge.occurrences.len(),
+                locations: edge.occurrences,
+            })
+            .collect();
+
+        let packages: Vec<ManifestPackage> = packages
+            .into_iter()
+            .map(|(path, classes)| ManifestPackage {
+                id: package_id(&path),
+                path,
+                classes,
+            })
+            .collect();
+
+        let metrics = ManifestMetrics {
+            total_classes: classes.len(),
+            total_packages: packages.len(),
+            total_links: links.len(),
+            isolated_classes: graph.isolated_classes().len(),
+            cycles: graph.find_cycles().len(),
+        };
+        let isolation = findings::classify_isolated(
+            root,
+            files,
+            &merged,
+            &findings::default_entry_points(),
+            &impact::default_test_patterns(),
+        );
+
+        Self {
+            schema: MANIFEST_SCHEMA.to_string(),
+            generator: format!("classlink-checker {}", env!("CARGO_PKG_VERSION")),
+            classes,
+            packages,
+            links,
+            metrics,
+            rule_results: findings::builtin(root, files, &merged, &isolation, &Catalog::default()),
+        }
+    }
+}
+
+impl ArchitectureManifest {
+    // The per-file analyses a manifest was built from, placed under `root`.
+    // Class sizes and budgets are not recorded and come back empty. Links
+    // without recorded locations are attributed to the first file of their
+    // source class, once per occurrence.
+    pub fn files(&self, root: &str) -> Vec<FileAnalysis> {
+        let mut results: BTreeMap<&str, AnalysisResult> = BTreeMap::new();
+        let mut first_file: BTreeMap<&str, &str> = BTreeMap::new();
+        for class in &self.classes {
+            for file in &class.files {
+                let result = results.entry(file).or_default();
+                result.classes.insert(class.name.clone());
+                if !class.tags.is_empty() {
+                    result.class_tags.insert(class.name.clone(), class.tags.clone());
+                }
+            }
+            if let Some(file) = class.files.first() {
+                first_file.insert(&class.id, file);
+            }
+        }
+        let name = |id: &str| id.strip_prefix("class:").unwrap_or(id).to_string();
+        for link in &self.links {
+            let link_at = |line_number, kind| ClassLink {
+                from_class: name(&link.from),
+                to_class: name(&link.to),
+                line_number,
+                kind,
+                position: None,
+            };
+            if link.locations.is_empty() {
+                if let Some(file) = first_file.get(link.from.as_str()) {
+                    let result = results.entry(file).or_default();
+                    result.links.extend((0..link.occurrences).map(|_| link_at(0, Default::default())));
+                }
+            }
+            for location in &link.locations {
+                let result = results.entry(&location.file).or_default();
+                result.links.push(link_at(location.line, location.kind));
+            }
+        }
+
+        results
+            .into_iter()
+            .map(|(file, result)| FileAnalysis {
+                path: Path::new(root).join(file).to_string_lossy().to_string(),
+                result,
+                encoding: Default::default(),
+            })
+            .collect()
+    }
+}
+
+// Paths in the manifest are root-relative with `/` separators so the file is
+// identical on every machine and OS
+pub fn relative_path(root: &str, path: &str) -> String {
+    let relative = Path::new(path)
+        .strip_prefix(root)
+        .unwrap_or_else(|_| Path::new(path));
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    if parts.is_empty() {
+        Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    } else {
+        parts.join("/")
+    }
+}
+
+pub fn package_of(relative_file: &str) -> String {
+    match relative_file.rsplit_once('/') {
+        Some((dir, _)) => dir.to_string(),
+        None => ".".to_string(),
+    }
+}
+
+// Package of every defined class. A class defined in several directories
+// is attributed to the first package by name, so the choice is stable.
+pub fn class_packages(root: &str, files: &[FileAnalysis]) -> BTreeMap<String, String> {
+    let mut packages: BTreeMap<String, String> = BTreeMap::new();
+    for file in files {
+        let package = package_of(&relative_path(root, &file.path));
+        for class in &file.result.classes {
+            packages
+                .entry(class.clone())
+                .and_modify(|existing| {
+                    if package < *existing {
+                        *existing = package.clone();
+                    }
+                })
+                .or_insert_with(|| package.clone());
+        }
+    }
+    packages
+}
+
+// What class roll-ups (package coupling, package exports, offenders) group
+// by: the directory of the defining file, or the class's namespace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Grouping {
+    #[default]
+    Directory,
+    Namespace,
+}
+
+impl FromStr for Grouping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "directory" | "dir" => Ok(Grouping::Directory),
+            "namespace" => Ok(Grouping::Namespace),
+            _ => Err(format!("Unknown grouping: {} (expected directory, namespace)", s)),
+        }
+    }
+}
+
+// Namespace implied by the path of a file declaring none: the dotted module
+// path for Python (`shop/cart.py` is `shop.cart`, `shop/__init__.py` is
+// `shop`), the module path for TypeScript and JavaScript (`src/shop/cart`, with
+// `index` files naming their directory) and the module path for Rust
+// (`src::shop::cart`, with `mod.rs` naming its directory)
+pub fn path_namespace(relative_file: &str) -> Option<String> {
+    let file_type = UnifiedAnalyzer::detect_file_type(relative_file);
+    let index = match file_type {
+        FileType::Python => "__init__",
+        FileType::TypeScript | FileType::JavaScript => "index",
+        FileType::Rust => "mod",
+        _ => return None,
+    };
+    let module = relative_file.rsplit_once('.')?.0;
+    let module = match module.rsplit_once('/') {
+        Some((dir, name)) if name == index => dir,
+        None if module == index => return None,
+        _ => module,
+    };
+    Some(match file_type {
+        FileType::Python => module.replace('/', "."),
+        FileType::Rust => module.replace('/', "::"),
+        _ => module.to_string(),
+    })
+}
+
+// Namespace of every defined class: the declared one, else the one its path
+// implies, else its directory package so documentation pages still group.
+// Among files of the same standing the first namespace by name wins.
+pub fn class_namespaces(root: &str, files: &[FileAnalysis]) -> BTreeMap<String, String> {
+    let mut namespaces: BTreeMap<String, (u8, String)> = BTreeMap::new();
+    for file in files {
+        let relative = relative_path(root, &file.path);
+        let inferred = path_namespace(&relative);
+        for class in &file.result.classes {
+            let candidate = match (file.result.namespaces.get(class), &inferred) {
+                (Some(declared), _) => (2, declared.clone()),
+                (None, Some(inferred)) => (1, inferred.clone()),
+                (None, None) => (0, package_of(&relative)),
+            };
+            namespaces
+                .entry(class.clone())
+                .and_modify(|existing| {
+                    if candidate.0 > existing.0 || (candidate.0 == existing.0 && candidate.1 < existing.1) {
+                        *existing = candidate.clone();
+                    }
+                })
+                .or_insert(candidate);
+        }
+    }
+    namespaces.into_iter().map(|(class, (_, namespace))| (class, namespace)).collect()
+}
+
+pub fn class_groups(root: &str, files: &[FileAnalysis], grouping: Grouping) -> BTreeMap<String, String> {
+    match grouping {
+        Grouping::Directory => class_packages(root, files),
+        Grouping::Namespace => class_namespaces(root, files),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, classes: &[&str], links: &[(&str, &str)]) -> FileAnalysis {
+        FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|s| s.to_string()).collect(),
+                links: links
+                    .iter()
+                    .map(|(from, to)| ClassLink {
+                        from_class: from.to_string(),
+                        to_class: to.to_string(),
+                        line_number: 1,
+                        kind: Default::default(),
+                        position: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_build_manifest_with_stable_ids() {
+        let files = vec![
+            file("/repo/app/service.py", &["Service"], &[("Service", "Repo"), ("Service", "Repo")]),
+            file("/repo/data/repo.py", &["Repo"], &[]),
+            file("/repo/main.py", &["Main"], &[]),
+        ];
+
+        let manifest = ArchitectureManifest::build("/repo", &files);
+
+        assert_eq!(manifest.schema, MANIFEST_SCHEMA);
+        let service = manifest.classes.iter().find(|c| c.name == "Service").unwrap();
+        assert_eq!(service.id, "class:Service");
+        assert_eq!(service.package, "package:app");
+        assert_eq!(service.files, vec!["app/service.py"]);
+        assert_eq!(service.fan_out, 1);
+
+        let packages: Vec<&str> = manifest.packages.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(packages, vec![".", "app", "data"]);
+
+        assert_eq!(manifest.links.len(), 1);
+        assert_eq!(manifest.links[0].id, "link:Service->Repo");
+        assert_eq!(manifest.links[0].occurrences, 2);
+        assert_eq!(manifest.links[0].locations.len(), 2);
+        assert_eq!(manifest.links[0].locations[0].file, "app/service.py");
+        assert_eq!(manifest.metrics.isolated_classes, 1);
+        assert_eq!(manifest.rule_results.len(), 1);
+    }
+
+    #[test]
+    fn test_files_rebuild_the_manifest() {
+        let files = vec![
+            file("/repo/app/service.py", &["Service"], &[("Service", "Repo"), ("Service", "Repo")]),
+            file("/repo/data/repo.py", &["Repo"], &[]),
+        ];
+        let manifest = ArchitectureManifest::build("/repo", &files);
+
+        let rebuilt = manifest.files("/billing");
+
+        let paths: Vec<&str> = rebuilt.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, vec!["/billing/app/service.py", "/billing/data/repo.py"]);
+        assert_eq!(rebuilt[0].result.links.len(), 2);
+        let again = ArchitectureManifest::build("/billing", &rebuilt);
+        assert_eq!(again.classes, manifest.classes);
+        assert_eq!(again.links, manifest.links);
+    }
+
+    #[test]
+    fn test_manifest_is_deterministic() {
+        let files = vec![
+            file("/repo/b.py", &["B", "C"], &[("B", "C")]),
+            file("/repo/a.py", &["A"], &[("A", "B")]),
+        ];
+        let mut reversed = files.clone();
+        reversed.reverse();
+
+        let first = serde_json::to_string(&ArchitectureManifest::build("/repo", &files)).unwrap();
+        let second = serde_json::to_string(&ArchitectureManifest::build("/repo", &reversed)).unwrap();
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    #[cfg(all(feature = "python", feature = "typescript", feature = "csharp", feature = "rust"))]
+    fn test_class_namespaces_prefer_declarations_over_paths() {
+        let csharp = "namespace Shop.Billing;\n\npublic class Invoice\n{\n}\n";
+        let files = vec![
+            FileAnalysis {
+                path: "/repo/src/Billing/Invoice.cs".to_string(),
+                result: UnifiedAnalyzer::new().analyze_file(csharp, "Invoice.cs"),
+                encoding: Default::default(),
+            },
+            file("/repo/docs/Invoice.md", &["Invoice", "Guide"], &[]),
+            file("/repo/shop/cart.py", &["Cart"], &[("Cart", "Invoice")]),
+            file("/repo/shop/__init__.py", &["Shop"], &[]),
+            file("/repo/web/src/app/index.ts", &["App"], &[("App", "Cart")]),
+        ];
+
+        let namespaces = class_namespaces("/repo", &files);
+
+        let found: Vec<(&str, &str)> = namespaces.iter().map(|(c, n)| (c.as_str(), n.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![("App", "web/src/app"), ("Cart", "shop.cart"), ("Guide", "docs"), ("Invoice", "Shop.Billing"), ("Shop", "shop")]
+        );
+        assert_eq!(class_groups("/repo", &files, Grouping::Directory)["Invoice"], "docs");
+        assert_eq!(path_namespace("src/shop/mod.rs").as_deref(), Some("src::shop"));
+        assert_eq!("Namespace".parse::<Grouping>(), Ok(Grouping::Namespace));
+        let collapsed = crate::export::collapse_to_packages("/repo", &files, Grouping::Namespace);
+        let edges: Vec<(&str, &str)> = collapsed.links.iter().map(|l| (l.from_class.as_str(), l.to_class.as_str())).collect();
+        assert_eq!(edges, vec![("shop.cart", "Shop.Billing"), ("web/src/app", "shop.cart")]);
+    }
+}
@@ -0,0 +1,238 @@
+use crate::analyzer::LinkAnalyzer;
+use crate::graph::ClassGraph;
+use crate::jsonrpc::{self, Handler, RpcError, INTERNAL_ERROR, INVALID_PARAMS, METHOD_NOT_FOUND};
+use crate::project::ProjectAnalyzer;
+use serde_json::{json, Value};
+
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+// Model Context Protocol server over stdio. Every tool call re-analyzes the
+// project so answers stay current while an assistant edits files.
+pub struct McpServer {
+    root: String,
+    recursive: bool,
+}
+
+impl McpServer {
+    pub fn new(root: &str, recursive: bool) -> Self {
+        Self {
+            root: root.to_string(),
+            recursive,
+        }
+    }
+
+    pub fn handle_request(&self, message: &Value) -> Option<Value> {
+        let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+        // Notifications carry no id and never get a response
+        let id = message.get("id")?.clone();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(self.initialize()),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => self.call_tool(&params),
+            _ => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+        };
+
+        Some(jsonrpc::response(id, result))
+    }
+
+    fn initialize(&self) -> Value {
+        json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {} },
+            "serverInfo": {
+                "name": "classlink-checker",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        })
+    }
+
+    fn call_tool(&self, params: &Value) -> Result<Value, RpcError> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+        let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+
+        let analysis = ProjectAnalyzer::new()
+            .recursive(self.recursive)
+            .analyze(&self.root)
+            .map_err(|e| (INTERNAL_ERROR, format!("Error analyzing project: {}", e)))?;
+        let graph = ClassGraph::new(&analysis.classes, &analysis.links);
+
+        let payload = match name {
+            "get_class_dependencies" => {
+                let class = jsonrpc::string_param(&arguments, "class")?;
+                if !graph.contains(class) {
+                    return Ok(tool_error(&format!("Unknown class: {}", class)));
+                }
+                json!({
+                    "class": class,
+                    "dependencies": graph.dependencies(class),
+                    "dependents": graph.dependents(class),
+                })
+            }
+            "find_cycles" => json!({ "cycles": graph.find_cycles() }),
+            "get_isolated_classes" => json!({ "isolated_classes": graph.isolated_classes() }),
+            "get_statistics" => {
+                let stats = LinkAnalyzer::new().analyze(analysis.classes, analysis.links);
+                serde_json::to_value(stats).map_err(|e| (INTERNAL_ERROR, e.to_string()))?
+            }
+            _ => return Err((INVALID_PARAMS, format!("Unknown tool: {}", name))),
+        };
+
+        Ok(json!({
+            "content": [{ "type": "text", "text": payload.to_string() }],
+            "isError": false,
+        }))
+    }
+}
+
+impl Handler for McpServer {
+    fn handle_message(&mut self, message: &Value) -> Vec<Value> {
+        self.handle_request(message).into_iter().collect()
+    }
+}
+
+fn tool_definitions() -> Value {
+    let no_arguments = json!({ "type": "object", "properties": {} });
+    json!([
+        {
+            "name": "get_class_dependencies",
+            "description": "List the classes a class links to and the classes linking to it",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "class": { "type": "string", "description": "Class name" },
+                },
+                "required": ["class"],
+            },
+        },
+        {
+            "name": "find_cycles",
+            "description": "Find groups of classes that depend on each other circularly",
+            "inputSchema": no_arguments,
+        },
+        {
+            "name": "get_isolated_classes",
+            "description": "List classes with no incoming or outgoing links",
+            "inputSchema": no_arguments,
+        },
+        {
+            "name": "get_statistics",
+            "description": "Return the full link statistics report for the project",
+            "inputSchema": no_arguments,
+        },
+    ])
+}
+
+fn tool_error(message: &str) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": message }],
+        "isError": true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jsonrpc::PARSE_ERROR;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_project() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("Alpha.md"),
+            "# Alpha\n\nUses [Beta](Beta.md).\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join("Beta.md"),
+            "# Beta\n\nUses [Alpha](Alpha.md).\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("Lonely.md"), "# Lonely\n").unwrap();
+        temp_dir
+    }
+
+    fn call(server: &McpServer, tool: &str, arguments: Value) -> Value {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": { "name": tool, "arguments": arguments },
+        });
+        let response = server.handle_request(&request).unwrap();
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_initialize_and_list_tools() {
+        let server = McpServer::new(".", false);
+
+        let init = server
+            .handle_request(&json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize" }))
+            .unwrap();
+        assert_eq!(init["result"]["protocolVersion"], PROTOCOL_VERSION);
+
+        let tools = server
+            .handle_request(&json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }))
+            .unwrap();
+        let names: Vec<&str> = tools["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert!(names.contains(&"get_class_dependencies"));
+        assert!(names.contains(&"find_cycles"));
+        assert!(names.contains(&"get_isolated_classes"));
+    }
+
+    #[test]
+    fn test_notifications_get_no_response() {
+        let server = McpServer::new(".", false);
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+
+        assert!(server.handle_request(&notification).is_none());
+    }
+
+    #[test]
+    fn test_graph_tools() {
+        let project = create_project();
+        let server = McpServer::new(&project.path().to_string_lossy(), false);
+
+        let deps = call(&server, "get_class_dependencies", json!({ "class": "Alpha" }));
+        assert_eq!(deps["dependencies"], json!(["Beta"]));
+        assert_eq!(deps["dependents"], json!(["Beta"]));
+
+        let cycles = call(&server, "find_cycles", json!({}));
+        assert_eq!(cycles["cycles"], json!([["Alpha", "Beta"]]));
+
+        let isolated = call(&server, "get_isolated_classes", json!({}));
+        assert_eq!(isolated["isolated_classes"], json!(["Lonely"]));
+    }
+
+    #[test]
+    fn test_unknown_method_and_parse_errors() {
+        let mut server = McpServer::new(".", false);
+        let mut output = Vec::new();
+
+        let input = "not json\n{\"jsonrpc\":\"2.0\",\"id\":7,\"method\":\"nope\"}\n";
+
+        jsonrpc::serve(&mut server, input.as_bytes(), &mut output).unwrap();
+
+        let lines: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines[0]["error"]["code"], PARSE_ERROR);
+        assert_eq!(lines[1]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(lines[1]["id"], 7);
+    }
+}
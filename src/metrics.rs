@@ -0,0 +1,70 @@
+use crate::coupling::CouplingReport;
+use serde::Serialize;
+use std::collections::HashMap;
+
+pub use classlink_core::metrics::*;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Hotspot {
+    pub class: String,
+    pub lines: usize,
+    pub methods: usize,
+    pub coupling: f64,
+    // Size times weighted coupling: big AND highly coupled ranks first
+    pub score: f64,
+}
+
+pub fn hotspots(coupling: &CouplingReport, metrics: &HashMap<String, ClassMetrics>) -> Vec<Hotspot> {
+    let mut hotspots: Vec<Hotspot> = coupling
+        .classes
+        .iter()
+        .filter_map(|score| {
+            let size = metrics.get(&score.name)?;
+            Some(Hotspot {
+                class: score.name.clone(),
+                lines: size.lines,
+                methods: size.methods,
+                coupling: score.total,
+                score: size.lines as f64 * score.total,
+            })
+        })
+        .filter(|hotspot| hotspot.score > 0.0)
+        .collect();
+    hotspots.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.class.cmp(&b.class)));
+    hotspots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coupling::CouplingScore;
+
+    #[test]
+    fn test_hotspots_rank_size_times_coupling() {
+        let score = |name: &str, total: f64| CouplingScore {
+            name: name.to_string(),
+            afferent: total,
+            efferent: 0.0,
+            total,
+        };
+        let coupling = CouplingReport {
+            classes: vec![score("Hub", 6.0), score("GodClass", 4.0), score("External", 9.0)],
+            packages: vec![],
+        };
+        let size = |lines: usize| ClassMetrics {
+            start_line: 1,
+            end_line: lines,
+            lines,
+            methods: lines / 10,
+        };
+        let metrics: HashMap<String, ClassMetrics> =
+            [("Hub".to_string(), size(20)), ("GodClass".to_string(), size(400))].into_iter().collect();
+
+        let ranked = hotspots(&coupling, &metrics);
+
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].class, "GodClass");
+        assert_eq!(ranked[0].score, 1600.0);
+        assert_eq!(ranked[1].methods, 2);
+    }
+}
@@ -0,0 +1,272 @@
+use crate::glob;
+use crate::graph::ClassGraph;
+use crate::i18n::{Catalog, Message};
+use crate::parser::LinkKind;
+use crate::project::{self, FileAnalysis};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+// Team of authors no `teams` pattern matches
+pub const UNASSIGNED: &str = "unassigned";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct OwnershipSummary {
+    pub classes: usize,
+    pub average_fan_in: f64,
+    pub average_fan_out: f64,
+    // Links from a class of one team to a class of another whose first
+    // occurrence is on a line this author or team wrote
+    pub cross_team_links: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct OwnershipReport {
+    // `Name <email>` -> summary
+    pub authors: BTreeMap<String, OwnershipSummary>,
+    // Empty unless teams are configured
+    pub teams: BTreeMap<String, OwnershipSummary>,
+    // Owner of every defined class
+    pub owners: BTreeMap<String, String>,
+}
+
+// Author of each line of a file, as `Name <email>`, from `git blame`.
+// Uncommitted lines belong to git's `Not Committed Yet` author.
+pub fn blame(path: &str) -> io::Result<Vec<String>> {
+    let path = Path::new(path);
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["blame", "--line-porcelain", "--"])
+        .arg(path.file_name().unwrap_or_default())
+        .output()
+        .map_err(|e| io::Error::new(e.kind(), format!("failed to run git blame: {}", e)))?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "git blame failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(parse_blame(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn parse_blame(porcelain: &str) -> Vec<String> {
+    let mut authors = Vec::new();
+    let (mut name, mut mail) = ("", "");
+    for line in porcelain.lines() {
+        if let Some(value) = line.strip_prefix("author ") {
+            name = value;
+        } else if let Some(value) = line.strip_prefix("author-mail ") {
+            mail = value;
+        } else if line.starts_with('\t') {
+            authors.push(format!("{} {}", name, mail));
+        }
+    }
+    authors
+}
+
+// Blames every analyzed file; files git does not track are left out
+pub fn blame_files(files: &[FileAnalysis]) -> BTreeMap<String, Vec<String>> {
+    files
+        .iter()
+        .filter_map(|file| Some((file.path.clone(), blame(&file.path).ok()?)))
+        .collect()
+}
+
+// First team, by name, with a glob matching the author's name or email
+fn team_of<'a>(author: &str, teams: &'a BTreeMap<String, Vec<String>>) -> &'a str {
+    let (name, mail) = author.rsplit_once(' ').unwrap_or((author, ""));
+    let mail = mail.trim_start_matches('<').trim_end_matches('>');
+    teams
+        .iter()
+        .find(|(_, patterns)| glob::matches_any(patterns, name) || glob::matches_any(patterns, mail))
+        .map_or(UNASSIGNED, |(team, _)| team.as_str())
+}
+
+// A class belongs to whoever wrote most of its lines (the whole file for
+// classes without a measured body, like documentation pages); ties go to
+// the first author by name. `blames` maps file paths to line authors.
+pub fn ownership(
+    files: &[FileAnalysis],
+    blames: &BTreeMap<String, Vec<String>>,
+    teams: &BTreeMap<String, Vec<String>>,
+) -> OwnershipReport {
+    let mut lines_by: BTreeMap<&str, BTreeMap<&str, usize>> = BTreeMap::new();
+    for file in files {
+        let Some(authors) = blames.get(&file.path) else {
+            continue;
+        };
+        for class in &file.result.classes {
+            let range = match file.result.class_metrics.get(class) {
+                Some(metrics) => metrics.start_line.saturating_sub(1)..metrics.end_line,
+                None => 0..authors.len(),
+            };
+            let counts = lines_by.entry(class).or_default();
+            for author in authors.get(range).unwrap_or_default() {
+                *counts.entry(author).or_default() += 1;
+            }
+        }
+    }
+    let owners: BTreeMap<String, String> = lines_by
+        .into_iter()
+        .filter_map(|(class, counts)| {
+            let top = counts.values().copied().max()?;
+            let owner = counts.into_iter().find(|(_, lines)| *lines == top)?.0;
+            Some((class.to_string(), owner.to_string()))
+        })
+        .collect();
+
+    let merged = project::merge(files.to_vec());
+    let graph = ClassGraph::new(&merged.classes, &merged.links);
+    let mut report = OwnershipReport::default();
+    let mut fan: BTreeMap<(bool, &str), (usize, usize, usize)> = BTreeMap::new();
+    for (class, owner) in &owners {
+        let (fan_in, fan_out) = (graph.dependents(class).len(), graph.dependencies(class).len());
+        let groups = [(false, owner.as_str()), (true, team_of(owner, teams))];
+        for group in groups.into_iter().filter(|(is_team, _)| !is_team || !teams.is_empty()) {
+            let entry = fan.entry(group).or_default();
+            *entry = (entry.0 + 1, entry.1 + fan_in, entry.2 + fan_out);
+        }
+    }
+    for ((is_team, name), (classes, fan_in, fan_out)) in fan {
+        let summary = OwnershipSummary {
+            classes,
+            average_fan_in: fan_in as f64 / classes as f64,
+            average_fan_out: fan_out as f64 / classes as f64,
+            cross_team_links: 0,
+        };
+        let groups = if is_team { &mut report.teams } else { &mut report.authors };
+        groups.insert(name.to_string(), summary);
+    }
+
+    // Each cross-team dependency counts once, for the author of the line it
+    // first occurs on
+    let mut counted = BTreeSet::new();
+    for file in files {
+        for link in file.result.links.iter().filter(|link| link.kind != LinkKind::Doc) {
+            let (Some(from), Some(to)) = (owners.get(&link.from_class), owners.get(&link.to_class)) else {
+                continue;
+            };
+            if team_of(from, teams) == team_of(to, teams) || !counted.insert((&link.from_class, &link.to_class)) {
+                continue;
+            }
+            let line = link.line_number.checked_sub(1);
+            let Some(author) = blames.get(&file.path).and_then(|authors| authors.get(line?)) else {
+                continue;
+            };
+            report.authors.entry(author.clone()).or_default().cross_team_links += 1;
+            if !teams.is_empty() {
+                report.teams.entry(team_of(author, teams).to_string()).or_default().cross_team_links += 1;
+            }
+        }
+    }
+    report.owners = owners;
+    report
+}
+
+pub fn render_ownership(report: &OwnershipReport, catalog: &Catalog) -> String {
+    let mut out = String::new();
+    let sections = [(Message::OwnershipTeamsHeader, &report.teams), (Message::OwnershipAuthorsHeader, &report.authors)];
+    for (header, groups) in sections {
+        if groups.is_empty() {
+            continue;
+        }
+        writeln!(out, "\n{}", catalog.text(header, &[])).unwrap();
+        for (name, summary) in groups {
+            let line = catalog.text(
+                Message::OwnershipLine,
+                &[
+                    name,
+                    &summary.classes,
+                    &format!("{:.1}", summary.average_fan_in),
+                    &format!("{:.1}", summary.average_fan_out),
+                    &summary.cross_team_links,
+                ],
+            );
+            writeln!(out, "{}", line).unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_analyzer::AnalysisResult;
+    use crate::metrics::ClassMetrics;
+    use crate::parser::ClassLink;
+
+    fn file(path: &str, classes: &[(&str, usize, usize)], links: &[(&str, &str, usize)]) -> FileAnalysis {
+        FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|(name, _, _)| name.to_string()).collect(),
+                class_metrics: classes
+                    .iter()
+                    .map(|(name, start, end)| {
+                        let metrics = ClassMetrics {
+                            start_line: *start,
+                            end_line: *end,
+                            lines: end - start + 1,
+                            methods: 0,
+                        };
+                        (name.to_string(), metrics)
+                    })
+                    .collect(),
+                links: links
+                    .iter()
+                    .map(|(from, to, line)| ClassLink {
+                        from_class: from.to_string(),
+                        to_class: to.to_string(),
+                        line_number: *line,
+                        kind: Default::default(),
+                        position: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_blame_porcelain_gives_line_authors() {
+        let porcelain = "4e1c 1 1 2\nauthor Ann\nauthor-mail <ann@shop.io>\nsummary init\n\tclass Cart:\n4e1c 2 2\nauthor Ann\nauthor-mail <ann@shop.io>\n\t    pass\n";
+        assert_eq!(parse_blame(porcelain), vec!["Ann <ann@shop.io>", "Ann <ann@shop.io>"]);
+    }
+
+    #[test]
+    fn test_ownership_by_author_and_team() {
+        let (ann, bob, cid) = ("Ann <ann@shop.io>", "Bob <bob@pay.io>", "Cid <cid@pay.io>");
+        let files = vec![
+            file("shop.py", &[("Shop", 1, 3), ("Cart", 4, 6)], &[("Shop", "Cart", 2), ("Shop", "Payment", 3)]),
+            file("pay.py", &[("Payment", 1, 3)], &[]),
+        ];
+        let blames = BTreeMap::from([
+            ("shop.py".to_string(), [ann, ann, bob, cid, cid, ann].map(String::from).to_vec()),
+            ("pay.py".to_string(), [bob, bob, cid].map(String::from).to_vec()),
+        ]);
+        let teams = BTreeMap::from([
+            ("checkout".to_string(), vec!["ann@*".to_string()]),
+            ("payments".to_string(), vec!["*@pay.io".to_string()]),
+        ]);
+
+        let report = ownership(&files, &blames, &teams);
+
+        assert_eq!(report.owners["Shop"], ann);
+        assert_eq!(report.owners["Cart"], cid);
+        assert_eq!(report.owners["Payment"], bob);
+        // Shop -> Cart and Shop -> Payment cross teams; Bob wrote line 3
+        assert_eq!(report.authors[ann].cross_team_links, 1);
+        assert_eq!(report.authors[bob].cross_team_links, 1);
+        assert_eq!(report.authors[ann].average_fan_out, 2.0);
+        let payments = &report.teams["payments"];
+        assert_eq!((payments.classes, payments.average_fan_in, payments.cross_team_links), (2, 1.0, 1));
+        assert!(render_ownership(&report, &Catalog::default()).contains("  checkout: 1 classes, fan-in 0.0, fan-out 2.0, 1 cross-team links"));
+        assert!(ownership(&files, &blames, &BTreeMap::new()).teams.is_empty());
+    }
+}
@@ -0,0 +1,530 @@
+use crate::alias::AliasMap;
+use crate::analyzer::{LinkAnalyzer, LinkStatistics};
+use crate::cancel::{CancellationToken, Interruption};
+use crate::config::{Config, OutputFormat};
+use crate::churn;
+use crate::coupling;
+use crate::custom_analyzer::CustomAnalyzer;
+use crate::exceptions::{self, Date, SuppressedFinding};
+use crate::findings::{self, Finding, Severity};
+use crate::graph::ClassGraph;
+use crate::i18n::Catalog;
+use crate::plugin::Plugin;
+use crate::metrics;
+use crate::ownership;
+use crate::project::{self, ProjectAnalyzer};
+use crate::provenance::Provenance;
+use crate::registry::{MetricRegistry, ParserRegistry};
+use crate::tags;
+use crate::template::Template;
+use crate::report::{self, Report};
+use crate::stream::LinkStream;
+use crate::reporter::{self, Hyperlinks, Reporter, DEFAULT_EDITOR_URL};
+#[cfg(feature = "scripting")]
+use crate::script::{self, ScriptModel};
+use serde::Serialize;
+use std::fs;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+// One-call entry point: walk, analyze, check, and (if `output_path` is set)
+// write the rendered report. Build scripts get the same results as the CLI.
+pub fn run(config: Config) -> io::Result<Report> {
+    Pipeline::new(config).run()
+}
+
+// Analyze and check without writing anything, for wrappers that make their
+// own pass/fail decision instead of using the exit code
+pub fn check(config: Config) -> io::Result<CheckOutcome> {
+    Pipeline::new(config).check()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckOutcome {
+    pub findings: Vec<Finding>,
+    pub suppressed: Vec<SuppressedFinding>,
+    pub stats: LinkStatistics,
+    // No warning or error findings, the same rule the CLI exit code uses
+    pub passed: bool,
+    // Set when the scan stopped early and the outcome covers part of the tree
+    pub interrupted: Option<Interruption>,
+}
+
+impl CheckOutcome {
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings.iter().filter(|finding| finding.severity == severity).count()
+    }
+
+    // Stricter or looser gates than `passed`, e.g. failing only on errors
+    pub fn passes_at(&self, threshold: Severity) -> bool {
+        self.findings.iter().all(|finding| finding.severity < threshold)
+    }
+}
+
+impl From<Report> for CheckOutcome {
+    fn from(report: Report) -> Self {
+        Self {
+            passed: report.passed(),
+            findings: report.findings,
+            suppressed: report.suppressed,
+            stats: report.statistics,
+            interrupted: report.interrupted,
+        }
+    }
+}
+
+pub struct Pipeline {
+    config: Config,
+    reporters: Vec<Box<dyn Reporter>>,
+    parsers: ParserRegistry,
+    metrics: MetricRegistry,
+    cancellation: Option<CancellationToken>,
+}
+
+impl Pipeline {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            reporters: Vec::new(),
+            parsers: ParserRegistry::new(),
+            metrics: MetricRegistry::new(),
+            cancellation: None,
+        }
+    }
+
+    // Cancelling the token stops the scan; the run then completes with the
+    // files analyzed so far and `Report::interrupted` set
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    // Parser crates for languages the checker does not build in
+    pub fn with_parsers(mut self, parsers: ParserRegistry) -> Self {
+        self.parsers = parsers;
+        self
+    }
+
+    // Team-specific metrics, computed over the whole graph on every run
+    pub fn with_metrics(mut self, metrics: MetricRegistry) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn with_reporter(mut self, reporter: Box<dyn Reporter>) -> Self {
+        self.register_reporter(reporter);
+        self
+    }
+
+    pub fn register_reporter(&mut self, reporter: Box<dyn Reporter>) {
+        self.reporters.push(reporter);
+    }
+
+    pub fn run(mut self) -> io::Result<Report> {
+        // JSON lines go out while the scan runs, to the output file when
+        // there is one
+        let stream = match (&self.config.output, &self.config.output_path) {
+            (OutputFormat::JsonLines, Some(path)) => Some(LinkStream::new(&self.config.path, Box::new(fs::File::create(path)?))),
+            (OutputFormat::JsonLines, None) => Some(LinkStream::new(&self.config.path, Box::new(io::stdout()))),
+            _ => None,
+        };
+        let report = self.analyze(stream)?;
+        let config = &self.config;
+        if let (Some(path), Some(diagnostics)) = (&config.diagnostics_path, &report.diagnostics) {
+            fs::write(path, serde_json::to_string_pretty(diagnostics)?)?;
+        }
+        if let Some(path) = &config.metrics_path {
+            // Written atomically so the textfile collector never reads half a file
+            let partial = format!("{}.tmp", path);
+            fs::write(&partial, report.render_metrics())?;
+            fs::rename(&partial, path)?;
+        }
+        if let Some(output_path) = &config.output_path {
+            // Terminal escapes stay out of files
+            let rendered = match config.output {
+                OutputFormat::Text => report.render_console(None),
+                format => report.render(format),
+            };
+            if config.output == OutputFormat::JsonLines {
+                fs::OpenOptions::new().append(true).open(output_path)?.write_all(rendered.as_bytes())?;
+            } else {
+                fs::write(output_path, rendered)?;
+            }
+        }
+
+        for reporter in &mut self.reporters {
+            reporter::dispatch(&report, reporter.as_mut())?;
+        }
+
+        Ok(report)
+    }
+
+    // Skips the report file, the diagnostics file and registered reporters
+    pub fn check(self) -> io::Result<CheckOutcome> {
+        self.analyze(None).map(CheckOutcome::from)
+    }
+
+    // The files the report would be built from, for commands that work on
+    // the analyzed files instead of the report
+    pub fn analyze_files(&self) -> io::Result<Vec<project::FileAnalysis>> {
+        let aliases =
+            AliasMap::from_config(&self.config.aliases).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let scan = self.analyzer()?.scan(&self.config.path)?;
+        Ok(aliases.apply(scan.files))
+    }
+
+    // The walk and parser settings of the config, for scanning other roots
+    pub fn analyzer(&self) -> io::Result<ProjectAnalyzer> {
+        self.project_analyzer(Plugin::from_configs(&self.config.plugins)?)
+    }
+
+    fn project_analyzer(&self, plugins: Vec<Plugin>) -> io::Result<ProjectAnalyzer> {
+        let config = &self.config;
+        let custom_analyzers = CustomAnalyzer::from_configs(&config.custom_analyzers)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let deadline = config.timeout_secs.map(|secs| Instant::now() + Duration::from_secs(secs));
+        let mut analyzer = ProjectAnalyzer::new()
+            .recursive(config.recursive)
+            .max_depth(config.max_depth)
+            .follow_symlinks(config.follow_symlinks)
+            .max_file_size(config.max_file_size_mb.map(|mb| mb * 1024 * 1024))
+            .skip_binary(config.skip_binary)
+            .skip_vendored(config.skip_vendored)
+            .exclude(config.exclude.clone())
+            .respect_gitignore(config.respect_gitignore)
+            .custom_analyzers(custom_analyzers)
+            .parsers(self.parsers.clone())
+            .parser_options(config.parsers.clone())
+            .global_resolution(config.global_resolution)
+            .follow_imports(config.follow_imports)
+            .external_types(config.external_types.clone())
+            .external_coupling(config.external_coupling)
+            .external_nodes(config.external_nodes)
+            .plugins(plugins)
+            .diagnostics(config.diagnostics_path.is_some())
+            .capture_snippets(config.capture_snippets)
+            .column_encoding(config.column_encoding)
+            .limits(config.limits.clone())
+            .cache(config.cache_path.clone())
+            .io(config.io.clone())
+            .deadline(deadline);
+        if let Some(token) = &self.cancellation {
+            analyzer = analyzer.cancellation(token.clone());
+        }
+        Ok(analyzer)
+    }
+
+    fn analyze(&self, stream: Option<LinkStream>) -> io::Result<Report> {
+        let config = &self.config;
+        let aliases =
+            AliasMap::from_config(&config.aliases).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        config
+            .parsers
+            .markdown
+            .validate()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let template = config.template.as_deref().map(Template::from_file).transpose()?;
+        if config.output == OutputFormat::Template && template.is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the template output format needs a template file",
+            ));
+        }
+        let plugins = Plugin::from_configs(&config.plugins)?;
+        let rule_plugins: Vec<Plugin> = plugins.iter().filter(|plugin| plugin.is_rule()).cloned().collect();
+        let mut analyzer = self.project_analyzer(plugins)?;
+        // A focus needs the whole graph, so its stream is written at the end
+        let (stream, deferred) = match stream.map(|stream| stream.aliases(aliases.clone())) {
+            Some(stream) if config.focus.is_some() => (None, Some(stream)),
+            stream => (stream, None),
+        };
+        if let Some(stream) = stream {
+            analyzer = analyzer.stream(stream);
+        }
+        let scan = analyzer.scan(&config.path)?;
+        let inputs: Vec<String> = scan.files.iter().map(|file| file.path.clone()).collect();
+        let skipped: Vec<String> = scan.skipped.iter().map(|file| file.path.clone()).collect();
+        let mut provenance = Provenance::record(config, &inputs, &skipped)?;
+        for language in self.parsers.languages() {
+            provenance.parsers.insert(format!("parser:{}", language), "registered".to_string());
+        }
+        let files = aliases.apply(scan.files);
+        let files = match &config.focus {
+            Some(focus) => focus.apply(files),
+            None => files,
+        };
+        if let Some(stream) = &deferred {
+            for file in &files {
+                stream.write_file(file)?;
+            }
+        }
+
+        let merged = project::merge(files.clone());
+        let catalog = Catalog::new(config.locale);
+        let isolation =
+            findings::classify_isolated(&config.path, &files, &merged, &config.entry_points, &config.test_patterns);
+        let mut findings = findings::builtin(&config.path, &files, &merged, &isolation, &catalog);
+        for plugin in &rule_plugins {
+            findings.extend(plugin.check(&merged)?);
+        }
+        if config.layer_order.len() >= 2 {
+            findings.extend(findings::layer_skips(&config.path, &files, &config.tags, &config.layer_order, &catalog));
+        }
+        findings.extend(scripted_findings(config, &files, &catalog)?);
+        let (findings, suppressed) = exceptions::apply(&config.exceptions, findings, Date::today(), &catalog);
+        let coupling = coupling::weighted_coupling(&config.path, &files, &config.link_weights, config.group_by);
+        let hotspots = metrics::hotspots(&coupling, &merged.class_metrics);
+        let offenders = findings::offenders(&config.path, &files, &findings, config.group_by);
+        let churn_hotspots = if config.churn {
+            let history = churn::commits(&config.path, config.churn_since.as_deref())?;
+            churn::churn_hotspots(&coupling, &churn::class_churn(&config.path, &files, &history))
+        } else {
+            Vec::new()
+        };
+        let doc_urls = match &config.doc_url {
+            Some(template) => report::class_doc_urls(template, merged.classes.iter()),
+            None => Default::default(),
+        };
+        let tags = (!config.tags.is_empty()).then(|| tags::tag_statistics(&config.path, &files, &config.tags));
+        let travel_distances = (config.layer_order.len() >= 2)
+            .then(|| tags::travel_distances(&config.path, &files, &config.tags, &config.layer_order));
+        let ownership = config
+            .ownership
+            .then(|| ownership::ownership(&files, &ownership::blame_files(&files), &config.teams));
+        let analyzer = LinkAnalyzer::new();
+        let abstraction_coupling = config
+            .abstraction_coupling
+            .then(|| analyzer.abstraction_coupling(&merged.classes, &merged.links, &merged.class_tags));
+        let custom_metrics = self.metrics.compute(&ClassGraph::new(&merged.classes, &merged.links));
+        let statistics = LinkStatistics {
+            abstraction_coupling,
+            isolation,
+            custom_metrics,
+            ..analyzer.analyze(merged.classes, merged.links)
+        };
+
+        Ok(Report {
+            statistics,
+            findings,
+            suppressed,
+            root: config.path.clone(),
+            files,
+            skipped: scan.skipped,
+            diagnostics: scan.diagnostics,
+            interrupted: scan.interrupted,
+            truncated: scan.truncated,
+            external_coupling: scan.external_coupling,
+            external_nodes: scan.external_nodes,
+            cross_repo: None,
+            tags,
+            travel_distances,
+            ownership,
+            provenance: Some(provenance),
+            coupling,
+            hotspots,
+            churn_hotspots,
+            offenders,
+            doc_urls,
+            locale: config.locale,
+            hyperlinks: config
+                .hyperlinks
+                .then(|| Hyperlinks::new(config.editor_url.as_deref().unwrap_or(DEFAULT_EDITOR_URL))),
+            collapse_packages: config.collapse_packages,
+            grouping: config.group_by,
+            export_filter: config.export_filter.clone(),
+            diagram_limits: config.diagram_limits,
+            diagram_color: config.diagram_color,
+            template,
+        })
+    }
+}
+
+#[cfg(feature = "scripting")]
+fn scripted_findings(config: &Config, files: &[project::FileAnalysis], catalog: &Catalog) -> io::Result<Vec<Finding>> {
+    if config.rule_scripts.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut rules = Vec::new();
+    for path in &config.rule_scripts {
+        let script = fs::read_to_string(path)?;
+        let parsed = script::parse(&script)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("{}: {}", path, e)))?;
+        rules.extend(parsed);
+    }
+    Ok(ScriptModel::build(&config.path, files, &config.layers).evaluate(&rules, catalog))
+}
+
+#[cfg(not(feature = "scripting"))]
+fn scripted_findings(config: &Config, _files: &[project::FileAnalysis], _catalog: &Catalog) -> io::Result<Vec<Finding>> {
+    if config.rule_scripts.is_empty() {
+        return Ok(Vec::new());
+    }
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "rule_scripts needs classlink-checker built with the `scripting` feature",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OutputFormat;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_run_analyzes_checks_and_writes_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("docs");
+        fs::create_dir_all(project.join("nested")).unwrap();
+        fs::write(project.join("Alpha.md"), "# Alpha\n\n[Beta](Beta.md)\n").unwrap();
+        fs::write(project.join("nested").join("Beta.md"), "# Beta\n").unwrap();
+        fs::write(project.join("Lonely.md"), "# Lonely\n").unwrap();
+        let output_path = temp_dir.path().join("report.json");
+
+        let report = run(Config {
+            path: project.to_string_lossy().to_string(),
+            output: OutputFormat::Json,
+            output_path: Some(output_path.to_string_lossy().to_string()),
+            ..Config::default()
+        })
+        .unwrap();
+
+        assert_eq!(report.statistics.total_classes, 3);
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.exit_code(), 2);
+        assert!(!report.passed());
+
+        let written: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output_path).unwrap()).unwrap();
+        assert_eq!(written["total_classes"], 3);
+    }
+
+    #[test]
+    fn test_custom_analyzers_from_config() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("checkout.flow"), "flow Checkout\n  call Payment\n").unwrap();
+        fs::write(temp_dir.path().join("payment.flow"), "flow Payment\n").unwrap();
+        let mut config: Config = serde_json::from_str(
+            r#"{ "custom_analyzers": [{
+                "name": "flow",
+                "files": ["*.flow"],
+                "class_regex": "^flow\\s+(\\w+)",
+                "reference_regex": "call\\s+(\\w+)"
+            }] }"#,
+        )
+        .unwrap();
+        config.path = temp_dir.path().to_string_lossy().to_string();
+
+        let report = run(config.clone()).unwrap();
+        assert_eq!(report.statistics.total_classes, 2);
+        assert!(report.findings.is_empty());
+
+        config.custom_analyzers[0].class_regex = "(".to_string();
+        let error = run(config).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_registered_reporters_receive_the_report() {
+        struct Collect(std::rc::Rc<std::cell::RefCell<Vec<String>>>);
+
+        impl Reporter for Collect {
+            fn on_finding(&mut self, finding: &crate::findings::Finding) {
+                self.0.borrow_mut().push(finding.rule.clone());
+            }
+
+            fn finish(&mut self) -> io::Result<()> {
+                self.0.borrow_mut().push("finish".to_string());
+                Ok(())
+            }
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("Lonely.md"), "# Lonely\n").unwrap();
+        let seen = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+
+        Pipeline::new(Config::new(&temp_dir.path().to_string_lossy()))
+            .with_reporter(Box::new(Collect(seen.clone())))
+            .run()
+            .unwrap();
+
+        assert_eq!(*seen.borrow(), vec!["isolated-class", "finish"]);
+    }
+
+    #[test]
+    fn test_registered_metrics_reach_every_output() {
+        struct FanOutSquared;
+
+        impl crate::registry::Metric for FanOutSquared {
+            fn name(&self) -> &str {
+                "fan_out_squared"
+            }
+
+            fn compute(&self, graph: &ClassGraph) -> std::collections::BTreeMap<String, f64> {
+                graph.classes().map(|class| (class.clone(), graph.dependencies(class).len().pow(2) as f64)).collect()
+            }
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("Shop.md"), "# Shop\n\n[Cart](Cart.md) [Order](Order.md)\n").unwrap();
+        fs::write(temp_dir.path().join("Cart.md"), "# Cart\n").unwrap();
+
+        let report = Pipeline::new(Config::new(&temp_dir.path().to_string_lossy()))
+            .with_metrics(MetricRegistry::new().register(FanOutSquared))
+            .run()
+            .unwrap();
+
+        assert_eq!(report.statistics.custom_metrics["fan_out_squared"]["Shop"], 4.0);
+        assert!(report.render(OutputFormat::Text).contains("📏 fan_out_squared (highest 5):\n  - Shop: 4.00\n"));
+        assert!(report.render(OutputFormat::Json).contains("\"custom_metrics\""));
+        assert!(report.render(OutputFormat::Sarif).contains("\"customMetrics\""));
+        assert!(report.render_metrics().contains("classlink_custom_metric{metric=\"fan_out_squared\",class=\"Shop\"} 4"));
+    }
+
+    #[test]
+    fn test_template_output_renders_the_users_layout() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("Lonely.md"), "# Lonely\n").unwrap();
+        let template_path = temp_dir.path().join("report.hbs");
+        fs::write(
+            &template_path,
+            "# Classes: {{statistics.total_classes}}\n{{#each findings}}\n- {{rule}}: {{message}}\n{{/each}}\n",
+        )
+        .unwrap();
+        let config = Config {
+            output: OutputFormat::Template,
+            template: Some(template_path.to_string_lossy().to_string()),
+            ..Config::new(&temp_dir.path().to_string_lossy())
+        };
+
+        let report = run(config.clone()).unwrap();
+
+        assert_eq!(
+            report.render(OutputFormat::Template),
+            "# Classes: 1\n- isolated-class: Lonely has no incoming or outgoing links\n"
+        );
+        fs::write(&template_path, "{{#each findings}}\n").unwrap();
+        assert_eq!(run(config.clone()).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        let untemplated = Config { template: None, ..config };
+        assert_eq!(run(untemplated).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_check_returns_outcome_without_writing() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("Lonely.md"), "# Lonely\n").unwrap();
+        let output_path = temp_dir.path().join("report.json");
+
+        let outcome = check(Config {
+            output_path: Some(output_path.to_string_lossy().to_string()),
+            ..Config::new(&temp_dir.path().to_string_lossy())
+        })
+        .unwrap();
+
+        assert!(!output_path.exists());
+        assert_eq!(outcome.stats.total_classes, 1);
+        assert_eq!(outcome.count(Severity::Warning), 1);
+        assert!(!outcome.passed);
+        assert!(outcome.passes_at(Severity::Error));
+    }
+}
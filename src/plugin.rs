@@ -0,0 +1,263 @@
+use crate::file_analyzer::AnalysisResult;
+use crate::findings::Finding;
+use crate::glob;
+use crate::parser::{ClassLink, LinkKind};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+
+// Out-of-process extensions loaded by path at runtime. A plugin is any
+// command that reads one JSON request on stdin and writes one JSON response
+// on stdout, so it can be a script, a native binary, or a WASM module run
+// through a runtime such as `wasmtime run plugin.wasm`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginConfig {
+    pub name: String,
+    // Program and arguments
+    pub command: Vec<String>,
+    // Files the plugin analyzes, as globs; empty if it is not an analyzer
+    #[serde(default)]
+    pub files: Vec<String>,
+    // Whether the plugin is asked for findings over the merged graph
+    #[serde(default)]
+    pub rule: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum PluginRequest {
+    Analyze { path: String, content: String },
+    Check { classes: Vec<String>, links: Vec<PluginLink> },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginLink {
+    pub from: String,
+    pub to: String,
+    #[serde(default = "first_line")]
+    pub line: usize,
+    #[serde(default)]
+    pub kind: LinkKind,
+}
+
+fn first_line() -> usize {
+    1
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalyzeResponse {
+    pub classes: Vec<String>,
+    pub links: Vec<PluginLink>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CheckResponse {
+    pub findings: Vec<Finding>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Plugin {
+    config: PluginConfig,
+}
+
+impl Plugin {
+    pub fn new(config: PluginConfig) -> io::Result<Self> {
+        if config.command.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("plugin '{}' has an empty command", config.name),
+            ));
+        }
+        Ok(Self { config })
+    }
+
+    pub fn from_configs(configs: &[PluginConfig]) -> io::Result<Vec<Self>> {
+        configs.iter().cloned().map(Self::new).collect()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    pub fn file_patterns(&self) -> &[String] {
+        &self.config.files
+    }
+
+    pub fn is_rule(&self) -> bool {
+        self.config.rule
+    }
+
+    pub fn handles(&self, path: &str) -> bool {
+        self.config.files.iter().any(|pattern| glob::matches_tail(pattern, path))
+    }
+
+    // Like `FileAnalyzer::analyze`, but a failing plugin is an error rather
+    // than an empty result
+    pub fn analyze_source(&self, content: &str, path: &str) -> io::Result<AnalysisResult> {
+        let response: AnalyzeResponse = self.call(&PluginRequest::Analyze {
+            path: path.to_string(),
+            content: content.to_string(),
+        })?;
+
+        Ok(AnalysisResult {
+            classes: response.classes.into_iter().collect(),
+            links: response
+                .links
+                .into_iter()
+                .map(|link| ClassLink {
+                    from_class: link.from,
+                    to_class: link.to,
+                    line_number: link.line,
+                    kind: link.kind,
+                    position: None,
+                })
+                .collect(),
+            ..Default::default()
+        })
+    }
+
+    pub fn check(&self, graph: &AnalysisResult) -> io::Result<Vec<Finding>> {
+        let mut classes: Vec<String> = graph.classes.iter().cloned().collect();
+        classes.sort();
+        let links = graph
+            .links
+            .iter()
+            .map(|link| PluginLink {
+                from: link.from_class.clone(),
+                to: link.to_class.clone(),
+                line: link.line_number,
+                kind: link.kind,
+            })
+            .collect();
+
+        let response: CheckResponse = self.call(&PluginRequest::Check { classes, links })?;
+        Ok(response.findings)
+    }
+
+    fn call<T: for<'de> Deserialize<'de>>(&self, request: &PluginRequest) -> io::Result<T> {
+        let error = |message: String| io::Error::other(format!("plugin '{}': {}", self.config.name, message));
+
+        let mut child = Command::new(&self.config.command[0])
+            .args(&self.config.command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| error(format!("cannot start {}: {}", self.config.command[0], e)))?;
+
+        let input = serde_json::to_vec(request)?;
+        let stdin = child.stdin.take();
+        // The request is written while the output is read, so a plugin that
+        // answers before it has read everything cannot fill both pipes
+        let output = thread::scope(|scope| {
+            let writer = scope.spawn(move || match stdin.map(|mut stdin| stdin.write_all(&input)) {
+                // A plugin that exits without reading its input closes the pipe
+                Some(Err(e)) if e.kind() != io::ErrorKind::BrokenPipe => Err(e),
+                _ => Ok(()),
+            });
+            let output = child.wait_with_output();
+            writer.join().unwrap_or_else(|_| Err(error("request writer panicked".to_string())))?;
+            output
+        })?;
+        if !output.status.success() {
+            return Err(error(format!("exited with {}", output.status)));
+        }
+        serde_json::from_slice(&output.stdout).map_err(|e| error(format!("invalid response: {}", e)))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::findings::Severity;
+
+    fn shell_plugin(script: &str, files: &[&str], rule: bool) -> Plugin {
+        Plugin::new(PluginConfig {
+            name: "test".to_string(),
+            command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+            files: files.iter().map(|s| s.to_string()).collect(),
+            rule,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_analyzer_plugin() {
+        let plugin = shell_plugin(
+            r#"cat > /dev/null; echo '{"classes": ["Job"], "links": [{"from": "Job", "to": "Queue", "line": 3, "kind": "composition"}]}'"#,
+            &["*.job"],
+            false,
+        );
+        assert!(plugin.handles("/repo/nightly.job"));
+
+        let result = plugin.analyze_source("job Job", "/repo/nightly.job").unwrap();
+
+        assert!(result.classes.contains("Job"));
+        assert_eq!(result.links[0].to_class, "Queue");
+        assert_eq!(result.links[0].line_number, 3);
+        assert_eq!(result.links[0].kind, LinkKind::Composition);
+    }
+
+    #[test]
+    fn test_rule_plugin_receives_the_graph() {
+        // Echoes back how many links it was sent
+        let plugin = shell_plugin(
+            r#"count=$(grep -o '"from"' | wc -l | tr -d ' '); echo "{\"findings\": [{\"rule\": \"layering\", \"severity\": \"error\", \"message\": \"$count links\", \"classes\": []}]}""#,
+            &[],
+            true,
+        );
+        let graph = AnalysisResult {
+            classes: ["A".to_string()].into_iter().collect(),
+            links: vec![ClassLink {
+                from_class: "A".to_string(),
+                to_class: "B".to_string(),
+                line_number: 1,
+                kind: Default::default(),
+                position: None,
+            }],
+            ..Default::default()
+        };
+
+        let findings = plugin.check(&graph).unwrap();
+
+        assert_eq!(findings[0].rule, "layering");
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert_eq!(findings[0].message, "1 links");
+    }
+
+    #[test]
+    fn test_plugin_answering_before_reading_its_input_does_not_block() {
+        // Fills its stdout pipe before reading a request that fills stdin's
+        let plugin = shell_plugin(
+            r#"printf '%*s' 200000 ''; cat > /dev/null; echo '{"classes": ["Job"], "links": []}'"#,
+            &["*.job"],
+            false,
+        );
+        let content = "job Job\n".repeat(30_000);
+
+        let result = plugin.analyze_source(&content, "/repo/nightly.job").unwrap();
+
+        assert!(result.classes.contains("Job"));
+    }
+
+    #[test]
+    fn test_plugin_failures_are_errors() {
+        let failing = shell_plugin("exit 4", &["*.job"], false);
+        let error = failing.analyze_source("", "a.job").unwrap_err();
+        assert!(error.to_string().contains("plugin 'test': exited with"));
+
+        let garbage = shell_plugin("echo not json", &[], true);
+        assert!(garbage.check(&AnalysisResult::default()).unwrap_err().to_string().contains("invalid response"));
+
+        assert!(Plugin::new(PluginConfig {
+            name: "empty".to_string(),
+            command: vec![],
+            files: vec![],
+            rule: false,
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,1538 @@
+use crate::annotations::{self, ClassTag};
+use crate::cache::ParseCache;
+use crate::cancel::{CancellationToken, Interruption, StopCondition};
+use crate::custom_analyzer::CustomAnalyzer;
+use crate::diagnostics::{self, AnalysisDiagnostics, ParserFallback};
+use crate::encoding::{DecodedText, Encoding};
+use crate::external_nodes::{self, ExternalNodeReport};
+use crate::external_types::{ExternalCouplingReport, ExternalTypes};
+use crate::file_analyzer::{AnalysisResult, FileType, ParserOptions, UnifiedAnalyzer};
+use crate::imports;
+use crate::limits::{Budget, Limit, LimitExceeded, ResourceLimits};
+use crate::manifest::relative_path;
+use crate::parser::{ClassLink, LinkKind};
+use crate::plugin::Plugin;
+use crate::position::{self, ColumnEncoding};
+use crate::provenance;
+use crate::registry::ParserRegistry;
+use crate::resolution;
+use crate::scan_io::{self, IoOptions};
+use crate::stream::LinkStream;
+use crate::walker::{self, SkipReason, SkippedFile, WalkOptions, Walker};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+#[derive(Debug, Clone)]
+pub struct FileAnalysis {
+    pub path: String,
+    pub result: AnalysisResult,
+    pub encoding: Encoding,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ProjectScan {
+    pub files: Vec<FileAnalysis>,
+    pub skipped: Vec<SkippedFile>,
+    // Only collected when asked for, see `ProjectAnalyzer::diagnostics`
+    pub diagnostics: Option<AnalysisDiagnostics>,
+    // Set when the scan was cancelled or timed out; `files` then holds the
+    // results collected up to that point
+    pub interrupted: Option<Interruption>,
+    // Set when a resource limit cut the scan short
+    pub truncated: Option<LimitExceeded>,
+    // Only collected when asked for, see `ProjectAnalyzer::external_coupling`
+    pub external_coupling: Option<ExternalCouplingReport>,
+    // Only collected when asked for, see `ProjectAnalyzer::external_nodes`
+    pub external_nodes: Option<ExternalNodeReport>,
+}
+
+pub fn merge(files: Vec<FileAnalysis>) -> AnalysisResult {
+    let mut merged = AnalysisResult::default();
+    for file in files {
+        merged.classes.extend(file.result.classes);
+        merged.links.extend(file.result.links);
+        for (class, size) in file.result.class_metrics {
+            merged
+                .class_metrics
+                .entry(class)
+                .and_modify(|existing| existing.combine(&size))
+                .or_insert(size);
+        }
+        annotations::merge_tags(&mut merged.class_tags, file.result.class_tags);
+        annotations::merge_budgets(&mut merged.dependency_budgets, file.result.dependency_budgets);
+        for (class, namespace) in file.result.namespaces {
+            merged
+                .namespaces
+                .entry(class)
+                .and_modify(|existing| *existing = existing.clone().min(namespace.clone()))
+                .or_insert(namespace);
+        }
+    }
+    merged
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LinkOccurrence {
+    pub file: String,
+    pub line: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    pub kind: LinkKind,
+    // The source line, when snippets were captured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+}
+
+// One logical `from -> to` dependency with every place it occurs
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogicalEdge {
+    pub from: String,
+    pub to: String,
+    pub occurrences: Vec<LinkOccurrence>,
+}
+
+// Groups links by (from, to); occurrences are sorted by file and line with
+// paths relative to `root`
+pub fn group_links(root: &str, files: &[FileAnalysis]) -> Vec<LogicalEdge> {
+    let mut edges: BTreeMap<(&str, &str), Vec<LinkOccurrence>> = BTreeMap::new();
+    for file in files {
+        let relative = relative_path(root, &file.path);
+        for link in &file.result.links {
+            edges
+                .entry((&link.from_class, &link.to_class))
+                .or_default()
+                .push(LinkOccurrence {
+                    file: relative.clone(),
+                    line: link.line_number,
+                    column: link.position.map(|position| position.column),
+                    kind: link.kind,
+                    snippet: file.result.snippets.get(&link.line_number).cloned(),
+                });
+        }
+    }
+
+    edges
+        .into_iter()
+        .map(|((from, to), mut occurrences)| {
+            occurrences.sort();
+            LogicalEdge {
+                from: from.to_string(),
+                to: to.to_string(),
+                occurrences,
+            }
+        })
+        .collect()
+}
+
+const SNIPPET_MAX_CHARS: usize = 200;
+
+fn snippets(content: &str, result: &AnalysisResult) -> BTreeMap<usize, String> {
+    let lines: Vec<&str> = content.lines().collect();
+    result
+        .links
+        .iter()
+        .filter_map(|link| {
+            let text = lines.get(link.line_number.checked_sub(1)?)?.trim();
+            Some((link.line_number, text.chars().take(SNIPPET_MAX_CHARS).collect()))
+        })
+        .collect()
+}
+
+// A file read and parsed on one of the scan's threads, waiting to be added
+// to the scan in walk order
+struct Parsed {
+    path: String,
+    source: DecodedText,
+    outcome: Result<Parse, SkipReason>,
+}
+
+struct Parse {
+    result: AnalysisResult,
+    fallback: Option<ParserFallback>,
+    // Parser key and content hash, for the cache
+    cache_key: Option<(String, String)>,
+}
+
+pub struct ProjectAnalyzer {
+    options: WalkOptions,
+    skip_binary: bool,
+    custom_analyzers: Vec<CustomAnalyzer>,
+    parsers: ParserRegistry,
+    parser_options: ParserOptions,
+    external_types: ExternalTypes,
+    external_coupling: bool,
+    external_nodes: bool,
+    plugins: Vec<Plugin>,
+    diagnostics: bool,
+    capture_snippets: bool,
+    global_resolution: bool,
+    follow_imports: bool,
+    column_encoding: ColumnEncoding,
+    stop: StopCondition,
+    limits: ResourceLimits,
+    cache_path: Option<String>,
+    stream: Option<LinkStream>,
+    io: IoOptions,
+}
+
+impl Default for ProjectAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProjectAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            options: WalkOptions::default(),
+            skip_binary: true,
+            custom_analyzers: Vec::new(),
+            parsers: ParserRegistry::new(),
+            parser_options: ParserOptions::default(),
+            external_types: ExternalTypes::default(),
+            external_coupling: false,
+            external_nodes: false,
+            plugins: Vec::new(),
+            diagnostics: false,
+            capture_snippets: false,
+            global_resolution: false,
+            follow_imports: false,
+            column_encoding: ColumnEncoding::default(),
+            stop: StopCondition::default(),
+            limits: ResourceLimits::default(),
+            cache_path: None,
+            stream: None,
+            io: IoOptions::default(),
+        }
+    }
+
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.options.recursive = recursive;
+        self
+    }
+
+    pub fn max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.options.max_depth = max_depth;
+        self
+    }
+
+    pub fn follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.options.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    pub fn max_file_size(mut self, max_file_size: Option<u64>) -> Self {
+        self.options.max_file_size = max_file_size;
+        self
+    }
+
+    pub fn skip_vendored(mut self, skip_vendored: bool) -> Self {
+        self.options.skip_vendored = skip_vendored;
+        self
+    }
+
+    // Replaces the default excludes, `walker::DEFAULT_EXCLUDES`
+    pub fn exclude(mut self, patterns: Vec<String>) -> Self {
+        self.options.exclude = patterns;
+        self
+    }
+
+    pub fn respect_gitignore(mut self, respect_gitignore: bool) -> Self {
+        self.options.respect_gitignore = respect_gitignore;
+        self
+    }
+
+    pub fn skip_binary(mut self, skip_binary: bool) -> Self {
+        self.skip_binary = skip_binary;
+        self
+    }
+
+    pub fn custom_analyzers(mut self, analyzers: Vec<CustomAnalyzer>) -> Self {
+        self.options.extra_files.extend(
+            analyzers
+                .iter()
+                .flat_map(|analyzer| analyzer.file_patterns().iter().cloned()),
+        );
+        self.custom_analyzers = analyzers;
+        self
+    }
+
+    // Parser crates registered by the embedding program, e.g. behind its
+    // own cargo features
+    pub fn parsers(mut self, parsers: ParserRegistry) -> Self {
+        self.options.extra_files.extend(parsers.file_patterns());
+        self.parsers = parsers;
+        self
+    }
+
+    // Analyzer plugins take precedence over custom and built-in analyzers
+    pub fn plugins(mut self, plugins: Vec<Plugin>) -> Self {
+        self.options
+            .extra_files
+            .extend(plugins.iter().flat_map(|plugin| plugin.file_patterns().iter().cloned()));
+        self.plugins = plugins;
+        self
+    }
+
+    // Keeps file contents until the scan ends to look for unmatched lines
+    pub fn diagnostics(mut self, diagnostics: bool) -> Self {
+        self.diagnostics = diagnostics;
+        self
+    }
+
+    // Keeps the source line of every link so reports can show it without
+    // re-reading files
+    pub fn capture_snippets(mut self, capture_snippets: bool) -> Self {
+        self.capture_snippets = capture_snippets;
+        self
+    }
+
+    // Resolves references a second time, against the classes of every file,
+    // see `resolution`
+    pub fn global_resolution(mut self, global_resolution: bool) -> Self {
+        self.global_resolution = global_resolution;
+        self
+    }
+
+    // Records classes the project imports from files outside the root,
+    // see `external_imports`
+    pub fn follow_imports(mut self, follow_imports: bool) -> Self {
+        self.follow_imports = follow_imports;
+        self
+    }
+
+    pub fn column_encoding(mut self, column_encoding: ColumnEncoding) -> Self {
+        self.column_encoding = column_encoding;
+        self
+    }
+
+    pub fn parser_options(mut self, options: ParserOptions) -> Self {
+        self.parser_options = options;
+        self
+    }
+
+    // Library types whose links are dropped; the built-in lists by default
+    pub fn external_types(mut self, external_types: ExternalTypes) -> Self {
+        self.external_types = external_types;
+        self
+    }
+
+    // Reports links to framework types per class and package instead of
+    // only dropping them
+    pub fn external_coupling(mut self, external_coupling: bool) -> Self {
+        self.external_coupling = external_coupling;
+        self
+    }
+
+    // Moves links to classes the project does not define out of the graph
+    // and into a report of external nodes, see `external_nodes`
+    pub fn external_nodes(mut self, external_nodes: bool) -> Self {
+        self.external_nodes = external_nodes;
+        self
+    }
+
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.stop.token = Some(token);
+        self
+    }
+
+    // Stops the scan once `deadline` passes, keeping what was analyzed
+    pub fn deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.stop.deadline = deadline;
+        self
+    }
+
+    pub fn limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    // Reuse parse results of files unchanged since the last scan that used
+    // this cache file, see `cache`
+    pub fn cache(mut self, path: Option<String>) -> Self {
+        self.cache_path = path;
+        self
+    }
+
+    // Write each file's classes and links to `stream` as soon as it is
+    // analyzed, see `StreamGate`
+    pub fn stream(mut self, stream: LinkStream) -> Self {
+        self.stream = Some(stream);
+        self
+    }
+
+    // Threads, read size and prefetching for reading and parsing files
+    pub fn io(mut self, io: IoOptions) -> Self {
+        self.io = io;
+        self
+    }
+
+    pub fn analyze(&self, path: &str) -> io::Result<AnalysisResult> {
+        Ok(merge(self.analyze_files(path)?))
+    }
+
+    pub fn analyze_files(&self, path: &str) -> io::Result<Vec<FileAnalysis>> {
+        Ok(self.scan(path)?.files)
+    }
+
+    fn unified_analyzer(&self) -> UnifiedAnalyzer {
+        UnifiedAnalyzer::new()
+            .with_custom_analyzers(self.custom_analyzers.clone())
+            .with_registry(self.parsers.clone())
+            .with_column_encoding(self.column_encoding)
+            .with_parser_options(self.parser_options.clone())
+    }
+
+    fn skip_reason(&self, file_path: &str, source: &DecodedText) -> Option<SkipReason> {
+        if self.skip_binary && source.is_binary() {
+            Some(SkipReason::Binary)
+        } else if self.options.skip_vendored && !file_path.ends_with(".ipynb") && walker::looks_minified(&source.content) {
+            Some(SkipReason::Minified)
+        } else {
+            None
+        }
+    }
+
+    fn analyze_source(
+        &self,
+        unified_analyzer: &UnifiedAnalyzer,
+        file_path: &str,
+        content: &str,
+    ) -> io::Result<(AnalysisResult, Option<ParserFallback>)> {
+        Ok(match self.plugins.iter().find(|plugin| plugin.handles(file_path)) {
+            Some(plugin) => {
+                let mut result = plugin.analyze_source(content, file_path)?;
+                position::locate_links(content, &mut result.links, self.column_encoding);
+                (result, None)
+            }
+            None => unified_analyzer.analyze_file_with_fallback(content, file_path),
+        })
+    }
+
+    // Parses the file, or takes the result of the previous run when the
+    // content and the parser are the same. Only built-in parsers are cached;
+    // the parser key and content hash are returned for the next run's cache.
+    fn analyze_cached(
+        &self,
+        unified_analyzer: &UnifiedAnalyzer,
+        file_path: &str,
+        content: &str,
+        previous: &ParseCache,
+    ) -> io::Result<Parse> {
+        let plugin = self.plugins.iter().any(|plugin| plugin.handles(file_path));
+        let Some(key) = unified_analyzer.parser_key(file_path).filter(|_| !plugin) else {
+            let (result, fallback) = self.analyze_source(unified_analyzer, file_path, content)?;
+            return Ok(Parse {
+                result,
+                fallback,
+                cache_key: None,
+            });
+        };
+        let hash = provenance::content_hash(content.as_bytes());
+        let (result, fallback) = match previous.get(file_path, &key, &hash) {
+            Some(result) => (result.clone(), None),
+            None => self.analyze_source(unified_analyzer, file_path, content)?,
+        };
+        Ok(Parse {
+            result,
+            fallback,
+            cache_key: Some((key, hash)),
+        })
+    }
+
+    fn parse(
+        &self,
+        unified_analyzer: &UnifiedAnalyzer,
+        path: String,
+        source: DecodedText,
+        previous: Option<&ParseCache>,
+    ) -> io::Result<Parsed> {
+        let outcome = match (self.skip_reason(&path, &source), previous) {
+            (Some(reason), _) => Err(reason),
+            (None, Some(previous)) => Ok(self.analyze_cached(unified_analyzer, &path, &source.content, previous)?),
+            (None, None) => {
+                let (result, fallback) = self.analyze_source(unified_analyzer, &path, &source.content)?;
+                Ok(Parse {
+                    result,
+                    fallback,
+                    cache_key: None,
+                })
+            }
+        };
+        Ok(Parsed { path, source, outcome })
+    }
+
+    pub fn scan(&self, path: &str) -> io::Result<ProjectScan> {
+        let unified_analyzer = self.unified_analyzer();
+        let previous = self.cache_path.as_deref().map(ParseCache::load);
+        let mut cache = ParseCache::default();
+        let walked = Walker::new(self.options.clone()).with_stop(self.stop.clone()).walk(path)?;
+        let mut scan = ProjectScan {
+            files: Vec::new(),
+            skipped: walked.skipped,
+            diagnostics: None,
+            interrupted: walked.interrupted,
+            truncated: None,
+            external_coupling: None,
+            external_nodes: None,
+        };
+        let mut gate = self.stream.as_ref().map(|stream| StreamGate::new(stream, self));
+        let mut budget = Budget::new(&self.limits);
+        let mut diagnostics = AnalysisDiagnostics::new();
+        let mut sources = Vec::new();
+        for file_path in &walked.unsupported {
+            diagnostics.record_unsupported(file_path);
+        }
+
+        // Files are read and parsed on `threads` threads but added in walk
+        // order, so limits, the stream and the report see the same sequence
+        // as a single-threaded scan. Files past a limit or the stop may have
+        // been parsed already and are thrown away.
+        let threads = self.io.thread_count();
+        let previous = previous.as_ref();
+        let mut add = |parsed: io::Result<Parsed>| -> io::Result<bool> {
+            if scan.interrupted.is_none() {
+                scan.interrupted = self.stop.check();
+            }
+            if scan.interrupted.is_some() {
+                return Ok(false);
+            }
+            let Parsed { path: file_path, source, outcome } = parsed?;
+            let Parse {
+                mut result,
+                fallback,
+                cache_key,
+            } = match outcome {
+                Ok(parse) => parse,
+                Err(reason) => {
+                    scan.skipped.push(SkippedFile {
+                        path: file_path,
+                        reason,
+                    });
+                    return Ok(true);
+                }
+            };
+            // Skipped files do not count toward `max_files`
+            if let Some(hit) = budget.next_file() {
+                scan.truncated = Some(hit);
+                return Ok(false);
+            }
+
+            if self.diagnostics {
+                diagnostics.record_encoding(&file_path, &source);
+                diagnostics.parser_fallbacks.extend(fallback);
+            }
+            if let Some((key, hash)) = cache_key {
+                cache.insert(&file_path, key, hash, result.clone());
+            }
+            scan.truncated = budget.admit(&mut result);
+            if scan.truncated.is_some_and(|hit| hit.limit == Limit::Classes) {
+                return Ok(false);
+            }
+            if self.capture_snippets {
+                result.snippets = snippets(&source.content, &result);
+            }
+            let file = FileAnalysis {
+                path: file_path,
+                result,
+                encoding: source.encoding,
+            };
+            if let Some(gate) = &mut gate {
+                gate.write_file(&file)?;
+            }
+            scan.files.push(file);
+            if self.diagnostics || self.global_resolution || self.follow_imports || self.external_nodes {
+                sources.push(source.content);
+            }
+            Ok(scan.truncated.is_none())
+        };
+        if self.io.prefetch {
+            let mut read = Vec::new();
+            scan_io::ordered_map(walked.files, threads, |path| (self.io.read_source(&path), path), |file| {
+                read.push(file);
+                Ok::<_, io::Error>(self.stop.check().is_none())
+            })?;
+            scan_io::ordered_map(
+                read,
+                threads,
+                |(source, path)| self.parse(&unified_analyzer, path, source?, previous),
+                &mut add,
+            )?;
+        } else {
+            scan_io::ordered_map(
+                walked.files,
+                threads,
+                |path| {
+                    let source = self.io.read_source(&path)?;
+                    self.parse(&unified_analyzer, path, source, previous)
+                },
+                &mut add,
+            )?;
+        }
+
+        // Files removed since the last run drop out of the cache, unless the
+        // scan stopped before it could tell which files are gone
+        if let Some(cache_path) = &self.cache_path {
+            if let Some(previous) = previous.filter(|_| scan.interrupted.is_some() || scan.truncated.is_some()) {
+                cache.keep_unvisited(previous);
+            }
+            cache.save(cache_path)?;
+        }
+
+        if self.global_resolution {
+            let symbols: HashSet<String> = scan.files.iter().flat_map(|f| f.result.classes.iter().cloned()).collect();
+            let mut links: usize = scan.files.iter().map(|file| file.result.links.len()).sum();
+            for (file, content) in scan.files.iter_mut().zip(&sources) {
+                let mut resolved = resolution::resolve_references(&file.path, content, &file.result, &symbols);
+                if let Some(max) = self.limits.max_links.filter(|max| links + resolved.len() > *max) {
+                    resolved.truncate(max.saturating_sub(links));
+                    scan.truncated = Some(LimitExceeded { limit: Limit::Links, max });
+                }
+                links += resolved.len();
+                position::locate_links(content, &mut resolved, self.column_encoding);
+                if let Some(stream) = &self.stream {
+                    stream.write_links(&file.path, &resolved)?;
+                }
+                file.result.links.extend(resolved);
+                if self.capture_snippets {
+                    file.result.snippets = snippets(content, &file.result);
+                }
+            }
+        }
+
+        if self.follow_imports {
+            let external = self.external_imports(path, &scan.files, &sources, &unified_analyzer)?;
+            // External nodes leave the files themselves out of the graph
+            if let Some(gate) = gate.as_mut().filter(|_| !self.external_nodes) {
+                for file in &external {
+                    gate.write_file(file)?;
+                }
+            }
+            scan.files.extend(external);
+        }
+        if self.external_coupling {
+            let (files, report) = self.external_types.split_frameworks(path, scan.files);
+            scan.files = files;
+            scan.external_coupling = Some(report);
+        }
+        scan.files = self.external_types.apply(scan.files);
+        if self.external_nodes {
+            let (files, report) = external_nodes::split_external_nodes(path, scan.files, &sources);
+            scan.files = files;
+            scan.external_nodes = Some(report);
+        }
+        if let Some(gate) = gate {
+            gate.finish(&scan.files)?;
+        }
+        scan.skipped.sort_by(|a, b| a.path.cmp(&b.path));
+        if self.diagnostics {
+            let known: HashSet<String> = scan.files.iter().flat_map(|f| f.result.classes.iter().cloned()).collect();
+            for (file, content) in scan.files.iter().zip(&sources) {
+                diagnostics
+                    .unmatched_lines
+                    .extend(diagnostics::unmatched_lines(&file.path, content, &file.result, &known));
+            }
+            diagnostics.skipped = scan.skipped.clone();
+            scan.diagnostics = Some(diagnostics);
+        }
+        Ok(scan)
+    }
+
+    // Files outside `root` the project imports by relative path, such as a
+    // sibling package, are analyzed for the classes project files link to.
+    // Those become external nodes tagged `external`, with no links of their
+    // own, instead of targets nothing is known about. Imports of the
+    // external files are not followed further.
+    fn external_imports(
+        &self,
+        root: &str,
+        files: &[FileAnalysis],
+        sources: &[String],
+        unified_analyzer: &UnifiedAnalyzer,
+    ) -> io::Result<Vec<FileAnalysis>> {
+        let root = fs::canonicalize(root)?;
+        let root = if root.is_file() { root.parent().map(Path::to_path_buf).unwrap_or_default() } else { root };
+        let defined: HashSet<&String> = files.iter().flat_map(|file| &file.result.classes).collect();
+        let targets: HashSet<&String> = files
+            .iter()
+            .flat_map(|file| file.result.links.iter().map(|link| &link.to_class))
+            .filter(|class| !defined.contains(class))
+            .collect();
+        let outside: BTreeSet<PathBuf> = files
+            .iter()
+            .zip(sources)
+            .flat_map(|(file, content)| imports::relative_imports(&file.path, content))
+            .filter_map(|import| fs::canonicalize(import).ok())
+            .filter(|import| !import.starts_with(&root))
+            .collect();
+
+        let mut external = Vec::new();
+        for file_path in outside {
+            let file_path = file_path.to_string_lossy().to_string();
+            let source = self.io.read_source(&file_path)?;
+            if self.skip_reason(&file_path, &source).is_some() {
+                continue;
+            }
+            let (analyzed, _) = self.analyze_source(unified_analyzer, &file_path, &source.content)?;
+            let classes: HashSet<String> = analyzed.classes.into_iter().filter(|class| targets.contains(class)).collect();
+            if classes.is_empty() {
+                continue;
+            }
+            let mut class_tags = analyzed.class_tags;
+            class_tags.retain(|class, _| classes.contains(class));
+            for class in &classes {
+                class_tags.entry(class.clone()).or_default().insert(ClassTag::External);
+            }
+            external.push(FileAnalysis {
+                path: file_path,
+                result: AnalysisResult {
+                    classes,
+                    class_tags,
+                    ..Default::default()
+                },
+                encoding: source.encoding,
+            });
+        }
+        Ok(external)
+    }
+
+    pub fn find_supported_files(&self, path: &str) -> io::Result<Vec<String>> {
+        Ok(Walker::new(self.options.clone()).walk(path)?.files)
+    }
+
+    pub fn analysis(self, path: &str) -> io::Result<ProjectAnalysis> {
+        ProjectAnalysis::new(self, path)
+    }
+}
+
+// A scanned project kept in memory by interactive tools, with a reverse
+// index from each class to the files defining it or linking from or to it.
+// After a localized edit only those files are parsed again. Resource limits
+// and diagnostics apply to the initial scan only.
+pub struct ProjectAnalysis {
+    analyzer: ProjectAnalyzer,
+    unified_analyzer: UnifiedAnalyzer,
+    files: BTreeMap<String, FileAnalysis>,
+    index: BTreeMap<String, BTreeSet<String>>,
+    // Class -> files defining it, a subset of `index`
+    definitions: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ProjectAnalysis {
+    pub fn new(analyzer: ProjectAnalyzer, path: &str) -> io::Result<Self> {
+        let files = analyzer.analyze_files(path)?;
+        let mut analysis = Self {
+            unified_analyzer: analyzer.unified_analyzer(),
+            analyzer,
+            files: files.into_iter().map(|file| (file.path.clone(), file)).collect(),
+            index: BTreeMap::new(),
+            definitions: BTreeMap::new(),
+        };
+        let paths: Vec<String> = analysis.files.keys().cloned().collect();
+        for path in &paths {
+            analysis.index_file(path);
+        }
+        Ok(analysis)
+    }
+
+    // Sorted by path
+    pub fn files(&self) -> impl Iterator<Item = &FileAnalysis> {
+        self.files.values()
+    }
+
+    pub fn merged(&self) -> AnalysisResult {
+        merge(self.files.values().cloned().collect())
+    }
+
+    pub fn file(&self, path: &str) -> Option<&FileAnalysis> {
+        self.files.get(path)
+    }
+
+    // Classes defined in the file, sorted; empty for files not in the project
+    pub fn classes_in(&self, path: &str) -> Vec<&str> {
+        let mut classes: Vec<&str> = self
+            .files
+            .get(path)
+            .map_or_else(Vec::new, |file| file.result.classes.iter().map(String::as_str).collect());
+        classes.sort_unstable();
+        classes
+    }
+
+    // Sorted by line, then by the classes linked
+    pub fn links_in(&self, path: &str) -> Vec<&ClassLink> {
+        let mut links: Vec<&ClassLink> = self.files.get(path).map_or_else(Vec::new, |file| file.result.links.iter().collect());
+        links.sort_by(|a, b| {
+            (a.line_number, &a.from_class, &a.to_class, a.kind).cmp(&(b.line_number, &b.from_class, &b.to_class, b.kind))
+        });
+        links
+    }
+
+    // Files defining or linking from or to `class`
+    pub fn files_of_class(&self, class: &str) -> Vec<&str> {
+        paths(&self.index, class)
+    }
+
+    // Sorted by path. Partial classes and classes documented next to their
+    // code have several
+    pub fn defining_files(&self, class: &str) -> Vec<&str> {
+        paths(&self.definitions, class)
+    }
+
+    // Parses the files defining or referencing `class` again and returns
+    // their paths; files deleted since are dropped. A file that only starts
+    // mentioning the class with this edit is not indexed yet, refresh it
+    // with `reanalyze_file`.
+    pub fn reanalyze_class(&mut self, class: &str) -> io::Result<Vec<String>> {
+        let paths: Vec<String> = self.index.get(class).map_or_else(Vec::new, |paths| paths.iter().cloned().collect());
+        self.reanalyze(&paths)?;
+        Ok(paths)
+    }
+
+    pub fn reanalyze_file(&mut self, path: &str) -> io::Result<()> {
+        self.reanalyze(&[path.to_string()])
+    }
+
+    fn reanalyze(&mut self, paths: &[String]) -> io::Result<()> {
+        for path in paths {
+            self.unindex_file(path);
+            self.files.remove(path);
+            if !Path::new(path).is_file() {
+                continue;
+            }
+            let source = self.analyzer.io.read_source(path)?;
+            if self.analyzer.skip_reason(path, &source).is_some() {
+                continue;
+            }
+            let (mut result, _) = self.analyzer.analyze_source(&self.unified_analyzer, path, &source.content)?;
+            if self.analyzer.global_resolution {
+                let symbols: HashSet<String> = self
+                    .files
+                    .values()
+                    .flat_map(|f| f.result.classes.iter())
+                    .chain(&result.classes)
+                    .cloned()
+                    .collect();
+                let mut resolved = resolution::resolve_references(path, &source.content, &result, &symbols);
+                position::locate_links(&source.content, &mut resolved, self.analyzer.column_encoding);
+                result.links.extend(resolved);
+            }
+            if self.analyzer.capture_snippets {
+                result.snippets = snippets(&source.content, &result);
+            }
+            self.files.insert(
+                path.clone(),
+                FileAnalysis {
+                    path: path.clone(),
+                    result,
+                    encoding: source.encoding,
+                },
+            );
+        }
+        // Which library types are dropped depends on the classes of the
+        // whole project
+        let files = self.analyzer.external_types.apply(std::mem::take(&mut self.files).into_values().collect());
+        self.files = files.into_iter().map(|file| (file.path.clone(), file)).collect();
+        for path in paths {
+            self.index_file(path);
+        }
+        Ok(())
+    }
+
+    fn index_file(&mut self, path: &str) {
+        let Some(file) = self.files.get(path) else {
+            return;
+        };
+        for class in &file.result.classes {
+            self.definitions.entry(class.clone()).or_default().insert(path.to_string());
+        }
+        let links = file.result.links.iter().flat_map(|link| [&link.from_class, &link.to_class]);
+        for class in file.result.classes.iter().chain(links) {
+            self.index.entry(class.clone()).or_default().insert(path.to_string());
+        }
+    }
+
+    fn unindex_file(&mut self, path: &str) {
+        for index in [&mut self.index, &mut self.definitions] {
+            index.retain(|_, paths| {
+                paths.remove(path);
+                !paths.is_empty()
+            });
+        }
+    }
+}
+
+// Streams a scan's files as they are added, holding back the links the end
+// of the scan may still drop: those to library and, with external coupling,
+// framework types, and with external nodes those to any class outside the
+// project. Held links go out once a project file defines their target, or
+// when the scan is done if the final graph kept them, so the stream has the
+// links of the report.
+struct StreamGate<'a> {
+    stream: &'a LinkStream,
+    external_types: &'a ExternalTypes,
+    external_coupling: bool,
+    external_nodes: bool,
+    defined: HashSet<String>,
+    // Target -> the files and links waiting for it
+    withheld: BTreeMap<String, Vec<(String, ClassLink)>>,
+}
+
+impl<'a> StreamGate<'a> {
+    fn new(stream: &'a LinkStream, analyzer: &'a ProjectAnalyzer) -> Self {
+        Self {
+            stream,
+            external_types: &analyzer.external_types,
+            external_coupling: analyzer.external_coupling,
+            external_nodes: analyzer.external_nodes,
+            defined: HashSet::new(),
+            withheld: BTreeMap::new(),
+        }
+    }
+
+    fn write_file(&mut self, file: &FileAnalysis) -> io::Result<()> {
+        self.defined.extend(file.result.classes.iter().cloned());
+        let file_type = UnifiedAnalyzer::detect_file_type(&file.path);
+        let droppable = self.external_types.droppable(&file_type, self.external_coupling);
+        let outside_dropped = self.external_nodes && file_type != FileType::Markdown;
+        let settled =
+            |link: &ClassLink| self.defined.contains(&link.to_class) || !(outside_dropped || droppable.contains(&link.to_class));
+        self.stream.write_file_with(file, settled)?;
+        for link in file.result.links.iter().filter(|link| !settled(link)) {
+            self.withheld.entry(link.to_class.clone()).or_default().push((file.path.clone(), link.clone()));
+        }
+        for class in &file.result.classes {
+            for (path, link) in self.withheld.remove(class).unwrap_or_default() {
+                self.stream.write_links(&path, &[link])?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self, files: &[FileAnalysis]) -> io::Result<()> {
+        let kept: HashSet<(&str, &str, &str, usize)> = files
+            .iter()
+            .flat_map(|file| {
+                file.result
+                    .links
+                    .iter()
+                    .map(|link| (file.path.as_str(), link.from_class.as_str(), link.to_class.as_str(), link.line_number))
+            })
+            .collect();
+        for (path, link) in self.withheld.into_values().flatten() {
+            if kept.contains(&(path.as_str(), link.from_class.as_str(), link.to_class.as_str(), link.line_number)) {
+                self.stream.write_links(&path, &[link])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn paths<'a>(index: &'a BTreeMap<String, BTreeSet<String>>, class: &str) -> Vec<&'a str> {
+    index.get(class).map_or_else(Vec::new, |paths| paths.iter().map(String::as_str).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_find_supported_files_respects_recursion() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("nested")).unwrap();
+        fs::write(root.join("top.py"), "class Top:\n    pass\n").unwrap();
+        fs::write(root.join("notes.txt"), "not analyzed").unwrap();
+        fs::write(root.join("nested").join("Deep.md"), "# Deep\n").unwrap();
+
+        let root = root.to_string_lossy();
+        let flat = ProjectAnalyzer::new().find_supported_files(&root).unwrap();
+        let deep = ProjectAnalyzer::new()
+            .recursive(true)
+            .find_supported_files(&root)
+            .unwrap();
+
+        assert_eq!(flat.len(), 1);
+        assert_eq!(deep.len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_analyze_merges_file_results() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("a.py"), "class Alpha:\n    pass\n").unwrap();
+        fs::write(root.join("Beta.md"), "# Beta\n\nSee [Alpha](Alpha.md).\n").unwrap();
+
+        let result = ProjectAnalyzer::new()
+            .analyze(&root.to_string_lossy())
+            .unwrap();
+
+        assert!(result.classes.contains("Alpha"));
+        assert!(result.classes.contains("Beta"));
+        assert_eq!(result.links.len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_scan_truncates_at_resource_limits() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_string_lossy().to_string();
+        fs::write(temp_dir.path().join("a.py"), "class Alpha(Base):\n    pass\n").unwrap();
+        fs::write(temp_dir.path().join("b.py"), "class Beta(Alpha):\n    pass\n").unwrap();
+        let limited = |limits| ProjectAnalyzer::new().limits(limits).scan(&root).unwrap();
+
+        let scan = limited(ResourceLimits {
+            max_files: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(scan.files.len(), 1);
+        assert_eq!(scan.truncated, Some(LimitExceeded { limit: Limit::Files, max: 1 }));
+
+        let scan = limited(ResourceLimits {
+            max_links: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(scan.files.iter().map(|f| f.result.links.len()).sum::<usize>(), 1);
+        assert_eq!(scan.truncated.map(|hit| hit.limit), Some(Limit::Links));
+        assert_eq!(limited(ResourceLimits::default()).truncated, None);
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_skipped_files_do_not_count_toward_max_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_string_lossy().to_string();
+        fs::write(temp_dir.path().join("a.py"), "class Alpha:\n    pass\n").unwrap();
+        fs::write(temp_dir.path().join("b.py"), "class Blob:\0\n").unwrap();
+        let limits = ResourceLimits {
+            max_files: Some(1),
+            ..Default::default()
+        };
+
+        let scan = ProjectAnalyzer::new().limits(limits).scan(&root).unwrap();
+        assert_eq!(scan.files.len(), 1);
+        assert_eq!(scan.skipped.len(), 1);
+        assert_eq!(scan.truncated, None);
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_scan_results_do_not_depend_on_threads_or_prefetching() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_string_lossy().to_string();
+        for i in 0..6 {
+            let content = format!("class C{}(Base):\n    pass\n", i);
+            fs::write(temp_dir.path().join(format!("m{:02}.py", i)), content).unwrap();
+        }
+        fs::write(temp_dir.path().join("blob.py"), "class Blob:\0\n").unwrap();
+        let summary = |io: IoOptions, max_files: Option<usize>| {
+            let limits = ResourceLimits {
+                max_files,
+                ..Default::default()
+            };
+            let scan = ProjectAnalyzer::new().io(io).limits(limits).scan(&root).unwrap();
+            let files: Vec<(String, usize)> = scan.files.into_iter().map(|f| (f.path, f.result.links.len())).collect();
+            (files, scan.skipped.len())
+        };
+
+        let expected = summary(IoOptions { threads: Some(1), ..Default::default() }, None);
+        assert_eq!(expected.0.len(), 6);
+        assert_eq!(expected.1, 1);
+        for prefetch in [false, true] {
+            let io = IoOptions {
+                threads: Some(4),
+                read_buffer_kb: 1,
+                prefetch,
+            };
+            assert_eq!(summary(io.clone(), None), expected, "prefetch: {}", prefetch);
+            assert_eq!(summary(io, Some(3)).0, expected.0[..3], "prefetch: {}", prefetch);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_scan_stops_when_cancelled_or_past_deadline() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_string_lossy().to_string();
+        fs::write(temp_dir.path().join("a.py"), "class Alpha:\n    pass\n").unwrap();
+
+        let token = CancellationToken::new();
+        let analyzer = ProjectAnalyzer::new().cancellation(token.clone());
+        let complete = analyzer.scan(&root).unwrap();
+        assert_eq!(complete.interrupted, None);
+        assert_eq!(complete.files.len(), 1);
+
+        token.cancel();
+        let cancelled = analyzer.scan(&root).unwrap();
+        assert_eq!(cancelled.interrupted, Some(Interruption::Cancelled));
+        assert!(cancelled.files.is_empty());
+
+        let late = ProjectAnalyzer::new().deadline(Some(Instant::now())).scan(&root).unwrap();
+        assert_eq!(late.interrupted, Some(Interruption::TimedOut));
+    }
+
+    #[test]
+    #[cfg(all(feature = "python", feature = "typescript"))]
+    fn test_cached_results_are_reused_until_content_or_parser_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_string_lossy().to_string();
+        fs::write(temp_dir.path().join("alpha.py"), "class Alpha:\n    pass\n").unwrap();
+        fs::write(temp_dir.path().join("beta.ts"), "export class Beta {}\n").unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        let cache_path = cache_dir.path().join("cache/parse-cache.json").to_string_lossy().to_string();
+        let classes = |scan: &ProjectScan| -> Vec<String> {
+            let mut classes: Vec<String> = scan.files.iter().flat_map(|f| f.result.classes.iter().cloned()).collect();
+            classes.sort();
+            classes
+        };
+        let analyzer = || ProjectAnalyzer::new().cache(Some(cache_path.clone()));
+        assert_eq!(classes(&analyzer().scan(&root).unwrap()), vec!["Alpha", "Beta"]);
+
+        // A result only the cache can know about shows what was reused
+        let mut cached: serde_json::Value = serde_json::from_str(&fs::read_to_string(&cache_path).unwrap()).unwrap();
+        for entry in cached["entries"].as_object_mut().unwrap().values_mut() {
+            entry["result"]["classes"] = serde_json::json!(["Cached"]);
+        }
+        fs::write(&cache_path, cached.to_string()).unwrap();
+        assert_eq!(classes(&analyzer().scan(&root).unwrap()), vec!["Cached", "Cached"]);
+
+        let mut options = ParserOptions::default();
+        options.typescript.known_targets_only = false;
+        assert_eq!(classes(&analyzer().parser_options(options).scan(&root).unwrap()), vec!["Beta", "Cached"]);
+
+        fs::write(temp_dir.path().join("alpha.py"), "class Gamma:\n    pass\n").unwrap();
+        assert_eq!(classes(&analyzer().scan(&root).unwrap()), vec!["Beta", "Gamma"]);
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_truncated_scan_keeps_cache_entries_of_unvisited_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_string_lossy().to_string();
+        fs::write(temp_dir.path().join("a.py"), "class Alpha:\n    pass\n").unwrap();
+        fs::write(temp_dir.path().join("b.py"), "class Beta:\n    pass\n").unwrap();
+        let cache_dir = TempDir::new().unwrap();
+        let cache_path = cache_dir.path().join("parse-cache.json").to_string_lossy().to_string();
+        let cached = || -> usize {
+            let cache: serde_json::Value = serde_json::from_str(&fs::read_to_string(&cache_path).unwrap()).unwrap();
+            cache["entries"].as_object().unwrap().len()
+        };
+        let analyzer = || ProjectAnalyzer::new().cache(Some(cache_path.clone()));
+        analyzer().scan(&root).unwrap();
+        assert_eq!(cached(), 2);
+
+        let limits = ResourceLimits {
+            max_files: Some(1),
+            ..Default::default()
+        };
+        let scan = analyzer().limits(limits).scan(&root).unwrap();
+        assert!(scan.truncated.is_some());
+        assert_eq!(cached(), 2);
+
+        // A complete scan still drops files that are gone
+        fs::remove_file(temp_dir.path().join("b.py")).unwrap();
+        analyzer().scan(&root).unwrap();
+        assert_eq!(cached(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "typescript")]
+    fn test_group_links_keeps_every_occurrence() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("service.ts"),
+            "import { Repo } from './repo';\nexport class Service {\n  constructor(private repo: Repo) {}\n  find(): Repo {}\n}\n",
+        )
+        .unwrap();
+        fs::write(root.join("Guide.md"), "# Guide\n\n[Repo](Repo.md)\n").unwrap();
+
+        let root = root.to_string_lossy();
+        let files = ProjectAnalyzer::new().analyze_files(&root).unwrap();
+        let edges = group_links(&root, &files);
+
+        let service = edges.iter().find(|e| e.from == "Service" && e.to == "Repo").unwrap();
+        let places: Vec<(&str, usize, LinkKind)> =
+            service.occurrences.iter().map(|o| (o.file.as_str(), o.line, o.kind)).collect();
+        assert_eq!(
+            places,
+            vec![("service.ts", 3, LinkKind::Composition), ("service.ts", 4, LinkKind::Usage)]
+        );
+        assert_eq!(edges.iter().filter(|e| e.from == "Guide").count(), 1);
+    }
+
+    #[test]
+    #[cfg(all(feature = "python", feature = "typescript"))]
+    fn test_global_resolution_links_mentions_of_classes_in_other_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("order.py"), "class Order:\n    pass\n").unwrap();
+        fs::write(root.join("audit.ts"), "export class Audit {\n  check(x: unknown) {\n    return x instanceof Order;\n  }\n}\n").unwrap();
+
+        let root = root.to_string_lossy();
+        let targets = |files: &[FileAnalysis]| -> Vec<(String, usize)> {
+            let audit = files.iter().find(|file| file.path.ends_with("audit.ts")).unwrap();
+            audit.result.links.iter().map(|link| (link.to_class.clone(), link.line_number)).collect()
+        };
+        assert!(targets(&ProjectAnalyzer::new().analyze_files(&root).unwrap()).is_empty());
+
+        let files = ProjectAnalyzer::new().global_resolution(true).capture_snippets(true).analyze_files(&root).unwrap();
+        assert_eq!(targets(&files), vec![("Order".to_string(), 3)]);
+        let audit = files.iter().find(|file| file.path.ends_with("audit.ts")).unwrap();
+        assert_eq!(audit.result.links[0].position.map(|p| p.column), Some(25));
+        assert_eq!(audit.result.snippets[&3], "return x instanceof Order;");
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_capture_snippets_keeps_link_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("shop.py"), "class Shop(Base):\n    def pay(self, m: Money):\n        pass\n").unwrap();
+
+        let root = root.to_string_lossy();
+        let plain = ProjectAnalyzer::new().analyze_files(&root).unwrap();
+        assert!(plain[0].result.snippets.is_empty());
+
+        let files = ProjectAnalyzer::new().capture_snippets(true).analyze_files(&root).unwrap();
+        let edges = group_links(&root, &files);
+
+        let base = edges.iter().find(|e| e.to == "Base").unwrap();
+        assert_eq!(base.occurrences[0].snippet.as_deref(), Some("class Shop(Base):"));
+        let money = edges.iter().find(|e| e.to == "Money").unwrap();
+        assert_eq!(money.occurrences[0].snippet.as_deref(), Some("def pay(self, m: Money):"));
+    }
+
+    #[test]
+    #[cfg(feature = "csharp")]
+    fn test_analyze_files_transcodes_utf16_sources() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut bytes = vec![0xFF, 0xFE];
+        for unit in "public class Legacy : IService\n{\n}\n".encode_utf16() {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+        fs::write(temp_dir.path().join("Legacy.cs"), bytes).unwrap();
+
+        let files = ProjectAnalyzer::new()
+            .analyze_files(&temp_dir.path().to_string_lossy())
+            .unwrap();
+
+        assert_eq!(files[0].encoding, Encoding::Utf16Le);
+        assert!(files[0].result.classes.contains("Legacy"));
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_scan_collects_diagnostics_on_request() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("a.py"), "class Alpha:\n    pass\n\nDEFAULT = Beta\n").unwrap();
+        fs::write(root.join("b.py"), [0xEF, 0xBB, 0xBF].iter().chain(b"class Beta:\n    pass\n").copied().collect::<Vec<u8>>())
+            .unwrap();
+        fs::write(root.join("deploy.rb"), "class Deploy; end\n").unwrap();
+
+        let root = root.to_string_lossy();
+        assert!(ProjectAnalyzer::new().scan(&root).unwrap().diagnostics.is_none());
+        let diagnostics = ProjectAnalyzer::new().diagnostics(true).scan(&root).unwrap().diagnostics.unwrap();
+
+        assert_eq!(diagnostics.unsupported_extensions.get("rb"), Some(&1));
+        assert_eq!(diagnostics.encoding_fallbacks.len(), 1);
+        assert!(diagnostics.encoding_fallbacks[0].path.ends_with("b.py"));
+        assert_eq!(diagnostics.unmatched_lines.len(), 1);
+        assert_eq!(diagnostics.unmatched_lines[0].line, 4);
+        assert_eq!(diagnostics.unmatched_lines[0].tokens, vec!["Beta"]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "python", feature = "typescript"))]
+    fn test_scan_reports_binary_and_oversized_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("a.py"), "class Alpha:\n    pass\n").unwrap();
+        fs::write(root.join("dump.py"), b"class Dump:\x00\x00\x01\x02").unwrap();
+        fs::write(root.join("bundle.ts"), format!("class Bundle {{}}\n{}", "x ".repeat(256))).unwrap();
+
+        let scan = ProjectAnalyzer::new()
+            .max_file_size(Some(256))
+            .scan(&root.to_string_lossy())
+            .unwrap();
+
+        assert_eq!(scan.files.len(), 1);
+        assert!(scan.files[0].result.classes.contains("Alpha"));
+        let reasons: Vec<SkipReason> = scan.skipped.iter().map(|s| s.reason).collect();
+        assert_eq!(
+            reasons,
+            vec![SkipReason::TooLarge { size: 528, limit: 256 }, SkipReason::Binary]
+        );
+
+        let unguarded = ProjectAnalyzer::new()
+            .max_file_size(None)
+            .skip_binary(false)
+            .scan(&root.to_string_lossy())
+            .unwrap();
+        assert_eq!(unguarded.files.len(), 3);
+        assert!(unguarded.skipped.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "typescript")]
+    fn test_scan_skips_minified_sources_unless_vendored_code_is_included() {
+        let temp_dir = TempDir::new().unwrap();
+        let bundle = "class A{m(){return new B()}}".repeat(40);
+        fs::write(temp_dir.path().join("bundle.ts"), &bundle).unwrap();
+        let root = temp_dir.path().to_string_lossy();
+
+        let scan = ProjectAnalyzer::new().scan(&root).unwrap();
+        assert!(scan.files.is_empty());
+        assert_eq!(scan.skipped[0].reason, SkipReason::Minified);
+
+        let included = ProjectAnalyzer::new().skip_vendored(false).scan(&root).unwrap();
+        assert_eq!(included.files.len(), 1);
+    }
+
+    struct ElixirParser;
+
+    impl crate::file_analyzer::FileAnalyzer for ElixirParser {
+        fn analyze(&self, content: &str, _file_path: &str) -> AnalysisResult {
+            AnalysisResult {
+                classes: content
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("defmodule ")?.split_whitespace().next())
+                    .map(str::to_string)
+                    .collect(),
+                ..Default::default()
+            }
+        }
+    }
+
+    impl crate::registry::LanguageParser for ElixirParser {
+        fn language(&self) -> &str {
+            "elixir"
+        }
+
+        fn extensions(&self) -> Vec<String> {
+            vec!["ex".to_string()]
+        }
+    }
+
+    #[test]
+    fn test_registered_parsers_find_and_analyze_their_files() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("cart.ex"), "defmodule Cart do\nend\n").unwrap();
+        fs::write(temp_dir.path().join("Shop.md"), "# Shop\n\n[Cart](Cart.md)\n").unwrap();
+        let root = temp_dir.path().to_string_lossy();
+
+        assert!(!ProjectAnalyzer::new().analyze(&root).unwrap().classes.contains("Cart"));
+        let result = ProjectAnalyzer::new()
+            .parsers(ParserRegistry::new().register(ElixirParser))
+            .analyze(&root)
+            .unwrap();
+
+        assert!(result.classes.contains("Cart"));
+        assert!(result.classes.contains("Shop"));
+    }
+
+    // Stands for an AST backend that gives up on decorators
+    #[cfg(feature = "python")]
+    struct StrictPythonParser;
+
+    #[cfg(feature = "python")]
+    impl crate::file_analyzer::FileAnalyzer for StrictPythonParser {
+        fn analyze(&self, _content: &str, _file_path: &str) -> AnalysisResult {
+            AnalysisResult::default()
+        }
+    }
+
+    #[cfg(feature = "python")]
+    impl crate::registry::LanguageParser for StrictPythonParser {
+        fn language(&self) -> &str {
+            "python-ast"
+        }
+
+        fn extensions(&self) -> Vec<String> {
+            vec!["py".to_string()]
+        }
+
+        fn try_parse(&self, content: &str, _file_path: &str) -> Result<AnalysisResult, crate::registry::ParseError> {
+            match content.lines().position(|line| line.starts_with('@')) {
+                Some(index) => Err(crate::registry::ParseError {
+                    line: index + 1,
+                    column: Some(1),
+                    message: "decorators are not supported".to_string(),
+                }),
+                None => Ok(AnalysisResult::default()),
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_failed_parsers_fall_back_to_the_regex_parser() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("cart.py"), "@dataclass\nclass Cart:\n    pass\n").unwrap();
+        fs::write(temp_dir.path().join("shop.py"), "class Shop:\n    pass\n").unwrap();
+
+        let scan = ProjectAnalyzer::new()
+            .parsers(ParserRegistry::new().register(StrictPythonParser))
+            .diagnostics(true)
+            .scan(&temp_dir.path().to_string_lossy())
+            .unwrap();
+
+        let classes: BTreeSet<&String> = scan.files.iter().flat_map(|file| &file.result.classes).collect();
+        assert_eq!(classes, BTreeSet::from([&"Cart".to_string()]));
+        let fallbacks = scan.diagnostics.unwrap().parser_fallbacks;
+        assert_eq!(fallbacks.len(), 1);
+        assert!(fallbacks[0].path.ends_with("cart.py"));
+        assert_eq!((fallbacks[0].parser.as_str(), fallbacks[0].line, fallbacks[0].column), ("python-ast", 1, Some(1)));
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_follow_imports_records_classes_outside_the_root_as_external() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("app")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("shared")).unwrap();
+        fs::write(temp_dir.path().join("shared/money.py"), "class Money:\n    pass\n\nclass Unused:\n    pass\n").unwrap();
+        fs::write(
+            temp_dir.path().join("app/cart.py"),
+            "from ..shared.money import Money\n\nclass Cart:\n    def total(self) -> Money:\n        pass\n",
+        )
+        .unwrap();
+        let root = temp_dir.path().join("app").to_string_lossy().to_string();
+
+        assert_eq!(ProjectAnalyzer::new().analyze_files(&root).unwrap().len(), 1);
+        let files = ProjectAnalyzer::new().follow_imports(true).analyze_files(&root).unwrap();
+
+        assert_eq!(files.len(), 2);
+        assert!(files[1].path.ends_with("money.py"));
+        assert_eq!(files[1].result.classes, HashSet::from(["Money".to_string()]));
+        assert!(files[1].result.class_tags["Money"].contains(&ClassTag::External));
+        assert!(files[1].result.links.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "java")]
+    fn test_java_links_classes_of_the_same_package_without_imports() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = |name: &str| temp_dir.path().join(name);
+        fs::write(
+            path("Order.java"),
+            "package com.shop;\n\npublic class Order extends Entity {\n    private Customer customer;\n    private String note;\n\n    public void pay(PaymentMethod method) {}\n}\n",
+        )
+        .unwrap();
+        fs::write(path("Customer.java"), "package com.shop;\n\npublic class Customer {}\n").unwrap();
+        fs::write(path("PaymentMethod.java"), "package com.shop;\n\npublic interface PaymentMethod {}\n").unwrap();
+
+        let result = ProjectAnalyzer::new().analyze(&temp_dir.path().to_string_lossy()).unwrap();
+
+        let links: BTreeSet<(&str, &str, LinkKind)> =
+            result.links.iter().map(|l| (l.from_class.as_str(), l.to_class.as_str(), l.kind)).collect();
+        // `String` is a library type, dropped once the project is known
+        let expected = [
+            ("Order", "Customer", LinkKind::Composition),
+            ("Order", "Entity", LinkKind::Inheritance),
+            ("Order", "PaymentMethod", LinkKind::Usage),
+        ];
+        assert_eq!(links, BTreeSet::from(expected));
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_stream_has_the_links_the_scan_keeps() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("src");
+        fs::create_dir(&root).unwrap();
+        // `Task` is a library type until c.py defines it; `Optional` and
+        // `Missing` stay outside the project
+        fs::write(root.join("a.py"), "class A(B, Task, Optional, Missing):\n    pass\n").unwrap();
+        fs::write(root.join("b.py"), "class B:\n    pass\n").unwrap();
+        fs::write(root.join("c.py"), "class Task:\n    pass\n").unwrap();
+        let out = temp_dir.path().join("links.jsonl");
+        let root = root.to_string_lossy();
+
+        for external_nodes in [false, true] {
+            let stream = LinkStream::new(&root, Box::new(fs::File::create(&out).unwrap()));
+            let scan = ProjectAnalyzer::new()
+                .io(IoOptions { threads: Some(1), ..IoOptions::default() })
+                .external_nodes(external_nodes)
+                .stream(stream)
+                .scan(&root)
+                .unwrap();
+
+            let mut streamed: Vec<String> = fs::read_to_string(&out)
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+                .filter(|record| record["type"] == "link")
+                .map(|record| record["to"].as_str().unwrap().to_string())
+                .collect();
+            streamed.sort();
+            let mut kept: Vec<String> = scan.files.iter().flat_map(|file| &file.result.links).map(|link| link.to_class.clone()).collect();
+            kept.sort();
+            assert_eq!(streamed, kept, "external nodes: {}", external_nodes);
+            assert!(!streamed.contains(&"Optional".to_string()));
+            assert!(streamed.contains(&"Task".to_string()));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_reanalyze_class_refreshes_only_indexed_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = |name: &str| temp_dir.path().join(name).to_string_lossy().to_string();
+        fs::write(path("cart.py"), "class Cart:\n    pass\n").unwrap();
+        fs::write(path("shop.py"), "class Shop(Cart):\n    pass\n").unwrap();
+        fs::write(path("user.py"), "class User:\n    pass\n").unwrap();
+        let mut analysis = ProjectAnalyzer::new()
+            .analysis(&temp_dir.path().to_string_lossy())
+            .unwrap();
+        assert_eq!(analysis.files_of_class("Cart"), vec![path("cart.py"), path("shop.py")]);
+        assert_eq!(analysis.defining_files("Cart"), vec![path("cart.py")]);
+        assert_eq!(analysis.classes_in(&path("shop.py")), vec!["Shop"]);
+        assert_eq!(analysis.links_in(&path("shop.py"))[0].to_class, "Cart");
+
+        fs::write(path("cart.py"), "class Cart(Base):\n    pass\n").unwrap();
+        // Not indexed under Cart, so the edit stays unseen
+        fs::write(path("user.py"), "class User(Cart):\n    pass\n").unwrap();
+        fs::remove_file(path("shop.py")).unwrap();
+        let refreshed = analysis.reanalyze_class("Cart").unwrap();
+
+        assert_eq!(refreshed, vec![path("cart.py"), path("shop.py")]);
+        assert_eq!(analysis.files().count(), 2);
+        assert_eq!(analysis.files_of_class("Cart"), vec![path("cart.py")]);
+        assert_eq!(analysis.files_of_class("Base"), vec![path("cart.py")]);
+        assert!(analysis.defining_files("Shop").is_empty() && analysis.links_in(&path("shop.py")).is_empty());
+        assert_eq!(analysis.merged().links.len(), 1);
+        analysis.reanalyze_file(&path("user.py")).unwrap();
+        assert_eq!(analysis.files_of_class("Cart"), vec![path("cart.py"), path("user.py")]);
+    }
+
+    #[test]
+    #[cfg(feature = "python")]
+    fn test_project_analysis_lookups_are_sorted() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = |name: &str| temp_dir.path().join(name).to_string_lossy().to_string();
+        fs::write(path("zoo.py"), "class Zebra(Yak):\n    pass\nclass Ant:\n    pass\nclass Moth(Yak, Bee):\n    pass\n").unwrap();
+        fs::write(path("b.py"), "class Yak:\n    pass\n").unwrap();
+        fs::write(path("a.py"), "class Yak:\n    pass\n").unwrap();
+        let analysis = ProjectAnalyzer::new()
+            .analysis(&temp_dir.path().to_string_lossy())
+            .unwrap();
+
+        assert_eq!(analysis.classes_in(&path("zoo.py")), vec!["Ant", "Moth", "Zebra"]);
+        assert_eq!(analysis.defining_files("Yak"), vec![path("a.py"), path("b.py")]);
+        let links: Vec<(&str, &str)> = analysis
+            .links_in(&path("zoo.py"))
+            .iter()
+            .map(|link| (link.from_class.as_str(), link.to_class.as_str()))
+            .collect();
+        assert_eq!(links, vec![("Zebra", "Yak"), ("Moth", "Bee"), ("Moth", "Yak")]);
+    }
+}
@@ -0,0 +1,292 @@
+use crate::config::Config;
+use crate::i18n::{Catalog, Message};
+use crate::manifest::relative_path;
+use crate::project::ProjectAnalyzer;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+
+// What a report was computed from, so an audit can confirm later that the
+// same inputs still give the same result. Paths are root-relative.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub tool: String,
+    // Built-in parsers carry the tool version; custom analyzers and plugins
+    // are described by their configuration
+    pub parsers: BTreeMap<String, String>,
+    pub config: Config,
+    pub files: Vec<InputFile>,
+    // Found by the walk but not analyzed, e.g. binary or minified files
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputFile {
+    pub path: String,
+    // `sha256:` and the hex digest of the file's bytes
+    pub hash: String,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Verification {
+    pub changed: Vec<String>,
+    pub missing: Vec<String>,
+    // Analyzable files the report does not cover
+    pub added: Vec<String>,
+    // Recorded tool version, when the running one differs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+}
+
+fn tool() -> String {
+    format!("classlink-checker {}", env!("CARGO_PKG_VERSION"))
+}
+
+pub fn hash_file(path: &str) -> io::Result<InputFile> {
+    let bytes = fs::read(path)?;
+    Ok(InputFile {
+        path: path.to_string(),
+        hash: content_hash(&bytes),
+        bytes: bytes.len() as u64,
+    })
+}
+
+// `sha256:` and the hex digest
+pub fn content_hash(bytes: &[u8]) -> String {
+    let digest: String = sha256(bytes).iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("sha256:{}", digest)
+}
+
+impl Provenance {
+    pub fn record(config: &Config, files: &[String], skipped: &[String]) -> io::Result<Self> {
+        let built_in = [
+            ("markdown", true),
+            ("python", cfg!(feature = "python")),
+            ("typescript", cfg!(feature = "typescript")),
+            ("csharp", cfg!(feature = "csharp")),
+            ("rust", cfg!(feature = "rust")),
+            ("java", cfg!(feature = "java")),
+            ("go", cfg!(feature = "go")),
+            ("javascript", cfg!(feature = "javascript")),
+            ("html", cfg!(feature = "typescript")),
+        ];
+        let mut parsers: BTreeMap<String, String> = built_in
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(language, _)| language)
+            .map(|language| (language.to_string(), env!("CARGO_PKG_VERSION").to_string()))
+            .collect();
+        for analyzer in &config.custom_analyzers {
+            parsers.insert(format!("custom:{}", analyzer.name), "regex".to_string());
+        }
+        for plugin in &config.plugins {
+            parsers.insert(format!("plugin:{}", plugin.name), plugin.command.join(" "));
+        }
+
+        let mut inputs = Vec::new();
+        for path in files {
+            inputs.push(InputFile {
+                path: relative_path(&config.path, path),
+                ..hash_file(path)?
+            });
+        }
+        inputs.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut skipped: Vec<String> = skipped.iter().map(|path| relative_path(&config.path, path)).collect();
+        skipped.sort();
+
+        Ok(Self {
+            tool: tool(),
+            parsers,
+            config: config.clone(),
+            files: inputs,
+            skipped,
+        })
+    }
+
+    // Reads the `provenance` section of a JSON report
+    pub fn from_report(path: &str) -> Result<Self, Box<dyn Error>> {
+        let mut report: serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let provenance = report
+            .get_mut("provenance")
+            .map(serde_json::Value::take)
+            .ok_or("report has no provenance section")?;
+        Ok(serde_json::from_value(provenance)?)
+    }
+
+    // Re-hashes the recorded files under `root` (the recorded root when
+    // `None`) and walks it again with the recorded settings to find new files
+    pub fn verify(&self, root: Option<&str>) -> io::Result<Verification> {
+        let root = root.unwrap_or(&self.config.path);
+        let mut verification = Verification {
+            tool: (self.tool != tool()).then(|| self.tool.clone()),
+            ..Default::default()
+        };
+        for file in &self.files {
+            let path = std::path::Path::new(root).join(&file.path);
+            match hash_file(&path.to_string_lossy()) {
+                Ok(current) if current.hash == file.hash => {}
+                Ok(_) => verification.changed.push(file.path.clone()),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => verification.missing.push(file.path.clone()),
+                Err(e) => return Err(e),
+            }
+        }
+
+        let known: BTreeSet<&str> = self
+            .files
+            .iter()
+            .map(|file| file.path.as_str())
+            .chain(self.skipped.iter().map(String::as_str))
+            .collect();
+        let walked = ProjectAnalyzer::new()
+            .recursive(self.config.recursive)
+            .follow_symlinks(self.config.follow_symlinks)
+            .max_file_size(self.config.max_file_size_mb.map(|mb| mb * 1024 * 1024))
+            .skip_vendored(self.config.skip_vendored)
+            .find_supported_files(root)?;
+        verification.added = walked
+            .iter()
+            .map(|path| relative_path(root, path))
+            .filter(|path| !known.contains(path.as_str()))
+            .collect();
+        verification.added.sort();
+        Ok(verification)
+    }
+}
+
+impl Verification {
+    pub fn is_match(&self) -> bool {
+        self.changed.is_empty() && self.missing.is_empty() && self.added.is_empty() && self.tool.is_none()
+    }
+
+    pub fn render_text(&self, catalog: &Catalog) -> String {
+        let mut out = String::new();
+        if self.is_match() {
+            writeln!(out, "{}", catalog.text(Message::VerifyMatch, &[])).unwrap();
+            return out;
+        }
+        writeln!(out, "{}", catalog.text(Message::VerifyMismatch, &[])).unwrap();
+        if let Some(tool) = &self.tool {
+            writeln!(out, "  {}", catalog.text(Message::VerifyTool, &[tool, &self::tool()])).unwrap();
+        }
+        let sections = [
+            (Message::VerifyChanged, &self.changed),
+            (Message::VerifyMissing, &self.missing),
+            (Message::VerifyAdded, &self.added),
+        ];
+        for (header, paths) in sections {
+            for path in paths {
+                writeln!(out, "  {}", catalog.text(header, &[path])).unwrap();
+            }
+        }
+        out
+    }
+}
+
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5, 0xd807aa98,
+    0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786,
+    0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8,
+    0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13,
+    0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819,
+    0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a,
+    0x5b9cca4f, 0x682e6ff3, 0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+    0xc67178f2,
+];
+
+// FIPS 180-4 SHA-256, small enough to keep the tool free of a crypto
+// dependency. Only used for change detection, never for secrets.
+fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    ];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut words = [0u32; 64];
+        for (i, word) in block.chunks(4).enumerate() {
+            words[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = words[i - 15].rotate_right(7) ^ words[i - 15].rotate_right(18) ^ (words[i - 15] >> 3);
+            let s1 = words[i - 2].rotate_right(17) ^ words[i - 2].rotate_right(19) ^ (words[i - 2] >> 10);
+            words[i] = words[i - 16].wrapping_add(s0).wrapping_add(words[i - 7]).wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choose = (e & f) ^ (!e & g);
+            let t1 = h.wrapping_add(s1).wrapping_add(choose).wrapping_add(ROUND_CONSTANTS[i]).wrapping_add(words[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(majority);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (value, add) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *value = value.wrapping_add(add);
+        }
+    }
+
+    let mut digest = [0u8; 32];
+    for (chunk, value) in digest.chunks_mut(4).zip(state) {
+        chunk.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256_known_digests() {
+        let hex = |data: &[u8]| -> String { sha256(data).iter().map(|byte| format!("{:02x}", byte)).collect() };
+
+        assert_eq!(hex(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(hex(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        let long = "a".repeat(1000);
+        assert_eq!(hex(long.as_bytes()), "41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3");
+    }
+
+    #[test]
+    fn test_verify_detects_changed_missing_and_added_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_str().unwrap();
+        for name in ["A.md", "B.md", "C.md"] {
+            fs::write(dir.path().join(name), format!("# {}\n", &name[..1])).unwrap();
+        }
+        let config = Config {
+            path: root.to_string(),
+            ..Default::default()
+        };
+        let files: Vec<String> = ["A.md", "B.md", "C.md"].iter().map(|name| format!("{}/{}", root, name)).collect();
+        let provenance = Provenance::record(&config, &files, &[]).unwrap();
+        assert!(provenance.verify(None).unwrap().is_match());
+
+        fs::write(dir.path().join("A.md"), "# A\n\n[B](B.md)\n").unwrap();
+        fs::remove_file(dir.path().join("C.md")).unwrap();
+        fs::write(dir.path().join("D.md"), "# D\n").unwrap();
+        let verification = provenance.verify(Some(root)).unwrap();
+
+        assert!(!verification.is_match());
+        assert_eq!(verification.changed, vec!["A.md"]);
+        assert_eq!(verification.missing, vec!["C.md"]);
+        assert_eq!(verification.added, vec!["D.md"]);
+    }
+}
@@ -0,0 +1,949 @@
+// Cypher-like queries over the class graph:
+//
+//     MATCH (a)-[:INHERITS]->(b {name: "BaseService"}) RETURN a
+//     MATCH (a)-[r:USES|COMPOSES]->(b) WHERE a.package <> b.package AND b.deprecated RETURN DISTINCT a.package, b.name
+//     MATCH (a)-->(b)-->(a) RETURN a, b LIMIT 10
+//
+// One MATCH path of nodes and single-hop relationships (`-[..]->`, `<-[..]-`,
+// `-[..]-`, `-->`, `<--`, `--`), an optional WHERE, and RETURN with optional
+// DISTINCT and LIMIT. Nodes are classes, relationships are logical links
+// (all occurrences of one `from -> to` pair). There is no aggregation.
+// A `name` with `*` or `?` in a node pattern is a glob, like
+// `(s {name: "*Service"})`.
+use crate::file_analyzer::AnalysisResult;
+use crate::glob;
+use crate::i18n::{Catalog, Message};
+use crate::manifest::{self, relative_path};
+use crate::parser::LinkKind;
+use crate::project::{self, FileAnalysis};
+use crate::search;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::{self, Write};
+
+const NODE_PROPERTIES: &[&str] = &[
+    "name", "package", "namespace", "file", "fan_in", "fan_out", "lines", "methods", "defined", "deprecated",
+    "internal",
+];
+const REL_PROPERTIES: &[&str] = &["kind", "count", "from", "to"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    pub message: String,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    Null,
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Str(s) => f.write_str(s),
+            Value::Num(n) => write!(f, "{}", n),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Null => f.write_str("null"),
+        }
+    }
+}
+
+// Whole numbers (counts, fan-in) serialize as JSON integers
+impl Serialize for Value {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Str(s) => serializer.serialize_str(s),
+            Value::Num(n) if n.fract() == 0.0 && n.abs() < 1e15 => serializer.serialize_i64(*n as i64),
+            Value::Num(n) => serializer.serialize_f64(*n),
+            Value::Bool(b) => serializer.serialize_bool(*b),
+            Value::Null => serializer.serialize_none(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Out,
+    In,
+    Either,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct NodePattern {
+    var: Option<String>,
+    properties: Vec<(String, Value)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RelPattern {
+    var: Option<String>,
+    // Empty matches every kind
+    kinds: Vec<LinkKind>,
+    direction: Direction,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Literal(Value),
+    Variable(String),
+    Property(String, String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(Box<Expr>, &'static str, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    nodes: Vec<NodePattern>,
+    rels: Vec<RelPattern>,
+    condition: Option<Expr>,
+    returns: Vec<Expr>,
+    columns: Vec<String>,
+    distinct: bool,
+    limit: Option<usize>,
+}
+
+// The relationship type of a link kind, as patterns and the Cypher export
+// write it
+pub fn rel_type_name(kind: LinkKind) -> &'static str {
+    match kind {
+        LinkKind::Inheritance => "INHERITS",
+        LinkKind::Composition => "COMPOSES",
+        LinkKind::Usage => "USES",
+        LinkKind::Binding => "BINDS",
+        LinkKind::Doc => "DOCUMENTS",
+    }
+}
+
+// Relationship types as written in patterns; link kind names work too
+fn rel_type(name: &str) -> Result<LinkKind, String> {
+    match name.to_uppercase().as_str() {
+        "INHERITS" => Ok(LinkKind::Inheritance),
+        "COMPOSES" => Ok(LinkKind::Composition),
+        "USES" => Ok(LinkKind::Usage),
+        "BINDS" => Ok(LinkKind::Binding),
+        "DOCUMENTS" => Ok(LinkKind::Doc),
+        _ => name.parse().map_err(|_| {
+            format!("unknown relationship type '{}' (expected INHERITS, COMPOSES, USES, BINDS, DOCUMENTS)", name)
+        }),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Sym(&'static str),
+}
+
+const SYMBOLS: &[&str] = &[
+    "<>", "<=", ">=", "=~", "->", "<-", "(", ")", "[", "]", "{", "}", ":", ",", ".", "-", "<", ">", "=", "|",
+];
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '"' || c == '\'' {
+            let mut value = String::new();
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' && i + 1 < chars.len() {
+                    i += 1;
+                }
+                value.push(chars[i]);
+                i += 1;
+            }
+            if i == chars.len() {
+                return Err("unterminated string".to_string());
+            }
+            tokens.push(Token::Str(value));
+            i += 1;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let number: String = chars[start..i].iter().collect();
+            tokens.push(Token::Num(number.parse().map_err(|_| format!("invalid number '{}'", number))?));
+        } else if c.is_alphanumeric() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            let rest: String = chars[i..].iter().take(2).collect();
+            let symbol = SYMBOLS
+                .iter()
+                .find(|symbol| rest.starts_with(**symbol))
+                .ok_or_else(|| format!("unexpected character '{}'", c))?;
+            tokens.push(Token::Sym(symbol));
+            i += symbol.len();
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    node_vars: BTreeSet<String>,
+    rel_vars: BTreeSet<String>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Sym(s)) if *s == symbol) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), String> {
+        if self.eat(symbol) {
+            Ok(())
+        } else {
+            Err(format!("expected '{}'", symbol))
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(word)) if word.eq_ignore_ascii_case(keyword)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn identifier(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(Token::Ident(name)) => {
+                let name = name.clone();
+                self.position += 1;
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    fn node(&mut self) -> Result<NodePattern, String> {
+        self.expect("(")?;
+        let var = self.identifier();
+        if let Some(var) = &var {
+            if self.rel_vars.contains(var) {
+                return Err(format!("'{}' is already a relationship", var));
+            }
+            self.node_vars.insert(var.clone());
+        }
+        let mut properties = Vec::new();
+        if self.eat("{") {
+            loop {
+                let name = self.identifier().ok_or("expected a property name")?;
+                if !NODE_PROPERTIES.contains(&name.as_str()) {
+                    return Err(format!("unknown node property '{}'", name));
+                }
+                self.expect(":")?;
+                properties.push((name, self.literal()?));
+                if !self.eat(",") {
+                    break;
+                }
+            }
+            self.expect("}")?;
+        }
+        self.expect(")")?;
+        Ok(NodePattern { var, properties })
+    }
+
+    // `-[r:TYPE|TYPE]->`, `<-[..]-`, `-[..]-` or the bracketless `-->`, `<--`, `--`
+    fn rel(&mut self) -> Result<Option<RelPattern>, String> {
+        let incoming = if self.eat("<-") {
+            true
+        } else if self.eat("-") {
+            false
+        } else {
+            return Ok(None);
+        };
+        let mut var = None;
+        let mut kinds = Vec::new();
+        if self.eat("[") {
+            var = self.identifier();
+            if let Some(var) = &var {
+                if self.node_vars.contains(var) || !self.rel_vars.insert(var.clone()) {
+                    return Err(format!("'{}' is already bound", var));
+                }
+            }
+            if self.eat(":") {
+                loop {
+                    let name = self.identifier().ok_or("expected a relationship type")?;
+                    kinds.push(rel_type(&name)?);
+                    if !self.eat("|") {
+                        break;
+                    }
+                }
+            }
+            self.expect("]")?;
+        }
+        let direction = match (incoming, self.next()) {
+            (false, Some(Token::Sym("->"))) => Direction::Out,
+            (true, Some(Token::Sym("-"))) => Direction::In,
+            (false, Some(Token::Sym("-"))) => Direction::Either,
+            _ => return Err("malformed relationship, expected '-[...]->', '<-[...]-' or '-[...]-'".to_string()),
+        };
+        Ok(Some(RelPattern { var, kinds, direction }))
+    }
+
+    fn literal(&mut self) -> Result<Value, String> {
+        match self.next() {
+            Some(Token::Str(text)) => Ok(Value::Str(text)),
+            Some(Token::Num(number)) => Ok(Value::Num(number)),
+            Some(Token::Ident(word)) if word.eq_ignore_ascii_case("true") => Ok(Value::Bool(true)),
+            Some(Token::Ident(word)) if word.eq_ignore_ascii_case("false") => Ok(Value::Bool(false)),
+            Some(Token::Ident(word)) if word.eq_ignore_ascii_case("null") => Ok(Value::Null),
+            _ => Err("expected a string, number or boolean".to_string()),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.eat_keyword("OR") {
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.not()?;
+        while self.eat_keyword("AND") {
+            left = Expr::And(Box::new(left), Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        if self.eat_keyword("NOT") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let left = self.operand()?;
+        let op = match self.peek() {
+            Some(Token::Sym(op)) if ["=", "<>", "<", "<=", ">", ">=", "=~"].contains(op) => *op,
+            Some(Token::Ident(word)) if word.eq_ignore_ascii_case("CONTAINS") => "contains",
+            _ => return Ok(left),
+        };
+        self.position += 1;
+        Ok(Expr::Compare(Box::new(left), op, Box::new(self.operand()?)))
+    }
+
+    fn operand(&mut self) -> Result<Expr, String> {
+        if self.eat("(") {
+            let inner = self.or()?;
+            self.expect(")")?;
+            return Ok(inner);
+        }
+        match self.peek() {
+            Some(Token::Ident(word))
+                if !["true", "false", "null"].iter().any(|literal| word.eq_ignore_ascii_case(literal)) =>
+            {
+                self.reference()
+            }
+            _ => Ok(Expr::Literal(self.literal()?)),
+        }
+    }
+
+    // `a` or `a.property`, for a variable bound in MATCH
+    fn reference(&mut self) -> Result<Expr, String> {
+        let var = self.identifier().ok_or("expected a variable")?;
+        let is_node = self.node_vars.contains(&var);
+        if !is_node && !self.rel_vars.contains(&var) {
+            return Err(format!("unknown variable '{}'", var));
+        }
+        if !self.eat(".") {
+            return Ok(Expr::Variable(var));
+        }
+        let property = self.identifier().ok_or("expected a property name")?;
+        let known = if is_node { NODE_PROPERTIES } else { REL_PROPERTIES };
+        if !known.contains(&property.as_str()) {
+            return Err(format!("unknown property '{}.{}'", var, property));
+        }
+        Ok(Expr::Property(var, property))
+    }
+}
+
+pub fn parse(text: &str) -> Result<Query, QueryError> {
+    parse_query(text).map_err(|message| QueryError { message })
+}
+
+fn parse_query(text: &str) -> Result<Query, String> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        position: 0,
+        node_vars: BTreeSet::new(),
+        rel_vars: BTreeSet::new(),
+    };
+    if !parser.eat_keyword("MATCH") {
+        return Err("expected 'MATCH'".to_string());
+    }
+    let mut nodes = vec![parser.node()?];
+    let mut rels = Vec::new();
+    while let Some(rel) = parser.rel()? {
+        rels.push(rel);
+        nodes.push(parser.node()?);
+    }
+
+    let condition = if parser.eat_keyword("WHERE") {
+        Some(parser.or()?)
+    } else {
+        None
+    };
+    if !parser.eat_keyword("RETURN") {
+        return Err("expected 'RETURN'".to_string());
+    }
+    let distinct = parser.eat_keyword("DISTINCT");
+    let mut returns = Vec::new();
+    let mut columns = Vec::new();
+    loop {
+        let start = parser.position;
+        returns.push(parser.reference()?);
+        columns.push(
+            parser.tokens[start..parser.position]
+                .iter()
+                .map(|token| match token {
+                    Token::Ident(name) => name.as_str(),
+                    _ => ".",
+                })
+                .collect(),
+        );
+        if !parser.eat(",") {
+            break;
+        }
+    }
+    let limit = if parser.eat_keyword("LIMIT") {
+        match parser.next() {
+            Some(Token::Num(number)) if number.fract() == 0.0 => Some(number as usize),
+            _ => return Err("expected a whole number after LIMIT".to_string()),
+        }
+    } else {
+        None
+    };
+    if let Some(token) = parser.peek() {
+        return Err(format!("unexpected {:?} after the query", token));
+    }
+
+    Ok(Query {
+        nodes,
+        rels,
+        condition,
+        returns,
+        columns,
+        distinct,
+        limit,
+    })
+}
+
+// What a query sees of one class. Link targets that are not defined in the
+// project get empty strings and zeroes.
+#[derive(Debug, Clone, Default)]
+struct NodeFacts {
+    package: String,
+    namespace: String,
+    file: String,
+    fan_in: usize,
+    fan_out: usize,
+    lines: usize,
+    methods: usize,
+    defined: bool,
+    deprecated: bool,
+    internal: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Edge {
+    from: String,
+    to: String,
+    kinds: BTreeSet<LinkKind>,
+    count: usize,
+}
+
+#[derive(Debug, Clone)]
+enum Bound {
+    Node(String),
+    Rel(usize),
+}
+
+type Binding = BTreeMap<String, Bound>;
+
+pub struct QueryGraph {
+    nodes: BTreeMap<String, NodeFacts>,
+    edges: Vec<Edge>,
+    outgoing: HashMap<String, Vec<usize>>,
+    incoming: HashMap<String, Vec<usize>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl QueryGraph {
+    pub fn build(root: &str, files: &[FileAnalysis]) -> Self {
+        let merged: AnalysisResult = project::merge(files.to_vec());
+        let packages = manifest::class_packages(root, files);
+        let namespaces = manifest::class_namespaces(root, files);
+        let mut nodes: BTreeMap<String, NodeFacts> = BTreeMap::new();
+        for file in files {
+            let relative = relative_path(root, &file.path);
+            for class in &file.result.classes {
+                let facts = nodes.entry(class.clone()).or_default();
+                facts.defined = true;
+                if facts.file.is_empty() || relative < facts.file {
+                    facts.file = relative.clone();
+                }
+            }
+        }
+
+        let mut pairs: BTreeMap<(String, String), Edge> = BTreeMap::new();
+        for link in &merged.links {
+            let edge = pairs
+                .entry((link.from_class.clone(), link.to_class.clone()))
+                .or_insert_with(|| Edge {
+                    from: link.from_class.clone(),
+                    to: link.to_class.clone(),
+                    kinds: BTreeSet::new(),
+                    count: 0,
+                });
+            edge.kinds.insert(link.kind);
+            edge.count += 1;
+        }
+        let edges: Vec<Edge> = pairs.into_values().collect();
+
+        let mut outgoing: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut incoming: HashMap<String, Vec<usize>> = HashMap::new();
+        for (index, edge) in edges.iter().enumerate() {
+            outgoing.entry(edge.from.clone()).or_default().push(index);
+            incoming.entry(edge.to.clone()).or_default().push(index);
+            nodes.entry(edge.from.clone()).or_default().fan_out += 1;
+            nodes.entry(edge.to.clone()).or_default().fan_in += 1;
+        }
+        for (class, facts) in nodes.iter_mut() {
+            facts.package = packages.get(class).cloned().unwrap_or_default();
+            facts.namespace = namespaces.get(class).cloned().unwrap_or_default();
+            if let Some(size) = merged.class_metrics.get(class) {
+                facts.lines = size.lines;
+                facts.methods = size.methods;
+            }
+            if let Some(tags) = merged.class_tags.get(class) {
+                facts.deprecated = tags.contains(&crate::annotations::ClassTag::Deprecated);
+                facts.internal = tags.contains(&crate::annotations::ClassTag::Internal);
+            }
+        }
+
+        Self {
+            nodes,
+            edges,
+            outgoing,
+            incoming,
+        }
+    }
+
+    pub fn run(&self, query: &Query) -> QueryResult {
+        let mut rows = Vec::new();
+        let mut seen = BTreeSet::new();
+        for start in self.nodes.keys() {
+            if let Some(bound) = self.bind_node(&query.nodes[0], start, &Binding::new()) {
+                self.extend(query, 1, start, bound, &mut Vec::new(), &mut |binding| {
+                    let row: Vec<Value> = query.returns.iter().map(|expr| self.evaluate(expr, binding)).collect();
+                    if !query.distinct || seen.insert(format!("{:?}", row)) {
+                        rows.push(row);
+                    }
+                    query.limit.is_some_and(|limit| rows.len() >= limit)
+                });
+                if query.limit.is_some_and(|limit| rows.len() >= limit) {
+                    break;
+                }
+            }
+        }
+        QueryResult {
+            columns: query.columns.clone(),
+            rows,
+        }
+    }
+
+    // Walks the rest of the path from `current`, calling `emit` with every
+    // complete binding that passes WHERE; `emit` returns true to stop
+    fn extend(
+        &self,
+        query: &Query,
+        index: usize,
+        current: &str,
+        binding: Binding,
+        used: &mut Vec<usize>,
+        emit: &mut dyn FnMut(&Binding) -> bool,
+    ) -> bool {
+        if index == query.nodes.len() {
+            let passes = query
+                .condition
+                .as_ref()
+                .is_none_or(|condition| truthy(&self.evaluate(condition, &binding)));
+            return passes && emit(&binding);
+        }
+        let rel = &query.rels[index - 1];
+        let none = Vec::new();
+        let out = self.outgoing.get(current).unwrap_or(&none);
+        let inc = self.incoming.get(current).unwrap_or(&none);
+        let candidates: Vec<(usize, &str)> = match rel.direction {
+            Direction::Out => out.iter().map(|&e| (e, self.edges[e].to.as_str())).collect(),
+            Direction::In => inc.iter().map(|&e| (e, self.edges[e].from.as_str())).collect(),
+            Direction::Either => out
+                .iter()
+                .map(|&e| (e, self.edges[e].to.as_str()))
+                .chain(inc.iter().map(|&e| (e, self.edges[e].from.as_str())))
+                .collect(),
+        };
+
+        for (edge, next) in candidates {
+            // Like Cypher, one match never uses the same relationship twice
+            if used.contains(&edge) {
+                continue;
+            }
+            if !rel.kinds.is_empty() && !rel.kinds.iter().any(|kind| self.edges[edge].kinds.contains(kind)) {
+                continue;
+            }
+            let Some(mut bound) = self.bind_node(&query.nodes[index], next, &binding) else {
+                continue;
+            };
+            if let Some(var) = &rel.var {
+                bound.insert(var.clone(), Bound::Rel(edge));
+            }
+            used.push(edge);
+            let stop = self.extend(query, index + 1, next, bound, used, emit);
+            used.pop();
+            if stop {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn bind_node(&self, pattern: &NodePattern, class: &str, binding: &Binding) -> Option<Binding> {
+        if let Some(var) = &pattern.var {
+            if let Some(Bound::Node(existing)) = binding.get(var) {
+                if existing != class {
+                    return None;
+                }
+            }
+        }
+        let matches = pattern.properties.iter().all(|(property, value)| match (property.as_str(), value) {
+            ("name", Value::Str(name)) if name.contains(['*', '?']) => glob::matches(name, class),
+            _ => self.node_value(class, property) == *value,
+        });
+        if !matches {
+            return None;
+        }
+        let mut bound = binding.clone();
+        if let Some(var) = &pattern.var {
+            bound.insert(var.clone(), Bound::Node(class.to_string()));
+        }
+        Some(bound)
+    }
+
+    pub fn find_classes(&self, pattern: &str) -> Vec<String> {
+        search::find_classes(self.nodes.keys(), pattern)
+    }
+
+    // Names the query's node patterns pin that are no class of the graph,
+    // each with the closest classes, so a typo does not just return nothing
+    pub fn unknown_names(&self, query: &Query) -> Vec<(String, Vec<String>)> {
+        let mut unknown: Vec<(String, Vec<String>)> = Vec::new();
+        for node in &query.nodes {
+            for (property, value) in &node.properties {
+                let Value::Str(name) = value else {
+                    continue;
+                };
+                let known = name.contains(['*', '?']) || self.nodes.contains_key(name);
+                if property != "name" || known || unknown.iter().any(|(seen, _)| seen == name) {
+                    continue;
+                }
+                unknown.push((name.clone(), self.find_classes(name).into_iter().take(3).collect()));
+            }
+        }
+        unknown
+    }
+
+    fn node_value(&self, class: &str, property: &str) -> Value {
+        let facts = self.nodes.get(class).cloned().unwrap_or_default();
+        match property {
+            "name" => Value::Str(class.to_string()),
+            "package" => Value::Str(facts.package),
+            "namespace" => Value::Str(facts.namespace),
+            "file" => Value::Str(facts.file),
+            "fan_in" => Value::Num(facts.fan_in as f64),
+            "fan_out" => Value::Num(facts.fan_out as f64),
+            "lines" => Value::Num(facts.lines as f64),
+            "methods" => Value::Num(facts.methods as f64),
+            "deprecated" => Value::Bool(facts.deprecated),
+            "internal" => Value::Bool(facts.internal),
+            _ => Value::Bool(facts.defined),
+        }
+    }
+
+    // `kind` is the strongest kind of the pair
+    fn rel_value(&self, edge: usize, property: &str) -> Value {
+        let edge = &self.edges[edge];
+        match property {
+            "kind" => edge.kinds.iter().next().map_or(Value::Null, |kind| Value::Str(kind.to_string())),
+            "count" => Value::Num(edge.count as f64),
+            "from" => Value::Str(edge.from.clone()),
+            _ => Value::Str(edge.to.clone()),
+        }
+    }
+
+    fn evaluate(&self, expr: &Expr, binding: &Binding) -> Value {
+        match expr {
+            Expr::Literal(value) => value.clone(),
+            Expr::Variable(var) => match binding.get(var) {
+                Some(Bound::Node(class)) => Value::Str(class.clone()),
+                Some(Bound::Rel(edge)) => {
+                    let edge = &self.edges[*edge];
+                    Value::Str(format!("{} -> {}", edge.from, edge.to))
+                }
+                None => Value::Null,
+            },
+            Expr::Property(var, property) => match binding.get(var) {
+                Some(Bound::Node(class)) => self.node_value(class, property),
+                Some(Bound::Rel(edge)) => self.rel_value(*edge, property),
+                None => Value::Null,
+            },
+            Expr::Not(inner) => Value::Bool(!truthy(&self.evaluate(inner, binding))),
+            Expr::And(left, right) => {
+                Value::Bool(truthy(&self.evaluate(left, binding)) && truthy(&self.evaluate(right, binding)))
+            }
+            Expr::Or(left, right) => {
+                Value::Bool(truthy(&self.evaluate(left, binding)) || truthy(&self.evaluate(right, binding)))
+            }
+            Expr::Compare(left, op, right) => {
+                let (left, right) = (self.evaluate(left, binding), self.evaluate(right, binding));
+                Value::Bool(compare(&left, op, &right))
+            }
+        }
+    }
+}
+
+fn compare(left: &Value, op: &str, right: &Value) -> bool {
+    match (op, left, right) {
+        ("=", _, _) => left == right,
+        ("<>", _, _) => left != right,
+        ("contains", Value::Str(a), Value::Str(b)) => a.contains(b.as_str()),
+        ("=~", Value::Str(a), Value::Str(pattern)) => {
+            Regex::new(&format!("^(?:{})$", pattern)).is_ok_and(|regex| regex.is_match(a))
+        }
+        (op, Value::Num(a), Value::Num(b)) => ordered(op, a.partial_cmp(b)),
+        (op, Value::Str(a), Value::Str(b)) => ordered(op, Some(a.cmp(b))),
+        _ => false,
+    }
+}
+
+fn ordered(op: &str, ordering: Option<std::cmp::Ordering>) -> bool {
+    use std::cmp::Ordering::*;
+    matches!(
+        (op, ordering),
+        ("<", Some(Less)) | ("<=", Some(Less | Equal)) | (">", Some(Greater)) | (">=", Some(Greater | Equal))
+    )
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Num(n) => *n != 0.0,
+        Value::Str(s) => !s.is_empty(),
+        Value::Null => false,
+    }
+}
+
+impl QueryResult {
+    pub fn render_text(&self, catalog: &Catalog) -> String {
+        let cells: Vec<Vec<String>> = self.rows.iter().map(|row| row.iter().map(Value::to_string).collect()).collect();
+        let widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                cells
+                    .iter()
+                    .map(|row| row[i].chars().count())
+                    .chain([column.chars().count()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let line = |values: &[String]| {
+            let padded: Vec<String> = values
+                .iter()
+                .zip(&widths)
+                .map(|(value, width)| format!("{:<width$}", value, width = width))
+                .collect();
+            padded.join(" | ").trim_end().to_string()
+        };
+
+        let mut out = String::new();
+        writeln!(out, "{}", line(&self.columns)).unwrap();
+        let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+        writeln!(out, "{}", rule.join("-+-")).unwrap();
+        for row in &cells {
+            writeln!(out, "{}", line(row)).unwrap();
+        }
+        writeln!(out, "\n{}", catalog.text(Message::QueryRowCount, &[&self.rows.len()])).unwrap();
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ClassLink;
+
+    fn file(path: &str, classes: &[&str], links: &[(&str, &str, LinkKind)]) -> FileAnalysis {
+        FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|s| s.to_string()).collect(),
+                links: links
+                    .iter()
+                    .map(|(from, to, kind)| ClassLink {
+                        from_class: from.to_string(),
+                        to_class: to.to_string(),
+                        line_number: 1,
+                        kind: *kind,
+                        position: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        }
+    }
+
+    fn graph() -> QueryGraph {
+        QueryGraph::build(
+            "/repo",
+            &[
+                file(
+                    "/repo/services/orders.py",
+                    &["OrderService", "BaseService"],
+                    &[
+                        ("OrderService", "BaseService", LinkKind::Inheritance),
+                        ("OrderService", "Repo", LinkKind::Usage),
+                    ],
+                ),
+                file(
+                    "/repo/services/users.py",
+                    &["UserService"],
+                    &[("UserService", "BaseService", LinkKind::Inheritance)],
+                ),
+                file(
+                    "/repo/db/repo.py",
+                    &["Repo"],
+                    &[("Repo", "OrderService", LinkKind::Usage), ("Repo", "Json", LinkKind::Usage)],
+                ),
+            ],
+        )
+    }
+
+    fn column(result: &QueryResult, index: usize) -> Vec<String> {
+        result.rows.iter().map(|row| row[index].to_string()).collect()
+    }
+
+    fn run(query: &str) -> QueryResult {
+        graph().run(&parse(query).unwrap())
+    }
+
+    #[test]
+    fn test_match_by_relationship_type_and_properties() {
+        let result = run(r#"MATCH (a)-[:INHERITS]->(b {name: "BaseService"}) RETURN a"#);
+        assert_eq!(result.columns, vec!["a"]);
+        assert_eq!(column(&result, 0), vec!["OrderService", "UserService"]);
+
+        let result = run("MATCH (a)<--(b) WHERE a.defined AND b.package <> a.package RETURN a.name, b.package");
+        assert_eq!(result.columns, vec!["a.name", "b.package"]);
+        assert_eq!(
+            result.rows,
+            vec![
+                vec![Value::Str("OrderService".into()), Value::Str("db".into())],
+                vec![Value::Str("Repo".into()), Value::Str("services".into())],
+            ]
+        );
+
+        let result = run("match (a)-[r]->(b) where not b.defined return r, r.count");
+        assert_eq!(column(&result, 0), vec!["Repo -> Json"]);
+    }
+
+    #[test]
+    fn test_paths_distinct_and_limit() {
+        assert_eq!(column(&run("MATCH (a)-->(b)-->(a) RETURN a"), 0), vec!["OrderService", "Repo"]);
+        assert_eq!(column(&run("MATCH (a)--(b) WHERE a.name =~ 'Order.*' RETURN DISTINCT a"), 0), vec!["OrderService"]);
+        assert_eq!(run("MATCH (a)-->(b) RETURN a LIMIT 2").rows.len(), 2);
+    }
+
+    #[test]
+    fn test_name_globs_and_unknown_names() {
+        assert_eq!(column(&run(r#"MATCH (a {name: "*Service"})-->(b) RETURN DISTINCT a"#), 0), vec!["OrderService", "UserService"]);
+
+        let query = parse(r#"MATCH (a)-->(b {name: "BaseServise"}) WHERE a.name <> "Repo" RETURN a"#).unwrap();
+        let graph = graph();
+        assert!(graph.run(&query).rows.is_empty());
+        assert_eq!(graph.unknown_names(&query), vec![("BaseServise".to_string(), vec!["BaseService".to_string()])]);
+        assert_eq!(graph.find_classes("usrsvc"), vec!["UserService"]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |query: &str| parse(query).unwrap_err().message;
+        assert_eq!(error("MATCH (a)-[:CALLS]->(b) RETURN a").split(" (").next().unwrap(), "unknown relationship type 'CALLS'");
+        assert_eq!(error("MATCH (a) RETURN b"), "unknown variable 'b'");
+        assert_eq!(error("MATCH (a {size: 3}) RETURN a"), "unknown node property 'size'");
+        assert_eq!(error("MATCH (a)"), "expected 'RETURN'");
+    }
+
+    #[test]
+    fn test_render_text_table() {
+        let text = run(r#"MATCH (a)-[:INHERITS]->(b) RETURN a, b"#).render_text(&Catalog::default());
+        assert!(text.starts_with("a            | b\n-------------+------------\nOrderService | BaseService\n"));
+        assert!(text.ends_with("2 rows\n"));
+    }
+}
@@ -0,0 +1,137 @@
+use regex::Regex;
+use std::io;
+use std::process::Command;
+use tempfile::TempDir;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemoteSource {
+    Git { url: String, reference: Option<String> },
+    Tarball { url: String },
+}
+
+impl RemoteSource {
+    // Returns None for local paths so callers can fall through to the walker
+    pub fn parse(location: &str, reference: Option<&str>) -> Option<Self> {
+        let is_tarball = [".tar.gz", ".tgz"].iter().any(|ext| location.ends_with(ext));
+        // A scheme or scp-style `user@host:path`; a local `foo.git` is a path
+        let is_url = ["https://", "http://", "ssh://", "git://", "file://"]
+            .iter()
+            .any(|scheme| location.starts_with(scheme))
+            || Regex::new(r"^[\w.-]+@[\w.-]+:").unwrap().is_match(location);
+
+        if is_tarball && is_url {
+            Some(RemoteSource::Tarball {
+                url: location.to_string(),
+            })
+        } else if is_url {
+            Some(RemoteSource::Git {
+                url: location.to_string(),
+                reference: reference.map(str::to_string),
+            })
+        } else {
+            None
+        }
+    }
+
+    // Fetches the source into a temporary directory that is removed on drop
+    pub fn fetch(&self) -> io::Result<TempDir> {
+        let checkout = TempDir::new()?;
+
+        match self {
+            RemoteSource::Git { url, reference } => {
+                let mut command = Command::new("git");
+                command.args(["clone", "--quiet", "--depth", "1"]);
+                if let Some(reference) = reference {
+                    command.args(["--branch", reference]);
+                }
+                // `--` keeps a URL starting with `-` from being read as an option
+                command.arg("--").arg(url).arg(checkout.path());
+                run(&mut command, "git clone")?;
+            }
+            RemoteSource::Tarball { url } => {
+                let archive = checkout.path().join(".classlink-source.tar.gz");
+                run(
+                    Command::new("curl").args(["-sSfL", "-o"]).arg(&archive).arg(url),
+                    "curl",
+                )?;
+                run(
+                    Command::new("tar").arg("-xzf").arg(&archive).arg("-C").arg(checkout.path()),
+                    "tar",
+                )?;
+                std::fs::remove_file(&archive)?;
+            }
+        }
+
+        Ok(checkout)
+    }
+}
+
+fn run(command: &mut Command, name: &str) -> io::Result<()> {
+    let output = command.output().map_err(|e| {
+        io::Error::new(e.kind(), format!("failed to run {}: {}", name, e))
+    })?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "{} failed: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_parse_remote_locations() {
+        assert_eq!(
+            RemoteSource::parse("https://github.com/org/repo", Some("main")),
+            Some(RemoteSource::Git {
+                url: "https://github.com/org/repo".to_string(),
+                reference: Some("main".to_string()),
+            })
+        );
+        assert_eq!(
+            RemoteSource::parse("https://example.com/repo/archive/main.tar.gz", None),
+            Some(RemoteSource::Tarball {
+                url: "https://example.com/repo/archive/main.tar.gz".to_string(),
+            })
+        );
+        assert_eq!(
+            RemoteSource::parse("git@github.com:org/repo.git", None),
+            Some(RemoteSource::Git {
+                url: "git@github.com:org/repo.git".to_string(),
+                reference: None,
+            })
+        );
+        assert_eq!(RemoteSource::parse("./docs", None), None);
+        assert_eq!(RemoteSource::parse("vendor/foo.git", None), None);
+        assert_eq!(RemoteSource::parse("-uupload-pack=touch.git", None), None);
+    }
+
+    #[test]
+    fn test_fetch_shallow_clones_git_repository() {
+        let origin = TempDir::new().unwrap();
+        fs::write(origin.path().join("Service.md"), "# Service\n").unwrap();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .current_dir(origin.path())
+                .output()
+                .unwrap()
+        };
+        git(&["init", "--quiet"]);
+        git(&["add", "."]);
+        git(&["-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "--quiet", "-m", "init"]);
+
+        let url = format!("file://{}", origin.path().display());
+        let checkout = RemoteSource::parse(&url, None).unwrap().fetch().unwrap();
+
+        assert!(checkout.path().join("Service.md").exists());
+    }
+}
@@ -0,0 +1,595 @@
+use crate::analyzer::{ClassLinkCount, Isolation, LinkAnalyzer, LinkStatistics};
+use crate::cancel::Interruption;
+use crate::limits::LimitExceeded;
+use crate::churn::ChurnHotspot;
+use crate::config::OutputFormat;
+use crate::coupling::{self, CouplingReport, LinkWeights};
+use crate::diagnostics::AnalysisDiagnostics;
+use crate::exceptions::SuppressedFinding;
+use crate::external_nodes::ExternalNodeReport;
+use crate::external_types::ExternalCouplingReport;
+use crate::export::{self, ColorMetric, Diagram, DiagramLimits, ExportFilter};
+use crate::file_analyzer::AnalysisResult;
+use crate::federation::{self, CrossRepoReport, Repository};
+use crate::findings::{self, Finding, RuleOffenders};
+use crate::graph::ClassGraph;
+use crate::i18n::{Catalog, Locale, Message};
+use crate::impact;
+use crate::manifest::{self, ArchitectureManifest, Grouping};
+use crate::metrics::{self, Hotspot};
+use crate::ownership::OwnershipReport;
+use crate::provenance::Provenance;
+use crate::tags::{TagStatistics, TravelDistances};
+use crate::template::Template;
+use crate::project::{self, FileAnalysis};
+use crate::reporter::{self, ConsoleReporter, Hyperlinks, SarifReporter};
+use crate::stream;
+use crate::walker::SkippedFile;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub statistics: LinkStatistics,
+    pub findings: Vec<Finding>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suppressed: Vec<SuppressedFinding>,
+    pub skipped: Vec<SkippedFile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<AnalysisDiagnostics>,
+    // Set when the scan stopped early and the report is partial
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interrupted: Option<Interruption>,
+    // Set when a resource limit cut the scan short
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<LimitExceeded>,
+    pub coupling: CouplingReport,
+    // Links to framework types, when asked for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_coupling: Option<ExternalCouplingReport>,
+    // Classes the project uses but does not define, when asked for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_nodes: Option<ExternalNodeReport>,
+    // Links across repositories, set on merged reports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cross_repo: Option<CrossRepoReport>,
+    // Statistics per configured tag
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<TagStatistics>,
+    // Entry-to-data paths, when a layer order is configured
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub travel_distances: Option<TravelDistances>,
+    // Per-author and per-team statistics, when asked for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ownership: Option<OwnershipReport>,
+    // Inputs the report was computed from, set by pipeline runs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+    pub hotspots: Vec<Hotspot>,
+    // Commits times coupling, when churn is asked for
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub churn_hotspots: Vec<ChurnHotspot>,
+    // Findings per rule, file and package
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub offenders: Vec<RuleOffenders>,
+    // Documentation page of every defined class, when a URL template is set
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub doc_urls: BTreeMap<String, String>,
+    #[serde(skip)]
+    pub root: String,
+    #[serde(skip)]
+    pub files: Vec<FileAnalysis>,
+    #[serde(skip)]
+    pub locale: Locale,
+    // Clickable file locations in the text report
+    #[serde(skip)]
+    pub hyperlinks: Option<Hyperlinks>,
+    #[serde(skip)]
+    pub collapse_packages: bool,
+    #[serde(skip)]
+    pub grouping: Grouping,
+    #[serde(skip)]
+    pub export_filter: ExportFilter,
+    #[serde(skip)]
+    pub diagram_limits: Option<DiagramLimits>,
+    #[serde(skip)]
+    pub diagram_color: Option<ColorMetric>,
+    #[serde(skip)]
+    pub template: Option<Template>,
+}
+
+impl Report {
+    pub fn exit_code(&self) -> i32 {
+        findings::exit_code(&self.findings)
+    }
+
+    pub fn passed(&self) -> bool {
+        self.exit_code() == 0
+    }
+
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Text => self.render_console(self.hyperlinks.clone()),
+            OutputFormat::Json => to_pretty_json(&self.statistics),
+            OutputFormat::Manifest => {
+                let files = self.export_filter.apply(self.files.clone());
+                to_pretty_json(&ArchitectureManifest::build(&self.root, &files))
+            }
+            OutputFormat::NetworkX => {
+                let mut graph = export::to_node_link(&self.export_graph());
+                export::networkx::add_churn(&mut graph, &self.churn_hotspots);
+                export::networkx::add_custom_metrics(&mut graph, &self.statistics.custom_metrics);
+                to_pretty_json(&graph)
+            }
+            OutputFormat::Cypher => {
+                let files = self.export_filter.apply(self.files.clone());
+                export::to_cypher(&self.root, &files) + &export::cypher::churn_properties(&self.churn_hotspots)
+            }
+            OutputFormat::Dot => export::to_dot(&self.diagram()),
+            OutputFormat::Mermaid => export::to_mermaid(&self.diagram()),
+            OutputFormat::Sarif => {
+                let mut reporter = SarifReporter::new(Vec::new());
+                reporter::dispatch(self, &mut reporter).expect("in-memory report output");
+                String::from_utf8_lossy(&reporter.into_inner()).to_string()
+            }
+            OutputFormat::Ctags => export::to_ctags(&export::symbol_index(&self.root, &self.files)),
+            OutputFormat::Symbols => to_pretty_json(&export::symbol_index(&self.root, &self.files)),
+            // Without a template there is no layout to fill in; the text
+            // report stands in
+            OutputFormat::Template => match &self.template {
+                Some(template) => template.render(&self.template_data()),
+                None => self.render_console(None),
+            },
+            // The classes and links were streamed during the scan
+            OutputFormat::JsonLines => stream::summary(self),
+        }
+    }
+
+    // What templates see: the JSON report plus the tool name and the
+    // project root
+    pub fn template_data(&self) -> serde_json::Value {
+        let mut data = serde_json::to_value(self).unwrap_or_default();
+        data["generator"] = format!("classlink-checker {}", env!("CARGO_PKG_VERSION")).into();
+        data["root"] = self.root.clone().into();
+        data
+    }
+
+    // The filtered class graph, or package graph, the graph exports draw
+    fn export_graph(&self) -> AnalysisResult {
+        if self.collapse_packages {
+            let files = self.export_filter.apply_links(self.files.clone());
+            self.export_filter.retain_weight(export::collapse_to_packages(&self.root, &files, self.grouping))
+        } else {
+            project::merge(self.export_filter.apply(self.files.clone()))
+        }
+    }
+
+    fn diagram(&self) -> Diagram {
+        let graph = self.export_graph();
+        let diagram = Diagram::build(&graph, self.diagram_limits.as_ref());
+        match self.diagram_color {
+            Some(metric) => diagram.color_by(metric, &metric.values(&graph, &self.hotspots)),
+            None => diagram,
+        }
+    }
+
+    pub fn render_console(&self, hyperlinks: Option<Hyperlinks>) -> String {
+        let mut reporter = ConsoleReporter::new(Vec::new())
+            .with_locale(self.locale)
+            .with_hyperlinks(hyperlinks);
+        reporter::dispatch(self, &mut reporter).expect("in-memory report output");
+        String::from_utf8_lossy(&reporter.into_inner()).to_string()
+    }
+
+    // Prometheus textfile gauges over the whole graph; the export filter does
+    // not apply
+    pub fn render_metrics(&self) -> String {
+        let merged = project::merge(self.files.clone());
+        let cycles = ClassGraph::new(&merged.classes, &merged.links).find_cycles().len();
+        export::prometheus::to_textfile(&self.statistics, cycles, &self.findings, &self.coupling, &self.churn_hotspots)
+    }
+}
+
+impl Report {
+    // A report with the built-in rules and default link weights, for graphs
+    // that did not come from a pipeline run, e.g. loaded manifests
+    pub fn from_files(root: &str, files: Vec<FileAnalysis>, locale: Locale) -> Self {
+        let merged = project::merge(files.clone());
+        let isolation = findings::classify_isolated(
+            root,
+            &files,
+            &merged,
+            &findings::default_entry_points(),
+            &impact::default_test_patterns(),
+        );
+        let findings = findings::builtin(root, &files, &merged, &isolation, &Catalog::new(locale));
+        let coupling = coupling::weighted_coupling(root, &files, &LinkWeights::default(), Grouping::Directory);
+        let hotspots = metrics::hotspots(&coupling, &merged.class_metrics);
+        let offenders = findings::offenders(root, &files, &findings, Grouping::Directory);
+        Self {
+            statistics: LinkStatistics {
+                isolation,
+                ..LinkAnalyzer::new().analyze(merged.classes, merged.links)
+            },
+            findings,
+            suppressed: Vec::new(),
+            skipped: Vec::new(),
+            diagnostics: None,
+            interrupted: None,
+            truncated: None,
+            coupling,
+            external_coupling: None,
+            external_nodes: None,
+            cross_repo: None,
+            tags: None,
+            travel_distances: None,
+            ownership: None,
+            provenance: None,
+            hotspots,
+            churn_hotspots: Vec::new(),
+            offenders,
+            doc_urls: BTreeMap::new(),
+            root: root.to_string(),
+            files,
+            locale,
+            hyperlinks: None,
+            collapse_packages: false,
+            grouping: Grouping::Directory,
+            export_filter: ExportFilter::default(),
+            diagram_limits: Some(DiagramLimits::default()),
+            diagram_color: None,
+            template: None,
+        }
+    }
+
+    // Federates reports of separately analyzed repositories into one graph.
+    // Each report's files move under a directory named after its root, so
+    // `billing/src/app.py` and `shop/src/app.py` stay apart, and references
+    // are stitched across repositories (see `federation::stitch`).
+    // Statistics, built-in findings, coupling and hotspots are recomputed
+    // over the combined graph. Skipped files and doc URLs are carried over.
+    pub fn merge(reports: Vec<Report>) -> Report {
+        let locale = reports.first().map(|report| report.locale).unwrap_or_default();
+        let mut labels: BTreeSet<String> = BTreeSet::new();
+        let mut repositories = Vec::new();
+        let mut skipped = Vec::new();
+        let mut doc_urls = BTreeMap::new();
+        let mut interrupted = None;
+        for (index, report) in reports.into_iter().enumerate() {
+            let base = Path::new(&report.root)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| format!("report{}", index + 1));
+            let mut label = base.clone();
+            for n in 2.. {
+                if labels.insert(label.clone()) {
+                    break;
+                }
+                label = format!("{}-{}", base, n);
+            }
+            let moved = |path: &str| format!("{}/{}", label, manifest::relative_path(&report.root, path));
+            let files = report.files.into_iter().map(|file| FileAnalysis {
+                path: moved(&file.path),
+                ..file
+            });
+            let files = files.collect();
+            skipped.extend(report.skipped.into_iter().map(|file| SkippedFile {
+                path: moved(&file.path),
+                ..file
+            }));
+            repositories.push(Repository { name: label, files });
+            doc_urls.extend(report.doc_urls);
+            interrupted = interrupted.or(report.interrupted);
+        }
+
+        let (files, cross_repo) = federation::stitch(repositories);
+        Report {
+            skipped,
+            doc_urls,
+            interrupted,
+            cross_repo: Some(cross_repo),
+            ..Report::from_files("", files, locale)
+        }
+    }
+}
+
+// `{class}` in the template is replaced by the class name, e.g.
+// `docs/{class}.md` or `https://wiki.example.com/arch/{class}`
+pub fn class_doc_urls<S: AsRef<str>>(template: &str, classes: impl Iterator<Item = S>) -> BTreeMap<String, String> {
+    classes
+        .map(|class| {
+            let class = class.as_ref();
+            (class.to_string(), template.replace("{class}", class))
+        })
+        .collect()
+}
+
+fn to_pretty_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(value)
+        .unwrap_or_else(|e| format!("Error serializing to JSON: {}", e))
+}
+
+pub fn render_text(stats: &LinkStatistics, catalog: &Catalog) -> String {
+    let mut out = String::new();
+    write_header(&mut out, catalog);
+    write_overall_statistics(&mut out, stats, catalog);
+    write_isolated_classes(&mut out, stats, catalog);
+    write_class_link_counts(&mut out, stats, catalog);
+    write_most_significant_classes(&mut out, stats, catalog);
+    write_abstraction_coupling(&mut out, stats, catalog);
+    write_custom_metrics(&mut out, stats, catalog);
+    out
+}
+
+const COUPLING_TOP: usize = 10;
+
+pub fn render_coupling(coupling: &CouplingReport, catalog: &Catalog) -> String {
+    let mut out = String::new();
+    if coupling.classes.is_empty() {
+        return out;
+    }
+
+    writeln!(out, "\n{}", catalog.text(Message::CouplingHeader, &[&COUPLING_TOP])).unwrap();
+    for score in coupling.classes.iter().take(COUPLING_TOP) {
+        let line = catalog.text(
+            Message::CouplingLine,
+            &[
+                &score.name,
+                &format!("{:.1}", score.total),
+                &format!("{:.1}", score.afferent),
+                &format!("{:.1}", score.efferent),
+            ],
+        );
+        writeln!(out, "{}", line).unwrap();
+    }
+    out
+}
+
+pub fn render_external_coupling(external: &ExternalCouplingReport, catalog: &Catalog) -> String {
+    let mut out = String::new();
+    let sections = [
+        (Message::ExternalCouplingHeader, &external.classes),
+        (Message::ExternalPackagesHeader, &external.packages),
+    ];
+    for (header, scores) in sections {
+        if scores.is_empty() {
+            continue;
+        }
+        writeln!(out, "\n{}", catalog.text(header, &[&COUPLING_TOP])).unwrap();
+        for score in scores.iter().take(COUPLING_TOP) {
+            let frameworks: Vec<String> = score
+                .frameworks
+                .iter()
+                .map(|(framework, count)| format!("{} {}", framework, count))
+                .collect();
+            let line = catalog.text(Message::ExternalCouplingLine, &[&score.name, &score.total, &frameworks.join(", ")]);
+            writeln!(out, "{}", line).unwrap();
+        }
+    }
+    out
+}
+
+const HOTSPOTS_TOP: usize = 5;
+
+pub fn render_hotspots(hotspots: &[Hotspot], catalog: &Catalog) -> String {
+    let mut out = String::new();
+    if hotspots.is_empty() {
+        return out;
+    }
+
+    writeln!(out, "\n{}", catalog.text(Message::HotspotsHeader, &[&HOTSPOTS_TOP])).unwrap();
+    for hotspot in hotspots.iter().take(HOTSPOTS_TOP) {
+        let line = catalog.text(
+            Message::HotspotLine,
+            &[
+                &hotspot.class,
+                &hotspot.lines,
+                &hotspot.methods,
+                &format!("{:.1}", hotspot.coupling),
+            ],
+        );
+        writeln!(out, "{}", line).unwrap();
+    }
+    out
+}
+
+fn write_header(out: &mut String, catalog: &Catalog) {
+    writeln!(out, "{}\n", catalog.text(Message::ReportTitle, &[])).unwrap();
+}
+
+fn write_overall_statistics(out: &mut String, stats: &LinkStatistics, catalog: &Catalog) {
+    writeln!(out, "{}", catalog.text(Message::OverallStatistics, &[])).unwrap();
+    writeln!(out, "{}", catalog.text(Message::TotalClasses, &[&stats.total_classes])).unwrap();
+    writeln!(
+        out,
+        "{}",
+        catalog.text(Message::IsolatedClassCount, &[&stats.isolated_classes.len()])
+    )
+    .unwrap();
+    writeln!(out).unwrap();
+}
+
+fn write_isolated_classes(out: &mut String, stats: &LinkStatistics, catalog: &Catalog) {
+    // Unclassified classes count as dead
+    let of_kind = |kind: Isolation| -> Vec<&String> {
+        let kind_of = |class: &String| stats.isolation.get(class).copied().unwrap_or(Isolation::Dead);
+        stats.isolated_classes.iter().filter(|class| kind_of(class) == kind).collect()
+    };
+    let sections = [
+        (Message::IsolatedClassesHeader, Isolation::Dead),
+        (Message::IsolatedEntryPointsHeader, Isolation::EntryPoint),
+        (Message::IsolatedTestHelpersHeader, Isolation::TestHelper),
+        (Message::IsolatedExportsHeader, Isolation::Exported),
+    ];
+    for (header, kind) in sections {
+        let classes = of_kind(kind);
+        if classes.is_empty() {
+            continue;
+        }
+        writeln!(out, "{}", catalog.text(header, &[])).unwrap();
+        for class in classes {
+            writeln!(out, "  - {}", class).unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+}
+
+fn write_class_link_counts(out: &mut String, stats: &LinkStatistics, catalog: &Catalog) {
+    writeln!(out, "{}", catalog.text(Message::ClassLinkCounts, &[])).unwrap();
+    let sorted_classes = get_sorted_classes_by_total_links(stats);
+
+    for (class, counts) in sorted_classes {
+        if has_any_links(counts) {
+            let line = catalog.text(
+                Message::ClassLinkCountLine,
+                &[class, &counts.outgoing_links, &counts.incoming_links],
+            );
+            writeln!(out, "{}", line).unwrap();
+        }
+    }
+}
+
+fn get_sorted_classes_by_total_links(stats: &LinkStatistics) -> Vec<(&String, &ClassLinkCount)> {
+    let mut sorted_classes: Vec<_> = stats.class_link_counts.iter().collect();
+    sorted_classes.sort_by(|a, b| {
+        (b.1.outgoing_links + b.1.incoming_links).cmp(&(a.1.outgoing_links + a.1.incoming_links))
+    });
+    sorted_classes
+}
+
+fn has_any_links(counts: &ClassLinkCount) -> bool {
+    counts.outgoing_links > 0 || counts.incoming_links > 0
+}
+
+fn write_most_significant_classes(out: &mut String, stats: &LinkStatistics, catalog: &Catalog) {
+    if let Some((class, count)) = &stats.most_linking_class {
+        writeln!(out, "\n{}", catalog.text(Message::MostLinkingClass, &[class, count])).unwrap();
+    }
+
+    if let Some((class, count)) = &stats.most_linked_class {
+        writeln!(out, "{}", catalog.text(Message::MostLinkedClass, &[class, count])).unwrap();
+    }
+}
+
+const CUSTOM_METRIC_TOP: usize = 5;
+
+fn write_custom_metrics(out: &mut String, stats: &LinkStatistics, catalog: &Catalog) {
+    for (name, values) in stats.custom_metrics.iter().filter(|(_, values)| !values.is_empty()) {
+        writeln!(out, "\n{}", catalog.text(Message::CustomMetricHeader, &[name, &CUSTOM_METRIC_TOP])).unwrap();
+        let mut classes: Vec<_> = values.iter().collect();
+        classes.sort_by(|a, b| b.1.total_cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        for (class, value) in classes.into_iter().take(CUSTOM_METRIC_TOP) {
+            let line = catalog.text(Message::CustomMetricLine, &[class, &format!("{:.2}", value)]);
+            writeln!(out, "{}", line).unwrap();
+        }
+    }
+}
+
+// Classes depending mostly on concrete classes come first
+fn write_abstraction_coupling(out: &mut String, stats: &LinkStatistics, catalog: &Catalog) {
+    let Some(coupling) = stats.abstraction_coupling.as_ref().filter(|coupling| !coupling.is_empty()) else {
+        return;
+    };
+    writeln!(out, "\n{}", catalog.text(Message::AbstractionHeader, &[])).unwrap();
+    let mut classes: Vec<_> = coupling.iter().collect();
+    classes.sort_by(|a, b| a.1.ratio.total_cmp(&b.1.ratio));
+    for (class, counts) in classes {
+        let percent = format!("{:.0}", counts.ratio * 100.0);
+        let line = catalog.text(
+            Message::AbstractionLine,
+            &[class, &counts.abstract_links, &counts.concrete_links, &percent],
+        );
+        writeln!(out, "{}", line).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_analyzer::AnalysisResult;
+    use crate::parser::ClassLink;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_render_text_in_japanese() {
+        let classes: HashSet<String> = ["Lonely"].iter().map(|s| s.to_string()).collect();
+        let stats = LinkAnalyzer::new().analyze(classes, vec![]);
+
+        let text = render_text(&stats, &Catalog::new(Locale::Ja));
+
+        assert!(text.starts_with("=== クラスリンク分析レポート ==="));
+        assert!(text.contains("  クラス総数: 1"));
+        assert!(text.contains("🏝️  孤立クラス（リンクなし）:\n  - Lonely"));
+    }
+
+    #[test]
+    fn test_merge_federates_reports() {
+        let file = |path: &str, class: &str, uses: Option<&str>| FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: [class.to_string()].into_iter().collect(),
+                links: uses
+                    .map(|to| ClassLink {
+                        from_class: class.to_string(),
+                        to_class: to.to_string(),
+                        line_number: 1,
+                        kind: Default::default(),
+                        position: None,
+                    })
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        };
+        let billing = Report::from_files("/ci/billing", vec![file("/ci/billing/src/app.py", "Invoice", None)], Locale::En);
+        let shop = Report::from_files("/ci/shop", vec![file("/ci/shop/src/app.py", "Cart", Some("Invoice"))], Locale::En);
+        assert_eq!(billing.statistics.isolated_classes, vec!["Invoice"]);
+
+        let merged = Report::merge(vec![billing, shop.clone(), shop]);
+        assert_eq!(merged.cross_repo.as_ref().unwrap().boundaries.len(), 2);
+
+        let paths: Vec<&str> = merged.files.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, vec!["billing/src/app.py", "shop/src/app.py", "shop-2/src/app.py"]);
+        assert_eq!(merged.statistics.total_classes, 3);
+        assert!(merged.statistics.class_link_counts.contains_key("shop-2::Cart"));
+        assert!(merged.statistics.isolated_classes.is_empty());
+        assert_eq!(merged.statistics.class_link_counts["Invoice"].incoming_links, 2);
+        assert!(merged.findings.iter().all(|finding| finding.rule != "isolated-class"));
+    }
+
+    #[test]
+    fn test_render_coupling_lists_top_classes() {
+        let coupling = CouplingReport {
+            classes: vec![crate::coupling::CouplingScore {
+                name: "User".to_string(),
+                afferent: 3.0,
+                efferent: 2.1,
+                total: 5.1,
+            }],
+            packages: vec![],
+        };
+
+        let text = render_coupling(&coupling, &Catalog::default());
+
+        assert!(text.contains("  User: 5.1 (in 3.0, out 2.1)"));
+        assert!(render_coupling(&CouplingReport::default(), &Catalog::default()).is_empty());
+    }
+
+    #[test]
+    fn test_render_hotspots() {
+        let hotspots = vec![Hotspot {
+            class: "GodClass".to_string(),
+            lines: 400,
+            methods: 40,
+            coupling: 4.0,
+            score: 1600.0,
+        }];
+
+        let text = render_hotspots(&hotspots, &Catalog::default());
+
+        assert!(text.contains("  GodClass: 400 lines, 40 methods, coupling 4.0"));
+    }
+}
@@ -0,0 +1,602 @@
+use crate::cancel::Interruption;
+use crate::churn::{render_churn_hotspots, ChurnHotspot};
+use crate::exceptions::SuppressedFinding;
+use crate::federation::render_cross_repo;
+use crate::findings::{render_offenders, Finding, Severity};
+use crate::i18n::{Catalog, Locale, Message};
+use crate::manifest::relative_path;
+use crate::external_nodes::render_external_nodes;
+use crate::report::{render_coupling, render_external_coupling, render_hotspots, render_text, Report};
+use crate::ownership::render_ownership;
+use crate::tags::{render_tags, render_travel};
+use crate::walker::SkipReason;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io::{self, Write};
+
+// Output sinks for a pipeline run. Findings are streamed first, then the
+// summary, then `finish` flushes whatever the reporter buffered.
+pub trait Reporter {
+    fn on_finding(&mut self, _finding: &Finding) {}
+
+    fn on_summary(&mut self, _report: &Report) {}
+
+    fn finish(&mut self) -> io::Result<()>;
+}
+
+pub fn dispatch(report: &Report, reporter: &mut dyn Reporter) -> io::Result<()> {
+    for finding in &report.findings {
+        reporter.on_finding(finding);
+    }
+    reporter.on_summary(report);
+    reporter.finish()
+}
+
+pub const DEFAULT_EDITOR_URL: &str = "file://{path}";
+
+// OSC 8 escapes that make a file location clickable in terminals that
+// support them, e.g. `vscode://file/{path}:{line}`. `{path}` is the absolute
+// path and `{line}` the line number, 1 when unknown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hyperlinks {
+    url_template: String,
+}
+
+impl Default for Hyperlinks {
+    fn default() -> Self {
+        Self::new(DEFAULT_EDITOR_URL)
+    }
+}
+
+impl Hyperlinks {
+    pub fn new(url_template: &str) -> Self {
+        Self {
+            url_template: url_template.to_string(),
+        }
+    }
+
+    pub fn url(&self, path: &str, line: Option<usize>) -> String {
+        let absolute = fs::canonicalize(path).map_or_else(|_| path.to_string(), |p| p.to_string_lossy().to_string());
+        self.url_template
+            .replace("{path}", &absolute.replace(' ', "%20"))
+            .replace("{line}", &line.unwrap_or(1).to_string())
+    }
+
+    pub fn link(&self, text: &str, path: &str, line: Option<usize>) -> String {
+        format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", self.url(path, line), text)
+    }
+}
+
+pub struct ConsoleReporter<W: Write> {
+    out: W,
+    catalog: Catalog,
+    hyperlinks: Option<Hyperlinks>,
+    summary: String,
+    // Each finding line with the class whose definition it points at
+    findings: Vec<(String, Option<String>)>,
+    offenders: String,
+    // Where each class is defined: path and declaration line
+    locations: HashMap<String, (String, Option<usize>)>,
+    root: String,
+    suppressed: Vec<String>,
+    skipped: Vec<String>,
+    interrupted: Option<String>,
+    truncated: Option<String>,
+}
+
+impl<W: Write> ConsoleReporter<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            catalog: Catalog::default(),
+            hyperlinks: None,
+            summary: String::new(),
+            findings: Vec::new(),
+            offenders: String::new(),
+            locations: HashMap::new(),
+            root: String::new(),
+            suppressed: Vec::new(),
+            skipped: Vec::new(),
+            interrupted: None,
+            truncated: None,
+        }
+    }
+
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.catalog = Catalog::new(locale);
+        self
+    }
+
+    pub fn with_hyperlinks(mut self, hyperlinks: Option<Hyperlinks>) -> Self {
+        self.hyperlinks = hyperlinks;
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    // `path:line` relative to the scanned root, clickable when enabled
+    fn location(&self, path: &str, line: Option<usize>) -> String {
+        let mut text = relative_path(&self.root, path);
+        if let Some(line) = line {
+            text = format!("{}:{}", text, line);
+        }
+        match &self.hyperlinks {
+            Some(hyperlinks) => hyperlinks.link(&text, path, line),
+            None => text,
+        }
+    }
+}
+
+impl<W: Write> Reporter for ConsoleReporter<W> {
+    fn on_finding(&mut self, finding: &Finding) {
+        let marker = match finding.severity {
+            Severity::Error => "❌",
+            Severity::Warning => "⚠️ ",
+            Severity::Info => "ℹ️ ",
+        };
+        self.findings.push((
+            format!("  {} [{}] {}", marker, finding.rule, finding.message),
+            finding.classes.first().cloned(),
+        ));
+    }
+
+    fn on_summary(&mut self, report: &Report) {
+        self.root = report.root.clone();
+        // Code definitions, which carry a line, win over documentation pages
+        for file in &report.files {
+            for class in &file.result.classes {
+                let line = file.result.class_metrics.get(class).map(|metrics| metrics.start_line);
+                let known = self.locations.get(class).is_some_and(|(_, known)| known.is_some());
+                if !known {
+                    self.locations.insert(class.clone(), (file.path.clone(), line));
+                }
+            }
+        }
+        self.summary = render_text(&report.statistics, &self.catalog);
+        self.summary.push_str(&render_coupling(&report.coupling, &self.catalog));
+        if let Some(external) = &report.external_coupling {
+            self.summary.push_str(&render_external_coupling(external, &self.catalog));
+        }
+        if let Some(external) = &report.external_nodes {
+            self.summary.push_str(&render_external_nodes(external, &self.catalog));
+        }
+        if let Some(tags) = &report.tags {
+            self.summary.push_str(&render_tags(tags, &self.catalog));
+        }
+        if let Some(distances) = &report.travel_distances {
+            self.summary.push_str(&render_travel(distances, &self.catalog));
+        }
+        if let Some(ownership) = &report.ownership {
+            self.summary.push_str(&render_ownership(ownership, &self.catalog));
+        }
+        if let Some(cross_repo) = &report.cross_repo {
+            self.summary.push_str(&render_cross_repo(cross_repo, &self.catalog));
+        }
+        self.summary.push_str(&render_hotspots(&report.hotspots, &self.catalog));
+        self.summary.push_str(&render_churn_hotspots(&report.churn_hotspots, &self.catalog));
+        self.offenders = render_offenders(&report.offenders, &self.catalog);
+        self.suppressed = report
+            .suppressed
+            .iter()
+            .map(|suppressed| {
+                let finding = &suppressed.finding;
+                self.catalog
+                    .text(Message::SuppressedLine, &[&finding.rule, &finding.message, &suppressed.reason])
+            })
+            .collect();
+        self.skipped = report
+            .skipped
+            .iter()
+            .map(|file| {
+                let path = self.location(&file.path, None);
+                match file.reason {
+                    SkipReason::TooLarge { size, limit } => {
+                        self.catalog.text(Message::SkippedTooLarge, &[&path, &size, &limit])
+                    }
+                    SkipReason::Binary => self.catalog.text(Message::SkippedBinary, &[&path]),
+                    SkipReason::Vendored => self.catalog.text(Message::SkippedVendored, &[&path]),
+                    SkipReason::Minified => self.catalog.text(Message::SkippedMinified, &[&path]),
+                }
+            })
+            .collect();
+        self.interrupted = report.interrupted.map(|interruption| match interruption {
+            Interruption::Cancelled => self.catalog.text(Message::ScanCancelled, &[]),
+            Interruption::TimedOut => self.catalog.text(Message::ScanTimedOut, &[]),
+        });
+        self.truncated = report
+            .truncated
+            .map(|hit| self.catalog.text(Message::ScanTruncated, &[&hit.limit, &hit.max]));
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        write!(self.out, "{}", self.summary)?;
+        if !self.findings.is_empty() {
+            writeln!(self.out, "\n{}", self.catalog.text(Message::FindingsHeader, &[]))?;
+            for (line, class) in &self.findings {
+                match class.as_ref().and_then(|class| self.locations.get(class)) {
+                    Some((path, at)) => writeln!(self.out, "{} ({})", line, self.location(path, *at))?,
+                    None => writeln!(self.out, "{}", line)?,
+                }
+            }
+            write!(self.out, "{}", self.offenders)?;
+        }
+        if !self.suppressed.is_empty() {
+            writeln!(self.out, "\n{}", self.catalog.text(Message::SuppressedHeader, &[]))?;
+            for line in &self.suppressed {
+                writeln!(self.out, "{}", line)?;
+            }
+        }
+        if !self.skipped.is_empty() {
+            writeln!(self.out, "\n{}", self.catalog.text(Message::SkippedFilesHeader, &[]))?;
+            for line in &self.skipped {
+                writeln!(self.out, "{}", line)?;
+            }
+        }
+        for line in self.interrupted.iter().chain(&self.truncated) {
+            writeln!(self.out, "\n{}", line)?;
+        }
+        self.out.flush()
+    }
+}
+
+pub struct JsonReporter<W: Write> {
+    out: W,
+    document: Value,
+}
+
+impl<W: Write> JsonReporter<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            document: Value::Null,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> Reporter for JsonReporter<W> {
+    fn on_summary(&mut self, report: &Report) {
+        self.document = serde_json::to_value(report).unwrap_or(Value::Null);
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        writeln!(self.out, "{}", self.document)?;
+        self.out.flush()
+    }
+}
+
+// SARIF 2.1.0, understood by GitHub code scanning and most CI dashboards
+pub struct SarifReporter<W: Write> {
+    out: W,
+    rules: BTreeSet<String>,
+    findings: Vec<Finding>,
+    suppressed: Vec<SuppressedFinding>,
+    doc_urls: BTreeMap<String, String>,
+    churn_hotspots: Vec<ChurnHotspot>,
+    custom_metrics: BTreeMap<String, BTreeMap<String, f64>>,
+}
+
+impl<W: Write> SarifReporter<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            rules: BTreeSet::new(),
+            findings: Vec::new(),
+            suppressed: Vec::new(),
+            doc_urls: BTreeMap::new(),
+            churn_hotspots: Vec::new(),
+            custom_metrics: BTreeMap::new(),
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+pub fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "note",
+    }
+}
+
+// Whole-word class names in `text` become Markdown links to their docs
+pub fn link_classes(text: &str, classes: &[String], doc_urls: &BTreeMap<String, String>) -> String {
+    let mut names: Vec<&String> = classes.iter().filter(|c| doc_urls.contains_key(*c)).collect();
+    if names.is_empty() {
+        return text.to_string();
+    }
+    // Longest first so `UserService` wins over `User`
+    names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+    let alternatives: Vec<String> = names.iter().map(|name| regex::escape(name)).collect();
+    let pattern = Regex::new(&format!(r"\b(?:{})\b", alternatives.join("|"))).unwrap();
+    pattern
+        .replace_all(text, |caps: &regex::Captures| {
+            format!("[{}]({})", &caps[0], doc_urls[&caps[0]])
+        })
+        .to_string()
+}
+
+impl<W: Write> SarifReporter<W> {
+    fn result(&self, finding: &Finding) -> Value {
+        let mut result = json!({
+            "ruleId": finding.rule,
+            "level": sarif_level(finding.severity),
+            "message": { "text": finding.message },
+            "properties": { "classes": finding.classes },
+        });
+        let docs: BTreeMap<&String, &String> = finding
+            .classes
+            .iter()
+            .filter_map(|class| Some((class, self.doc_urls.get(class)?)))
+            .collect();
+        if !docs.is_empty() {
+            result["message"]["markdown"] = json!(link_classes(&finding.message, &finding.classes, &self.doc_urls));
+            result["properties"]["documentation"] = json!(docs);
+        }
+        result
+    }
+}
+
+impl<W: Write> Reporter for SarifReporter<W> {
+    fn on_finding(&mut self, finding: &Finding) {
+        self.rules.insert(finding.rule.clone());
+        self.findings.push(finding.clone());
+    }
+
+    fn on_summary(&mut self, report: &Report) {
+        self.doc_urls = report.doc_urls.clone();
+        self.suppressed = report.suppressed.clone();
+        self.churn_hotspots = report.churn_hotspots.clone();
+        self.custom_metrics = report.statistics.custom_metrics.clone();
+        for suppressed in &self.suppressed {
+            self.rules.insert(suppressed.finding.rule.clone());
+        }
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        let mut results: Vec<Value> = self.findings.iter().map(|finding| self.result(finding)).collect();
+        // Suppressed findings stay visible to SARIF viewers, marked as such
+        for suppressed in &self.suppressed {
+            let mut result = self.result(&suppressed.finding);
+            result["suppressions"] = json!([{ "kind": "external", "justification": suppressed.reason }]);
+            results.push(result);
+        }
+        let rules: Vec<Value> = self.rules.iter().map(|id| json!({ "id": id })).collect();
+        let mut document = json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "classlink-checker",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": "https://github.com/herring101/classlink-checker",
+                        "rules": rules,
+                    },
+                },
+                "results": results,
+            }],
+        });
+        // Rankings and metrics are not results; they ride along in the run's
+        // property bag
+        if !self.churn_hotspots.is_empty() {
+            document["runs"][0]["properties"]["churnHotspots"] = json!(self.churn_hotspots);
+        }
+        if !self.custom_metrics.is_empty() {
+            document["runs"][0]["properties"]["customMetrics"] = json!(self.custom_metrics);
+        }
+        let json = serde_json::to_string_pretty(&document).map_err(io::Error::other)?;
+        writeln!(self.out, "{}", json)?;
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::LinkAnalyzer;
+    use crate::report::class_doc_urls;
+    use crate::walker::SkippedFile;
+    use std::collections::HashSet;
+
+    fn sample_report() -> Report {
+        let classes: HashSet<String> = ["A"].iter().map(|s| s.to_string()).collect();
+        Report {
+            statistics: LinkAnalyzer::new().analyze(classes, vec![]),
+            findings: vec![Finding {
+                rule: "isolated-class".to_string(),
+                severity: Severity::Warning,
+                message: "A has no incoming or outgoing links".to_string(),
+                classes: vec!["A".to_string()],
+            }],
+            suppressed: vec![],
+            skipped: vec![SkippedFile {
+                path: "dist/bundle.ts".to_string(),
+                reason: SkipReason::TooLarge {
+                    size: 2048,
+                    limit: 1024,
+                },
+            }],
+            diagnostics: None,
+            interrupted: None,
+            truncated: None,
+            external_coupling: None,
+            external_nodes: None,
+            cross_repo: None,
+            tags: None,
+            travel_distances: None,
+            ownership: None,
+            provenance: None,
+            coupling: Default::default(),
+            hotspots: vec![],
+            churn_hotspots: vec![],
+            offenders: vec![],
+            collapse_packages: false,
+            grouping: Default::default(),
+            export_filter: Default::default(),
+            diagram_limits: None,
+            diagram_color: None,
+            template: None,
+            doc_urls: BTreeMap::new(),
+            root: ".".to_string(),
+            files: vec![],
+            locale: Locale::En,
+            hyperlinks: None,
+        }
+    }
+
+    fn render(reporter: &mut dyn Reporter) {
+        dispatch(&sample_report(), reporter).unwrap();
+    }
+
+    #[test]
+    fn test_console_reporter_lists_findings_after_summary() {
+        let mut reporter = ConsoleReporter::new(Vec::new());
+        render(&mut reporter);
+        let output = String::from_utf8(reporter.into_inner()).unwrap();
+
+        let summary = output.find("Overall Statistics").unwrap();
+        let finding = output.find("[isolated-class] A has no incoming").unwrap();
+        let skipped = output
+            .find("dist/bundle.ts (2048 bytes, over the 1024 byte limit)")
+            .unwrap();
+        assert!(summary < finding && finding < skipped);
+    }
+
+    #[test]
+    fn test_console_reporter_hyperlinks_locations() {
+        let mut report = sample_report();
+        report.root = "/repo".to_string();
+        let mut result = crate::file_analyzer::AnalysisResult::default();
+        result.classes.insert("A".to_string());
+        result.class_metrics.insert(
+            "A".to_string(),
+            crate::metrics::ClassMetrics {
+                start_line: 3,
+                ..Default::default()
+            },
+        );
+        report.files = vec![crate::project::FileAnalysis {
+            path: "/repo/src/a.py".to_string(),
+            result,
+            encoding: Default::default(),
+        }];
+
+        let plain = report.render_console(None);
+        assert!(plain.contains("[isolated-class] A has no incoming or outgoing links (src/a.py:3)"));
+
+        let linked = report.render_console(Some(Hyperlinks::new("vscode://file/{path}:{line}")));
+        assert!(linked.contains("(\x1b]8;;vscode://file//repo/src/a.py:3\x1b\\src/a.py:3\x1b]8;;\x1b\\)"));
+    }
+
+    #[test]
+    fn test_json_reporter_writes_report() {
+        let mut reporter = JsonReporter::new(Vec::new());
+        render(&mut reporter);
+        let output: Value = serde_json::from_slice(&reporter.into_inner()).unwrap();
+
+        assert_eq!(output["statistics"]["total_classes"], 1);
+        assert_eq!(output["findings"][0]["rule"], "isolated-class");
+    }
+
+    #[test]
+    fn test_sarif_reporter_maps_findings_to_results() {
+        let mut reporter = SarifReporter::new(Vec::new());
+        render(&mut reporter);
+        let output: Value = serde_json::from_slice(&reporter.into_inner()).unwrap();
+
+        assert_eq!(output["version"], "2.1.0");
+        let run = &output["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "isolated-class");
+        assert_eq!(run["results"][0]["level"], "warning");
+        assert_eq!(run["results"][0]["ruleId"], "isolated-class");
+        assert!(run["results"][0]["message"].get("markdown").is_none());
+    }
+
+    #[test]
+    fn test_suppressed_findings_are_listed_and_marked() {
+        let mut report = sample_report();
+        let finding = report.findings.remove(0);
+        report.suppressed = vec![SuppressedFinding {
+            finding,
+            reason: "kept for the plugin API".to_string(),
+        }];
+
+        let mut console = ConsoleReporter::new(Vec::new());
+        dispatch(&report, &mut console).unwrap();
+        let text = String::from_utf8(console.into_inner()).unwrap();
+        assert!(text.contains("  [isolated-class] A has no incoming or outgoing links (kept for the plugin API)"));
+        assert!(!text.contains("Findings:"));
+
+        let mut sarif = SarifReporter::new(Vec::new());
+        dispatch(&report, &mut sarif).unwrap();
+        let output: Value = serde_json::from_slice(&sarif.into_inner()).unwrap();
+        let result = &output["runs"][0]["results"][0];
+        assert_eq!(result["suppressions"][0]["justification"], "kept for the plugin API");
+    }
+
+    #[test]
+    fn test_sarif_reporter_links_class_docs() {
+        let mut report = sample_report();
+        report.doc_urls = class_doc_urls("https://wiki/{class}", ["A".to_string()].iter());
+        let mut reporter = SarifReporter::new(Vec::new());
+        dispatch(&report, &mut reporter).unwrap();
+        let output: Value = serde_json::from_slice(&reporter.into_inner()).unwrap();
+
+        let result = &output["runs"][0]["results"][0];
+        assert_eq!(result["message"]["markdown"], "[A](https://wiki/A) has no incoming or outgoing links");
+        assert_eq!(result["properties"]["documentation"]["A"], "https://wiki/A");
+    }
+
+    #[test]
+    fn test_link_classes_matches_whole_names() {
+        let urls = class_doc_urls("docs/{class}.md", ["User", "UserService"].iter());
+        let classes = vec!["User".to_string(), "UserService".to_string()];
+
+        let text = link_classes("UserService -> User -> Users", &classes, &urls);
+
+        assert_eq!(text, "[UserService](docs/UserService.md) -> [User](docs/User.md) -> Users");
+    }
+
+    #[test]
+    fn test_custom_reporter_receives_every_callback() {
+        #[derive(Default)]
+        struct Counting {
+            findings: usize,
+            summaries: usize,
+            finished: bool,
+        }
+
+        impl Reporter for Counting {
+            fn on_finding(&mut self, _finding: &Finding) {
+                self.findings += 1;
+            }
+
+            fn on_summary(&mut self, _report: &Report) {
+                self.summaries += 1;
+            }
+
+            fn finish(&mut self) -> io::Result<()> {
+                self.finished = true;
+                Ok(())
+            }
+        }
+
+        let mut reporter = Counting::default();
+        render(&mut reporter);
+
+        assert_eq!(reporter.findings, 1);
+        assert_eq!(reporter.summaries, 1);
+        assert!(reporter.finished);
+    }
+}
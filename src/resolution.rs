@@ -0,0 +1,78 @@
+use crate::file_analyzer::{AnalysisResult, FileType, UnifiedAnalyzer};
+use crate::parser::{ClassLink, LinkKind};
+use regex::Regex;
+use std::collections::HashSet;
+
+// Second pass of project-wide resolution. Parsers only see one file, so they
+// link what the file itself declares, imports or instantiates; with every
+// class of the project known, any mention of one inside a class body is a
+// usage too, like `isinstance(x, Order)`, `List[Order]` or
+// `Order.objects.get()`. Comments are left out. Only Python, TypeScript,
+// JavaScript, C#, Rust, Java and Go sources are resolved: Markdown links are explicit, and
+// notebook and template lines are not code.
+pub fn resolve_references(path: &str, content: &str, result: &AnalysisResult, symbols: &HashSet<String>) -> Vec<ClassLink> {
+    let comment = match UnifiedAnalyzer::detect_file_type(path) {
+        FileType::Python if !path.ends_with(".ipynb") => "#",
+        FileType::TypeScript | FileType::JavaScript | FileType::CSharp | FileType::Rust | FileType::Java | FileType::Go => {
+            "//"
+        }
+        _ => return Vec::new(),
+    };
+    let token_regex = Regex::new(r"\b[A-Za-z_]\w*\b").unwrap();
+    let linked: HashSet<(usize, &str, &str)> = result
+        .links
+        .iter()
+        .map(|link| (link.line_number, link.from_class.as_str(), link.to_class.as_str()))
+        .collect();
+
+    let mut links = Vec::new();
+    for (index, text) in content.lines().enumerate() {
+        let line = index + 1;
+        // The innermost class whose body holds the line
+        let Some(owner) = result
+            .class_metrics
+            .iter()
+            .filter(|(_, size)| (size.start_line..=size.end_line).contains(&line))
+            .max_by_key(|(_, size)| size.start_line)
+            .map(|(class, _)| class.as_str())
+        else {
+            continue;
+        };
+        let code = text.split(comment).next().unwrap_or_default();
+        let mut seen = HashSet::new();
+        for token in token_regex.find_iter(code).map(|m| m.as_str()) {
+            if token == owner || !symbols.contains(token) || linked.contains(&(line, owner, token)) || !seen.insert(token) {
+                continue;
+            }
+            links.push(ClassLink {
+                from_class: owner.to_string(),
+                to_class: token.to_string(),
+                line_number: line,
+                kind: LinkKind::Usage,
+                position: None,
+            });
+        }
+    }
+    links
+}
+
+#[cfg(all(test, feature = "python"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mentions_of_project_classes_become_usages() {
+        let content = "class Checkout:\n    def pay(self, order) -> bool:\n        if isinstance(order, Order):  # not a Refund\n            return Payment.charge(order, Currency)\n        return False\n\nhelper = Refund\n";
+        let result = UnifiedAnalyzer::new().analyze_file(content, "checkout.py");
+        let symbols: HashSet<String> = ["Checkout", "Order", "Payment", "Refund"].iter().map(|s| s.to_string()).collect();
+
+        let links = resolve_references("checkout.py", content, &result, &symbols);
+
+        let found: Vec<(&str, usize)> = links.iter().map(|link| (link.to_class.as_str(), link.line_number)).collect();
+        // `Currency` is not a project class, the comment and module-level
+        // lines are skipped
+        assert_eq!(found, vec![("Order", 3), ("Payment", 4)]);
+        assert!(links.iter().all(|link| link.from_class == "Checkout" && link.kind == LinkKind::Usage));
+        assert!(resolve_references("README.md", "# Order\n", &AnalysisResult::default(), &symbols).is_empty());
+    }
+}
@@ -0,0 +1,147 @@
+use crate::encoding::{self, DecodedText};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+
+pub const DEFAULT_READ_BUFFER_KB: usize = 64;
+
+// How a scan reads and parses its files. The defaults suit a local disk; on
+// a network mount, where every read waits on a round trip, larger reads and
+// prefetching keep the parsing threads busy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IoOptions {
+    // Files read and parsed at once; `None` for one per CPU core
+    pub threads: Option<usize>,
+    // Bytes asked for per read of a file, in KiB
+    pub read_buffer_kb: usize,
+    // Read every file before parsing any, so slow storage serves the reads
+    // in one burst; all sources are then held in memory at once
+    pub prefetch: bool,
+}
+
+impl Default for IoOptions {
+    fn default() -> Self {
+        Self {
+            threads: None,
+            read_buffer_kb: DEFAULT_READ_BUFFER_KB,
+            prefetch: false,
+        }
+    }
+}
+
+impl IoOptions {
+    pub fn thread_count(&self) -> usize {
+        self.threads
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, |threads| threads.get()))
+            .max(1)
+    }
+
+    // Like `encoding::read_source`, in reads of `read_buffer_kb`
+    pub fn read_source(&self, path: &str) -> io::Result<DecodedText> {
+        let mut file = File::open(path)?;
+        let size = file.metadata().map_or(0, |metadata| metadata.len() as usize);
+        let mut bytes = Vec::with_capacity(size);
+        let mut buffer = vec![0; self.read_buffer_kb.max(1) * 1024];
+        loop {
+            match file.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => bytes.extend_from_slice(&buffer[..read]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(encoding::decode(&bytes))
+    }
+}
+
+// Runs `work` on each item on up to `threads` threads and hands the results
+// to `consume` in item order, so the output does not depend on scheduling.
+// `consume` returning `Ok(false)` or an error ends the run; items no thread
+// has started by then are left alone.
+pub fn ordered_map<T, R, E>(
+    items: Vec<T>,
+    threads: usize,
+    work: impl Fn(T) -> R + Sync,
+    mut consume: impl FnMut(R) -> Result<bool, E>,
+) -> Result<(), E>
+where
+    T: Send,
+    R: Send,
+{
+    if threads <= 1 {
+        for item in items {
+            if !consume(work(item))? {
+                break;
+            }
+        }
+        return Ok(());
+    }
+    let queue = Mutex::new(items.into_iter().enumerate());
+    let stopped = AtomicBool::new(false);
+    thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..threads {
+            let (sender, queue, stopped, work) = (sender.clone(), &queue, &stopped, &work);
+            scope.spawn(move || {
+                while !stopped.load(Ordering::Relaxed) {
+                    let Some((index, item)) = queue.lock().ok().and_then(|mut queue| queue.next()) else {
+                        break;
+                    };
+                    if sender.send((index, work(item))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+        // Results that arrived ahead of an earlier item still being worked on
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        let mut run = || {
+            for (index, result) in receiver.iter() {
+                pending.insert(index, result);
+                while let Some(result) = pending.remove(&next) {
+                    next += 1;
+                    if !consume(result)? {
+                        return Ok(());
+                    }
+                }
+            }
+            Ok(())
+        };
+        let outcome = run();
+        stopped.store(true, Ordering::Relaxed);
+        outcome
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_ordered_map_keeps_item_order_and_stops_early() {
+        // Early items take longest, so they finish last
+        let work = |i: u64| {
+            thread::sleep(Duration::from_millis(20u64.saturating_sub(i * 2)));
+            i * 10
+        };
+        for threads in [1, 4] {
+            let mut seen = Vec::new();
+            let outcome: Result<(), ()> = ordered_map((0..10).collect(), threads, work, |result| {
+                seen.push(result);
+                Ok(result < 50)
+            });
+            assert_eq!(outcome, Ok(()));
+            assert_eq!(seen, vec![0, 10, 20, 30, 40, 50], "{} threads", threads);
+        }
+        let failed = ordered_map((0..10).collect(), 4, work, |result| if result == 30 { Err(result) } else { Ok(true) });
+        assert_eq!(failed, Err(30));
+    }
+}
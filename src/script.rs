@@ -0,0 +1,605 @@
+// Scripted rules over the class graph, one rule per line:
+//
+//     domain-to-web: for each link where from.layer == "domain" && to.layer == "web" -> error "{from.name} reaches into the web layer"
+//     for each class where lines > 500 && fan_in > 10 -> warning
+//
+// Expressions support `==`, `!=`, `<`, `<=`, `>`, `>=`, `matches` (glob),
+// `&&`, `||`, `!` and parentheses over strings, numbers and booleans.
+use crate::annotations::ClassTag;
+use crate::findings::{Finding, Severity};
+use crate::glob;
+use crate::i18n::{Catalog, Message};
+use crate::manifest::relative_path;
+use crate::project::{self, FileAnalysis};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+const CLASS_FIELDS: &[&str] = &[
+    "name", "package", "namespace", "layer", "file", "fan_in", "fan_out", "lines", "methods", "defined", "deprecated",
+    "internal",
+];
+const LINK_FIELDS: &[&str] = &["kind", "file", "line"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Str(s) => f.write_str(s),
+            Value::Num(n) => write!(f, "{}", n),
+            Value::Bool(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Link,
+    Class,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Literal(Value),
+    Field(String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(Box<Expr>, &'static str, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptRule {
+    pub id: String,
+    pub target: Target,
+    condition: Expr,
+    pub severity: Severity,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Op(&'static str),
+}
+
+const OPERATORS: &[&str] = &["==", "!=", "<=", ">=", "&&", "||", "->", "<", ">", "!", "(", ")", ":"];
+
+fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '"' {
+            let mut text = String::new();
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                if chars[i] == '\\' && i + 1 < chars.len() {
+                    i += 1;
+                }
+                text.push(chars[i]);
+                i += 1;
+            }
+            if i == chars.len() {
+                return Err("unterminated string".to_string());
+            }
+            tokens.push(Token::Str(text));
+            i += 1;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            tokens.push(Token::Num(text.parse().map_err(|_| format!("invalid number '{}'", text))?));
+        } else if c.is_alphanumeric() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.' || chars[i] == '-') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            let rest: String = chars[i..].iter().take(2).collect();
+            let op = OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(**op))
+                .ok_or_else(|| format!("unexpected character '{}'", c))?;
+            tokens.push(Token::Op(op));
+            i += op.len();
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    target: Target,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        if self.peek() == Some(&Token::Op(Self::op(op))) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn op(op: &str) -> &'static str {
+        OPERATORS.iter().find(|known| **known == op).copied().unwrap_or("")
+    }
+
+    fn expect_word(&mut self, word: &str) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Ident(ident)) if ident == word => Ok(()),
+            _ => Err(format!("expected '{}'", word)),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.eat_op("||") {
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.not()?;
+        while self.eat_op("&&") {
+            left = Expr::And(Box::new(left), Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        if self.eat_op("!") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let left = self.value()?;
+        let op = match self.peek() {
+            Some(Token::Op(op)) if ["==", "!=", "<", "<=", ">", ">="].contains(op) => *op,
+            Some(Token::Ident(word)) if word == "matches" => "matches",
+            _ => return Ok(left),
+        };
+        self.position += 1;
+        Ok(Expr::Compare(Box::new(left), op, Box::new(self.value()?)))
+    }
+
+    fn value(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Str(text)) => Ok(Expr::Literal(Value::Str(text))),
+            Some(Token::Num(number)) => Ok(Expr::Literal(Value::Num(number))),
+            Some(Token::Ident(word)) if word == "true" || word == "false" => {
+                Ok(Expr::Literal(Value::Bool(word == "true")))
+            }
+            Some(Token::Ident(field)) => {
+                if !is_field(self.target, &field) {
+                    return Err(format!("unknown field '{}'", field));
+                }
+                Ok(Expr::Field(field))
+            }
+            Some(Token::Op("(")) => {
+                let inner = self.or()?;
+                if !self.eat_op(")") {
+                    return Err("expected ')'".to_string());
+                }
+                Ok(inner)
+            }
+            Some(token) => Err(format!("unexpected {:?}", token)),
+            None => Err("unexpected end of rule".to_string()),
+        }
+    }
+}
+
+fn is_field(target: Target, field: &str) -> bool {
+    match target {
+        Target::Class => CLASS_FIELDS.contains(&field),
+        Target::Link => {
+            LINK_FIELDS.contains(&field)
+                || ["from.", "to."].iter().any(|side| {
+                    field
+                        .strip_prefix(side)
+                        .is_some_and(|rest| CLASS_FIELDS.contains(&rest))
+                })
+        }
+    }
+}
+
+fn parse_rule(line: &str, line_number: usize) -> Result<ScriptRule, String> {
+    let mut tokens = tokenize(line)?;
+    let id = match tokens.as_slice() {
+        [Token::Ident(id), Token::Op(":"), ..] => {
+            let id = id.clone();
+            tokens.drain(..2);
+            id
+        }
+        _ => format!("script-rule-{}", line_number),
+    };
+
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+        target: Target::Link,
+    };
+    parser.expect_word("for")?;
+    parser.expect_word("each")?;
+    parser.target = match parser.next() {
+        Some(Token::Ident(word)) if word == "link" => Target::Link,
+        Some(Token::Ident(word)) if word == "class" => Target::Class,
+        _ => return Err("expected 'link' or 'class'".to_string()),
+    };
+    parser.expect_word("where")?;
+    let condition = parser.or()?;
+    if !parser.eat_op("->") {
+        return Err("expected '->' and a severity".to_string());
+    }
+    let severity = match parser.next() {
+        Some(Token::Ident(word)) if word == "error" => Severity::Error,
+        Some(Token::Ident(word)) if word == "warning" => Severity::Warning,
+        Some(Token::Ident(word)) if word == "info" => Severity::Info,
+        _ => return Err("expected severity 'error', 'warning' or 'info'".to_string()),
+    };
+    let message = match parser.next() {
+        Some(Token::Str(message)) => Some(message),
+        None => None,
+        Some(_) => return Err("expected a message string".to_string()),
+    };
+    if parser.peek().is_some() {
+        return Err("unexpected text after the message".to_string());
+    }
+
+    Ok(ScriptRule {
+        id,
+        target: parser.target,
+        condition,
+        severity,
+        message,
+    })
+}
+
+// Blank lines and `#` comments are ignored
+pub fn parse(script: &str) -> Result<Vec<ScriptRule>, ScriptError> {
+    script
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line, text)| parse_rule(text, line).map_err(|message| ScriptError { line, message }))
+        .collect()
+}
+
+fn evaluate(expr: &Expr, lookup: &dyn Fn(&str) -> Value) -> Value {
+    match expr {
+        Expr::Literal(value) => value.clone(),
+        Expr::Field(field) => lookup(field),
+        Expr::Not(inner) => Value::Bool(!truthy(&evaluate(inner, lookup))),
+        Expr::And(left, right) => {
+            Value::Bool(truthy(&evaluate(left, lookup)) && truthy(&evaluate(right, lookup)))
+        }
+        Expr::Or(left, right) => {
+            Value::Bool(truthy(&evaluate(left, lookup)) || truthy(&evaluate(right, lookup)))
+        }
+        Expr::Compare(left, op, right) => {
+            let (left, right) = (evaluate(left, lookup), evaluate(right, lookup));
+            let result = match (*op, &left, &right) {
+                ("matches", _, Value::Str(pattern)) => glob::matches(pattern, &left.to_string()),
+                ("==", _, _) => left == right,
+                ("!=", _, _) => left != right,
+                (op, Value::Num(a), Value::Num(b)) => match op {
+                    "<" => a < b,
+                    "<=" => a <= b,
+                    ">" => a > b,
+                    _ => a >= b,
+                },
+                (op, Value::Str(a), Value::Str(b)) => match op {
+                    "<" => a < b,
+                    "<=" => a <= b,
+                    ">" => a > b,
+                    _ => a >= b,
+                },
+                _ => false,
+            };
+            Value::Bool(result)
+        }
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Num(n) => *n != 0.0,
+        Value::Str(s) => !s.is_empty(),
+    }
+}
+
+// `{from.name}`-style placeholders are replaced by field values
+fn render_message(template: &str, lookup: &dyn Fn(&str) -> Value) -> String {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        out.push_str(&lookup(&rest[start + 1..start + end]).to_string());
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+// What a script sees of one class. Link targets that are not defined in the
+// project get empty strings and zeroes.
+#[derive(Debug, Clone, Default)]
+struct ClassFacts {
+    package: String,
+    namespace: String,
+    layer: String,
+    file: String,
+    fan_in: usize,
+    fan_out: usize,
+    lines: usize,
+    methods: usize,
+    defined: bool,
+    tags: BTreeSet<ClassTag>,
+}
+
+fn class_value(name: &str, facts: Option<&ClassFacts>, field: &str) -> Value {
+    let facts = facts.cloned().unwrap_or_default();
+    match field {
+        "name" => Value::Str(name.to_string()),
+        "package" => Value::Str(facts.package),
+        "namespace" => Value::Str(facts.namespace),
+        "layer" => Value::Str(facts.layer),
+        "file" => Value::Str(facts.file),
+        "fan_in" => Value::Num(facts.fan_in as f64),
+        "fan_out" => Value::Num(facts.fan_out as f64),
+        "lines" => Value::Num(facts.lines as f64),
+        "methods" => Value::Num(facts.methods as f64),
+        "deprecated" => Value::Bool(facts.tags.contains(&ClassTag::Deprecated)),
+        "internal" => Value::Bool(facts.tags.contains(&ClassTag::Internal)),
+        _ => Value::Bool(facts.defined),
+    }
+}
+
+pub struct ScriptModel {
+    classes: BTreeMap<String, ClassFacts>,
+    links: Vec<project::LogicalEdge>,
+}
+
+impl ScriptModel {
+    // `layers` maps a layer name to globs over root-relative file paths;
+    // a class takes the first layer, by name, matching one of its files
+    pub fn build(root: &str, files: &[FileAnalysis], layers: &BTreeMap<String, Vec<String>>) -> Self {
+        let merged = project::merge(files.to_vec());
+        let links = project::group_links(root, files);
+        let packages = crate::manifest::class_packages(root, files);
+        let namespaces = crate::manifest::class_namespaces(root, files);
+        let mut classes: BTreeMap<String, ClassFacts> = BTreeMap::new();
+
+        for file in files {
+            let relative = relative_path(root, &file.path);
+            for class in &file.result.classes {
+                let facts = classes.entry(class.clone()).or_default();
+                facts.defined = true;
+                if facts.file.is_empty() || relative < facts.file {
+                    facts.file = relative.clone();
+                }
+                if facts.layer.is_empty() {
+                    if let Some((layer, _)) = layers.iter().find(|(_, globs)| glob::matches_any(globs, &relative)) {
+                        facts.layer = layer.clone();
+                    }
+                }
+            }
+        }
+        for (class, facts) in classes.iter_mut() {
+            facts.package = packages.get(class).cloned().unwrap_or_default();
+            facts.namespace = namespaces.get(class).cloned().unwrap_or_default();
+            if let Some(size) = merged.class_metrics.get(class) {
+                facts.lines = size.lines;
+                facts.methods = size.methods;
+            }
+            facts.tags = merged.class_tags.get(class).cloned().unwrap_or_default();
+        }
+        for edge in &links {
+            if let Some(facts) = classes.get_mut(&edge.from) {
+                facts.fan_out += 1;
+            }
+            if let Some(facts) = classes.get_mut(&edge.to) {
+                facts.fan_in += 1;
+            }
+        }
+
+        Self { classes, links }
+    }
+
+    pub fn evaluate(&self, rules: &[ScriptRule], catalog: &Catalog) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for rule in rules {
+            match rule.target {
+                Target::Class => {
+                    for name in self.classes.keys() {
+                        let lookup = |field: &str| class_value(name, self.classes.get(name), field);
+                        if truthy(&evaluate(&rule.condition, &lookup)) {
+                            let message = match &rule.message {
+                                Some(template) => render_message(template, &lookup),
+                                None => catalog.text(Message::ScriptClassFinding, &[name, &rule.id]),
+                            };
+                            findings.push(finding(rule, message, vec![name.clone()]));
+                        }
+                    }
+                }
+                Target::Link => {
+                    for edge in &self.links {
+                        let lookup = |field: &str| self.link_value(edge, field);
+                        if truthy(&evaluate(&rule.condition, &lookup)) {
+                            let message = match &rule.message {
+                                Some(template) => render_message(template, &lookup),
+                                None => catalog.text(Message::ScriptLinkFinding, &[&edge.from, &edge.to, &rule.id]),
+                            };
+                            findings.push(finding(rule, message, vec![edge.from.clone(), edge.to.clone()]));
+                        }
+                    }
+                }
+            }
+        }
+        findings
+    }
+
+    fn link_value(&self, edge: &project::LogicalEdge, field: &str) -> Value {
+        // The strongest kind and the first place the link occurs
+        let first = edge.occurrences.first();
+        match field {
+            "kind" => Value::Str(
+                edge.occurrences
+                    .iter()
+                    .map(|o| o.kind)
+                    .min()
+                    .and_then(|kind| serde_json::to_value(kind).ok())
+                    .and_then(|kind| kind.as_str().map(str::to_string))
+                    .unwrap_or_default(),
+            ),
+            "file" => Value::Str(first.map(|o| o.file.clone()).unwrap_or_default()),
+            "line" => Value::Num(first.map_or(0.0, |o| o.line as f64)),
+            _ => match field.split_once('.') {
+                Some(("from", rest)) => class_value(&edge.from, self.classes.get(&edge.from), rest),
+                Some((_, rest)) => class_value(&edge.to, self.classes.get(&edge.to), rest),
+                None => Value::Bool(false),
+            },
+        }
+    }
+}
+
+fn finding(rule: &ScriptRule, message: String, classes: Vec<String>) -> Finding {
+    Finding {
+        rule: rule.id.clone(),
+        severity: rule.severity,
+        message,
+        classes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_analyzer::AnalysisResult;
+    use crate::parser::ClassLink;
+
+    fn file(path: &str, classes: &[&str], links: &[(&str, &str)]) -> FileAnalysis {
+        FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|s| s.to_string()).collect(),
+                links: links
+                    .iter()
+                    .map(|(from, to)| ClassLink {
+                        from_class: from.to_string(),
+                        to_class: to.to_string(),
+                        line_number: 7,
+                        kind: Default::default(),
+                        position: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        }
+    }
+
+    fn model() -> ScriptModel {
+        let files = vec![
+            file("/repo/src/domain/order.py", &["Order"], &[("Order", "Controller"), ("Order", "Money")]),
+            file("/repo/src/domain/money.py", &["Money"], &[]),
+            file("/repo/src/web/controller.py", &["Controller"], &[("Controller", "Order")]),
+        ];
+        let layers = [
+            ("domain".to_string(), vec!["src/domain/**".to_string()]),
+            ("web".to_string(), vec!["src/web/**".to_string()]),
+        ]
+        .into_iter()
+        .collect();
+        ScriptModel::build("/repo", &files, &layers)
+    }
+
+    #[test]
+    fn test_link_rule_with_layers() {
+        let rules = parse(
+            "# layering\n\ndomain-to-web: for each link where from.layer == \"domain\" && to.layer == \"web\" -> error \"{from.name} uses {to.name} at {file}:{line}\"\n",
+        )
+        .unwrap();
+
+        let findings = model().evaluate(&rules, &Catalog::default());
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "domain-to-web");
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert_eq!(findings[0].message, "Order uses Controller at src/domain/order.py:7");
+        assert_eq!(findings[0].classes, vec!["Order", "Controller"]);
+    }
+
+    #[test]
+    fn test_class_rule_and_default_message() {
+        let rules =
+            parse("for each class where (fan_in >= 2 || name matches \"*Controller\") && !(layer == \"\") -> warning").unwrap();
+
+        let findings = model().evaluate(&rules, &Catalog::default());
+
+        let names: Vec<&str> = findings.iter().map(|f| f.classes[0].as_str()).collect();
+        assert_eq!(names, vec!["Controller"]);
+        assert_eq!(findings[0].rule, "script-rule-1");
+        assert_eq!(findings[0].message, "Controller matches rule script-rule-1");
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_line() {
+        let error = parse("# ok\nfor each link where from.colour == \"red\" -> error").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(error.message, "unknown field 'from.colour'");
+
+        assert!(parse("for each link where kind == \"usage\" -> fatal").is_err());
+        assert!(parse("for each module where true -> info").is_err());
+        assert!(parse("for each class where name == \"A -> info").is_err());
+    }
+}
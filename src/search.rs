@@ -0,0 +1,112 @@
+use crate::glob;
+
+// Class names matching `pattern`, best first, for people who do not
+// remember the exact name. A pattern with `*` or `?` is a glob over the
+// whole name and its matches come sorted by name. Anything else matches
+// fuzzily and ignores case: the name itself, names starting with or
+// containing the pattern, names holding its letters in order (`ordsvc` for
+// `OrderService`), and last names a few typos away (`OrderServise`).
+pub fn find_classes<I, S>(classes: I, pattern: &str) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return Vec::new();
+    }
+    let names = classes.into_iter().map(|class| class.as_ref().to_string());
+    if pattern.contains(['*', '?']) {
+        let mut found: Vec<String> = names.filter(|name| glob::matches(pattern, name)).collect();
+        found.sort();
+        found.dedup();
+        return found;
+    }
+    let mut scored: Vec<((u8, i64), String)> = names.filter_map(|name| Some((score(pattern, &name)?, name))).collect();
+    // Among equally good matches the shorter name is the likelier one
+    scored.sort_by(|(a, x), (b, y)| b.cmp(a).then_with(|| x.len().cmp(&y.len())).then_with(|| x.cmp(y)));
+    scored.dedup_by(|a, b| a.1 == b.1);
+    scored.into_iter().map(|(_, name)| name).collect()
+}
+
+// The kind of match, best highest, and how good it is within its kind
+fn score(pattern: &str, name: &str) -> Option<(u8, i64)> {
+    if name == pattern {
+        return Some((5, 0));
+    }
+    let (pattern, lower) = (pattern.to_lowercase(), name.to_lowercase());
+    if lower == pattern {
+        Some((4, 0))
+    } else if lower.starts_with(&pattern) {
+        Some((3, 0))
+    } else if lower.contains(&pattern) {
+        Some((2, 0))
+    } else if let Some(quality) = subsequence(&pattern, name) {
+        Some((1, quality))
+    } else {
+        let distance = edit_distance(&pattern, &lower);
+        (distance <= (pattern.chars().count() / 4).max(1)).then_some((0, -(distance as i64)))
+    }
+}
+
+// Matches the lowercase pattern's letters in order, scoring letters that
+// start a word (`S` in `OrderService`, the letter after `_`) and runs of
+// adjacent letters
+fn subsequence(pattern: &str, name: &str) -> Option<i64> {
+    let chars: Vec<char> = name.chars().collect();
+    let word_start = |i: usize| {
+        i == 0
+            || (chars[i].is_uppercase() && !chars[i - 1].is_uppercase())
+            || !chars[i - 1].is_alphanumeric()
+    };
+    let mut quality = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in pattern.chars() {
+        let found = (next..chars.len()).find(|&i| chars[i].to_lowercase().eq(wanted.to_lowercase()))?;
+        if word_start(found) {
+            quality += 2;
+        }
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            quality += 1;
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+    Some(quality)
+}
+
+// Levenshtein distance over characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb { diagonal } else { 1 + diagonal.min(above).min(row[j]) };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_classes_by_glob_and_fuzzy_match() {
+        let classes = ["OrderService", "OrderRepository", "Order", "UserService", "ServiceOrder", "Invoice"];
+
+        assert_eq!(find_classes(classes, "*Service"), vec!["OrderService", "UserService"]);
+        assert_eq!(find_classes(classes, "Order?*y"), vec!["OrderRepository"]);
+        assert_eq!(find_classes(classes, "order"), vec!["Order", "OrderService", "OrderRepository", "ServiceOrder"]);
+        assert_eq!(find_classes(classes, "ordsvc"), vec!["OrderService"]);
+        assert_eq!(find_classes(classes, "OrderServise"), vec!["OrderService"]);
+        assert_eq!(find_classes(classes, "Invoise"), vec!["Invoice"]);
+        assert!(find_classes(classes, "Payment").is_empty());
+        assert!(find_classes(classes, " ").is_empty());
+    }
+}
@@ -0,0 +1,356 @@
+use crate::diff::GraphDiff;
+use crate::graph::ClassGraph;
+use crate::manifest::{
+    self, class_id, link_id, package_id, ArchitectureManifest, ManifestClass, ManifestLink, ManifestMetrics,
+    ManifestPackage,
+};
+use crate::parser::ClassLink;
+use crate::project::FileAnalysis;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Files start with the magic bytes and a format version, followed by a
+// string table and then classes and links that index into it. Integers are
+// LEB128 varints, so a graph of a few thousand classes takes tens of KB
+// where the JSON manifest takes megabytes.
+pub const MAGIC: &[u8; 4] = b"CLSN";
+pub const VERSION: u8 = 1;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Snapshot {
+    pub classes: Vec<SnapshotClass>,
+    pub links: Vec<SnapshotLink>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotClass {
+    pub name: String,
+    pub package: String,
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotLink {
+    pub from: String,
+    pub to: String,
+    pub occurrences: usize,
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("invalid snapshot: {}", message))
+}
+
+pub fn is_snapshot(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+impl Snapshot {
+    pub fn build(root: &str, files: &[FileAnalysis]) -> Self {
+        Self::from_manifest(&ArchitectureManifest::build(root, files))
+    }
+
+    // Keeps what graph comparisons need; rule results, metrics and link
+    // locations are recomputed or dropped
+    pub fn from_manifest(manifest: &ArchitectureManifest) -> Self {
+        let name = |id: &str| id.strip_prefix("class:").unwrap_or(id).to_string();
+        Self {
+            classes: manifest
+                .classes
+                .iter()
+                .map(|class| SnapshotClass {
+                    name: class.name.clone(),
+                    package: class.package.strip_prefix("package:").unwrap_or(&class.package).to_string(),
+                    files: class.files.clone(),
+                })
+                .collect(),
+            links: manifest
+                .links
+                .iter()
+                .map(|link| SnapshotLink {
+                    from: name(&link.from),
+                    to: name(&link.to),
+                    occurrences: link.occurrences,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_manifest(&self) -> ArchitectureManifest {
+        let classes: HashSet<String> = self.classes.iter().map(|class| class.name.clone()).collect();
+        let links: Vec<ClassLink> = self
+            .links
+            .iter()
+            .map(|link| ClassLink {
+                from_class: link.from.clone(),
+                to_class: link.to.clone(),
+                line_number: 0,
+                kind: Default::default(),
+                position: None,
+            })
+            .collect();
+        let graph = ClassGraph::new(&classes, &links);
+
+        let mut packages: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for class in &self.classes {
+            packages.entry(&class.package).or_default().push(class_id(&class.name));
+        }
+        let metrics = ManifestMetrics {
+            total_classes: self.classes.len(),
+            total_packages: packages.len(),
+            total_links: self.links.len(),
+            isolated_classes: graph.isolated_classes().len(),
+            cycles: graph.find_cycles().len(),
+        };
+
+        ArchitectureManifest {
+            schema: manifest::MANIFEST_SCHEMA.to_string(),
+            generator: format!("classlink-checker {}", env!("CARGO_PKG_VERSION")),
+            classes: self
+                .classes
+                .iter()
+                .map(|class| ManifestClass {
+                    id: class_id(&class.name),
+                    name: class.name.clone(),
+                    package: package_id(&class.package),
+                    files: class.files.clone(),
+                    fan_in: graph.dependents(&class.name).len(),
+                    fan_out: graph.dependencies(&class.name).len(),
+                    tags: Default::default(),
+                })
+                .collect(),
+            packages: packages
+                .into_iter()
+                .map(|(path, classes)| ManifestPackage {
+                    id: package_id(path),
+                    path: path.to_string(),
+                    classes,
+                })
+                .collect(),
+            links: self
+                .links
+                .iter()
+                .map(|link| ManifestLink {
+                    id: link_id(&link.from, &link.to),
+                    from: class_id(&link.from),
+                    to: class_id(&link.to),
+                    occurrences: link.occurrences,
+                    locations: Vec::new(),
+                })
+                .collect(),
+            metrics,
+            rule_results: Vec::new(),
+        }
+    }
+
+    pub fn compare(&self, newer: &Snapshot) -> GraphDiff {
+        GraphDiff::between(&self.to_manifest(), &newer.to_manifest())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut strings = StringTable::default();
+        for class in &self.classes {
+            strings.intern(&class.name);
+            strings.intern(&class.package);
+            class.files.iter().for_each(|file| strings.intern(file));
+        }
+        for link in &self.links {
+            strings.intern(&link.from);
+            strings.intern(&link.to);
+        }
+
+        let mut out = MAGIC.to_vec();
+        out.push(VERSION);
+        write_varint(&mut out, strings.values.len());
+        for value in &strings.values {
+            write_varint(&mut out, value.len());
+            out.extend_from_slice(value.as_bytes());
+        }
+        write_varint(&mut out, self.classes.len());
+        for class in &self.classes {
+            write_varint(&mut out, strings.index[&class.name]);
+            write_varint(&mut out, strings.index[&class.package]);
+            write_varint(&mut out, class.files.len());
+            for file in &class.files {
+                write_varint(&mut out, strings.index[file]);
+            }
+        }
+        write_varint(&mut out, self.links.len());
+        for link in &self.links {
+            write_varint(&mut out, strings.index[&link.from]);
+            write_varint(&mut out, strings.index[&link.to]);
+            write_varint(&mut out, link.occurrences);
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        if !is_snapshot(bytes) {
+            return Err(invalid("missing header"));
+        }
+        let mut reader = Reader { bytes, at: MAGIC.len() };
+        let version = reader.byte()?;
+        if version != VERSION {
+            return Err(invalid(&format!("unsupported version {}", version)));
+        }
+
+        let mut strings = Vec::new();
+        for _ in 0..reader.varint()? {
+            let len = reader.varint()?;
+            let value = std::str::from_utf8(reader.take(len)?).map_err(|_| invalid("string is not UTF-8"))?;
+            strings.push(value.to_string());
+        }
+        let string = |reader: &mut Reader| -> io::Result<String> {
+            let index = reader.varint()?;
+            strings.get(index).cloned().ok_or_else(|| invalid("string index out of range"))
+        };
+
+        let mut snapshot = Snapshot::default();
+        for _ in 0..reader.varint()? {
+            let name = string(&mut reader)?;
+            let package = string(&mut reader)?;
+            let files = (0..reader.varint()?).map(|_| string(&mut reader)).collect::<io::Result<_>>()?;
+            snapshot.classes.push(SnapshotClass { name, package, files });
+        }
+        for _ in 0..reader.varint()? {
+            snapshot.links.push(SnapshotLink {
+                from: string(&mut reader)?,
+                to: string(&mut reader)?,
+                occurrences: reader.varint()?,
+            });
+        }
+        if reader.at != bytes.len() {
+            return Err(invalid("trailing data"));
+        }
+        Ok(snapshot)
+    }
+}
+
+#[derive(Default)]
+struct StringTable {
+    values: Vec<String>,
+    index: HashMap<String, usize>,
+}
+
+impl StringTable {
+    fn intern(&mut self, value: &str) {
+        if !self.index.contains_key(value) {
+            self.index.insert(value.to_string(), self.values.len());
+            self.values.push(value.to_string());
+        }
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> io::Result<u8> {
+        let byte = *self.bytes.get(self.at).ok_or_else(|| invalid("unexpected end of file"))?;
+        self.at += 1;
+        Ok(byte)
+    }
+
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        let end = self.at.checked_add(len).filter(|end| *end <= self.bytes.len());
+        let end = end.ok_or_else(|| invalid("unexpected end of file"))?;
+        let slice = &self.bytes[self.at..end];
+        self.at = end;
+        Ok(slice)
+    }
+
+    fn varint(&mut self) -> io::Result<usize> {
+        let mut value = 0usize;
+        for shift in (0..usize::BITS).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as usize) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("integer too large"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class(name: &str, package: &str, file: &str) -> SnapshotClass {
+        SnapshotClass {
+            name: name.to_string(),
+            package: package.to_string(),
+            files: vec![file.to_string()],
+        }
+    }
+
+    fn link(from: &str, to: &str, occurrences: usize) -> SnapshotLink {
+        SnapshotLink {
+            from: from.to_string(),
+            to: to.to_string(),
+            occurrences,
+        }
+    }
+
+    #[test]
+    fn test_round_trip_and_compare() {
+        let old = Snapshot {
+            classes: vec![class("Shop", "app", "app/shop.py"), class("Cart", "app", "app/shop.py")],
+            links: vec![link("Shop", "Cart", 300)],
+        };
+        let new = Snapshot {
+            classes: vec![
+                class("Shop", "app", "app/shop.py"),
+                class("Cart", "app", "app/shop.py"),
+                class("Db", "infra", "infra/db.py"),
+            ],
+            links: vec![link("Shop", "Cart", 2), link("Cart", "Db", 1)],
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.snapshot");
+
+        old.save(&path).unwrap();
+        let loaded = Snapshot::load(&path).unwrap();
+
+        assert_eq!(loaded, old);
+        let diff = loaded.compare(&new);
+        assert_eq!(diff.added_classes, vec!["Db"]);
+        assert_eq!(diff.added_links.len(), 1);
+        assert!(diff.removed_links.is_empty());
+        assert_eq!(old.to_manifest().metrics.total_packages, 1);
+    }
+
+    #[test]
+    fn test_rejects_truncated_and_foreign_files() {
+        let bytes = Snapshot {
+            classes: vec![class("Shop", "app", "app/shop.py")],
+            links: vec![],
+        }
+        .to_bytes();
+
+        assert!(Snapshot::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Snapshot::from_bytes(b"{\"schema\": 1}").is_err());
+        let mut newer = bytes.clone();
+        newer[MAGIC.len()] = VERSION + 1;
+        assert!(Snapshot::from_bytes(&newer).is_err());
+    }
+}
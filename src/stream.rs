@@ -0,0 +1,253 @@
+use crate::alias::AliasMap;
+use crate::cancel::Interruption;
+use crate::limits::LimitExceeded;
+use crate::manifest::relative_path;
+use crate::parser::{ClassLink, LinkKind};
+use crate::project::FileAnalysis;
+use crate::report::Report;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+// One line of the `jsonl` output. Classes and links are written as the scan
+// finds them, the summary once the run is done, so a consumer knows the
+// stream is complete.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum StreamRecord<'a> {
+    Class {
+        name: &'a str,
+        file: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        line: Option<usize>,
+    },
+    Link {
+        from: &'a str,
+        to: &'a str,
+        kind: LinkKind,
+        file: String,
+        line: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        column: Option<usize>,
+    },
+    Summary {
+        files: usize,
+        classes: usize,
+        links: usize,
+        findings: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        interrupted: Option<Interruption>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        truncated: Option<LimitExceeded>,
+    },
+}
+
+// Where a scan writes its records while it runs. Paths are root-relative
+// like the manifest's.
+pub struct LinkStream {
+    root: String,
+    out: Mutex<Box<dyn Write + Send>>,
+    aliases: AliasMap,
+    // (file, from, to, line) of the links written, to drop the duplicates
+    // renaming creates as `AliasMap::apply` does
+    written: Mutex<HashSet<(String, String, String, usize)>>,
+}
+
+impl LinkStream {
+    pub fn new(root: &str, out: Box<dyn Write + Send>) -> Self {
+        Self {
+            root: root.to_string(),
+            out: Mutex::new(out),
+            aliases: AliasMap::new(),
+            written: Mutex::new(HashSet::new()),
+        }
+    }
+
+    // Classes and links are written under their canonical names, like the
+    // report's
+    pub fn aliases(mut self, aliases: AliasMap) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    // The file's classes, then its links, in the order the parser found
+    // them
+    pub fn write_file(&self, file: &FileAnalysis) -> io::Result<()> {
+        self.write_file_with(file, |_| true)
+    }
+
+    // Like `write_file`, with only the links `keep` accepts
+    pub fn write_file_with(&self, file: &FileAnalysis, keep: impl Fn(&ClassLink) -> bool) -> io::Result<()> {
+        let path = relative_path(&self.root, &file.path);
+        let mut classes: Vec<(&str, Option<usize>)> = file
+            .result
+            .classes
+            .iter()
+            .map(|class| (self.aliases.canonical(class), file.result.class_metrics.get(class).map(|metrics| metrics.start_line)))
+            .collect();
+        classes.sort();
+        classes.dedup_by_key(|(name, _)| *name);
+        let mut records: Vec<StreamRecord> = classes
+            .into_iter()
+            .map(|(name, line)| StreamRecord::Class {
+                name,
+                file: path.clone(),
+                line,
+            })
+            .collect();
+        let links: Vec<&ClassLink> = file.result.links.iter().filter(|link| keep(link)).collect();
+        records.extend(self.link_records(&links, &path)?);
+        self.write(&records)
+    }
+
+    // Links a later pass adds to a file already written, like
+    // `--global-resolution`'s
+    pub fn write_links(&self, path: &str, links: &[ClassLink]) -> io::Result<()> {
+        let path = relative_path(&self.root, path);
+        let links: Vec<&ClassLink> = links.iter().collect();
+        let records = self.link_records(&links, &path)?;
+        self.write(&records)
+    }
+
+    fn link_records<'a>(&'a self, links: &[&'a ClassLink], path: &str) -> io::Result<Vec<StreamRecord<'a>>> {
+        let mut records = Vec::new();
+        let mut written = self.written.lock().map_err(|_| io::Error::other("link stream poisoned"))?;
+        for link in links {
+            let (from, to) = (self.aliases.canonical(&link.from_class), self.aliases.canonical(&link.to_class));
+            if self.aliases.is_empty()
+                || (from != to && written.insert((path.to_string(), from.to_string(), to.to_string(), link.line_number)))
+            {
+                records.push(link_record(link, from, to, path));
+            }
+        }
+        Ok(records)
+    }
+
+    // Flushed per file, so a consumer sees each file as soon as it is done
+    fn write(&self, records: &[StreamRecord]) -> io::Result<()> {
+        let mut out = self.out.lock().map_err(|_| io::Error::other("link stream poisoned"))?;
+        for record in records {
+            writeln!(out, "{}", serde_json::to_string(record)?)?;
+        }
+        out.flush()
+    }
+}
+
+fn link_record<'a>(link: &ClassLink, from: &'a str, to: &'a str, path: &str) -> StreamRecord<'a> {
+    StreamRecord::Link {
+        from,
+        to,
+        kind: link.kind,
+        file: path.to_string(),
+        line: link.line_number,
+        column: link.position.map(|position| position.column),
+    }
+}
+
+// The last line of the stream, for the report the run ended with
+pub fn summary(report: &Report) -> String {
+    let record = StreamRecord::Summary {
+        files: report.files.len(),
+        classes: report.statistics.total_classes,
+        links: report.files.iter().map(|file| file.result.links.len()).sum(),
+        findings: report.findings.len(),
+        interrupted: report.interrupted,
+        truncated: report.truncated,
+    };
+    serde_json::to_string(&record).unwrap_or_default() + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_analyzer::AnalysisResult;
+    use crate::metrics::ClassMetrics;
+    use std::sync::Arc;
+
+    // Collects what the stream writes, for a test to read back
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(bytes)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_stream_writes_classes_then_links_as_json_lines() {
+        let mut result = AnalysisResult::default();
+        result.classes.insert("Order".to_string());
+        result.class_metrics.insert(
+            "Order".to_string(),
+            ClassMetrics {
+                start_line: 3,
+                end_line: 9,
+                lines: 7,
+                methods: 1,
+            },
+        );
+        result.links.push(ClassLink {
+            from_class: "Order".to_string(),
+            to_class: "Invoice".to_string(),
+            line_number: 5,
+            kind: LinkKind::Composition,
+            position: None,
+        });
+        let file = FileAnalysis {
+            path: "/repo/src/order.py".to_string(),
+            result,
+            encoding: Default::default(),
+        };
+        let buffer = Buffer::default();
+        let stream = LinkStream::new("/repo", Box::new(buffer.clone()));
+
+        stream.write_file(&file).unwrap();
+        stream.write_links("/repo/src/order.py", &file.result.links).unwrap();
+
+        let written = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let link = r#"{"type":"link","from":"Order","to":"Invoice","kind":"composition","file":"src/order.py","line":5}"#;
+        assert_eq!(
+            written.lines().collect::<Vec<_>>(),
+            vec![r#"{"type":"class","name":"Order","file":"src/order.py","line":3}"#, link, link]
+        );
+    }
+
+    #[test]
+    fn test_stream_writes_canonical_names() {
+        let link = |from: &str, to: &str| ClassLink {
+            from_class: from.to_string(),
+            to_class: to.to_string(),
+            line_number: 2,
+            kind: LinkKind::Composition,
+            position: None,
+        };
+        let mut result = AnalysisResult::default();
+        result.classes.insert("UserDto".to_string());
+        result.links = vec![link("UserDto", "User"), link("UserDto", "Order"), link("UserModel", "Order")];
+        let file = FileAnalysis {
+            path: "/repo/user.ts".to_string(),
+            result,
+            encoding: Default::default(),
+        };
+        let aliases = AliasMap::from_config(&[("User".to_string(), vec!["UserDto".to_string(), "UserModel".to_string()])].into()).unwrap();
+        let buffer = Buffer::default();
+        let stream = LinkStream::new("/repo", Box::new(buffer.clone())).aliases(aliases);
+
+        stream.write_file(&file).unwrap();
+
+        let written = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(
+            written.lines().collect::<Vec<_>>(),
+            vec![
+                r#"{"type":"class","name":"User","file":"user.ts"}"#,
+                r#"{"type":"link","from":"User","to":"Order","kind":"composition","file":"user.ts","line":2}"#,
+            ]
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use crate::annotations::ClassTag;
+use crate::file_analyzer::AnalysisResult;
+use crate::glob;
+use crate::graph::ClassGraph;
+use crate::project::{self, FileAnalysis};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+// Neighbourhood of one class: everything within `depth` links of it, in
+// either direction
+pub fn subgraph_around(result: &AnalysisResult, class: &str, depth: usize) -> AnalysisResult {
+    let graph = ClassGraph::new(&result.classes, &result.links);
+    restrict(result, &graph.neighbourhood(class, depth))
+}
+
+// Classes whose name matches a glob (`Billing*`, `*Repository`) and the
+// links between them
+pub fn subgraph_matching(result: &AnalysisResult, pattern: &str) -> AnalysisResult {
+    restrict(result, &matching_classes(result, pattern))
+}
+
+fn matching_classes(result: &AnalysisResult, pattern: &str) -> BTreeSet<String> {
+    let referenced = result
+        .links
+        .iter()
+        .flat_map(|link| [&link.from_class, &link.to_class]);
+    result
+        .classes
+        .iter()
+        .chain(referenced)
+        .filter(|class| glob::matches(pattern, class))
+        .cloned()
+        .collect()
+}
+
+// Keeps the given classes and only the links with both ends among them
+pub fn restrict(result: &AnalysisResult, keep: &BTreeSet<String>) -> AnalysisResult {
+    AnalysisResult {
+        classes: result.classes.iter().filter(|c| keep.contains(*c)).cloned().collect(),
+        links: result
+            .links
+            .iter()
+            .filter(|link| keep.contains(&link.from_class) && keep.contains(&link.to_class))
+            .cloned()
+            .collect(),
+        class_metrics: result
+            .class_metrics
+            .iter()
+            .filter(|(class, _)| keep.contains(*class))
+            .map(|(class, size)| (class.clone(), *size))
+            .collect(),
+        class_tags: result
+            .class_tags
+            .iter()
+            .filter(|(class, _)| keep.contains(*class))
+            .map(|(class, tags)| (class.clone(), tags.clone()))
+            .collect(),
+        dependency_budgets: result
+            .dependency_budgets
+            .iter()
+            .filter(|(class, _)| keep.contains(*class))
+            .map(|(class, max)| (class.clone(), *max))
+            .collect(),
+        namespaces: result
+            .namespaces
+            .iter()
+            .filter(|(class, _)| keep.contains(*class))
+            .map(|(class, namespace)| (class.clone(), namespace.clone()))
+            .collect(),
+        assertions: result.assertions.clone(),
+        snippets: result.snippets.clone(),
+    }
+}
+
+// A focused view of the project, selected in the config file or with
+// `--focus`/`--match`, applied before reporting and export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Focus {
+    Around { class: String, depth: usize },
+    Matching { pattern: String },
+    // Classes carrying the tag plus their direct neighbours, so e.g. the
+    // users of deprecated classes stay visible
+    Tagged { tag: ClassTag },
+}
+
+impl Focus {
+    pub fn classes(&self, merged: &AnalysisResult) -> BTreeSet<String> {
+        match self {
+            Focus::Around { class, depth } => {
+                ClassGraph::new(&merged.classes, &merged.links).neighbourhood(class, *depth)
+            }
+            Focus::Matching { pattern } => matching_classes(merged, pattern),
+            Focus::Tagged { tag } => {
+                let graph = ClassGraph::new(&merged.classes, &merged.links);
+                merged
+                    .class_tags
+                    .iter()
+                    .filter(|(_, tags)| tags.contains(tag))
+                    .flat_map(|(class, _)| graph.neighbourhood(class, 1))
+                    .collect()
+            }
+        }
+    }
+
+    // Restricts each file to the focused classes, keeping per-file results
+    // (and so file paths in manifests) intact. Files left empty are dropped.
+    pub fn apply(&self, files: Vec<FileAnalysis>) -> Vec<FileAnalysis> {
+        let keep = self.classes(&project::merge(files.clone()));
+        files
+            .into_iter()
+            .map(|file| FileAnalysis {
+                result: restrict(&file.result, &keep),
+                ..file
+            })
+            .filter(|file| !file.result.classes.is_empty() || !file.result.links.is_empty())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ClassLink;
+
+    fn result(classes: &[&str], links: &[(&str, &str)]) -> AnalysisResult {
+        AnalysisResult {
+            classes: classes.iter().map(|s| s.to_string()).collect(),
+            links: links
+                .iter()
+                .map(|(from, to)| ClassLink {
+                    from_class: from.to_string(),
+                    to_class: to.to_string(),
+                    line_number: 1,
+                    kind: Default::default(),
+                    position: None,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn sorted(result: &AnalysisResult) -> Vec<&str> {
+        let mut classes: Vec<&str> = result.classes.iter().map(String::as_str).collect();
+        classes.sort();
+        classes
+    }
+
+    #[test]
+    fn test_subgraph_around_one_class() {
+        let full = result(
+            &["Api", "Service", "Repo", "Db", "Unrelated"],
+            &[("Api", "Service"), ("Service", "Repo"), ("Repo", "Db"), ("Unrelated", "Db")],
+        );
+
+        let around = subgraph_around(&full, "Service", 1);
+
+        assert_eq!(sorted(&around), vec!["Api", "Repo", "Service"]);
+        assert_eq!(around.links.len(), 2);
+        assert_eq!(subgraph_around(&full, "Service", 2).classes.len(), 4);
+        assert!(subgraph_around(&full, "Missing", 2).classes.is_empty());
+    }
+
+    #[test]
+    fn test_subgraph_matching_pattern() {
+        let full = result(
+            &["BillingApi", "BillingRepo", "UserRepo"],
+            &[("BillingApi", "BillingRepo"), ("BillingRepo", "UserRepo")],
+        );
+
+        let billing = subgraph_matching(&full, "Billing*");
+
+        assert_eq!(sorted(&billing), vec!["BillingApi", "BillingRepo"]);
+        assert_eq!(billing.links.len(), 1);
+        assert_eq!(subgraph_matching(&full, "*Repo").links.len(), 1);
+    }
+
+    #[test]
+    fn test_focus_applies_per_file() {
+        let files = vec![
+            FileAnalysis {
+                path: "/repo/api.py".to_string(),
+                result: result(&["Api"], &[("Api", "Service")]),
+                encoding: Default::default(),
+            },
+            FileAnalysis {
+                path: "/repo/other.py".to_string(),
+                result: result(&["Other"], &[]),
+                encoding: Default::default(),
+            },
+        ];
+
+        let focused = Focus::Around { class: "Service".to_string(), depth: 1 }.apply(files);
+
+        assert_eq!(focused.len(), 1);
+        assert_eq!(focused[0].path, "/repo/api.py");
+        assert_eq!(focused[0].result.links.len(), 1);
+    }
+
+    #[test]
+    fn test_tagged_focus_keeps_direct_users() {
+        let mut full = result(
+            &["Shop", "OldCart", "Db", "Unrelated"],
+            &[("Shop", "OldCart"), ("OldCart", "Db"), ("Db", "Unrelated")],
+        );
+        full.class_tags.entry("OldCart".to_string()).or_default().insert(ClassTag::Deprecated);
+
+        let classes: Vec<String> = Focus::Tagged { tag: ClassTag::Deprecated }.classes(&full).into_iter().collect();
+
+        assert_eq!(classes, vec!["Db", "OldCart", "Shop"]);
+        assert!(Focus::Tagged { tag: ClassTag::Internal }.classes(&full).is_empty());
+    }
+}
@@ -0,0 +1,289 @@
+use crate::glob;
+use crate::i18n::{Catalog, Message};
+use crate::manifest::relative_path;
+use crate::parser::LinkKind;
+use crate::project::FileAnalysis;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+// Row and column of the matrix for classes no pattern tags
+pub const UNTAGGED: &str = "untagged";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TagSummary {
+    pub classes: usize,
+    // Links between two classes carrying this tag
+    pub internal_links: usize,
+    // Links from this tag's classes to classes without it
+    pub outgoing_links: usize,
+    // Links from classes without this tag to this tag's classes
+    pub incoming_links: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TagStatistics {
+    pub tags: BTreeMap<String, TagSummary>,
+    // Link occurrences from classes with the row tag to classes with the
+    // column tag; a class with several tags counts for each
+    pub matrix: BTreeMap<String, BTreeMap<String, usize>>,
+}
+
+// Tags of every defined class. `patterns` maps a tag to globs matched
+// against the class name and against its root-relative defining files, so
+// both `*Legacy*` and `**/legacy/**` work.
+pub fn class_tags(
+    root: &str,
+    files: &[FileAnalysis],
+    patterns: &BTreeMap<String, Vec<String>>,
+) -> BTreeMap<String, BTreeSet<String>> {
+    let mut tags: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for file in files {
+        let relative = relative_path(root, &file.path);
+        for class in &file.result.classes {
+            let class_tags = tags.entry(class.clone()).or_default();
+            for (tag, globs) in patterns {
+                if glob::matches_any(globs, class) || glob::matches_any(globs, &relative) {
+                    class_tags.insert(tag.clone());
+                }
+            }
+        }
+    }
+    tags
+}
+
+// Doc links and links to classes the project does not define are left out
+pub fn tag_statistics(root: &str, files: &[FileAnalysis], patterns: &BTreeMap<String, Vec<String>>) -> TagStatistics {
+    let tags = class_tags(root, files, patterns);
+    let untagged = BTreeSet::from([UNTAGGED.to_string()]);
+    let tags_of = |class: &str| match tags.get(class) {
+        Some(class_tags) if !class_tags.is_empty() => class_tags,
+        _ => &untagged,
+    };
+
+    let mut statistics = TagStatistics::default();
+    for tag in patterns.keys() {
+        statistics.tags.insert(tag.clone(), TagSummary::default());
+    }
+    for class_tags in tags.values() {
+        for tag in class_tags {
+            statistics.tags.entry(tag.clone()).or_default().classes += 1;
+        }
+    }
+    for link in files.iter().flat_map(|file| &file.result.links) {
+        if link.kind == LinkKind::Doc || !tags.contains_key(&link.to_class) || !tags.contains_key(&link.from_class) {
+            continue;
+        }
+        let (from, to) = (tags_of(&link.from_class), tags_of(&link.to_class));
+        for from_tag in from {
+            for to_tag in to {
+                *statistics.matrix.entry(from_tag.clone()).or_default().entry(to_tag.clone()).or_default() += 1;
+            }
+        }
+        for (tag, summary) in statistics.tags.iter_mut() {
+            match (from.contains(tag), to.contains(tag)) {
+                (true, true) => summary.internal_links += 1,
+                (true, false) => summary.outgoing_links += 1,
+                (false, true) => summary.incoming_links += 1,
+                (false, false) => {}
+            }
+        }
+    }
+    statistics
+}
+
+// Shortest paths from each class of the first layer in `order` (the entry
+// layer, e.g. UI) to any class of the last one (the data layer)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TravelDistances {
+    pub entry: String,
+    pub data: String,
+    // Hops -> entry classes whose nearest data class is that far
+    pub distribution: BTreeMap<usize, usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average: Option<f64>,
+    // Entry classes with no path to the data layer
+    pub unreachable: Vec<String>,
+}
+
+// Position in `order` of the first layer a class is tagged with
+pub fn layer_of(class_tags: Option<&BTreeSet<String>>, order: &[String]) -> Option<usize> {
+    let class_tags = class_tags?;
+    order.iter().position(|layer| class_tags.contains(layer))
+}
+
+pub fn travel_distances(
+    root: &str,
+    files: &[FileAnalysis],
+    patterns: &BTreeMap<String, Vec<String>>,
+    order: &[String],
+) -> TravelDistances {
+    let (Some(entry), Some(data)) = (order.first(), order.last()) else {
+        return TravelDistances::default();
+    };
+    let tags = class_tags(root, files, patterns);
+    let mut outgoing: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for link in files.iter().flat_map(|file| &file.result.links) {
+        if link.kind != LinkKind::Doc {
+            outgoing.entry(&link.from_class).or_default().insert(&link.to_class);
+        }
+    }
+    let last = order.len() - 1;
+
+    let mut distances = TravelDistances {
+        entry: entry.clone(),
+        data: data.clone(),
+        ..Default::default()
+    };
+    let mut total = 0;
+    for class in tags.keys().filter(|class| layer_of(tags.get(*class), order) == Some(0)) {
+        let mut seen = BTreeSet::from([class.as_str()]);
+        let mut frontier = vec![class.as_str()];
+        let mut hops = 0;
+        let found = loop {
+            if frontier.is_empty() {
+                break None;
+            }
+            if frontier.iter().any(|at| hops > 0 && layer_of(tags.get(*at), order) == Some(last)) {
+                break Some(hops);
+            }
+            let next: Vec<&str> = frontier
+                .iter()
+                .flat_map(|at| outgoing.get(at).into_iter().flatten().copied())
+                .filter(|to| seen.insert(to))
+                .collect();
+            frontier = next;
+            hops += 1;
+        };
+        match found {
+            Some(hops) => {
+                *distances.distribution.entry(hops).or_default() += 1;
+                total += hops;
+            }
+            None => distances.unreachable.push(class.clone()),
+        }
+    }
+    let reached: usize = distances.distribution.values().sum();
+    distances.average = (reached > 0).then(|| total as f64 / reached as f64);
+    distances
+}
+
+pub fn render_travel(distances: &TravelDistances, catalog: &Catalog) -> String {
+    let mut out = String::new();
+    writeln!(out, "\n{}", catalog.text(Message::TravelHeader, &[&distances.entry, &distances.data])).unwrap();
+    for (hops, classes) in &distances.distribution {
+        writeln!(out, "{}", catalog.text(Message::TravelLine, &[hops, classes])).unwrap();
+    }
+    if let Some(average) = distances.average {
+        writeln!(out, "{}", catalog.text(Message::TravelAverage, &[&format!("{:.1}", average)])).unwrap();
+    }
+    if !distances.unreachable.is_empty() {
+        let line = catalog.text(Message::TravelUnreachable, &[&distances.data, &distances.unreachable.join(", ")]);
+        writeln!(out, "{}", line).unwrap();
+    }
+    out
+}
+
+pub fn render_tags(statistics: &TagStatistics, catalog: &Catalog) -> String {
+    let mut out = String::new();
+    if statistics.tags.is_empty() {
+        return out;
+    }
+    writeln!(out, "\n{}", catalog.text(Message::TagStatisticsHeader, &[])).unwrap();
+    for (tag, summary) in &statistics.tags {
+        let line = catalog.text(
+            Message::TagLine,
+            &[tag, &summary.classes, &summary.internal_links, &summary.incoming_links, &summary.outgoing_links],
+        );
+        writeln!(out, "{}", line).unwrap();
+    }
+    let crossings: Vec<(&String, &String, &usize)> = statistics
+        .matrix
+        .iter()
+        .flat_map(|(from, row)| row.iter().map(move |(to, count)| (from, to, count)))
+        .filter(|(from, to, _)| from != to)
+        .collect();
+    if !crossings.is_empty() {
+        writeln!(out, "\n{}", catalog.text(Message::TagMatrixHeader, &[])).unwrap();
+        for (from, to, count) in crossings {
+            writeln!(out, "{}", catalog.text(Message::TagMatrixLine, &[from, to, count])).unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_analyzer::AnalysisResult;
+    use crate::parser::ClassLink;
+
+    fn file(path: &str, classes: &[&str], links: &[(&str, &str)]) -> FileAnalysis {
+        FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|s| s.to_string()).collect(),
+                links: links
+                    .iter()
+                    .map(|(from, to)| ClassLink {
+                        from_class: from.to_string(),
+                        to_class: to.to_string(),
+                        line_number: 1,
+                        kind: Default::default(),
+                        position: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_tag_statistics_and_matrix() {
+        let files = vec![
+            file("/repo/src/legacy/billing.py", &["OldBilling", "OldTax"], &[("OldBilling", "OldTax")]),
+            file(
+                "/repo/src/shop.py",
+                &["Shop", "CartV2"],
+                &[("Shop", "OldBilling"), ("Shop", "OldBilling"), ("CartV2", "OldTax"), ("Shop", "CartV2"), ("Shop", "Optional")],
+            ),
+        ];
+        let patterns = BTreeMap::from([
+            ("legacy".to_string(), vec!["**/legacy/**".to_string()]),
+            ("v2".to_string(), vec!["*V2".to_string()]),
+        ]);
+
+        let statistics = tag_statistics("/repo", &files, &patterns);
+
+        assert_eq!(class_tags("/repo", &files, &patterns)["CartV2"], BTreeSet::from(["v2".to_string()]));
+        let legacy = &statistics.tags["legacy"];
+        assert_eq!((legacy.classes, legacy.internal_links, legacy.incoming_links, legacy.outgoing_links), (2, 1, 3, 0));
+        assert_eq!(statistics.matrix["untagged"]["legacy"], 2);
+        assert_eq!(statistics.matrix["v2"]["legacy"], 1);
+        assert_eq!(statistics.matrix["untagged"]["v2"], 1);
+        let text = render_tags(&statistics, &Catalog::default());
+        assert!(text.contains("  untagged -> legacy: 2 links"));
+    }
+
+    #[test]
+    fn test_travel_distances_from_entry_to_data() {
+        let files = vec![
+            file("/repo/ui/pages.py", &["OrderPage", "AdminPage", "AboutPage"], &[("OrderPage", "OrderService"), ("AdminPage", "UserRepo")]),
+            file("/repo/services/orders.py", &["OrderService", "Pricing"], &[("OrderService", "Pricing"), ("Pricing", "PriceRepo")]),
+            file("/repo/data/repos.py", &["PriceRepo", "UserRepo"], &[]),
+        ];
+        let patterns = BTreeMap::from([
+            ("ui".to_string(), vec!["ui/**".to_string()]),
+            ("service".to_string(), vec!["services/**".to_string()]),
+            ("data".to_string(), vec!["data/**".to_string()]),
+        ]);
+        let order = vec!["ui".to_string(), "service".to_string(), "data".to_string()];
+
+        let distances = travel_distances("/repo", &files, &patterns, &order);
+
+        assert_eq!(distances.distribution, BTreeMap::from([(1, 1), (3, 1)]));
+        assert_eq!(distances.average, Some(2.0));
+        assert_eq!(distances.unreachable, vec!["AboutPage"]);
+    }
+}
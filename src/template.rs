@@ -0,0 +1,441 @@
+// User-provided report layouts in a Handlebars subset, rendered over the
+// JSON report:
+//
+//     # {{generator}}
+//     {{#each findings}}
+//     - **{{severity}}** {{message}}
+//     {{else}}
+//     No findings.
+//     {{/each}}
+//
+// `{{path}}` inserts a value HTML-escaped, `{{{path}}}` raw. Blocks are
+// `{{#each path}}`, `{{#if path}}` and `{{#unless path}}`, each with an
+// optional `{{else}}`; `{{! ... }}` is a comment. Paths are dotted
+// (`statistics.total_classes`, `findings.length`), looked up in the current
+// `each` item first and then in the enclosing scopes; `this`, `../name`,
+// `@index`, `@key`, `@first` and `@last` work as in Handlebars. A block tag
+// alone on its line leaves no blank line behind.
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::io;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Block {
+    Each,
+    If,
+    Unless,
+}
+
+impl Block {
+    fn name(self) -> &'static str {
+        match self {
+            Block::Each => "each",
+            Block::If => "if",
+            Block::Unless => "unless",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    Value { path: String, escape: bool },
+    Block { block: Block, path: String, body: Vec<Node>, otherwise: Vec<Node> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tag {
+    Value { path: String, escape: bool },
+    Open(Block, String),
+    Else,
+    Close(Block),
+    Comment,
+}
+
+impl Tag {
+    // Tags that produce no output of their own
+    fn is_standalone_kind(&self) -> bool {
+        !matches!(self, Tag::Value { .. })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Text(String),
+    Tag(Tag, usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self, TemplateError> {
+        let mut tokens = tokenize(source)?;
+        trim_standalone(&mut tokens);
+        let mut stack: Vec<OpenBlock> = Vec::new();
+        let mut nodes = Vec::new();
+        for token in tokens {
+            let (tag, line) = match token {
+                Token::Text(text) if text.is_empty() => continue,
+                Token::Text(text) => {
+                    current(&mut stack, &mut nodes).push(Node::Text(text));
+                    continue;
+                }
+                Token::Tag(tag, line) => (tag, line),
+            };
+            match tag {
+                Tag::Value { path, escape } => current(&mut stack, &mut nodes).push(Node::Value { path, escape }),
+                Tag::Open(block, path) => stack.push((block, path, line, Vec::new(), None)),
+                Tag::Else => match stack.last_mut() {
+                    Some((_, _, _, body, otherwise @ None)) => *otherwise = Some(std::mem::take(body)),
+                    Some(_) => return Err(error(line, "second {{else}} in one block")),
+                    None => return Err(error(line, "{{else}} outside a block")),
+                },
+                Tag::Comment => {}
+                Tag::Close(block) => {
+                    let Some((open, path, _, body, otherwise)) = stack.pop() else {
+                        return Err(error(line, &format!("{{{{/{}}}}} without an open block", block.name())));
+                    };
+                    if open != block {
+                        let message = format!("{{{{/{}}}}} closes {{{{#{}}}}}", block.name(), open.name());
+                        return Err(error(line, &message));
+                    }
+                    let (body, otherwise) = match otherwise {
+                        Some(before_else) => (before_else, body),
+                        None => (body, Vec::new()),
+                    };
+                    current(&mut stack, &mut nodes).push(Node::Block { block, path, body, otherwise });
+                }
+            }
+        }
+        if let Some((block, _, line, _, _)) = stack.last() {
+            return Err(error(*line, &format!("{{{{#{}}}}} is never closed", block.name())));
+        }
+        Ok(Self { nodes })
+    }
+
+    pub fn from_file(path: &str) -> io::Result<Self> {
+        let source = fs::read_to_string(path)?;
+        Self::parse(&source).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("{}: {}", path, e)))
+    }
+
+    // Missing values render as nothing, like in Handlebars
+    pub fn render(&self, data: &Value) -> String {
+        let mut out = String::new();
+        let scopes = vec![Scope {
+            value: data,
+            index: None,
+            key: None,
+            last: false,
+        }];
+        render_nodes(&self.nodes, &scopes, &mut out);
+        out
+    }
+}
+
+// Block, path, line, body so far and the body before `{{else}}`
+type OpenBlock = (Block, String, usize, Vec<Node>, Option<Vec<Node>>);
+
+fn error(line: usize, message: &str) -> TemplateError {
+    TemplateError {
+        line,
+        message: message.to_string(),
+    }
+}
+
+fn current<'a>(stack: &'a mut [OpenBlock], nodes: &'a mut Vec<Node>) -> &'a mut Vec<Node> {
+    match stack.last_mut() {
+        Some((_, _, _, body, _)) => body,
+        None => nodes,
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, TemplateError> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    let mut line = 1;
+    while let Some(start) = rest.find("{{") {
+        let text = &rest[..start];
+        line += text.matches('\n').count();
+        tokens.push(Token::Text(text.to_string()));
+        let after = &rest[start..];
+        let (close, raw) = if after.starts_with("{{{") {
+            ("}}}", true)
+        } else if after.starts_with("{{!--") {
+            ("--}}", false)
+        } else {
+            ("}}", false)
+        };
+        let end = after.find(close).ok_or_else(|| error(line, "unclosed {{"))?;
+        let inner = after[..end].trim_start_matches('{').trim();
+        let tag = if raw {
+            Tag::Value {
+                path: inner.to_string(),
+                escape: false,
+            }
+        } else {
+            parse_tag(inner, line)?
+        };
+        tokens.push(Token::Tag(tag, line));
+        line += after[..end].matches('\n').count();
+        rest = &after[end + close.len()..];
+    }
+    tokens.push(Token::Text(rest.to_string()));
+    Ok(tokens)
+}
+
+fn parse_tag(inner: &str, line: usize) -> Result<Tag, TemplateError> {
+    let block = |name: &str| match name {
+        "each" => Ok(Block::Each),
+        "if" => Ok(Block::If),
+        "unless" => Ok(Block::Unless),
+        _ => Err(error(line, &format!("unknown block helper `{}`", name))),
+    };
+    if inner.starts_with('!') {
+        return Ok(Tag::Comment);
+    }
+    if let Some(open) = inner.strip_prefix('#') {
+        let mut words = open.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let path = words.next().ok_or_else(|| error(line, &format!("{{{{#{}}}}} needs a path", name)))?;
+        if words.next().is_some() {
+            return Err(error(line, &format!("{{{{#{}}}}} takes one path", name)));
+        }
+        return Ok(Tag::Open(block(name)?, path.to_string()));
+    }
+    if let Some(close) = inner.strip_prefix('/') {
+        return Ok(Tag::Close(block(close.trim())?));
+    }
+    if inner == "else" {
+        return Ok(Tag::Else);
+    }
+    if inner.is_empty() || inner.contains(char::is_whitespace) {
+        return Err(error(line, &format!("unsupported expression `{{{{{}}}}}`", inner)));
+    }
+    Ok(Tag::Value {
+        path: inner.to_string(),
+        escape: true,
+    })
+}
+
+// Drops the indentation and line break around block tags and comments that
+// are alone on their line. Decided on the untouched text, then applied, so
+// neighbouring standalone lines do not affect each other.
+fn trim_standalone(tokens: &mut [Token]) {
+    let last = tokens.len() - 1;
+    let text = |token: &Token| match token {
+        Token::Text(text) => Some(text.clone()),
+        Token::Tag(..) => None,
+    };
+    let mut trims = Vec::new();
+    for index in 1..last {
+        let Token::Tag(tag, _) = &tokens[index] else {
+            continue;
+        };
+        let (Some(before), Some(after)) = (text(&tokens[index - 1]), text(&tokens[index + 1])) else {
+            continue;
+        };
+        let line_start = before.rfind('\n').map(|at| at + 1).or((index == 1).then_some(0));
+        let line_end = after.find('\n').map(|at| at + 1).or((index + 1 == last).then_some(after.len()));
+        let (Some(line_start), Some(line_end)) = (line_start, line_end) else {
+            continue;
+        };
+        let blank = |s: &str| s.chars().all(|c| matches!(c, ' ' | '\t' | '\r' | '\n'));
+        if tag.is_standalone_kind() && blank(&before[line_start..]) && blank(&after[..line_end]) {
+            trims.push((index, line_start, line_end));
+        }
+    }
+    // Indentation first: it is at the end of a text, the line break at the
+    // start, so cutting one never moves the other
+    for &(index, line_start, _) in &trims {
+        if let Token::Text(before) = &mut tokens[index - 1] {
+            before.truncate(line_start);
+        }
+    }
+    for &(index, _, line_end) in &trims {
+        if let Token::Text(after) = &mut tokens[index + 1] {
+            let cut = line_end.min(after.len());
+            after.replace_range(..cut, "");
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Scope<'a> {
+    value: &'a Value,
+    index: Option<usize>,
+    key: Option<&'a str>,
+    last: bool,
+}
+
+fn lookup(path: &str, scopes: &[Scope]) -> Option<Value> {
+    let mut scopes = scopes;
+    let mut path = path;
+    let mut explicit = false;
+    while let Some(rest) = path.strip_prefix("../") {
+        scopes = &scopes[..scopes.len().saturating_sub(1).max(1)];
+        path = rest;
+        explicit = true;
+    }
+    let scope = scopes.last()?;
+    match path {
+        "@index" => return scope.index.map(Value::from),
+        "@key" => return scope.key.map(Value::from),
+        "@first" => return scope.index.map(|index| Value::Bool(index == 0)),
+        "@last" => return scope.index.map(|_| Value::Bool(scope.last)),
+        "this" | "." => return Some(scope.value.clone()),
+        _ => {}
+    }
+    if let Some(rest) = path.strip_prefix("this.") {
+        path = rest;
+        explicit = true;
+    }
+    let segments: Vec<&str> = path.split('.').collect();
+    // `this.` and `../` paths name one scope; bare ones fall back outwards
+    let searched: Vec<&Scope> = if explicit {
+        vec![scope]
+    } else {
+        scopes.iter().rev().collect()
+    };
+    searched.into_iter().find_map(|scope| descend(scope.value, &segments))
+}
+
+fn descend(value: &Value, segments: &[&str]) -> Option<Value> {
+    let mut value = value.clone();
+    for segment in segments {
+        value = match (&value, *segment) {
+            (Value::Array(items), "length") => Value::from(items.len()),
+            (Value::Object(map), "length") if !map.contains_key("length") => Value::from(map.len()),
+            (Value::Array(items), index) => items.get(index.parse::<usize>().ok()?)?.clone(),
+            (Value::Object(map), key) => map.get(key)?.clone(),
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+fn truthy(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) | Some(Value::Bool(false)) => false,
+        Some(Value::Number(number)) => number.as_f64() != Some(0.0),
+        Some(Value::String(text)) => !text.is_empty(),
+        Some(Value::Array(items)) => !items.is_empty(),
+        Some(Value::Object(map)) => !map.is_empty(),
+        Some(Value::Bool(true)) => true,
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#x27;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn render_nodes(nodes: &[Node], scopes: &[Scope], out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Value { path, escape } => {
+                let text = match lookup(path, scopes) {
+                    None | Some(Value::Null) => String::new(),
+                    Some(Value::String(text)) => text,
+                    Some(value) => value.to_string(),
+                };
+                out.push_str(&if *escape { escape_html(&text) } else { text });
+            }
+            Node::Block { block, path, body, otherwise } => {
+                let value = lookup(path, scopes);
+                match block {
+                    Block::If | Block::Unless => {
+                        let shown = truthy(value.as_ref()) == (*block == Block::If);
+                        render_nodes(if shown { body } else { otherwise }, scopes, out);
+                    }
+                    Block::Each => {
+                        let items: Vec<(Option<&str>, &Value)> = match &value {
+                            Some(Value::Array(items)) => items.iter().map(|item| (None, item)).collect(),
+                            Some(Value::Object(map)) => map.iter().map(|(key, item)| (Some(key.as_str()), item)).collect(),
+                            _ => Vec::new(),
+                        };
+                        if items.is_empty() {
+                            render_nodes(otherwise, scopes, out);
+                        }
+                        let count = items.len();
+                        for (index, (key, item)) in items.into_iter().enumerate() {
+                            let mut inner = scopes.to_vec();
+                            inner.push(Scope {
+                                value: item,
+                                index: Some(index),
+                                key,
+                                last: index + 1 == count,
+                            });
+                            render_nodes(body, &inner, out);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_values_blocks_and_standalone_lines() {
+        let template = Template::parse(
+            "# {{title}} ({{findings.length}})\n{{! findings first }}\n{{#each findings}}\n- {{@index}}: {{message}} [{{../title}}]{{#if @last}}.{{/if}}\n{{else}}\nNone\n{{/each}}\n{{#unless clean}}\nRaw: {{{html}}} / {{html}}\n{{/unless}}\n{{#each counts}}{{@key}}={{this}} {{/each}}\n",
+        )
+        .unwrap();
+        let data = json!({
+            "title": "Shop",
+            "html": "<b>",
+            "clean": false,
+            "counts": { "errors": 1, "warnings": 0 },
+            "findings": [{ "message": "Cart is isolated" }, { "message": "A -> B -> A" }],
+        });
+
+        assert_eq!(
+            template.render(&data),
+            "# Shop (2)\n- 0: Cart is isolated [Shop]\n- 1: A -&gt; B -&gt; A [Shop].\nRaw: <b> / &lt;b&gt;\nerrors=1 warnings=0 \n"
+        );
+        assert_eq!(template.render(&json!({ "clean": true })), "#  ()\nNone\n\n");
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_line() {
+        let error = Template::parse("# Report\n{{#each findings}}\n- {{message}}\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: {{#each}} is never closed");
+        let error = Template::parse("{{#if a}}\n{{/each}}").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(Template::parse("{{#with a}}{{/with}}").is_err());
+        assert!(Template::parse("{{lookup a b}}").is_err());
+    }
+}
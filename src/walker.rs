@@ -0,0 +1,536 @@
+use crate::cancel::{Interruption, StopCondition};
+use crate::file_analyzer::{FileType, UnifiedAnalyzer};
+use crate::glob;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, Metadata};
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+// Package manager and vendoring directories whose contents are third-party
+pub const VENDORED_DIRS: &[&str] = &[
+    "node_modules",
+    "bower_components",
+    "jspm_packages",
+    "vendor",
+    "third_party",
+];
+
+// Build output, virtualenv and version control directories, excluded unless
+// the exclude list is replaced
+pub const DEFAULT_EXCLUDES: &[&str] = &["target", "venv", ".venv", "__pycache__", ".tox", ".git"];
+
+const MINIFIED_LINE_LENGTH: usize = 500;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WalkOptions {
+    pub recursive: bool,
+    // Directory levels below the root a recursive walk enters; `None` for
+    // no limit, `Some(0)` for the root only
+    pub max_depth: Option<usize>,
+    pub follow_symlinks: bool,
+    pub max_file_size: Option<u64>,
+    pub skip_vendored: bool,
+    // Globs over root-relative paths of files and directories to leave out,
+    // see `glob`
+    pub exclude: Vec<String>,
+    // Leaves out what the `.gitignore` files found inside the root ignore
+    pub respect_gitignore: bool,
+    // Globs for files handled by custom analyzers, walked in addition to
+    // the built-in languages
+    pub extra_files: Vec<String>,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            recursive: false,
+            max_depth: None,
+            follow_symlinks: false,
+            max_file_size: Some(DEFAULT_MAX_FILE_SIZE),
+            skip_vendored: true,
+            exclude: DEFAULT_EXCLUDES.iter().map(|p| p.to_string()).collect(),
+            respect_gitignore: true,
+            extra_files: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: SkipReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum SkipReason {
+    TooLarge { size: u64, limit: u64 },
+    Binary,
+    Vendored,
+    Minified,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WalkResult {
+    pub files: Vec<String>,
+    pub skipped: Vec<SkippedFile>,
+    // Files no analyzer handles
+    pub unsupported: Vec<String>,
+    // Set when the walk stopped early; the lists hold what was found so far
+    pub interrupted: Option<Interruption>,
+}
+
+// Identity used to visit each physical file once, however many symlinks or
+// hardlinks point at it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum FileIdentity {
+    #[cfg(unix)]
+    Inode(u64, u64),
+    #[cfg(not(unix))]
+    Canonical(PathBuf),
+}
+
+#[cfg(unix)]
+fn identity(_path: &Path, metadata: &Metadata) -> io::Result<FileIdentity> {
+    use std::os::unix::fs::MetadataExt;
+    Ok(FileIdentity::Inode(metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn identity(path: &Path, _metadata: &Metadata) -> io::Result<FileIdentity> {
+    Ok(FileIdentity::Canonical(fs::canonicalize(path)?))
+}
+
+// One line of a `.gitignore`, relative to the directory holding it
+#[derive(Debug, Clone, PartialEq)]
+struct IgnoreRule {
+    base: String,
+    pattern: String,
+    negated: bool,
+    dir_only: bool,
+    // A leading `/`: only matches directly inside `base`
+    anchored: bool,
+}
+
+// The subset of gitignore syntax `glob` covers: comments, `!` negation,
+// trailing `/` for directories only, and a leading or inner `/` anchoring
+// the pattern to the `.gitignore` directory. Escapes are not supported.
+fn parse_gitignore(base: &str, content: &str) -> Vec<IgnoreRule> {
+    content
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (negated, line) = match line.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let (dir_only, line) = match line.strip_suffix('/') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let anchored = line.starts_with('/');
+            IgnoreRule {
+                base: base.to_string(),
+                pattern: line.trim_start_matches('/').to_string(),
+                negated,
+                dir_only,
+                anchored,
+            }
+        })
+        .collect()
+}
+
+pub struct Walker {
+    options: WalkOptions,
+    root: PathBuf,
+    ignore_rules: Vec<IgnoreRule>,
+    visited_dirs: HashSet<PathBuf>,
+    seen_files: HashSet<FileIdentity>,
+    result: WalkResult,
+    stop: StopCondition,
+}
+
+impl Walker {
+    pub fn new(options: WalkOptions) -> Self {
+        Self {
+            options,
+            root: PathBuf::new(),
+            ignore_rules: Vec::new(),
+            visited_dirs: HashSet::new(),
+            seen_files: HashSet::new(),
+            result: WalkResult::default(),
+            stop: StopCondition::default(),
+        }
+    }
+
+    pub fn with_stop(mut self, stop: StopCondition) -> Self {
+        self.stop = stop;
+        self
+    }
+
+    pub fn walk(mut self, root: &str) -> io::Result<WalkResult> {
+        let root = Path::new(root);
+        self.root = root.to_path_buf();
+
+        // The root itself is always resolved, even when it is a symlink
+        if root.is_file() {
+            self.visit_file(root, &fs::metadata(root)?)?;
+        } else if root.is_dir() {
+            self.visit_dir(root, 0)?;
+        }
+
+        self.result.files.sort();
+        self.result.unsupported.sort();
+        self.result.skipped.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(self.result)
+    }
+
+    fn visit_dir(&mut self, dir: &Path, depth: usize) -> io::Result<()> {
+        // Canonical paths break symlink loops like `a/link -> ..`
+        if !self.visited_dirs.insert(fs::canonicalize(dir)?) {
+            return Ok(());
+        }
+
+        let mut entries: Vec<PathBuf> = fs::read_dir(dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<_>>()?;
+        entries.sort();
+        let inherited_rules = self.ignore_rules.len();
+        if self.options.respect_gitignore {
+            if let Ok(content) = fs::read_to_string(dir.join(".gitignore")) {
+                let base = self.relative(dir);
+                self.ignore_rules.extend(parse_gitignore(&base, &content));
+            }
+        }
+
+        for entry in entries {
+            if self.result.interrupted.is_some() {
+                break;
+            }
+            if let Some(interruption) = self.stop.check() {
+                self.result.interrupted = Some(interruption);
+                break;
+            }
+            let link_metadata = fs::symlink_metadata(&entry)?;
+            let is_symlink = link_metadata.file_type().is_symlink();
+            if is_symlink && !self.options.follow_symlinks {
+                continue;
+            }
+
+            // Dangling symlinks have no target metadata; skip them
+            let metadata = match fs::metadata(&entry) {
+                Ok(metadata) => metadata,
+                Err(_) if is_symlink => continue,
+                Err(e) => return Err(e),
+            };
+
+            if self.is_excluded(&entry, metadata.is_dir()) {
+                continue;
+            }
+            if metadata.is_file() {
+                self.visit_file(&entry, &metadata)?;
+            } else if metadata.is_dir() && self.options.recursive && self.options.max_depth.is_none_or(|max| depth < max) {
+                if self.options.skip_vendored && is_vendored_dir(&entry) {
+                    self.skip(&entry, SkipReason::Vendored);
+                } else {
+                    self.visit_dir(&entry, depth + 1)?;
+                }
+            }
+        }
+
+        self.ignore_rules.truncate(inherited_rules);
+        Ok(())
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    // Excluded and ignored entries are left out silently, like git does,
+    // since they were named on purpose. Ignored directories are not entered,
+    // so a negation cannot re-include files inside them.
+    fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        let relative = self.relative(path);
+        if glob::matches_any(&self.options.exclude, &relative) {
+            return true;
+        }
+        let mut ignored = false;
+        for rule in &self.ignore_rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            let within = match rule.base.as_str() {
+                "" => Some(relative.as_str()),
+                base => relative.strip_prefix(base).and_then(|rest| rest.strip_prefix('/')),
+            };
+            let Some(within) = within else {
+                continue;
+            };
+            // Patterns with an inner `/` are matched over the whole path anyway
+            if rule.anchored && !rule.pattern.contains('/') && within.contains('/') {
+                continue;
+            }
+            if glob::matches(&rule.pattern, within) {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+
+    fn visit_file(&mut self, path: &Path, metadata: &Metadata) -> io::Result<()> {
+        let is_extra = || {
+            let path = path.to_string_lossy();
+            self.options.extra_files.iter().any(|pattern| glob::matches_tail(pattern, &path))
+        };
+        if !is_supported(path) && !is_extra() {
+            self.result.unsupported.push(path.to_string_lossy().to_string());
+            return Ok(());
+        }
+        if !self.seen_files.insert(identity(path, metadata)?) {
+            return Ok(());
+        }
+
+        if self.options.skip_vendored && is_minified_name(path) {
+            self.skip(path, SkipReason::Minified);
+            return Ok(());
+        }
+
+        let path = path.to_string_lossy().to_string();
+        match self.options.max_file_size {
+            Some(limit) if metadata.len() > limit => self.result.skipped.push(SkippedFile {
+                path,
+                reason: SkipReason::TooLarge {
+                    size: metadata.len(),
+                    limit,
+                },
+            }),
+            _ => self.result.files.push(path),
+        }
+        Ok(())
+    }
+
+    fn skip(&mut self, path: &Path, reason: SkipReason) {
+        self.result.skipped.push(SkippedFile {
+            path: path.to_string_lossy().to_string(),
+            reason,
+        });
+    }
+}
+
+fn is_vendored_dir(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| VENDORED_DIRS.contains(&name))
+}
+
+fn is_minified_name(path: &Path) -> bool {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .is_some_and(|stem| stem.ends_with(".min"))
+}
+
+// Minifiers collapse code onto a few very long lines with almost no
+// whitespace; hand-written long lines still have plenty of spaces
+pub fn looks_minified(content: &str) -> bool {
+    let longest = content.lines().map(str::len).max().unwrap_or(0);
+    if longest < MINIFIED_LINE_LENGTH {
+        return false;
+    }
+    let whitespace = content.bytes().filter(u8::is_ascii_whitespace).count();
+    whitespace * 8 < content.len()
+}
+
+pub fn is_supported(path: &Path) -> bool {
+    UnifiedAnalyzer::detect_file_type(&path.to_string_lossy()) != FileType::Unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn walk(root: &Path, follow_symlinks: bool) -> Vec<String> {
+        let walked = Walker::new(WalkOptions {
+            recursive: true,
+            follow_symlinks,
+            ..WalkOptions::default()
+        })
+        .walk(&root.to_string_lossy())
+        .unwrap();
+
+        walked
+            .files
+            .iter()
+            .map(|f| {
+                Path::new(f)
+                    .strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_non_recursive_walk_stays_at_top_level() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join("nested")).unwrap();
+        fs::write(temp_dir.path().join("Top.md"), "# Top\n").unwrap();
+        fs::write(temp_dir.path().join("nested").join("Deep.md"), "# Deep\n").unwrap();
+
+        let walked = Walker::new(WalkOptions::default())
+            .walk(&temp_dir.path().to_string_lossy())
+            .unwrap();
+
+        assert_eq!(walked.files.len(), 1);
+    }
+
+    #[test]
+    fn test_max_depth_limits_recursion() {
+        let temp_dir = TempDir::new().unwrap();
+        let deep = temp_dir.path().join("a").join("b");
+        fs::create_dir_all(&deep).unwrap();
+        fs::write(temp_dir.path().join("Top.md"), "# Top\n").unwrap();
+        fs::write(temp_dir.path().join("a").join("A.md"), "# A\n").unwrap();
+        fs::write(deep.join("B.md"), "# B\n").unwrap();
+        let walk = |max_depth| {
+            let options = WalkOptions {
+                recursive: true,
+                max_depth,
+                ..WalkOptions::default()
+            };
+            Walker::new(options).walk(&temp_dir.path().to_string_lossy()).unwrap().files.len()
+        };
+
+        assert_eq!((walk(Some(0)), walk(Some(1)), walk(Some(2)), walk(None)), (1, 2, 3, 3));
+    }
+
+    #[test]
+    #[cfg(feature = "typescript")]
+    fn test_files_over_size_limit_are_skipped_and_reported() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("bundle.ts"), "x".repeat(64)).unwrap();
+        fs::write(temp_dir.path().join("small.ts"), "class Small {}").unwrap();
+
+        let walked = Walker::new(WalkOptions {
+            max_file_size: Some(32),
+            ..WalkOptions::default()
+        })
+        .walk(&temp_dir.path().to_string_lossy())
+        .unwrap();
+
+        assert_eq!(walked.files.len(), 1);
+        assert!(walked.files[0].ends_with("small.ts"));
+        assert_eq!(walked.skipped.len(), 1);
+        assert_eq!(
+            walked.skipped[0].reason,
+            SkipReason::TooLarge { size: 64, limit: 32 }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "typescript")]
+    fn test_vendored_directories_and_min_files_are_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("node_modules").join("lib")).unwrap();
+        fs::write(root.join("node_modules").join("lib").join("index.ts"), "class Lib {}").unwrap();
+        fs::write(root.join("app.ts"), "class App {}").unwrap();
+        fs::write(root.join("app.min.ts"), "class App{}").unwrap();
+
+        let walked = Walker::new(WalkOptions {
+            recursive: true,
+            ..WalkOptions::default()
+        })
+        .walk(&root.to_string_lossy())
+        .unwrap();
+        let reasons: Vec<SkipReason> = walked.skipped.iter().map(|s| s.reason).collect();
+
+        assert_eq!(walked.files.len(), 1);
+        assert_eq!(reasons, vec![SkipReason::Minified, SkipReason::Vendored]);
+        assert_eq!(walk(root, false).len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "typescript")]
+    fn test_excludes_and_gitignore_leave_entries_out() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for dir in ["target/debug", "src/generated", "src/build", "docs"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        let files = ["target/debug/Out.md", "src/App.ts", "src/generated/Api.ts", "src/build/Keep.ts", "src/Scratch.ts"];
+        for file in files.iter().chain(&["docs/Guide.md", "docs/Draft.md"]) {
+            fs::write(root.join(file), "class A {}").unwrap();
+        }
+        fs::write(root.join(".gitignore"), "# generated code\n/build\ngenerated/\n*.md\n!Guide.md\n").unwrap();
+        fs::write(root.join("src").join(".gitignore"), "Scratch.ts\n").unwrap();
+
+        let mut files = walk(root, false);
+        files.sort();
+        // `/build` only matches at the root, and `target` is a default exclude
+        assert_eq!(files, vec!["docs/Guide.md", "src/App.ts", "src/build/Keep.ts"]);
+
+        let walked = Walker::new(WalkOptions {
+            recursive: true,
+            exclude: vec!["src/**".to_string()],
+            respect_gitignore: false,
+            ..WalkOptions::default()
+        })
+        .walk(&root.to_string_lossy())
+        .unwrap();
+        assert_eq!(walked.files.len(), 3);
+        assert!(walked.skipped.is_empty());
+    }
+
+    #[test]
+    fn test_looks_minified() {
+        let minified = format!("var a=1;{}", "class A{m(){return new B()}}".repeat(40));
+        let long_but_readable = format!("const message = \"{}\";", "some words here ".repeat(40));
+
+        assert!(looks_minified(&minified));
+        assert!(!looks_minified(&long_but_readable));
+        assert!(!looks_minified("class A {}\n"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_loops_terminate_and_duplicates_are_dropped() {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join("src")).unwrap();
+        fs::write(root.join("src").join("Service.md"), "# Service\n").unwrap();
+        symlink(root, root.join("src").join("loop")).unwrap();
+        symlink(root.join("src"), root.join("alias")).unwrap();
+        fs::hard_link(root.join("src").join("Service.md"), root.join("Copy.md")).unwrap();
+
+        assert_eq!(walk(root, true), vec!["Copy.md"]);
+        assert_eq!(walk(root, false), vec!["Copy.md"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_are_skipped_unless_followed() {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(outside.path().join("Shared.md"), "# Shared\n").unwrap();
+        fs::write(root.join("Own.md"), "# Own\n").unwrap();
+        symlink(outside.path(), root.join("shared")).unwrap();
+        symlink(root.join("missing"), root.join("dangling")).unwrap();
+
+        assert_eq!(walk(root, false), vec!["Own.md"]);
+        assert_eq!(walk(root, true), vec!["Own.md", "shared/Shared.md"]);
+    }
+}
@@ -0,0 +1,252 @@
+use crate::analyzer::{LinkAnalyzer, LinkStatistics};
+use crate::i18n::{Catalog, Message};
+use crate::project::{self, FileAnalysis, ProjectAnalyzer};
+use crate::report;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::io;
+
+// Several root directories analyzed as one project, e.g. a shared `libs/`
+// tree and the `apps/` using it. Class names resolve across all roots.
+#[derive(Debug, Clone, Default)]
+pub struct Workspace {
+    pub roots: Vec<WorkspaceRoot>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WorkspaceRoot {
+    pub path: String,
+    pub files: Vec<FileAnalysis>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RootSummary {
+    pub root: String,
+    pub files: usize,
+    // Classes defined here, with links from any root counted
+    pub statistics: LinkStatistics,
+    // Links from this root to classes defined only in other roots
+    pub outgoing_cross_root: usize,
+    // Links from other roots to classes defined here
+    pub incoming_cross_root: usize,
+    // Link targets defined in no root
+    pub unresolved: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceReport {
+    pub roots: Vec<RootSummary>,
+    pub combined: LinkStatistics,
+    // Which roots define each class
+    pub symbols: BTreeMap<String, Vec<String>>,
+    // Classes defined in more than one root
+    pub duplicate_classes: Vec<String>,
+}
+
+impl Workspace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn scan(analyzer: &ProjectAnalyzer, paths: &[String]) -> io::Result<Self> {
+        let mut workspace = Self::new();
+        for path in paths {
+            workspace.add_root(path, analyzer.analyze_files(path)?);
+        }
+        Ok(workspace)
+    }
+
+    pub fn add_root(&mut self, path: &str, files: Vec<FileAnalysis>) {
+        self.roots.push(WorkspaceRoot {
+            path: path.to_string(),
+            files,
+        });
+    }
+
+    pub fn files(&self) -> Vec<FileAnalysis> {
+        self.roots.iter().flat_map(|root| root.files.iter().cloned()).collect()
+    }
+
+    pub fn symbols(&self) -> BTreeMap<String, Vec<String>> {
+        let mut symbols: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for root in &self.roots {
+            let defined: BTreeSet<&String> = root.files.iter().flat_map(|f| &f.result.classes).collect();
+            for class in defined {
+                symbols.entry(class.clone()).or_default().push(root.path.clone());
+            }
+        }
+        symbols
+    }
+
+    pub fn report(&self) -> WorkspaceReport {
+        let symbols = self.symbols();
+        let combined = project::merge(self.files());
+
+        let roots = self
+            .roots
+            .iter()
+            .map(|root| {
+                let own = project::merge(root.files.clone());
+                let defined_here = |class: &str| symbols.get(class).is_some_and(|roots| roots.contains(&root.path));
+                let touching = combined
+                    .links
+                    .iter()
+                    .filter(|link| own.classes.contains(&link.from_class) || own.classes.contains(&link.to_class))
+                    .cloned()
+                    .collect();
+                let outgoing_cross_root = own
+                    .links
+                    .iter()
+                    .filter(|link| !defined_here(&link.to_class) && symbols.contains_key(&link.to_class))
+                    .count();
+                let incoming_cross_root = self
+                    .roots
+                    .iter()
+                    .filter(|other| other.path != root.path)
+                    .map(|other| {
+                        let defined_there =
+                            |class: &str| symbols.get(class).is_some_and(|roots| roots.contains(&other.path));
+                        other
+                            .files
+                            .iter()
+                            .flat_map(|f| &f.result.links)
+                            .filter(|link| defined_here(&link.to_class) && !defined_there(&link.to_class))
+                            .count()
+                    })
+                    .sum();
+                let unresolved: BTreeSet<String> = own
+                    .links
+                    .iter()
+                    .filter(|link| !symbols.contains_key(&link.to_class))
+                    .map(|link| link.to_class.clone())
+                    .collect();
+
+                RootSummary {
+                    root: root.path.clone(),
+                    files: root.files.len(),
+                    statistics: LinkAnalyzer::new().analyze(own.classes, touching),
+                    outgoing_cross_root,
+                    incoming_cross_root,
+                    unresolved: unresolved.into_iter().collect(),
+                }
+            })
+            .collect();
+
+        let duplicate_classes = symbols
+            .iter()
+            .filter(|(_, roots)| roots.len() > 1)
+            .map(|(class, _)| class.clone())
+            .collect();
+
+        WorkspaceReport {
+            roots,
+            combined: LinkAnalyzer::new().analyze(combined.classes, combined.links),
+            symbols,
+            duplicate_classes,
+        }
+    }
+}
+
+impl WorkspaceReport {
+    pub fn render_text(&self, catalog: &Catalog) -> String {
+        let mut out = String::new();
+        writeln!(out, "{}\n", catalog.text(Message::WorkspaceTitle, &[])).unwrap();
+        for root in &self.roots {
+            let line = catalog.text(
+                Message::WorkspaceRootLine,
+                &[
+                    &root.root,
+                    &root.files,
+                    &root.statistics.total_classes,
+                    &root.statistics.isolated_classes.len(),
+                    &root.outgoing_cross_root,
+                    &root.incoming_cross_root,
+                    &root.unresolved.len(),
+                ],
+            );
+            writeln!(out, "{}", line).unwrap();
+        }
+        if !self.duplicate_classes.is_empty() {
+            writeln!(out, "\n{}", catalog.text(Message::WorkspaceDuplicates, &[])).unwrap();
+            for class in &self.duplicate_classes {
+                writeln!(out, "  - {} ({})", class, self.symbols[class].join(", ")).unwrap();
+            }
+        }
+        writeln!(out).unwrap();
+        out.push_str(&report::render_text(&self.combined, catalog));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_analyzer::AnalysisResult;
+    use crate::parser::ClassLink;
+
+    fn file(path: &str, classes: &[&str], links: &[(&str, &str)]) -> FileAnalysis {
+        FileAnalysis {
+            path: path.to_string(),
+            result: AnalysisResult {
+                classes: classes.iter().map(|s| s.to_string()).collect(),
+                links: links
+                    .iter()
+                    .map(|(from, to)| ClassLink {
+                        from_class: from.to_string(),
+                        to_class: to.to_string(),
+                        line_number: 1,
+                        kind: Default::default(),
+                        position: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            encoding: Default::default(),
+        }
+    }
+
+    fn workspace() -> Workspace {
+        let mut workspace = Workspace::new();
+        workspace.add_root(
+            "libs",
+            vec![
+                file("libs/money.py", &["Money"], &[]),
+                file("libs/logger.py", &["Logger"], &[]),
+            ],
+        );
+        workspace.add_root(
+            "apps",
+            vec![file("apps/shop.py", &["Shop", "Logger"], &[("Shop", "Money"), ("Shop", "Cache")])],
+        );
+        workspace
+    }
+
+    #[test]
+    fn test_classes_resolve_across_roots() {
+        let report = workspace().report();
+
+        let libs = &report.roots[0];
+        assert_eq!(libs.incoming_cross_root, 1);
+        assert_eq!(libs.statistics.class_link_counts["Money"].incoming_links, 1);
+        assert!(!libs.statistics.isolated_classes.contains(&"Money".to_string()));
+
+        let apps = &report.roots[1];
+        assert_eq!(apps.outgoing_cross_root, 1);
+        assert_eq!(apps.unresolved, vec!["Cache"]);
+        assert_eq!(report.combined.total_classes, 3);
+    }
+
+    #[test]
+    fn test_symbol_table_flags_duplicates() {
+        let report = workspace().report();
+
+        assert_eq!(report.symbols["Money"], vec!["libs"]);
+        assert_eq!(report.symbols["Logger"], vec!["libs", "apps"]);
+        assert_eq!(report.duplicate_classes, vec!["Logger"]);
+
+        let text = report.render_text(&Catalog::default());
+        assert!(text.contains("  - Logger (libs, apps)"));
+        assert!(text.contains("apps: 1 files, 2 classes"));
+    }
+}
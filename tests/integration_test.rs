@@ -1,9 +1,13 @@
+#![cfg(all(feature = "python", feature = "typescript"))]
+
 use classlink_checker::{UnifiedAnalyzer, LinkAnalyzer};
 use std::collections::HashSet;
 use tempfile::TempDir;
 use std::fs;
+use std::process::Command;
 
 #[test]
+#[cfg(feature = "csharp")]
 fn test_multi_language_project_analysis() {
     let temp_dir = TempDir::new().unwrap();
     let project_path = temp_dir.path();
@@ -142,4 +146,90 @@ export class AnotherIsolatedClass {
     assert_eq!(stats.isolated_classes.len(), 2);
     assert!(stats.isolated_classes.contains(&"IsolatedClass".to_string()));
     assert!(stats.isolated_classes.contains(&"AnotherIsolatedClass".to_string()));
-}
\ No newline at end of file
+}
+
+fn classlink_checker(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_classlink-checker"))
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+// Classes with equal counts come out in any order, so compare line sets
+fn sorted_lines(output: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = output.lines().collect();
+    lines.sort_unstable();
+    lines
+}
+
+#[test]
+fn test_analyze_subcommand_matches_recursive_top_level_scan() {
+    let test_code = concat!(env!("CARGO_MANIFEST_DIR"), "/test-code");
+    
+    let subcommand = classlink_checker(&["analyze", test_code]);
+    let top_level = classlink_checker(&[test_code, "--recursive"]);
+    
+    assert!(subcommand.contains("Total Classes:"));
+    assert_eq!(sorted_lines(&subcommand), sorted_lines(&top_level));
+}
+
+#[test]
+fn test_config_file_merges_with_command_line_flags() {
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join("project");
+    fs::create_dir_all(project.join("sub")).unwrap();
+    fs::write(project.join("a.py"), "class A:\n    pass\n").unwrap();
+    fs::write(project.join("sub/b.py"), "class B(A):\n    pass\n").unwrap();
+    let config = temp_dir.path().join("classlink.json");
+    fs::write(&config, r#"{ "output": "json", "recursive": false }"#).unwrap();
+    let project = project.to_string_lossy();
+    let config = config.to_string_lossy();
+    let total_classes = |output: &str| serde_json::from_str::<serde_json::Value>(output).unwrap()["total_classes"].clone();
+    
+    // The config picks the format; `analyze` still scans recursively
+    assert_eq!(total_classes(&classlink_checker(&["analyze", &project, "-c", &config])), 2);
+    assert_eq!(total_classes(&classlink_checker(&[&project, "-c", &config])), 1);
+    // Flags given on the command line win over the config
+    let text = classlink_checker(&[&project, "-c", &config, "-o", "text", "--recursive"]);
+    assert!(text.contains("Total Classes: 2"), "{}", text);
+}
+
+#[test]
+fn test_subcommands_scan_with_the_config_and_walk_flags() {
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join("project");
+    fs::create_dir_all(project.join("sub")).unwrap();
+    fs::create_dir_all(project.join("vendor")).unwrap();
+    fs::create_dir_all(project.join("generated")).unwrap();
+    fs::write(project.join("a.py"), "class A:\n    pass\n").unwrap();
+    fs::write(project.join("sub/b.py"), "class B(A):\n    pass\n").unwrap();
+    fs::write(project.join("vendor/c.py"), "class C(A):\n    pass\n").unwrap();
+    fs::write(project.join("generated/d.py"), "class D(A):\n    pass\n").unwrap();
+    let config = temp_dir.path().join("classlink.json");
+    fs::write(&config, r#"{ "recursive": true, "exclude": ["generated/**"] }"#).unwrap();
+    let project = project.to_string_lossy();
+    let config = config.to_string_lossy();
+    let found = |args: &[&str]| {
+        let output = classlink_checker(&[&["find", "*", &project, "-o", "json"], args].concat());
+        let found: Vec<serde_json::Value> = serde_json::from_str(&output).unwrap();
+        let mut names: Vec<String> = found.iter().map(|class| class["name"].as_str().unwrap().to_string()).collect();
+        names.sort();
+        names
+    };
+
+    // Vendored directories are skipped and the config's excludes apply
+    assert_eq!(found(&["-c", &config]), vec!["A", "B"]);
+    assert_eq!(found(&["-c", &config, "--exclude", "sub/**"]), vec!["A"]);
+    assert_eq!(found(&["-r", "--include-vendored"]), vec!["A", "B", "C", "D"]);
+}
+
+#[test]
+fn test_analyze_subcommand_rejects_recursive_flag() {
+    let output = Command::new(env!("CARGO_BIN_EXE_classlink-checker"))
+        .args(["analyze", "-r", "."])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}